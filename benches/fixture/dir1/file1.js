@@ -0,0 +1,41 @@
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
+export function item1(x) { return x * 1 + Math.sqrt(x); }
@@ -0,0 +1,81 @@
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
+export function item21(x) { return x * 21 + Math.sqrt(x); }
@@ -0,0 +1,21 @@
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
+export function item41(x) { return x * 41 + Math.sqrt(x); }
@@ -0,0 +1,61 @@
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
+export function item61(x) { return x * 61 + Math.sqrt(x); }
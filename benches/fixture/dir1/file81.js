@@ -0,0 +1,101 @@
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
+export function item81(x) { return x * 81 + Math.sqrt(x); }
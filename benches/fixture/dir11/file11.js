@@ -0,0 +1,11 @@
+export function item11(x) { return x * 11 + Math.sqrt(x); }
+export function item11(x) { return x * 11 + Math.sqrt(x); }
+export function item11(x) { return x * 11 + Math.sqrt(x); }
+export function item11(x) { return x * 11 + Math.sqrt(x); }
+export function item11(x) { return x * 11 + Math.sqrt(x); }
+export function item11(x) { return x * 11 + Math.sqrt(x); }
+export function item11(x) { return x * 11 + Math.sqrt(x); }
+export function item11(x) { return x * 11 + Math.sqrt(x); }
+export function item11(x) { return x * 11 + Math.sqrt(x); }
+export function item11(x) { return x * 11 + Math.sqrt(x); }
+export function item11(x) { return x * 11 + Math.sqrt(x); }
@@ -0,0 +1,51 @@
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
+export function item31(x) { return x * 31 + Math.sqrt(x); }
@@ -0,0 +1,91 @@
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
+export function item51(x) { return x * 51 + Math.sqrt(x); }
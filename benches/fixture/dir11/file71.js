@@ -0,0 +1,31 @@
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
+export function item71(x) { return x * 71 + Math.sqrt(x); }
@@ -0,0 +1,71 @@
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
+export function item91(x) { return x * 91 + Math.sqrt(x); }
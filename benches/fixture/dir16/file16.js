@@ -0,0 +1,96 @@
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
+export function item16(x) { return x * 16 + Math.sqrt(x); }
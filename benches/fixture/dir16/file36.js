@@ -0,0 +1,36 @@
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
+export function item36(x) { return x * 36 + Math.sqrt(x); }
@@ -0,0 +1,76 @@
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
+export function item56(x) { return x * 56 + Math.sqrt(x); }
@@ -0,0 +1,16 @@
+export function item76(x) { return x * 76 + Math.sqrt(x); }
+export function item76(x) { return x * 76 + Math.sqrt(x); }
+export function item76(x) { return x * 76 + Math.sqrt(x); }
+export function item76(x) { return x * 76 + Math.sqrt(x); }
+export function item76(x) { return x * 76 + Math.sqrt(x); }
+export function item76(x) { return x * 76 + Math.sqrt(x); }
+export function item76(x) { return x * 76 + Math.sqrt(x); }
+export function item76(x) { return x * 76 + Math.sqrt(x); }
+export function item76(x) { return x * 76 + Math.sqrt(x); }
+export function item76(x) { return x * 76 + Math.sqrt(x); }
+export function item76(x) { return x * 76 + Math.sqrt(x); }
+export function item76(x) { return x * 76 + Math.sqrt(x); }
+export function item76(x) { return x * 76 + Math.sqrt(x); }
+export function item76(x) { return x * 76 + Math.sqrt(x); }
+export function item76(x) { return x * 76 + Math.sqrt(x); }
+export function item76(x) { return x * 76 + Math.sqrt(x); }
@@ -0,0 +1,56 @@
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
+export function item96(x) { return x * 96 + Math.sqrt(x); }
@@ -0,0 +1,66 @@
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
+export function item26(x) { return x * 26 + Math.sqrt(x); }
@@ -0,0 +1,6 @@
+export function item46(x) { return x * 46 + Math.sqrt(x); }
+export function item46(x) { return x * 46 + Math.sqrt(x); }
+export function item46(x) { return x * 46 + Math.sqrt(x); }
+export function item46(x) { return x * 46 + Math.sqrt(x); }
+export function item46(x) { return x * 46 + Math.sqrt(x); }
+export function item46(x) { return x * 46 + Math.sqrt(x); }
@@ -0,0 +1,26 @@
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
+export function item6(x) { return x * 6 + Math.sqrt(x); }
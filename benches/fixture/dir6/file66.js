@@ -0,0 +1,46 @@
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
+export function item66(x) { return x * 66 + Math.sqrt(x); }
@@ -0,0 +1,86 @@
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
+export function item86(x) { return x * 86 + Math.sqrt(x); }
@@ -0,0 +1,50 @@
+//! Benchmarks for looking up assets in a large asset set.
+//!
+//! Run with `cargo bench --features always-prod` to measure prod mode, which
+//! is the only one where lookup performance matters.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use reinda::Assets;
+
+
+const NUM_ASSETS: usize = 20_000;
+
+fn http_path(i: usize) -> String {
+    format!("static/dir{}/file{}.css", i % 100, i)
+}
+
+fn build(num_assets: usize) -> Assets {
+    let mut builder = Assets::builder();
+    for i in 0..num_assets {
+        builder.add_file(http_path(i), "tests/files/peter.txt");
+    }
+
+    tokio::runtime::Runtime::new().unwrap().block_on(builder.build()).unwrap()
+}
+
+fn lookup(c: &mut Criterion) {
+    let assets = build(NUM_ASSETS);
+    let paths = (0..NUM_ASSETS).step_by(7).map(http_path).collect::<Vec<_>>();
+    let missing = (0..NUM_ASSETS).step_by(7).map(|i| http_path(i + NUM_ASSETS)).collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group(format!("{NUM_ASSETS} assets"));
+    group.bench_function("get existing", |b| b.iter(|| {
+        paths.iter().filter(|p| assets.get(p).is_some()).count()
+    }));
+    group.bench_function("get missing", |b| b.iter(|| {
+        missing.iter().filter(|p| assets.get(p).is_some()).count()
+    }));
+    group.bench_function("paths_under", |b| b.iter(|| {
+        assets.paths_under("static/dir42/").count()
+    }));
+    group.bench_function("iter", |b| b.iter(|| assets.iter().count()));
+    group.bench_function("build", |b| b.iter_batched(
+        || (),
+        |_| build(NUM_ASSETS / 10),
+        BatchSize::PerIteration,
+    ));
+    group.finish();
+}
+
+criterion_group!(benches, lookup);
+criterion_main!(benches);
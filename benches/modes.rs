@@ -0,0 +1,220 @@
+//! Benchmarks for startup time, memory footprint and per-request latency of
+//! a representative asset set, comparing the three sources of assets:
+//! embedded via `embed!`, stored in a sidecar file (`embed!` with `sidecar`)
+//! and loaded from a directory at runtime (`Builder::add_dir`).
+//!
+//! Run `cargo bench --bench modes --features sidecar` to measure prod mode,
+//! where files are loaded, processed and compressed once at startup and then
+//! served from memory. Run `cargo bench --bench modes --features sidecar
+//! --profile dev` to measure dev mode, where files are loaded from disk on
+//! each request. Without the `sidecar` feature, that source is skipped.
+//!
+//! As `embed!` needs its files at compile time, the three sources are
+//! compared with the checked-in fixture in `benches/fixture` (100 CSS, JS,
+//! HTML, SVG and text files of up to 6 KB, written by `generate_fixture`).
+//! Additionally, a fixture of 1000 such assets of up to 60 KB is generated
+//! in a temporary directory and loaded at runtime. To benchmark your own
+//! asset tree instead, set `REINDA_BENCH_DIR` to its path. All files in it
+//! are added with their path relative to that directory as HTTP path.
+//!
+//! Assets are precompressed (with faster settings than the defaults) if the
+//! feature `compress` is enabled. The memory footprint is not measured by
+//! criterion, but printed to stderr before the benchmarks run.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use reinda::{http::Request, Assets};
+
+
+const NUM_FIXTURE_FILES: usize = 1000;
+
+/// Directory of the fixture embedded below, relative to `Cargo.toml`.
+const EMBED_DIR: &str = "benches/fixture";
+
+const EMBEDS: reinda::Embeds = reinda::embed! {
+    base_path: "benches/fixture",
+    files: ["**/*"],
+};
+
+#[cfg(feature = "sidecar")]
+const SIDECAR_EMBEDS: reinda::Embeds = reinda::embed! {
+    base_path: "benches/fixture",
+    files: ["**/*"],
+    sidecar: "target/reinda-bench-sidecar.pack",
+};
+
+/// Number of paths requested per iteration of the `serve` benchmarks.
+const NUM_REQUESTS: usize = 100;
+
+/// Counts the bytes currently allocated, to measure the memory footprint.
+struct CountingAlloc;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+
+/// Writes a fixture of `num_files` files into `dir`, each repeating one line
+/// up to about `max_repeat` times.
+fn generate_fixture(dir: &Path, num_files: usize, max_repeat: usize) {
+    for i in 0..num_files {
+        let (ext, line) = match i % 5 {
+            0 => ("css", ".item-{i} { color: #333; margin: 0 auto; padding: 4px 8px; }\n"),
+            1 => ("js", "export function item{i}(x) { return x * {i} + Math.sqrt(x); }\n"),
+            2 => ("html", "<li class=\"item\"><a href=\"/page/{i}\">Item {i}</a></li>\n"),
+            3 => ("svg", "<path d=\"M{i} 0 L{i} 100 Z\" stroke=\"black\" fill=\"none\"/>\n"),
+            _ => ("txt", "Line {i} of a plain text file with some repeated content.\n"),
+        };
+
+        let repeat = 4 + (i * 37) % max_repeat;
+        let content = line.replace("{i}", &i.to_string()).repeat(repeat);
+        let path = dir.join(format!("dir{}/file{i}.{ext}", i % 20));
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+}
+
+/// Returns all files below `dir` as `(http_path, fs_path)`.
+fn collect_files(dir: &Path) -> Vec<(String, PathBuf)> {
+    fn visit(base: &Path, dir: &Path, out: &mut Vec<(String, PathBuf)>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                visit(base, &path, out);
+            } else {
+                let relative = path.strip_prefix(base).unwrap();
+                let http_path = relative.components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                out.push((http_path, path));
+            }
+        }
+    }
+
+    let mut out = vec![];
+    visit(dir, dir, &mut out);
+    out.sort();
+    out
+}
+
+/// Where the assets of a benchmark come from.
+#[derive(Clone, Copy)]
+enum Source<'a> {
+    Embed(&'a reinda::Embeds),
+    Dir(&'a Path),
+}
+
+fn build(rt: &tokio::runtime::Runtime, source: Source<'_>) -> Assets {
+    let mut builder = Assets::builder();
+    #[cfg(feature = "compress")]
+    {
+        // The defaults compress as well as possible, which is too slow to
+        // build the large fixture over and over.
+        let mut config = reinda::CompressionConfig::default();
+        config.brotli_quality = 5;
+        config.gzip_level = 6;
+        builder.set_compression(config);
+    }
+    match source {
+        Source::Embed(embeds) => builder.add_embedded("", &embeds["**/*"]),
+        Source::Dir(dir) => builder.add_dir("", dir),
+    };
+    rt.block_on(builder.build()).unwrap()
+}
+
+/// Benchmarks building the assets from `source` and serving up to
+/// `NUM_REQUESTS` of the given `files`.
+fn bench_source(
+    c: &mut Criterion,
+    rt: &tokio::runtime::Runtime,
+    group_name: &str,
+    source_name: &str,
+    source: Source<'_>,
+    files: &[(String, PathBuf)],
+) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let assets = build(rt, source);
+    let retained = ALLOCATED.load(Ordering::Relaxed).saturating_sub(before);
+    eprintln!("{group_name}, {source_name}: {} KiB retained after build", retained / 1024);
+
+    let paths = files.iter()
+        .map(|(http_path, _)| http_path)
+        .step_by((files.len() / NUM_REQUESTS).max(1))
+        .take(NUM_REQUESTS)
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group(group_name);
+    group.bench_function(format!("{source_name}/build"), |b| b.iter_batched(
+        || (),
+        |_| build(rt, source),
+        BatchSize::PerIteration,
+    ));
+
+    group.throughput(Throughput::Elements(paths.len() as u64));
+    group.bench_function(format!("{source_name}/serve"), |b| b.iter(|| rt.block_on(async {
+        for path in &paths {
+            assert_eq!(assets.serve(&Request::get(path)).await.status(), 200);
+        }
+    })));
+    #[cfg(feature = "compress")]
+    group.bench_function(format!("{source_name}/serve gzip"), |b| b.iter(|| rt.block_on(async {
+        for path in &paths {
+            let req = Request::get(path).with_header("accept-encoding", "gzip");
+            assert_eq!(assets.serve(&req).await.status(), 200);
+        }
+    })));
+    group.finish();
+}
+
+fn modes(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let mode = if cfg!(prod_mode) { "prod" } else { "dev" };
+
+    // The sidecar file is looked up relative to this directory.
+    std::env::set_var("REINDA_SIDECAR_DIR", env!("CARGO_MANIFEST_DIR"));
+    let embed_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(EMBED_DIR);
+    let files = collect_files(&embed_dir);
+    let group = format!("{} embeddable assets ({mode})", files.len());
+    bench_source(c, &rt, &group, "embed", Source::Embed(&EMBEDS), &files);
+    #[cfg(feature = "sidecar")]
+    bench_source(c, &rt, &group, "sidecar", Source::Embed(&SIDECAR_EMBEDS), &files);
+    bench_source(c, &rt, &group, "dir", Source::Dir(&embed_dir), &files);
+
+    let (dir, generated) = match std::env::var_os("REINDA_BENCH_DIR") {
+        Some(dir) => (PathBuf::from(dir), false),
+        None => {
+            let dir = std::env::temp_dir().join(format!("reinda-bench-{}", std::process::id()));
+            generate_fixture(&dir, NUM_FIXTURE_FILES, 1000);
+            (dir, true)
+        }
+    };
+    let files = collect_files(&dir);
+    let group = format!("{} assets ({mode})", files.len());
+    bench_source(c, &rt, &group, "dir", Source::Dir(&dir), &files);
+
+    if generated {
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+criterion_group!(benches, modes);
+criterion_main!(benches);
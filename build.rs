@@ -1,6 +1,19 @@
 fn main() {
+    // Features are additive, so both might be enabled by different crates in
+    // the dependency graph. Erroring would make that graph unbuildable.
+    if std::env::var_os("CARGO_FEATURE_ALWAYS_PROD").is_some()
+        && std::env::var_os("CARGO_FEATURE_ALWAYS_DEV").is_some()
+    {
+        println!(
+            "cargo:warning=reinda: both `always-prod` and `always-dev` are enabled, \
+                using prod mode"
+        );
+    }
+
     cfg_aliases::cfg_aliases! {
-        prod_mode: { any(not(debug_assertions), feature = "always-prod") },
+        prod_mode: {
+            any(feature = "always-prod", all(not(debug_assertions), not(feature = "always-dev")))
+        },
         dev_mode: { not(prod_mode) },
     }
 }
@@ -0,0 +1,376 @@
+//! `cargo reinda`: lists all files that the `embed!` invocations of a crate
+//! would embed, their sizes and the changes since the last run.
+//!
+//! ```text
+//! cargo reinda [--manifest-path <path/to/Cargo.toml>] [--no-save]
+//! ```
+//!
+//! All `.rs` files in `src`, `examples`, `tests` and `benches` of the crate are
+//! searched for `embed!` invocations. They are parsed and their `base_path`
+//! and `files` resolved by the same code as in the macro, but nothing is
+//! compiled, so this is fast. The listed sizes are the uncompressed file
+//! sizes; for the sizes after compression, use `print_stats: true` in the
+//! macro or `Assets::embedded_size_by_entry` at runtime.
+//!
+//! The resulting file list is stored in `target/reinda-embedded.txt`
+//! (respecting `CARGO_TARGET_DIR`) and compared to on the next run, unless
+//! `--no-save` is passed.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use proc_macro2::{TokenStream, TokenTree};
+use reinda_shared::{
+    ast::{FileEntry, Input},
+    parse::parse,
+    plan::{base_paths, plan, Plan},
+};
+
+
+/// File in the target directory storing the result of the last run.
+const STATE_FILE: &str = "reinda-embedded.txt";
+
+const USAGE: &str = "\
+List the files embedded by `reinda::embed!` and their sizes.
+
+Usage: cargo reinda [--manifest-path <path/to/Cargo.toml>] [--no-save]
+
+Options:
+    --manifest-path <path>  Path to the `Cargo.toml` of the crate to inspect
+                            (default: `./Cargo.toml`)
+    --no-save               Do not store the result for comparison on the
+                            next run
+    -h, --help              Print this help
+";
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+}
+
+struct Args {
+    manifest_path: PathBuf,
+    save: bool,
+}
+
+fn parse_args() -> Result<Option<Args>, String> {
+    let mut args = std::env::args().skip(1).peekable();
+
+    // When invoked as `cargo reinda`, cargo passes the subcommand name.
+    if args.peek().map(|s| s.as_str()) == Some("reinda") {
+        args.next();
+    }
+
+    let mut out = Args { manifest_path: PathBuf::from("Cargo.toml"), save: true };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--manifest-path" => {
+                let path = args.next().ok_or("missing value for `--manifest-path`")?;
+                out.manifest_path = path.into();
+            }
+            "--no-save" => out.save = false,
+            "-h" | "--help" => return Ok(None),
+            other => return Err(format!("unknown argument '{other}'\n\n{USAGE}")),
+        }
+    }
+    Ok(Some(out))
+}
+
+fn run() -> Result<(), String> {
+    let Some(args) = parse_args()? else {
+        print!("{USAGE}");
+        return Ok(());
+    };
+    if !args.manifest_path.is_file() {
+        return Err(format!("'{}' does not exist", args.manifest_path.display()));
+    }
+    let crate_dir = args.manifest_path.canonicalize()
+        .map_err(|e| format!("failed to resolve '{}': {e}", args.manifest_path.display()))?;
+    let crate_dir = crate_dir.parent().expect("canonical file path has no parent");
+
+    // Find all invocations.
+    let mut sources = Vec::new();
+    for dir in ["src", "examples", "tests", "benches"] {
+        collect_rust_files(&crate_dir.join(dir), &mut sources)
+            .map_err(|e| format!("failed to read '{dir}': {e}"))?;
+    }
+    sources.sort();
+
+    let mut embedded = BTreeMap::new();
+    let mut total = 0;
+    for source_path in &sources {
+        let source = std::fs::read_to_string(source_path)
+            .map_err(|e| format!("failed to read '{}': {e}", source_path.display()))?;
+        let short_source = source_path.strip_prefix(crate_dir).unwrap_or(source_path);
+
+        for invocation in find_invocations(&source) {
+            println!("{}: embed! with base path {:?}", short_source.display(), invocation.base_path);
+            let bases = base_paths(crate_dir, &invocation.base_path);
+            for entry in &invocation.files {
+                let files = resolve(&bases, entry)
+                    .map_err(|e| format!("{}: '{}': {e}", short_source.display(), entry.path))?;
+                if files.is_empty() {
+                    println!("    warning: '{}' does not match any file", entry.path);
+                }
+                for (short_path, full_path) in files {
+                    let size = std::fs::metadata(&full_path)
+                        .map_err(|e| format!("failed to read '{}': {e}", full_path.display()))?
+                        .len();
+                    total += size;
+                    println!("    {:>9}  {short_path}", ByteSize(size).to_string());
+                    let key = full_path.strip_prefix(crate_dir).unwrap_or(&full_path);
+                    embedded.insert(key.display().to_string(), size);
+                }
+            }
+            println!();
+        }
+    }
+
+    if embedded.is_empty() {
+        println!("No embedded files found.");
+    }
+    println!("Total: {} files, {} (uncompressed)", embedded.len(), ByteSize(total));
+
+    // Compare with the last run.
+    let target_dir = std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| crate_dir.join("target"));
+    let state_path = target_dir.join(STATE_FILE);
+    if let Ok(previous) = std::fs::read_to_string(&state_path) {
+        let previous = parse_state(&previous);
+        let changes = diff(&previous, &embedded);
+        println!();
+        if changes.is_empty() {
+            println!("No changes since the last run.");
+        } else {
+            println!("Changes since the last run:");
+            for change in changes {
+                println!("    {change}");
+            }
+        }
+    }
+
+    if args.save {
+        std::fs::create_dir_all(&target_dir)
+            .and_then(|_| std::fs::write(&state_path, format_state(&embedded)))
+            .map_err(|e| format!("failed to write '{}': {e}", state_path.display()))?;
+    }
+
+    Ok(())
+}
+
+/// Recursively collects all `.rs` files in `dir`. Does nothing if `dir` does
+/// not exist.
+fn collect_rust_files(dir: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_rust_files(&path, out)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+
+/// Returns all files `entry` refers to as short path (relative to the base
+/// path) and full path, exactly like the macro finds them. A missing file
+/// without glob characters yields no files, like a glob without matches.
+fn resolve(bases: &[PathBuf], entry: &FileEntry) -> Result<Vec<(String, PathBuf)>, String> {
+    match plan(bases, entry).map_err(|e| e.msg)? {
+        Plan::Single { path, full_path } if Path::new(&full_path).is_file() => {
+            Ok(vec![(path, full_path.into())])
+        }
+        Plan::Single { .. } => Ok(vec![]),
+        Plan::Glob(files) => Ok(files.into_iter().map(|(short, full)| (short, full.into())).collect()),
+    }
+}
+
+/// Finds all `embed!` invocations in the given Rust source. Invocations that
+/// cannot be parsed (e.g. because they don't compile) are skipped, the
+/// compiler will complain about them anyway.
+fn find_invocations(source: &str) -> Vec<Input> {
+    let Ok(tokens) = source.parse::<TokenStream>() else {
+        return vec![];
+    };
+    let mut out = Vec::new();
+    visit(tokens, &mut out);
+    out
+}
+
+fn visit(tokens: TokenStream, out: &mut Vec<Input>) {
+    let tokens = tokens.into_iter().collect::<Vec<_>>();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Ident(ident) if ident == "embed" => {
+                let (Some(TokenTree::Punct(bang)), Some(TokenTree::Group(body))) = (tokens.get(i + 1), tokens.get(i + 2))
+                else { continue };
+                if bang.as_char() == '!' {
+                    out.extend(parse(body.stream()).ok());
+                }
+            }
+            TokenTree::Group(group) => visit(group.stream(), out),
+            _ => {}
+        }
+    }
+}
+
+
+/// One change between two runs.
+#[derive(Debug, PartialEq)]
+enum Change<'a> {
+    Added(&'a str, u64),
+    Removed(&'a str, u64),
+    Resized(&'a str, u64, u64),
+}
+
+impl fmt::Display for Change<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Added(path, size) => write!(f, "+ {path} ({})", ByteSize(*size)),
+            Self::Removed(path, size) => write!(f, "- {path} ({})", ByteSize(*size)),
+            Self::Resized(path, before, after) => {
+                write!(f, "~ {path} ({} -> {})", ByteSize(*before), ByteSize(*after))
+            }
+        }
+    }
+}
+
+fn diff<'a>(previous: &'a BTreeMap<String, u64>, current: &'a BTreeMap<String, u64>) -> Vec<Change<'a>> {
+    let mut out = Vec::new();
+    for (path, &size) in current {
+        match previous.get(path) {
+            None => out.push(Change::Added(path, size)),
+            Some(&before) if before != size => out.push(Change::Resized(path, before, size)),
+            Some(_) => {}
+        }
+    }
+    for (path, &size) in previous {
+        if !current.contains_key(path) {
+            out.push(Change::Removed(path, size));
+        }
+    }
+    out
+}
+
+/// Formats the state file: one `<size>\t<path>` line per file.
+fn format_state(files: &BTreeMap<String, u64>) -> String {
+    files.iter().map(|(path, size)| format!("{size}\t{path}\n")).collect()
+}
+
+fn parse_state(s: &str) -> BTreeMap<String, u64> {
+    s.lines()
+        .filter_map(|line| {
+            let (size, path) = line.split_once('\t')?;
+            Some((path.to_owned(), size.parse().ok()?))
+        })
+        .collect()
+}
+
+
+struct ByteSize(u64);
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 > 1500 * 1024 {
+            write!(f, "{:.1}MiB", (self.0 / 1024) as f32 / 1024.0)
+        } else if self.0 > 1500 {
+            write!(f, "{:.1}KiB", self.0 as f32 / 1024.0)
+        } else {
+            write!(f, "{}B", self.0)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(pattern: &str, except: &[&str]) -> FileEntry {
+        let span = proc_macro2::Span::call_site();
+        FileEntry {
+            path: pattern.into(),
+            span,
+            except: except.iter().map(|&p| (p.into(), span)).collect(),
+            always_embed: false,
+        }
+    }
+
+    #[test]
+    fn invocations() {
+        let source = r#"
+            const A: Embeds = reinda::embed! {
+                base_path: "assets",
+                print_stats: true,
+                files: ["index.html", "icons/*.svg"],
+            };
+            fn foo() {
+                let b = embed!(base_path: ["dist", "public"], files: [
+                    "*.js" except ["*.test.js", "vendor.js"] always_embed,
+                    "*.css" except "print.css",
+                ]);
+                let not_a_macro = embed;
+            }
+        "#;
+        let summary = find_invocations(source).into_iter()
+            .map(|invocation| {
+                let files = invocation.files.into_iter()
+                    .map(|f| (f.path, f.except.into_iter().map(|(p, _)| p).collect::<Vec<_>>()))
+                    .collect::<Vec<_>>();
+                (invocation.base_path, files)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(summary, [
+            (vec!["assets".to_owned()], vec![
+                ("index.html".to_owned(), vec![]),
+                ("icons/*.svg".to_owned(), vec![]),
+            ]),
+            (vec!["dist".to_owned(), "public".to_owned()], vec![
+                ("*.js".to_owned(), vec!["*.test.js".to_owned(), "vendor.js".to_owned()]),
+                ("*.css".to_owned(), vec!["print.css".to_owned()]),
+            ]),
+        ]);
+        assert!(find_invocations("embed! { base_path: \"x\" }").is_empty());
+        assert!(find_invocations("embed! { files: [\"a\"], unknown: 1 }").is_empty());
+    }
+
+    #[test]
+    fn resolve_files() {
+        let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let bases = base_paths(&crate_dir, &["tests/files".into(), "examples/assets".into()]);
+        let short_paths = |pattern, except| resolve(&bases, &file(pattern, except))
+            .unwrap()
+            .into_iter()
+            .map(|(short, _)| short)
+            .collect::<Vec<_>>();
+        assert_eq!(short_paths("robots.txt", &[]), ["robots.txt"]);
+        assert_eq!(short_paths("*.css", &[]), ["bom-crlf.css", "style.css"]);
+        assert!(short_paths("missing.txt", &[]).is_empty());
+
+        assert_eq!(short_paths("*.css", &["bom-*"]), ["style.css"]);
+        assert_eq!(short_paths("*.txt", &["peter.txt", "missing/*"]), ["robots.txt"]);
+        assert_eq!(short_paths("*.{css,txt}", &["{bom-*,peter.*}"]), ["robots.txt", "style.css"]);
+        assert!(resolve(&bases, &file("robots.txt", &["*.txt"])).is_err());
+    }
+
+    #[test]
+    fn state_diff() {
+        let previous = parse_state("10\ta.js\n20\tb.css\n30\tc.svg\n");
+        let current = parse_state(&format_state(&parse_state("10\ta.js\n25\tb.css\n5\td.png\n")));
+        assert_eq!(diff(&previous, &current), [
+            Change::Resized("b.css", 20, 25),
+            Change::Added("d.png", 5),
+            Change::Removed("c.svg", 30),
+        ]);
+    }
+}
@@ -0,0 +1,52 @@
+//! Answers arbitrary requests with `Assets::serve`. The input is split into
+//! lines: method, path and then values of the headers relevant for serving.
+//! Run with `cargo fuzz run serve`.
+
+#![no_main]
+
+use std::sync::OnceLock;
+
+use libfuzzer_sys::fuzz_target;
+use reinda::{http::Request, Assets};
+
+const HEADERS: &[&str] = &["accept", "accept-encoding", "if-none-match", "sec-ch-dpr", "save-data"];
+
+/// Path of a file in the repository, independent of the working directory.
+macro_rules! repo_file {
+    ($path:literal) => { concat!(env!("CARGO_MANIFEST_DIR"), "/../", $path) };
+}
+
+fn runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| tokio::runtime::Builder::new_current_thread().build().unwrap())
+}
+
+fn assets() -> &'static Assets {
+    static ASSETS: OnceLock<Assets> = OnceLock::new();
+    ASSETS.get_or_init(|| runtime().block_on(async {
+        let mut builder = Assets::builder();
+        builder.add_file("index.html", repo_file!("examples/assets/index.html"));
+        builder.add_file("img/pixel.png", repo_file!("tests/files/img/pixel.png"))
+            .with_variant("img/pixel.webp")
+            .with_dpr_variant(2.0, "img/pixel@2x.png");
+        builder.add_file("img/pixel.webp", repo_file!("tests/files/img/pixel.webp"));
+        builder.add_file("img/pixel@2x.png", repo_file!("tests/files/img/pixel@2x.png"));
+        builder.add_file("peter.txt", repo_file!("tests/files/peter.txt"))
+            .with_download("peter.txt");
+        builder.add_renamed("old/peter.txt", "peter.txt");
+        builder.use_client_hints();
+        builder.build().await.expect("failed to build assets")
+    }))
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else { return };
+    let mut lines = input.split('\n');
+    let (Some(method), Some(path)) = (lines.next(), lines.next()) else { return };
+
+    let mut req = Request::new(method, path);
+    for (name, value) in HEADERS.iter().zip(lines) {
+        req = req.with_header(name, value);
+    }
+    runtime().block_on(assets().serve(&req));
+});
@@ -0,0 +1,13 @@
+//! Renders arbitrary bytes as template. Run with `cargo fuzz run template`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use reinda::template::{self, Fragment};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = template::render(data, |fragment| match fragment {
+        Fragment::Path(path) => (!path.is_empty()).then(|| format!("{path}.hashed")),
+        _ => None,
+    });
+});
@@ -1,6 +1,8 @@
 fn main() {
     cfg_aliases::cfg_aliases! {
-        prod_mode: { any(not(debug_assertions), feature = "always-prod") },
+        prod_mode: {
+            any(feature = "always-prod", all(not(debug_assertions), not(feature = "always-dev")))
+        },
         dev_mode: { not(prod_mode) },
     }
 }
@@ -1,10 +1,12 @@
-use std::path::{Path, PathBuf};
-use glob::glob;
+use std::path::Path;
 
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
-
-use crate::{err, EmbedConfig, Error, Input};
+use reinda_shared::{
+    ast::{EmbedConfig, FileEntry, Input},
+    err::{err, Error},
+    plan::{base_paths, plan, Plan},
+};
 
 
 
@@ -12,31 +14,41 @@ use crate::{err, EmbedConfig, Error, Input};
 pub(crate) fn emit(input: Input) -> Result<TokenStream, Error> {
     let config = input.with_defaults();
 
-    // Figure out actual base path used for all paths below. We escape all glob
-    // patterns in these, as these base paths should not be interpreted as glob
-    // patterns. It would be nicer to give a base path to the glob walker API,
-    // but that's not supported.
+    // Figure out actual base paths used for all paths below.
     let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
         .expect("CARGO_MANIFEST_DIR not set");
     let manifest_dir = Path::new(&manifest_dir);
-    let base = match &config.base_path {
-        Some(base_path) => manifest_dir.join(&base_path),
-        None => PathBuf::from(manifest_dir),
-    };
-    let base_str = base.to_str()
+    let bases = base_paths(manifest_dir, &config.base_path);
+    let base_strs = bases.iter()
+        .map(|base| base.to_str())
+        .collect::<Option<Vec<_>>>()
         .ok_or_else(|| err!("base path or CARGO_MANIFEST_DIR is not valid UTF-8"))?;
-    let escaped_base = glob::Pattern::escape(&base_str);
-    let escaped_base = Path::new(&escaped_base);
+
+    // If a sidecar is used, all file contents are collected into this buffer
+    // instead of being embedded. That's only relevant in prod mode.
+    let mut sidecar = match &config.sidecar {
+        Some((_, span)) if cfg!(not(feature = "sidecar")) => {
+            return Err(err!(@span, "`sidecar` requires the crate feature `sidecar` of reinda"));
+        }
+        Some((path, span)) => Some(Sidecar {
+            path: path.clone(),
+            span: *span,
+            data: Vec::new(),
+        }),
+        None => None,
+    };
 
     let mut stats = Stats::default();
     let mut entries = Vec::new();
-    for (path, span) in &config.files {
-        let utf8_err = || err!(@span, "path is not valid UTF-8");
+    for entry in &config.files {
+        let FileEntry { path, span, except, always_embed } = entry;
+        let size_before = stats.compressed_size;
+        let files_before = stats.embedded_original + stats.embedded_compressed;
 
-        match Globness::check(path) {
-            Globness::NotGlob(unescaped) => {
-                let full_path = base.join(&unescaped).to_str().ok_or_else(utf8_err)?.to_owned();
-                let embed_tokens = embed(&unescaped, span, &full_path, &config, &mut stats)?;
+        match plan(&bases, entry)? {
+            Plan::Single { path: unescaped, full_path } => {
+                let embed_tokens = embed(
+                    &unescaped, span, &full_path, *always_embed, &config, &mut stats, &mut sidecar)?;
 
                 entries.push(quote! {
                     reinda::EmbeddedEntry::Single(
@@ -48,29 +60,12 @@ pub(crate) fn emit(input: Input) -> Result<TokenStream, Error> {
                 });
             }
 
-            Globness::Glob => {
-                // Construct full path.
-                let full_path = escaped_base.join(path).to_str().ok_or_else(utf8_err)?.to_owned();
-
-                // Iterate over all files matching the glob pattern.
-                let glob_walker = glob(&full_path)
-                    .map_err(|e| err!(@span, "invalid glob pattern: {e}"))?;
-                let mut files = Vec::new();
-                for entry in glob_walker {
-                    let file_path = entry
-                        .map_err(|e| err!(@span, "IO error while walking glob paths: {e}"))?;
-                    // skip directories
-                    if file_path.is_dir() {
-                        continue;
-                    }
-                    let short_path = file_path.strip_prefix(&base)
-                        .unwrap_or(&file_path)
-                        .to_str()
-                        .ok_or_else(utf8_err)?;
-                    let file_path = file_path.to_str().ok_or_else(utf8_err)?;
-
+            Plan::Glob(matches) => {
+                let mut files = Vec::with_capacity(matches.len());
+                for (short_path, file_path) in &matches {
                     // Load file the current build mode says so.
-                    let embed_tokens = embed(short_path, span, file_path, &config, &mut stats)?;
+                    let embed_tokens = embed(
+                        short_path, span, file_path, *always_embed, &config, &mut stats, &mut sidecar)?;
 
                     files.push(quote! {
                         reinda::EmbeddedFile {
@@ -83,8 +78,10 @@ pub(crate) fn emit(input: Input) -> Result<TokenStream, Error> {
                 let base_path_tokens = if cfg!(prod_mode) {
                     quote! {}
                 } else {
+                    let except = except.iter().map(|(pattern, _)| pattern);
                     quote! {
-                        base_path: #base_str,
+                        base_paths: &[ #(#base_strs ,)* ],
+                        except: &[ #(#except ,)* ],
                     }
                 };
 
@@ -97,8 +94,21 @@ pub(crate) fn emit(input: Input) -> Result<TokenStream, Error> {
                 });
             }
         }
+
+        stats.per_entry.push((
+            path.clone(),
+            stats.embedded_original + stats.embedded_compressed - files_before,
+            stats.compressed_size - size_before,
+        ));
     }
 
+    #[cfg(prod_mode)]
+    if let Some(sidecar) = sidecar {
+        write_sidecar(manifest_dir, &sidecar)?;
+    }
+    #[cfg(dev_mode)]
+    let _ = sidecar;
+
     if config.print_stats {
         #[cfg(prod_mode)]
         println!(
@@ -110,6 +120,18 @@ pub(crate) fn emit(input: Input) -> Result<TokenStream, Error> {
             ByteSize(stats.uncompressed_size),
         );
 
+        // Attribute the stored bytes to the entries, largest first, to make
+        // it easy to spot globs that accidentally match too many files.
+        #[cfg(prod_mode)]
+        {
+            let mut per_entry = stats.per_entry.iter().collect::<Vec<_>>();
+            per_entry.sort_by_key(|e| std::cmp::Reverse(e.2));
+            println!("[reinda] Embedded size by entry:");
+            for (pattern, files, size) in per_entry {
+                println!("[reinda]   {:>9}  {files:>5} files  '{pattern}'", ByteSize(*size).to_string());
+            }
+        }
+
         #[cfg(dev_mode)]
         println!("[reinda] Summary: in dev mode -> no files embedded");
     }
@@ -123,45 +145,35 @@ pub(crate) fn emit(input: Input) -> Result<TokenStream, Error> {
     })
 }
 
-#[cfg_attr(test, derive(PartialEq, Debug))]
-enum Globness {
-    NotGlob(String),
-    Glob,
+/// A file next to the executable storing the file contents, instead of them
+/// being embedded into the executable.
+#[allow(dead_code)]
+struct Sidecar {
+    /// Path relative to `CARGO_MANIFEST_DIR` at compile time, and relative to
+    /// the executable's directory at runtime.
+    path: String,
+    span: Span,
+    data: Vec<u8>,
 }
 
-impl Globness {
-    fn check(s: &str) -> Self {
-        let mut unescaped = String::new();
-        let mut offset = 0;
-        while let Some(i) = s[offset..].find(&['?', '*', '[', ']']) {
-            // Push the preceeding uninteresting part to the output string.
-            unescaped.push_str(&s[offset..][..i]);
-
-            // We found a meta character. The only way the input string isn't a
-            // glob is if this is a start of a simple escaped meta character.
-            // In that case, we undo the escaping.
-            match () {
-                () if s[offset + i..].starts_with("[?]") => unescaped.push('?'),
-                () if s[offset + i..].starts_with("[*]") => unescaped.push('*'),
-                () if s[offset + i..].starts_with("[]]") => unescaped.push(']'),
-                () if s[offset + i..].starts_with("[[]") => unescaped.push('['),
-                _ => return Self::Glob,
-            }
-
-            // The only way we reach this if we encountered the escaped meta
-            // character, so we advance by 3.
-            offset += i + 3;
-        }
+#[cfg(prod_mode)]
+fn write_sidecar(manifest_dir: &Path, sidecar: &Sidecar) -> Result<(), Error> {
+    let span = &sidecar.span;
+    let path = manifest_dir.join(&sidecar.path);
 
-        // Push the rest.
-        unescaped.push_str(&s[offset..]);
+    // Only write if something changed, to not confuse tools watching the file.
+    if std::fs::read(&path).ok().as_deref() == Some(&sidecar.data) {
+        return Ok(());
+    }
 
-        // We have not encountered meta characters, except simple escapes.
-        Self::NotGlob(unescaped)
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| err!(@span, "could not create '{}': {e}", parent.display()))?;
     }
+    std::fs::write(&path, &sidecar.data)
+        .map_err(|e| err!(@span, "could not write sidecar '{}': {e}", path.display()))
 }
 
-
 #[derive(Default)]
 #[allow(dead_code)]
 struct Stats {
@@ -169,6 +181,9 @@ struct Stats {
     compressed_size: usize,
     embedded_original: u32,
     embedded_compressed: u32,
+
+    /// Embed pattern, number of files and stored size of each entry.
+    per_entry: Vec<(String, u32, usize)>,
 }
 
 #[cfg(dev_mode)]
@@ -176,24 +191,36 @@ fn embed(
     _: &str,
     _: &Span,
     full_path: &str,
+    always_embed: bool,
     _: &EmbedConfig,
     _: &mut Stats,
+    _: &mut Option<Sidecar>,
 ) -> Result<TokenStream, Error> {
+    let content = if always_embed {
+        quote! { Some(include_bytes!(#full_path)) }
+    } else {
+        quote! { None }
+    };
     Ok(quote! {
         full_path: #full_path,
+        content: #content,
     })
 }
 
+/// In prod mode, all files are embedded, so `always_embed` makes no
+/// difference.
 #[cfg(prod_mode)]
 fn embed(
     path: &str,
     span: &Span,
     full_path: &str,
+    _always_embed: bool,
     config: &EmbedConfig,
     stats: &mut Stats,
+    sidecar: &mut Option<Sidecar>,
 ) -> Result<TokenStream, Error> {
     // Read the full file.
-    let data = std::fs::read(&full_path)
+    let data = std::fs::read(full_path)
         .map_err(|e| err!(@span, "could not read '{full_path}': {e}"))?;
     stats.uncompressed_size += data.len();
 
@@ -236,9 +263,48 @@ fn embed(
     }
 
 
-    let content = if let Some(compressed) = &use_compressed_data {
-        stats.compressed_size += compressed.len();
+    let stored = use_compressed_data.as_deref().unwrap_or(&data);
+    let sidecar_tokens = match sidecar {
+        #[cfg(feature = "sidecar")]
+        Some(sidecar) => {
+            use sha2::{Digest, Sha256};
+
+            let offset = sidecar.data.len();
+            let len = stored.len();
+            let sha256 = Sha256::digest(stored).iter()
+                .map(|b| format!("{b:02x}"))
+                .collect::<String>();
+            let sidecar_path = &sidecar.path;
+            sidecar.data.extend_from_slice(stored);
+            quote! {
+                Some(reinda::SidecarLocation {
+                    path: #sidecar_path,
+                    offset: #offset,
+                    len: #len,
+                    sha256: #sha256,
+                })
+            }
+        }
+        _ => quote! { None },
+    };
+
+    stats.compressed_size += stored.len();
+    if use_compressed_data.is_some() {
         stats.embedded_compressed += 1;
+    } else {
+        stats.embedded_original += 1;
+    }
+
+    let content = if sidecar.is_some() {
+        quote! {
+            {
+                // Only to make cargo recompile if the file changes, see below.
+                include_bytes!(#full_path);
+
+                &[]
+            }
+        }
+    } else if let Some(compressed) = &use_compressed_data {
         let lit = proc_macro2::Literal::byte_string(compressed);
         quote! {
             {
@@ -250,8 +316,6 @@ fn embed(
             }
         }
     } else {
-        stats.compressed_size += data.len();
-        stats.embedded_original += 1;
         quote! {
             include_bytes!(#full_path)
         }
@@ -262,6 +326,7 @@ fn embed(
     Ok(quote! {
         content: #content,
         compressed: #compressed,
+        sidecar: #sidecar_tokens,
     })
 }
 
@@ -280,22 +345,3 @@ impl std::fmt::Display for ByteSize {
         }
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::Globness;
-
-    #[test]
-    fn glob_classification() {
-        assert_eq!(Globness::check("foo.txt"), Globness::NotGlob("foo.txt".into()));
-        assert_eq!(Globness::check("bar/foo.txt"), Globness::NotGlob("bar/foo.txt".into()));
-        assert_eq!(Globness::check("fo[?]x.svg"), Globness::NotGlob("fo?x.svg".into()));
-        assert_eq!(Globness::check("fo[*]x.svg"), Globness::NotGlob("fo*x.svg".into()));
-        assert_eq!(Globness::check("fo[]]x.svg"), Globness::NotGlob("fo]x.svg".into()));
-        assert_eq!(Globness::check("fo[[]x.svg"), Globness::NotGlob("fo[x.svg".into()));
-
-        assert_eq!(Globness::check("fo*x.svg"), Globness::Glob);
-        assert_eq!(Globness::check("fo?x.svg"), Globness::Glob);
-        assert_eq!(Globness::check("fo[ab]x.svg"), Globness::Glob);
-    }
-}
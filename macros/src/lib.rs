@@ -1,14 +1,8 @@
 use proc_macro::TokenStream as TokenStream1;
 
-use self::{
-    err::{Error, err},
-    ast::{EmbedConfig, Input},
-};
+use reinda_shared::parse;
 
 mod emit;
-mod err;
-mod ast;
-mod parse;
 
 
 // See documentation in the main crate.
@@ -0,0 +1,53 @@
+//! Types to describe the abstract parsed input.
+
+use proc_macro2::Span;
+
+
+#[derive(Debug)]
+pub struct Input {
+    pub base_path: Vec<String>,
+    pub compression_threshold: Option<f32>,
+    pub compression_quality: Option<u8>,
+    pub print_stats: Option<bool>,
+    pub sidecar: Option<(String, Span)>,
+    pub files: Vec<FileEntry>,
+}
+
+/// One entry in the `files` array: a path or glob pattern, optionally
+/// followed by `except` and glob patterns of files to skip and/or
+/// `always_embed`.
+#[derive(Debug)]
+pub struct FileEntry {
+    pub path: String,
+    pub span: Span,
+    pub except: Vec<(String, Span)>,
+
+    /// Embed the files even in dev mode.
+    pub always_embed: bool,
+}
+
+impl Input {
+    pub fn with_defaults(self) -> EmbedConfig {
+        EmbedConfig {
+            base_path: self.base_path,
+            compression_threshold: self.compression_threshold.unwrap_or(0.85),
+            compression_quality: self.compression_quality.unwrap_or(9),
+            print_stats: self.print_stats.unwrap_or(false),
+            sidecar: self.sidecar,
+            files: self.files,
+        }
+    }
+}
+
+pub struct EmbedConfig {
+    /// Base paths searched in order. Empty if unspecified.
+    pub base_path: Vec<String>,
+    #[allow(dead_code)]
+    pub compression_threshold: f32,
+    #[allow(dead_code)]
+    pub compression_quality: u8,
+    pub print_stats: bool,
+    #[allow(dead_code)]
+    pub sidecar: Option<(String, Span)>,
+    pub files: Vec<FileEntry>,
+}
@@ -2,19 +2,22 @@ use proc_macro2::{Span, TokenStream};
 use quote::quote_spanned;
 
 
-pub(crate) struct Error {
-    pub(crate) msg: String,
-    pub(crate) span: Option<Span>,
+pub struct Error {
+    pub msg: String,
+    pub span: Option<Span>,
 }
 
 impl Error {
-    pub(crate) fn to_compile_error(&self) -> TokenStream {
+    pub fn to_compile_error(&self) -> TokenStream {
         let span = self.span.unwrap_or(Span::call_site());
         let msg = &self.msg;
         quote_spanned! {span=> compile_error!(#msg);}
     }
 }
 
+/// Creates an `Error` with a `format!` message, optionally with a span
+/// (`err!(@span, "...")`).
+#[macro_export]
 macro_rules! err {
     (@$span:expr, $fmt:literal $($t:tt)*) => {
         $crate::err::Error {
@@ -30,4 +33,4 @@ macro_rules! err {
     };
 }
 
-pub(crate) use err;
+pub use crate::err;
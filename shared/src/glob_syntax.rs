@@ -0,0 +1,150 @@
+//! Extended glob syntax of `embed!`, translated to plain patterns of the
+//! `glob` crate.
+
+
+/// Expands a pattern with extended syntax to plain `glob` patterns:
+///
+/// - `{a,b}` alternatives are expanded, e.g. `*.{js,css}` to `*.js` and
+///   `*.css`. They can be nested and empty (`app{,.min}.js`). Braces inside
+///   character classes (e.g. `[{]`) are literal.
+/// - A segment starting with `**` and followed by more characters is split,
+///   e.g. `**.js` becomes `**/*.js`.
+///
+/// Duplicates are removed, the order is kept. Returns an error message for
+/// unsupported syntax.
+pub fn expand(pattern: &str) -> Result<Vec<String>, String> {
+    let mut out = Vec::new();
+    for expanded in expand_braces(pattern)? {
+        let normalized = normalize_recursive(&expanded)?;
+        if !out.contains(&normalized) {
+            out.push(normalized);
+        }
+    }
+    Ok(out)
+}
+
+fn expand_braces(pattern: &str) -> Result<Vec<String>, String> {
+    let Some(braces) = find_braces(pattern)? else {
+        return Ok(vec![pattern.to_owned()]);
+    };
+
+    let (before, after) = (&pattern[..braces.start], &pattern[braces.end + 1..]);
+    let mut out = Vec::new();
+    for alternative in braces.alternatives {
+        let candidate = format!("{before}{alternative}{after}");
+        out.extend(expand_braces(&candidate)?);
+    }
+    Ok(out)
+}
+
+/// A top-level `{...}` group in a pattern.
+struct Braces<'a> {
+    /// Index of `{`.
+    start: usize,
+    /// Index of the matching `}`.
+    end: usize,
+    alternatives: Vec<&'a str>,
+}
+
+/// Finds the first top-level `{...}` group.
+fn find_braces(pattern: &str) -> Result<Option<Braces<'_>>, String> {
+    let bytes = pattern.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'[' => i = skip_class(bytes, i),
+            b'}' => return Err(format!("unmatched `}}` at offset {i} in '{pattern}'")),
+            b'{' => {
+                let start = i;
+                let mut depth = 0;
+                let mut alternatives = Vec::new();
+                let mut alt_start = start + 1;
+                while i < bytes.len() {
+                    match bytes[i] {
+                        b'[' => {
+                            i = skip_class(bytes, i);
+                            continue;
+                        }
+                        b'{' => depth += 1,
+                        b'}' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                alternatives.push(&pattern[alt_start..i]);
+                                return Ok(Some(Braces { start, end: i, alternatives }));
+                            }
+                        }
+                        b',' if depth == 1 => {
+                            alternatives.push(&pattern[alt_start..i]);
+                            alt_start = i + 1;
+                        }
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                return Err(format!("unclosed `{{` at offset {start} in '{pattern}'"));
+            }
+            _ => i += 1,
+        }
+    }
+    Ok(None)
+}
+
+/// Returns the index after the character class starting at `start`. A `]`
+/// directly after `[` or `[!` is part of the class. Unclosed classes are
+/// left to the `glob` crate to report.
+fn skip_class(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    if bytes.get(i) == Some(&b'!') {
+        i += 1;
+    }
+    if bytes.get(i) == Some(&b']') {
+        i += 1;
+    }
+    match bytes[i.min(bytes.len())..].iter().position(|&b| b == b']') {
+        Some(pos) => i + pos + 1,
+        None => start + 1,
+    }
+}
+
+/// Splits segments like `**.js` into `**/*.js` and rejects other segments
+/// containing `**`, which the `glob` crate does not support.
+fn normalize_recursive(pattern: &str) -> Result<String, String> {
+    let segments = pattern.split('/').map(|segment| {
+        match segment.strip_prefix("**") {
+            _ if segment == "**" || !segment.contains("**") => Ok(segment.to_owned()),
+            Some(rest) if !rest.contains("**") && !rest.starts_with('*') => Ok(format!("**/*{rest}")),
+            _ => Err(format!(
+                "`**` has to be a whole path segment or start one (like in `**/*.js` \
+                    or `**.js`), but '{pattern}' contains segment '{segment}'",
+            )),
+        }
+    });
+    Ok(segments.collect::<Result<Vec<_>, _>>()?.join("/"))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn braces() {
+        assert_eq!(expand("*.{js,css}").unwrap(), ["*.js", "*.css"]);
+        assert_eq!(expand("app{,.min}.js").unwrap(), ["app.js", "app.min.js"]);
+        assert_eq!(expand("{a,b/{c,d}}/*.txt").unwrap(), ["a/*.txt", "b/c/*.txt", "b/d/*.txt"]);
+        assert_eq!(expand("{a,a}.txt").unwrap(), ["a.txt"]);
+        assert_eq!(expand("[{]a,b[}].txt").unwrap(), ["[{]a,b[}].txt"]);
+        assert!(expand("*.{js,css").is_err());
+        assert!(expand("*.js}").is_err());
+    }
+
+    #[test]
+    fn recursive_wildcards() {
+        assert_eq!(expand("assets/**.js").unwrap(), ["assets/**/*.js"]);
+        assert_eq!(expand("**/*.js").unwrap(), ["**/*.js"]);
+        assert_eq!(expand("**.{js,css}").unwrap(), ["**/*.js", "**/*.css"]);
+        assert!(expand("assets/a**.js").is_err());
+        assert!(expand("assets/**.**").is_err());
+        assert!(expand("***.js").is_err());
+    }
+}
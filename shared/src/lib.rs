@@ -0,0 +1,17 @@
+//! Code shared by `reinda`, `reinda-macros` and `cargo-reinda`, so that all
+//! three interpret `embed!` inputs the same way. Do not use this crate
+//! directly, it has no stable API!
+//!
+//! Without the crate feature `parse`, only `glob_syntax` is available, which
+//! `reinda` needs at runtime. The other modules parse the macro input and
+//! find the files it refers to.
+
+#[cfg(feature = "parse")]
+pub mod ast;
+#[cfg(feature = "parse")]
+pub mod err;
+pub mod glob_syntax;
+#[cfg(feature = "parse")]
+pub mod parse;
+#[cfg(feature = "parse")]
+pub mod plan;
@@ -0,0 +1,183 @@
+use std::{convert::TryFrom, iter::Peekable};
+use proc_macro2::{token_stream::IntoIter, Delimiter, Span, TokenStream, TokenTree};
+
+use crate::{err::{err, Error}, ast::{FileEntry, Input}};
+
+
+pub fn parse(tokens: TokenStream) -> Result<Input, Error> {
+    let mut base_path = vec![];
+    let mut files = None;
+    let mut compression_threshold = None;
+    let mut compression_quality = None;
+    let mut print_stats = None;
+    let mut sidecar = None;
+
+    let mut it = tokens.into_iter().peekable();
+
+    while it.peek().is_some() {
+        // Parse field name.
+        let field_name = match it.next().unwrap() {
+            TokenTree::Ident(i) => i,
+            other => return Err(err!(@other.span(), "expected identifier, found something else")),
+        };
+
+        // Parse `:`
+        match it.next().ok_or_else(unexpected_end_of_input)? {
+            TokenTree::Punct(p) if p.as_char() == ':' => {}
+            other => return Err(err!(@other.span(), "expected `:`, found something else")),
+        }
+
+        // Parse value.
+        match field_name.to_string().as_str() {
+            "base_path" => {
+                base_path = match it.peek() {
+                    Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Bracket => {
+                        let span = g.span();
+                        let paths = parse_string_array(&mut it)?;
+                        if paths.is_empty() {
+                            return Err(err!(@span, "`base_path` array must not be empty"));
+                        }
+                        paths.into_iter().map(|(path, _)| path).collect()
+                    }
+                    _ => vec![parse_string_lit(&mut it)?],
+                };
+            }
+
+            "sidecar" => {
+                let span = it.peek().map(|t| t.span()).unwrap_or(field_name.span());
+                sidecar = Some((parse_string_lit(&mut it)?, span));
+            }
+
+            "print_stats" => {
+                print_stats = Some(parse_lit::<litrs::BoolLit>(&mut it)?.value());
+            }
+
+            "compression_threshold" => {
+                let lit = parse_lit::<litrs::FloatLit<String>>(&mut it)?;
+                let value = lit.number_part().parse()
+                    .map_err(|e| err!("failed to parse compression_threshold: {e}"))?;
+                compression_threshold = Some(value);
+            }
+
+            "compression_quality" => {
+                let lit = parse_lit::<litrs::IntegerLit<String>>(&mut it)?;
+                let value = lit.value::<u8>()
+                    .ok_or_else(|| err!("compression quality too large"))?;
+                compression_quality = Some(value);
+            }
+
+            "files" => {
+                files = Some(parse_files(&mut it)?);
+            }
+
+            other => return Err(err!(@field_name.span(), "unknown field name '{other}'")),
+        }
+
+        eat_comma_sep(&mut it)?;
+    }
+
+    Ok(Input {
+        base_path,
+        print_stats,
+        compression_threshold,
+        compression_quality,
+        sidecar,
+        files: files.ok_or_else(|| err!("missing field 'files' in input"))?,
+    })
+}
+
+fn unexpected_end_of_input() -> Error {
+    err!("unexpected end of input")
+}
+
+type ParseIter = Peekable<IntoIter>;
+
+fn eat_comma_sep(it: &mut ParseIter) -> Result<(), Error> {
+    match it.next() {
+        None => Ok(()),
+        Some(TokenTree::Punct(p)) if p.as_char() == ',' => Ok(()),
+        Some(other) => Err(err!(@other.span(), "expected comma or end of input")),
+    }
+}
+
+/// Parses an array of string literals `[...]`, returning each value with its
+/// span.
+fn parse_string_array(it: &mut ParseIter) -> Result<Vec<(String, Span)>, Error> {
+    let inner = match it.next().ok_or_else(unexpected_end_of_input)? {
+        TokenTree::Group(g) if g.delimiter() == Delimiter::Bracket => g.stream(),
+        other => return Err(err!(@other.span(), "expected string array `[...]`")),
+    };
+
+    let mut inner_it = inner.into_iter().peekable();
+    let mut values = vec![];
+    while inner_it.peek().is_some() {
+        let span = inner_it.peek().unwrap().span();
+        let value = parse_string_lit(&mut inner_it)?;
+        values.push((value, span));
+        eat_comma_sep(&mut inner_it)?;
+    }
+    Ok(values)
+}
+
+/// Parses the `files` array: string literals, each optionally followed by
+/// `except` and a string literal or array of string literals, and by
+/// `always_embed`, in any order.
+fn parse_files(it: &mut ParseIter) -> Result<Vec<FileEntry>, Error> {
+    let inner = match it.next().ok_or_else(unexpected_end_of_input)? {
+        TokenTree::Group(g) if g.delimiter() == Delimiter::Bracket => g.stream(),
+        other => return Err(err!(@other.span(), "expected string array `[...]`")),
+    };
+
+    let mut inner_it = inner.into_iter().peekable();
+    let mut entries = vec![];
+    while inner_it.peek().is_some() {
+        let span = inner_it.peek().unwrap().span();
+        let path = parse_string_lit(&mut inner_it)?;
+        let mut entry = FileEntry { path, span, except: vec![], always_embed: false };
+        let mut seen_except = false;
+        while let Some(TokenTree::Ident(i)) = inner_it.peek() {
+            let (ident, ident_span) = (i.to_string(), i.span());
+            match &*ident {
+                "except" if !seen_except => {
+                    seen_except = true;
+                    inner_it.next();
+                    entry.except = match inner_it.peek() {
+                        Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Bracket => {
+                            parse_string_array(&mut inner_it)?
+                        }
+                        Some(t) => {
+                            let span = t.span();
+                            vec![(parse_string_lit(&mut inner_it)?, span)]
+                        }
+                        None => return Err(unexpected_end_of_input()),
+                    };
+                }
+                "always_embed" if !entry.always_embed => {
+                    inner_it.next();
+                    entry.always_embed = true;
+                }
+                "except" | "always_embed" => {
+                    return Err(err!(@ident_span, "duplicate `{ident}`"));
+                }
+                _ => return Err(err!(@ident_span, "expected `except`, `always_embed` or `,`")),
+            }
+        }
+        entries.push(entry);
+        eat_comma_sep(&mut inner_it)?;
+    }
+    Ok(entries)
+}
+
+fn parse_string_lit(it: &mut ParseIter) -> Result<String, Error> {
+    parse_lit::<litrs::StringLit<String>>(it).map(|l| l.into_value().into_owned())
+}
+
+fn parse_lit<T>(it: &mut ParseIter) -> Result<T, Error>
+where
+    T: TryFrom<TokenTree>,
+    T::Error: std::fmt::Display,
+{
+    let token = it.next().ok_or_else(unexpected_end_of_input)?;
+    let span = token.span();
+    T::try_from(token).map_err(|e| err!(@span, "{e}"))
+}
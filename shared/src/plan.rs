@@ -0,0 +1,175 @@
+//! Finding the files an entry of `embed!` refers to.
+
+use std::{collections::HashSet, path::{Path, PathBuf}};
+use proc_macro2::Span;
+
+use crate::{ast::FileEntry, err, err::Error};
+
+
+/// Returns the base paths to search files in, in order: the `base_path`
+/// values relative to `manifest_dir` or, if there are none, `manifest_dir`
+/// itself.
+pub fn base_paths(manifest_dir: &Path, base_path: &[String]) -> Vec<PathBuf> {
+    match base_path.is_empty() {
+        true => vec![manifest_dir.to_owned()],
+        false => base_path.iter().map(|p| manifest_dir.join(p)).collect(),
+    }
+}
+
+/// The files an entry of `embed!` refers to.
+#[derive(Debug)]
+pub enum Plan {
+    /// A path without glob meta characters, after undoing simple escapes
+    /// like `[*]`. The full path is in the first base path containing the
+    /// file. If none does, it's in the first base path and does not exist.
+    Single { path: String, full_path: String },
+
+    /// All files matching a glob pattern (or one of its expansions) as
+    /// `(short path, full path)`, sorted by short path.
+    Glob(Vec<(String, String)>),
+}
+
+/// Finds the files `entry` refers to in `bases`.
+pub fn plan(bases: &[PathBuf], entry: &FileEntry) -> Result<Plan, Error> {
+    let FileEntry { path, span, except, .. } = entry;
+    let utf8_err = || err!(@span, "path is not valid UTF-8");
+
+    match Globness::check(path) {
+        Globness::NotGlob(_) if !except.is_empty() => {
+            Err(err!(@except[0].1, "`except` can only be used with glob patterns"))
+        }
+
+        Globness::NotGlob(unescaped) => {
+            // The first base path containing the file is used. If none
+            // does, the first one is used, leading to an error later.
+            let base = bases.iter()
+                .find(|base| base.join(&unescaped).exists())
+                .unwrap_or(&bases[0]);
+            let full_path = base.join(&unescaped).to_str().ok_or_else(utf8_err)?.to_owned();
+            Ok(Plan::Single { path: unescaped, full_path })
+        }
+
+        Globness::Glob => {
+            let expand = |pattern: &str, span: &Span| crate::glob_syntax::expand(pattern)
+                .map_err(|msg| err!(@span, "unsupported glob pattern: {msg}"));
+            let patterns = expand(path, span)?;
+            let mut excluded = Vec::new();
+            for (pattern, span) in except {
+                for expanded in expand(pattern, span)? {
+                    excluded.push(glob::Pattern::new(&expanded)
+                        .map_err(|e| err!(@span, "invalid glob pattern: {e}"))?);
+                }
+            }
+
+            // Iterate over all files matching the glob pattern (or one of
+            // its brace expansions) in all base paths. If a file exists
+            // in multiple base paths, the first one wins. Files matching
+            // an `except` pattern are skipped.
+            let mut seen = HashSet::new();
+            let mut matches = Vec::new();
+            let searches = bases.iter()
+                .flat_map(|base| patterns.iter().map(move |pattern| (base, pattern)));
+            for (base, pattern) in searches {
+                // We escape all glob patterns in the base path, as it should
+                // not be interpreted as glob pattern. It would be nicer to
+                // give a base path to the glob walker API, but that's not
+                // supported.
+                let base_str = base.to_str()
+                    .ok_or_else(|| err!("base path or CARGO_MANIFEST_DIR is not valid UTF-8"))?;
+                let full_path = Path::new(&glob::Pattern::escape(base_str))
+                    .join(pattern)
+                    .to_str()
+                    .ok_or_else(utf8_err)?
+                    .to_owned();
+
+                let glob_walker = glob::glob(&full_path)
+                    .map_err(|e| err!(@span, "invalid glob pattern: {e}"))?;
+                for entry in glob_walker {
+                    let file_path = entry
+                        .map_err(|e| err!(@span, "IO error while walking glob paths: {e}"))?;
+                    // skip directories
+                    if file_path.is_dir() {
+                        continue;
+                    }
+                    let short_path = file_path.strip_prefix(base)
+                        .unwrap_or(&file_path)
+                        .to_str()
+                        .ok_or_else(utf8_err)?;
+                    if excluded.iter().any(|p| p.matches(short_path)) {
+                        continue;
+                    }
+                    if !seen.insert(short_path.to_owned()) {
+                        continue;
+                    }
+                    let file_path = file_path.to_str().ok_or_else(utf8_err)?;
+                    matches.push((short_path.to_owned(), file_path.to_owned()));
+                }
+            }
+
+            // Files are sorted by path (byte-wise), so that the order
+            // does not depend on the file system or base path order.
+            matches.sort();
+            Ok(Plan::Glob(matches))
+        }
+    }
+}
+
+#[cfg_attr(test, derive(PartialEq, Debug))]
+enum Globness {
+    NotGlob(String),
+    Glob,
+}
+
+impl Globness {
+    fn check(s: &str) -> Self {
+        let mut unescaped = String::new();
+        let mut offset = 0;
+        while let Some(i) = s[offset..].find(['?', '*', '[', ']', '{', '}']) {
+            // Push the preceeding uninteresting part to the output string.
+            unescaped.push_str(&s[offset..][..i]);
+
+            // We found a meta character. The only way the input string isn't a
+            // glob is if this is a start of a simple escaped meta character.
+            // In that case, we undo the escaping.
+            match () {
+                () if s[offset + i..].starts_with("[?]") => unescaped.push('?'),
+                () if s[offset + i..].starts_with("[*]") => unescaped.push('*'),
+                () if s[offset + i..].starts_with("[]]") => unescaped.push(']'),
+                () if s[offset + i..].starts_with("[[]") => unescaped.push('['),
+                () if s[offset + i..].starts_with("[{]") => unescaped.push('{'),
+                () if s[offset + i..].starts_with("[}]") => unescaped.push('}'),
+                _ => return Self::Glob,
+            }
+
+            // The only way we reach this if we encountered the escaped meta
+            // character, so we advance by 3.
+            offset += i + 3;
+        }
+
+        // Push the rest.
+        unescaped.push_str(&s[offset..]);
+
+        // We have not encountered meta characters, except simple escapes.
+        Self::NotGlob(unescaped)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Globness;
+
+    #[test]
+    fn glob_classification() {
+        assert_eq!(Globness::check("foo.txt"), Globness::NotGlob("foo.txt".into()));
+        assert_eq!(Globness::check("bar/foo.txt"), Globness::NotGlob("bar/foo.txt".into()));
+        assert_eq!(Globness::check("fo[?]x.svg"), Globness::NotGlob("fo?x.svg".into()));
+        assert_eq!(Globness::check("fo[*]x.svg"), Globness::NotGlob("fo*x.svg".into()));
+        assert_eq!(Globness::check("fo[]]x.svg"), Globness::NotGlob("fo]x.svg".into()));
+        assert_eq!(Globness::check("fo[[]x.svg"), Globness::NotGlob("fo[x.svg".into()));
+
+        assert_eq!(Globness::check("fo*x.svg"), Globness::Glob);
+        assert_eq!(Globness::check("fo?x.svg"), Globness::Glob);
+        assert_eq!(Globness::check("fo[ab]x.svg"), Globness::Glob);
+    }
+}
@@ -0,0 +1,124 @@
+//! Integration with [actix-web](https://docs.rs/actix-web).
+//!
+//! [`Assets::into_actix_service`] returns a route serving all assets via
+//! [`Assets::serve`]. It's meant to be used as default service, so that the
+//! app's own routes take precedence:
+//!
+//! ```ignore
+//! let assets = my_app::assets().await?;
+//! HttpServer::new(move || {
+//!     App::new()
+//!         .route("/api/user", web::get().to(user))
+//!         .default_service(assets.clone().into_actix_service())
+//! })
+//! ```
+//!
+//! It can also be used inside a `web::scope`, in which case the scope's
+//! prefix is removed from the path before looking up the asset.
+//!
+//! Module is only available if the crate feature `actix` is enabled.
+
+use std::{convert::Infallible, pin::Pin, task::{Context, Poll}};
+
+use actix_web::{
+    body::{BodySize, BoxBody, MessageBody},
+    http::{
+        header::{HeaderName, HeaderValue, CONTENT_ENCODING},
+        Method, StatusCode,
+    },
+    web::{self, Bytes},
+    HttpRequest, HttpResponse, Responder, Route,
+};
+
+use crate::{http, Assets};
+
+
+impl Assets {
+    /// Returns an actix-web route answering all requests with
+    /// [`Assets::serve`], i.e. with the correct `Content-Type` and
+    /// `Cache-Control` headers, and a `404 Not Found` for paths without
+    /// asset. See the [module docs][crate::actix] for how to mount it.
+    ///
+    /// Method is only available if the crate feature `actix` is enabled.
+    pub fn into_actix_service(self) -> Route {
+        web::route().to(move |req: HttpRequest| {
+            let assets = self.clone();
+            async move { serve(&assets, &req).await }
+        })
+    }
+}
+
+/// Answers the given actix-web request with [`Assets::serve`]. Useful in
+/// custom handlers, e.g. to only serve assets to authenticated users. The
+/// path not yet matched by a scope is used, so the scope's prefix is
+/// removed.
+pub async fn serve(assets: &Assets, req: &HttpRequest) -> http::Response {
+    let mut path = req.match_info().unprocessed().to_owned();
+    if !req.query_string().is_empty() {
+        path.push('?');
+        path.push_str(req.query_string());
+    }
+
+    let mut request = http::Request::new(req.method().as_str(), &path);
+    for (name, value) in req.headers() {
+        if let Ok(value) = value.to_str() {
+            request = request.with_header(name.as_str(), value);
+        }
+    }
+
+    assets.serve(&request).await
+}
+
+/// Responses that are not [compressible][http::Response::is_compressible]
+/// get `Content-Encoding: identity`, which makes actix-web's `Compress`
+/// middleware skip them.
+impl Responder for http::Response {
+    type Body = BoxBody;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse {
+        let compressible = self.is_compressible();
+        let keep_length = req.method() == Method::HEAD && self.header("content-length").is_some();
+        let (status, headers, body) = self.into_parts();
+        let status = StatusCode::from_u16(status)
+            .expect("invalid status code created by `Assets::serve`");
+        let mut response = if keep_length {
+            let mut response = HttpResponse::with_body(status, HeadBody.boxed());
+            response.head_mut().no_chunking(true);
+            response
+        } else {
+            HttpResponse::with_body(status, body).map_into_boxed_body()
+        };
+        for (name, value) in headers {
+            let name = HeaderName::from_bytes(name.as_bytes());
+            let value = HeaderValue::from_str(&value);
+            match (name, value) {
+                (Ok(name), Ok(value)) => response.headers_mut().append(name, value),
+                _ => log::warn!(target: "reinda", "invalid header created by `Assets::serve`"),
+            }
+        }
+        if !compressible && !response.headers().contains_key(CONTENT_ENCODING) {
+            response.headers_mut().insert(CONTENT_ENCODING, HeaderValue::from_static("identity"));
+        }
+        response
+    }
+}
+
+/// Body of `HEAD` responses. actix-web replaces the `Content-Length` set by
+/// [`Assets::serve`] with the length of bodies of known size, i.e. with `0`,
+/// so this body reports an unknown size instead.
+struct HeadBody;
+
+impl MessageBody for HeadBody {
+    type Error = Infallible;
+
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        Poll::Ready(None)
+    }
+}
@@ -0,0 +1,91 @@
+//! Integration with [axum](https://docs.rs/axum).
+//!
+//! [`Assets::into_axum_router`] returns a router serving all assets via
+//! [`Assets::serve`]. It only has a fallback handler, so it can be merged
+//! into an app whose own routes take precedence:
+//!
+//! ```ignore
+//! let app = axum::Router::new()
+//!     .route("/api/user", get(user))
+//!     .merge(assets.into_axum_router());
+//! ```
+//!
+//! Or, to serve the assets under a path prefix:
+//!
+//! ```ignore
+//! let app = axum::Router::new().nest_service("/static", assets.into_axum_router());
+//! ```
+//!
+//! Module is only available if the crate feature `axum` is enabled.
+
+use ::axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header::{HeaderName, CONTENT_ENCODING}, HeaderValue, StatusCode},
+    response::IntoResponse,
+    Router,
+};
+
+use crate::{http, Assets};
+
+
+impl Assets {
+    /// Returns an axum router answering all requests with [`Assets::serve`],
+    /// i.e. with the correct `Content-Type` and `Cache-Control` headers, and
+    /// a `404 Not Found` for paths without asset. See the [module
+    /// docs][crate::axum] for how to mount it.
+    ///
+    /// Method is only available if the crate feature `axum` is enabled.
+    pub fn into_axum_router<S>(self) -> Router<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        Router::new().fallback(handler).with_state(self)
+    }
+}
+
+async fn handler(State(assets): State<Assets>, req: Request) -> ::axum::response::Response {
+    // The body is not `Sync`, so it's dropped before awaiting.
+    let (parts, _) = req.into_parts();
+    serve(&assets, &Request::from_parts(parts, ())).await.into_response()
+}
+
+/// Answers the given axum request with [`Assets::serve`]. Useful in custom
+/// handlers, e.g. to only serve assets to authenticated users. The body of
+/// the request is ignored.
+pub async fn serve<B>(assets: &Assets, req: &::axum::http::Request<B>) -> http::Response {
+    let path = req.uri().path_and_query().map_or("/", |pq| pq.as_str());
+    let mut request = http::Request::new(req.method().as_str(), path);
+    for (name, value) in req.headers() {
+        if let Ok(value) = value.to_str() {
+            request = request.with_header(name.as_str(), value);
+        }
+    }
+
+    assets.serve(&request).await
+}
+
+/// Responses that are not [compressible][http::Response::is_compressible]
+/// get `Content-Encoding: identity`, which makes compression middlewares
+/// (like the one of `tower-http`) skip them.
+impl IntoResponse for http::Response {
+    fn into_response(self) -> ::axum::response::Response {
+        let compressible = self.is_compressible();
+        let (status, headers, body) = self.into_parts();
+        let mut response = ::axum::response::Response::new(Body::from(body));
+        *response.status_mut() = StatusCode::from_u16(status)
+            .expect("invalid status code created by `Assets::serve`");
+        for (name, value) in headers {
+            let name = HeaderName::from_bytes(name.as_bytes());
+            let value = HeaderValue::from_str(&value);
+            match (name, value) {
+                (Ok(name), Ok(value)) => { response.headers_mut().append(name, value); }
+                _ => log::warn!(target: "reinda", "invalid header created by `Assets::serve`"),
+            }
+        }
+        if !compressible && !response.headers().contains_key(CONTENT_ENCODING) {
+            response.headers_mut().insert(CONTENT_ENCODING, HeaderValue::from_static("identity"));
+        }
+        response
+    }
+}
@@ -1,33 +1,131 @@
 use std::{
     borrow::Cow,
+    collections::{BTreeMap, HashSet},
+    fmt,
+    future::Future,
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use bytes::Bytes;
 
+#[cfg(feature = "compress")]
+use crate::CompressionConfig;
 use crate::{
-    Assets, BuildError, DataSource, EmbeddedEntry, EmbeddedFile, EmbeddedGlob, Modifier,
-    ModifierContext, PathHash, SplitGlob,
+    http::{HttpConfig, Request, ServeSettings, SourceMapPolicy, Variant},
+    pipeline::{Pipeline, Steps},
+    post_build::{BuildView, PostBuildHooks},
+    template::TemplateSettings,
+    dir::{DirFile, PathFilter},
+    transform::{Snippet, TransformSettings},
+    Assets, BuildError, ChangeDetection, DataSource, EmbeddedEntry, EmbeddedFile, EmbeddedGlob,
+    Generator, Modifier, ModifierContext, ModifierEnv, ModifierError, ModifierInfo, Modifiers,
+    PathHash, SplitGlob,
 };
 
 /// Helper to build [`Assets`].
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Builder<'a> {
     pub(crate) assets: Vec<EntryBuilder<'a>>,
+    #[cfg(feature = "compress")]
+    pub(crate) compression: Option<CompressionConfig>,
+    pub(crate) http: HttpConfig,
+    pub(crate) profile: Option<String>,
+    pub(crate) public_url: Option<Arc<str>>,
+    pub(crate) normalize_text: bool,
+    pub(crate) banner: Option<Arc<str>>,
+    pub(crate) warn_unreferenced: bool,
+    pub(crate) hot_cache: Option<usize>,
+    pub(crate) change_detection: Option<ChangeDetection>,
+    pub(crate) transform_timeout: Option<Duration>,
+    pub(crate) vars: BTreeMap<String, String>,
+    pub(crate) plugins: Steps,
+    pub(crate) post_build: PostBuildHooks,
+    pub(crate) renamed: Vec<(String, String)>,
+
+    /// Pattern and path of embedded glob files not starting with the fixed
+    /// prefix of the glob, reported by `Builder::build`.
+    pub(crate) invalid_glob_files: Vec<(&'static str, &'static str)>,
+    #[cfg_attr(prod_mode, allow(dead_code))]
+    pub(crate) base_path_env_var: Option<Cow<'static, str>>,
+    pub(crate) defaults: EntryDefaults<'a>,
+}
+
+/// Functions configuring new entries of a media type, see
+/// `Builder::defaults_for`.
+#[derive(Clone, Default)]
+pub(crate) struct EntryDefaults<'a>(Vec<(Cow<'static, str>, EntryDefaultsFn<'a>)>);
+
+type EntryDefaultsFn<'a> = Arc<dyn Send + Sync + Fn(&mut EntryBuilder<'a>)>;
+
+impl fmt::Debug for EntryDefaults<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.0.iter().map(|(media_type, _)| media_type)).finish()
+    }
+}
+
+/// Returns whether `media_type` (possibly with parameters) matches `pattern`,
+/// which is a media type without parameters or `type/*`.
+fn media_type_matches(pattern: &str, media_type: &str) -> bool {
+    let essence = media_type.split(';').next().unwrap_or(media_type).trim();
+    match pattern.strip_suffix("/*") {
+        Some(ty) => essence.split('/').next().is_some_and(|t| t.eq_ignore_ascii_case(ty)),
+        None => essence.eq_ignore_ascii_case(pattern),
+    }
 }
 
+/// Name of the environment variable that selects the active profile, see
+/// [`Builder::profile`].
+pub const PROFILE_ENV_VAR: &str = "REINDA_PROFILE";
+
+/// Name of the environment variable overriding the base paths of `embed!` in
+/// dev mode, see [`Builder::set_base_path_env_var`].
+pub const BASE_PATH_ENV_VAR: &str = "REINDA_BASE_PATH";
+
 /// Returned by the various `Builder::add_*` functions, allowing you to
 /// configure added assets.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct EntryBuilder<'a> {
     pub(crate) kind: EntryBuilderKind<'a>,
     #[cfg_attr(not(feature = "hash"), allow(dead_code))]
     pub(crate) path_hash: PathHash<'a>,
-    pub(crate) modifier: Modifier,
+    pub(crate) modifiers: Modifiers,
+    pub(crate) template: Option<TemplateSettings>,
+
+    /// Variables overriding the ones of the builder for this entry, see
+    /// `Self::add_var`.
+    pub(crate) vars: BTreeMap<String, String>,
+    pub(crate) internal: bool,
+    pub(crate) optional: bool,
+    pub(crate) verbatim: bool,
+    pub(crate) lazy: bool,
+    pub(crate) serve: ServeSettings,
+    pub(crate) transform: TransformSettings,
+
+    /// All steps registered via `Builder::register_plugin`, set in
+    /// `Builder::build`. Filtered per asset when processing it.
+    pub(crate) steps: Steps,
+
+    /// Variables and public URL for modifiers and steps, set in
+    /// `Builder::build`.
+    pub(crate) env: Arc<ModifierEnv>,
+
+    /// See `Self::with_transform_timeout`, defaults to the one of the builder.
+    pub(crate) transform_timeout: Option<Duration>,
+
+    /// See `Self::with_extra_watch`.
+    #[cfg_attr(prod_mode, allow(dead_code))]
+    pub(crate) extra_watch: Vec<PathBuf>,
+    #[cfg(feature = "compress")]
+    pub(crate) compression: Option<CompressionConfig>,
+
+    /// Embed pattern and number of bytes in the executable, for embedded
+    /// entries.
+    pub(crate) embedded: Option<(&'static str, usize)>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) enum EntryBuilderKind<'a> {
     Single {
         http_path: Cow<'a, str>,
@@ -38,12 +136,36 @@ pub(crate) enum EntryBuilderKind<'a> {
         #[cfg_attr(prod_mode, allow(dead_code))]
         glob: SplitGlob,
         files: Vec<GlobFile>,
+        filter: PathFilter,
         #[cfg(dev_mode)]
-        base_path: &'static str,
+        base_paths: &'static [&'static str],
+    },
+    Dir {
+        http_prefix: Cow<'a, str>,
+        fs_dir: PathBuf,
+        filter: PathFilter,
+
+        /// All files found, only set in `Builder::build`.
+        files: Vec<DirFile>,
     },
 }
 
-#[derive(Debug)]
+impl EntryBuilderKind<'_> {
+    /// Returns the *unhashed HTTP path* and source of all files.
+    pub(crate) fn into_files(self) -> Vec<(String, DataSource)> {
+        match self {
+            EntryBuilderKind::Single { http_path, source } => vec![(http_path.into_owned(), source)],
+            EntryBuilderKind::Glob { http_prefix, files, .. } => files.into_iter()
+                .map(|f| (f.http_path(&http_prefix), f.source))
+                .collect(),
+            EntryBuilderKind::Dir { http_prefix, files, .. } => files.into_iter()
+                .map(|f| (f.http_path(&http_prefix), DataSource::File(f.fs_path)))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub(crate) struct GlobFile {
     pub(crate) suffix: &'static str,
     pub(crate) source: DataSource,
@@ -58,15 +180,99 @@ impl<'a> Builder<'a> {
         http_path: impl Into<Cow<'a, str>>,
         fs_path: impl Into<PathBuf>,
     ) -> &mut EntryBuilder<'a> {
-        self.assets.push(EntryBuilder {
-            kind: EntryBuilderKind::Single {
-                http_path: http_path.into(),
-                source: DataSource::File(fs_path.into()),
-            },
-            path_hash: PathHash::None,
-            modifier: Modifier::None,
+        self.push(EntryBuilderKind::Single {
+            http_path: http_path.into(),
+            source: DataSource::File(fs_path.into()),
+        })
+    }
+
+    /// Adds an asset fetched from `url` (with a `GET` request), mounted under
+    /// the given HTTP path. Useful to pull a pinned copy of a vendor bundle
+    /// from a CDN into your assets, so that hashing, modifiers and all other
+    /// processing apply to it. In prod mode, it is fetched in
+    /// `Builder::build`, where non-2xx responses and network errors are
+    /// reported as [`BuildError::Io`][crate::BuildError::Io] (with the URL as
+    /// path). In dev mode, it is fetched on first use and then cached.
+    ///
+    /// Method is only available if the crate feature `url` is enabled.
+    #[cfg(feature = "url")]
+    pub fn add_url(
+        &mut self,
+        http_path: impl Into<Cow<'a, str>>,
+        url: impl Into<String>,
+    ) -> &mut EntryBuilder<'a> {
+        self.push(EntryBuilderKind::Single {
+            http_path: http_path.into(),
+            source: DataSource::Url { url: url.into(), cache: Default::default() },
+        })
+    }
+
+    /// Mounts all files in the directory `fs_dir` (recursively) under
+    /// `http_prefix`, e.g. `fs_dir/css/style.css` as `<http_prefix>css/style.css`.
+    /// Unlike with [`Self::add_embedded_glob`], nothing is embedded: in prod
+    /// mode, the directory is walked and all files are loaded in
+    /// `Builder::build`, failing if it does not exist. In dev mode, files are
+    /// looked up on each request, so files added later are served as well.
+    /// Use [`EntryBuilder::with_include`] and [`EntryBuilder::with_exclude`]
+    /// to only mount some files.
+    ///
+    /// Symlinks to directories are not followed. As the files are only known
+    /// once built, [`Self::defaults_for`] does not apply to this entry.
+    pub fn add_dir(
+        &mut self,
+        http_prefix: impl Into<Cow<'a, str>>,
+        fs_dir: impl Into<PathBuf>,
+    ) -> &mut EntryBuilder<'a> {
+        self.push(EntryBuilderKind::Dir {
+            http_prefix: http_prefix.into(),
+            fs_dir: fs_dir.into(),
+            filter: PathFilter::default(),
+            files: vec![],
+        })
+    }
+
+    /// Adds an asset with the given content, e.g. generated at startup, and
+    /// mounts it under the given HTTP path. It is treated like all other
+    /// assets, i.e. its path can be hashed and templates, modifiers and path
+    /// fixup apply.
+    pub fn add_bytes(
+        &mut self,
+        http_path: impl Into<Cow<'a, str>>,
+        content: impl Into<Bytes>,
+    ) -> &mut EntryBuilder<'a> {
+        self.push(EntryBuilderKind::Single {
+            http_path: http_path.into(),
+            source: DataSource::Loaded(content.into()),
+        })
+    }
+
+    /// Adds an asset whose content is produced by the async function
+    /// `generate`, e.g. a `config.js` or `robots.txt` depending on the
+    /// runtime configuration, and mounts it under the given HTTP path. Like
+    /// with [`Self::add_bytes`], all processing applies to it.
+    ///
+    /// In prod mode, `generate` is called once in `Builder::build`, where an
+    /// error is reported as [`BuildError::Io`][crate::BuildError::Io]. In dev
+    /// mode, it is called every time the asset is loaded, so the content
+    /// is always up to date.
+    pub fn add_with<F, Fut, T>(
+        &mut self,
+        http_path: impl Into<Cow<'a, str>>,
+        generate: F,
+    ) -> &mut EntryBuilder<'a>
+    where
+        F: 'static + Send + Sync + Fn() -> Fut,
+        Fut: 'static + Send + Future<Output = Result<T, std::io::Error>>,
+        T: Into<Bytes>,
+    {
+        let generator: Generator = Arc::new(move || {
+            let fut = generate();
+            Box::pin(async move { fut.await.map(Into::into) })
         });
-        self.assets.last_mut().unwrap()
+        self.push(EntryBuilderKind::Single {
+            http_path: http_path.into(),
+            source: DataSource::Generated(generator),
+        })
     }
 
     /// Adds an embedded entry (single file or glob). Just calls
@@ -89,15 +295,12 @@ impl<'a> Builder<'a> {
         http_path: impl Into<Cow<'a, str>>,
         file: &EmbeddedFile,
     ) -> &mut EntryBuilder<'a> {
-        self.assets.push(EntryBuilder {
-            kind: EntryBuilderKind::Single {
-                http_path: http_path.into(),
-                source: file.data_source(),
-            },
-            path_hash: PathHash::None,
-            modifier: Modifier::None,
+        let entry = self.push(EntryBuilderKind::Single {
+            http_path: http_path.into(),
+            source: file.data_source(),
         });
-        self.assets.last_mut().unwrap()
+        entry.embedded = Some((file.path(), file.embedded_size()));
+        entry
     }
 
     /// Adds an embedded glob. All files matching this glob are mounted with
@@ -119,45 +322,845 @@ impl<'a> Builder<'a> {
     ///
     /// This might sound complicated but should be fairly straight forward and
     /// is, I think, the must useful in practice.
+    ///
+    /// The files are always sorted by path (byte-wise), independent of the
+    /// file system and the order of base paths, so that e.g. bundles
+    /// concatenating them are reproducible.
+    ///
+    /// Files can be skipped with `except` in [`embed!`][crate::embed!], or
+    /// here via [`EntryBuilder::with_exclude`] and
+    /// [`EntryBuilder::with_filter`].
     pub fn add_embedded_glob(
         &mut self,
         http_path: impl Into<Cow<'a, str>>,
         glob: &'a EmbeddedGlob,
     ) -> &mut EntryBuilder<'a> {
         let split_glob = SplitGlob::new(glob.pattern);
-        self.assets.push(EntryBuilder {
-            kind: EntryBuilderKind::Glob {
-                http_prefix: http_path.into(),
-                files: glob
-                    .files
-                    .iter()
-                    .map(|f| {
-                        GlobFile {
-                            // This should never be `None`
-                            suffix: Path::new(f.path)
-                                .strip_prefix(split_glob.prefix)
-                                .expect("embedded file path does not start with glob prefix")
-                                .to_str()
-                                .expect("embedded file path contains invalid UTF-8 characters"),
-                            source: f.data_source(),
+        let embedded_size = glob.files().map(EmbeddedFile::embedded_size).sum();
+        let mut files = Vec::with_capacity(glob.files.len());
+        for f in glob.files {
+            // This should never fail, as `embed!` only emits matching files.
+            match crate::strip_glob_prefix(f.path, split_glob.prefix) {
+                Some(suffix) => files.push(GlobFile { suffix, source: f.data_source() }),
+                None => self.invalid_glob_files.push((glob.pattern, f.path)),
+            }
+        }
+
+        // `embed!` already sorts the files, but `EmbeddedGlob` could also be
+        // created by hand.
+        files.sort_by_key(|f| f.suffix);
+
+        // In prod mode, `embed!` already skipped the files matching an
+        // `except` pattern. In dev mode, files are also found at runtime, so
+        // they have to be skipped there.
+        #[allow(unused_mut)]
+        let mut filter = PathFilter::default();
+        #[cfg(dev_mode)]
+        if !glob.except.is_empty() {
+            let prefix = split_glob.prefix;
+            let except = glob.except.iter()
+                .flat_map(|p| reinda_shared::glob_syntax::expand(p).expect("invalid glob"))
+                .map(|p| glob::Pattern::new(&p).expect("invalid glob"))
+                .collect::<Vec<_>>();
+            filter.predicates.push(Arc::new(move |suffix| {
+                let path = format!("{prefix}{suffix}");
+                !except.iter().any(|p| p.matches(&path))
+            }));
+        }
+
+        let entry = self.push(EntryBuilderKind::Glob {
+            http_prefix: http_path.into(),
+            files,
+            glob: split_glob,
+            filter,
+            #[cfg(dev_mode)]
+            base_paths: glob.base_paths,
+        });
+        entry.embedded = Some((glob.pattern(), embedded_size));
+        entry
+    }
+
+    /// Enables precompression for all assets, using the given settings.
+    /// Individual entries can override this via
+    /// [`EntryBuilder::with_compression_config`].
+    ///
+    /// In prod mode, all compressed variants are created in
+    /// [`Self::build`]. In dev mode, they are created on the fly in
+    /// [`Asset::encoded_content`][crate::Asset::encoded_content]. As that
+    /// happens for every request, you might want to use faster settings in
+    /// dev mode.
+    ///
+    /// Method is only available if the crate feature `compress` is enabled.
+    #[cfg(feature = "compress")]
+    pub fn set_compression(&mut self, config: CompressionConfig) -> &mut Self {
+        self.compression = Some(config);
+        self
+    }
+
+    /// Configures all entries of the given media type added after this call
+    /// by calling `f` with their [`EntryBuilder`], e.g. to hash all
+    /// stylesheets without repeating that for every entry:
+    ///
+    /// ```ignore
+    /// builder.defaults_for("text/css", |entry| {
+    ///     entry.with_hash();
+    /// });
+    /// builder.defaults_for("image/*", |entry| {
+    ///     entry.with_hash().with_no_compression();
+    /// });
+    /// ```
+    ///
+    /// The media type of an entry is guessed from the file extension of its
+    /// HTTP path (see [`Asset::content_type`][crate::Asset::content_type]),
+    /// ignoring parameters like `charset`. Glob entries only match if all
+    /// their files do. A pattern ending in `/*` matches all subtypes.
+    ///
+    /// Entries added before this call are not affected. As `f` is applied
+    /// when the entry is added, methods called on the entry afterwards
+    /// override the defaults. If several patterns match, their functions are
+    /// called in the order they were registered.
+    pub fn defaults_for<F>(&mut self, media_type: impl Into<Cow<'static, str>>, f: F) -> &mut Self
+    where
+        F: 'static + Send + Sync + Fn(&mut EntryBuilder<'a>),
+    {
+        self.defaults.0.push((media_type.into(), Arc::new(f)));
+        self
+    }
+
+    /// Merges `other` into this builder: all entries of `other` are appended
+    /// to the ones of `self`. For global settings (e.g. compression or source
+    /// map policy), the ones of `self` take precedence; settings only
+    /// configured in `other` are taken over. The active profile of `self` is
+    /// kept.
+    ///
+    /// A warning is logged for each HTTP path of `other` that is already
+    /// used by an entry of `self`, as usually only one of those entries ends
+    /// up being served.
+    ///
+    /// This allows different parts of an application to each construct a
+    /// builder that are then combined.
+    pub fn merge(&mut self, other: Builder<'a>) -> &mut Self {
+        let existing = self.assets.iter()
+            .flat_map(|entry| entry.http_paths())
+            .collect::<HashSet<_>>();
+        for path in other.assets.iter().flat_map(|entry| entry.http_paths()) {
+            if existing.contains(&path) {
+                log::warn!(target: "reinda", "merged builder also has an asset at '{path}'");
+            }
+        }
+        self.assets.extend(other.assets);
+        #[cfg(feature = "compress")]
+        if self.compression.is_none() {
+            self.compression = other.compression;
+        }
+        if self.http.source_maps.is_none() {
+            self.http.source_maps = other.http.source_maps;
+        }
+        if self.http.rewriter.is_none() {
+            self.http.rewriter = other.http.rewriter;
+        }
+        if self.http.acme_dir.is_none() {
+            self.http.acme_dir = other.http.acme_dir;
+        }
+        if self.http.variant_selector.is_none() {
+            self.http.variant_selector = other.http.variant_selector;
+        }
+        self.http.client_hints |= other.http.client_hints;
+        if self.http.save_data_preload_limit.is_none() {
+            self.http.save_data_preload_limit = other.http.save_data_preload_limit;
+        }
+        if self.http.cache_key_header.is_none() {
+            self.http.cache_key_header = other.http.cache_key_header;
+        }
+        if self.http.inspector.is_none() {
+            self.http.inspector = other.http.inspector;
+        }
+        self.http.json_errors.extend(other.http.json_errors);
+        if self.public_url.is_none() {
+            self.public_url = other.public_url;
+        }
+        if self.banner.is_none() {
+            self.banner = other.banner;
+        }
+        if self.base_path_env_var.is_none() {
+            self.base_path_env_var = other.base_path_env_var;
+        }
+        self.normalize_text |= other.normalize_text;
+        self.warn_unreferenced |= other.warn_unreferenced;
+        if self.hot_cache.is_none() {
+            self.hot_cache = other.hot_cache;
+        }
+        if self.change_detection.is_none() {
+            self.change_detection = other.change_detection;
+        }
+        if self.transform_timeout.is_none() {
+            self.transform_timeout = other.transform_timeout;
+        }
+        for (name, value) in other.vars {
+            self.vars.entry(name).or_insert(value);
+        }
+        for plugin in other.plugins.0 {
+            if !self.plugins.0.iter().any(|p| p.name() == plugin.name()) {
+                self.plugins.0.push(plugin);
+            }
+        }
+        self.post_build.0.extend(other.post_build.0);
+        self.renamed.extend(other.renamed);
+        self.invalid_glob_files.extend(other.invalid_glob_files);
+        self
+    }
+
+    /// Sets the public URL under which the assets are reachable, including
+    /// the prefix they are mounted under, e.g. `https://example.com/docs/`
+    /// for an app deployed under `/docs/`. A trailing `/` is added if
+    /// missing. Required for [`EntryBuilder::with_base_href`] and
+    /// [`EntryBuilder::with_canonical_link`]. Modifiers can read it via
+    /// [`ModifierContext::public_url`].
+    pub fn set_public_url(&mut self, url: impl Into<String>) -> &mut Self {
+        let mut url = url.into();
+        if !url.ends_with('/') {
+            url.push('/');
+        }
+        self.public_url = Some(url.into());
+        self
+    }
+
+    /// Sets a banner (e.g. version and copyright notice) that is prepended as
+    /// `/*! <banner> */` comment to all JS and CSS assets (files ending in
+    /// `.js`, `.mjs` or `.css`). Most minifiers keep `/*!` comments. The
+    /// banner is applied after everything else, so it's always at the very
+    /// beginning. Source maps of those assets (with `.map` appended to the
+    /// path, e.g. `app.js.map`) are shifted accordingly. Only has an effect
+    /// in prod mode.
+    ///
+    /// ```ignore
+    /// builder.set_banner(concat!("my-app v", env!("CARGO_PKG_VERSION"), " (c) ACME Inc."));
+    /// ```
+    pub fn set_banner(&mut self, banner: impl Into<String>) -> &mut Self {
+        self.banner = Some(banner.into().into());
+        self
+    }
+
+    /// Enables [`EntryBuilder::normalize_text`] for all entries.
+    pub fn normalize_text(&mut self) -> &mut Self {
+        self.normalize_text = true;
+        self
+    }
+
+    /// Makes [`Self::build`] log a warning for each asset that is not
+    /// referenced by any other asset (see [`Assets::unreferenced`]). Only
+    /// has an effect in prod mode, as dev mode does not process assets while
+    /// building.
+    pub fn warn_unreferenced(&mut self) -> &mut Self {
+        self.warn_unreferenced = true;
+        self
+    }
+
+    /// Keeps the processed content of the `capacity` most requested assets
+    /// loaded from the file system in memory, so that they are not read and
+    /// processed again for each request. Only has an effect in dev mode, as
+    /// all assets are in memory in prod mode anyway. Useful when serving
+    /// runtime directories in dev mode under real traffic, e.g. on a staging
+    /// server.
+    ///
+    /// A cached asset is only used as long as its file is unchanged, which by
+    /// default costs one `stat` per request (see
+    /// [`Self::set_change_detection`]). Assets rendered as template, with
+    /// prepended or appended content or with
+    /// [extra watched files][EntryBuilder::with_extra_watch] depend on other
+    /// files and are never cached. Compressed variants of cached assets are
+    /// cached as well. See [`Assets::hot_cache_stats`] for metrics.
+    pub fn enable_hot_cache(&mut self, capacity: usize) -> &mut Self {
+        self.hot_cache = Some(capacity);
+        self
+    }
+
+    /// Sets how changes of files are detected in dev mode, by the
+    /// [hot cache][Self::enable_hot_cache] and by
+    /// [`Assets::subscribe_changes`]. Defaults to
+    /// [`ChangeDetection::Metadata`]. Use
+    /// [`ChangeDetection::MetadataThenContent`] if editors or build tools
+    /// touch files without changing them, causing spurious reloads. Has no
+    /// effect in prod mode.
+    pub fn set_change_detection(&mut self, detection: ChangeDetection) -> &mut Self {
+        self.change_detection = Some(detection);
+        self
+    }
+
+    /// Limits how long the pipeline steps and the modifiers of each asset may
+    /// run, e.g. to catch a regex stuck in catastrophic backtracking. If the
+    /// limit is exceeded, building fails with
+    /// [`BuildError::TransformTimeout`] in prod mode, and loading the asset
+    /// fails with an IO error wrapping it in dev mode. Can be overridden per
+    /// entry with [`EntryBuilder::with_transform_timeout`].
+    ///
+    /// A transform that timed out cannot be interrupted, so it keeps running
+    /// in the background on its own thread until it finishes (or forever).
+    /// Its result is discarded and nothing waits for it, not even the
+    /// shutdown of the tokio runtime. Unlike [`Self::build_with_deadline`],
+    /// this also applies in dev mode, where assets are processed on every
+    /// load. There, loading an asset whose transform timed out fails right
+    /// away until its file changes, instead of starting the transform again
+    /// next to the one still running.
+    pub fn set_transform_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.transform_timeout = Some(timeout);
+        self
+    }
+
+    /// Registers a hook that runs once after all assets are built, with
+    /// access to the final content and *hashed HTTP path* of every asset.
+    /// It can add assets via [`BuildView::add`], e.g. a sitemap referencing
+    /// all pages or the precache list of a service worker:
+    ///
+    /// ```ignore
+    /// builder.with_post_build(|view| {
+    ///     let paths = view.iter()
+    ///         .map(|asset| format!("\"/{}\"", asset.http_path))
+    ///         .collect::<Vec<_>>();
+    ///     view.add("precache.json", format!("[{}]", paths.join(",")));
+    /// });
+    /// ```
+    ///
+    /// Hooks run in registration order. In dev mode, they run in
+    /// [`Self::build`] as well, which then loads all assets once. The added
+    /// assets are not updated when files change later.
+    pub fn with_post_build(
+        &mut self,
+        hook: impl 'static + Send + Sync + Fn(&mut BuildView),
+    ) -> &mut Self {
+        self.post_build.0.push(Arc::new(hook));
+        self
+    }
+
+    /// Defines a variable for templates (see [`EntryBuilder::with_template`]),
+    /// e.g. to insert the version with `{{: var:version :}}` or to only
+    /// include debug-only scripts in dev builds:
+    ///
+    /// ```ignore
+    /// builder.add_var("version", env!("CARGO_PKG_VERSION"));
+    /// builder.add_var("debug", cfg!(debug_assertions).to_string());
+    /// ```
+    ///
+    /// Defining a variable again overwrites its value. It can be overridden
+    /// for single entries with [`EntryBuilder::add_var`]. Modifiers and
+    /// plugins can read variables via [`ModifierContext::var`].
+    pub fn add_var(&mut self, name: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        self.vars.insert(name.into(), value.into());
+        self
+    }
+
+    /// Registers a reusable processing step, which is applied to all assets
+    /// it [applies to][Pipeline::applies_to], except
+    /// [verbatim][EntryBuilder::verbatim] ones. Steps run in the order they
+    /// were registered, see [`Pipeline`] for details.
+    ///
+    /// ```ignore
+    /// builder.register_plugin(reinda_tailwind::Tailwind::new("tailwind.config.js"));
+    /// ```
+    pub fn register_plugin(&mut self, plugin: impl Pipeline) -> &mut Self {
+        self.plugins.0.push(Arc::new(plugin));
+        self
+    }
+
+    /// Returns all entries added so far, e.g. to inspect a builder you
+    /// received from somewhere else.
+    pub fn entries(&self) -> impl Iterator<Item = &EntryBuilder<'a>> {
+        self.assets.iter()
+    }
+
+    /// Returns all entries added so far, allowing you to modify them.
+    pub fn entries_mut(&mut self) -> impl Iterator<Item = &mut EntryBuilder<'a>> {
+        self.assets.iter_mut()
+    }
+
+    /// Sets whether source maps (files ending in `.map`) are served by
+    /// [`Assets::serve`]. By default, they are always served. This does not
+    /// influence [`Assets::get`].
+    pub fn set_source_map_policy(&mut self, policy: SourceMapPolicy) -> &mut Self {
+        self.http.source_maps = Some(policy);
+        self
+    }
+
+    /// Sets a function that rewrites request paths in [`Assets::serve`]
+    /// before the asset lookup, e.g. to strip a locale prefix or to map
+    /// `static/v2/` to `static/`. The function gets the path without leading
+    /// `/` and query string. This does not influence [`Assets::get`].
+    pub fn set_request_rewriter<F>(&mut self, rewriter: F) -> &mut Self
+    where
+        F: 'static + Send + Sync + for<'p> Fn(&'p str) -> Cow<'p, str>,
+    {
+        self.http.rewriter = Some(Arc::new(rewriter));
+        self
+    }
+
+    /// Sets a function that chooses which variant (see
+    /// [`EntryBuilder::with_variant`]) [`Assets::serve`] serves for assets
+    /// that have variants, replacing the default `Accept` based selection
+    /// ([`http::negotiate_accept`][crate::http::negotiate_accept]). It gets
+    /// the request and the asset's variants, and returns the index of the
+    /// variant to serve, or `None` to serve the asset itself.
+    ///
+    /// `vary` is the value of the `Vary` header of responses for these assets
+    /// and must list all request headers the selector looks at (e.g.
+    /// `"accept, cookie"`), so that caches do not mix up the variants.
+    ///
+    /// ```ignore
+    /// builder.set_variant_selector("accept, cookie", |req, variants| {
+    ///     let dark = req.header("cookie").is_some_and(|c| c.contains("theme=dark"));
+    ///     variants.iter().position(|v| dark && v.label() == Some("dark"))
+    ///         .or_else(|| reinda::http::negotiate_accept(req, variants))
+    /// });
+    /// ```
+    pub fn set_variant_selector<F>(&mut self, vary: impl Into<Cow<'static, str>>, selector: F) -> &mut Self
+    where
+        F: 'static + Send + Sync + Fn(&Request<'_>, &[Variant]) -> Option<usize>,
+    {
+        self.http.variant_selector = Some((vary.into(), Arc::new(selector)));
+        self
+    }
+
+    /// Makes the default variant selection of [`Assets::serve`] consider
+    /// the client hints `Save-Data` and `Sec-CH-DPR` (or the legacy `DPR`),
+    /// in addition to the `Accept` header:
+    ///
+    /// - With `Save-Data: on`, the smallest of the asset, its `Accept`ed
+    ///   variant and its variants with DPR of at most 1 is served.
+    /// - Otherwise, for a DPR above 1, the variant registered with
+    ///   [`EntryBuilder::with_dpr_variant`] with the smallest DPR that is at
+    ///   least the requested one is served (or the largest available).
+    ///
+    /// HTML responses get the header `Accept-CH: Sec-CH-DPR`, asking browsers
+    /// to send the DPR hint with subsequent requests. Has no effect if a
+    /// custom [variant selector][Self::set_variant_selector] is set. To also
+    /// skip heavy preloads for `Save-Data` requests, see
+    /// [`Self::set_save_data_preload_limit`].
+    pub fn use_client_hints(&mut self) -> &mut Self {
+        self.http.client_hints = true;
+        self
+    }
+
+    /// Makes [`Assets::serve`] remove `<link rel="preload">` and `<link
+    /// rel="prefetch">` tags referring to assets larger than `limit` bytes
+    /// from HTML responses to requests with `Save-Data: on`, so that clients
+    /// asking to save data do not load them ahead of time. Preloads of
+    /// external URLs and unknown paths are kept.
+    ///
+    /// HTML responses then get the header `Vary: save-data`. If preloads are
+    /// removed, the response is sent uncompressed and with a different
+    /// `ETag`. Independent of [`Self::use_client_hints`].
+    pub fn set_save_data_preload_limit(&mut self, limit: u64) -> &mut Self {
+        self.http.save_data_preload_limit = Some(limit);
+        self
+    }
+
+    /// Makes [`Assets::serve`] add a header with the given name (e.g.
+    /// `X-Cache-Key`) to successful responses, containing the
+    /// [cache key][crate::Asset::cache_key] of the served content. For
+    /// assets with variants, it is the key of the served variant. For
+    /// compressed responses, the content encoding is appended (e.g.
+    /// `<key>-gzip`), like in the `ETag`. Reverse
+    /// proxies can use it to key their caches consistently across replicas.
+    ///
+    /// Method is only available if the crate feature `hash` is enabled.
+    #[cfg(feature = "hash")]
+    pub fn set_cache_key_header(&mut self, name: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.http.cache_key_header = Some(name.into());
+        self
+    }
+
+    /// Records that the asset previously served under `old_path` was renamed
+    /// to `new_path` (both *unhashed HTTP paths*, without leading `/`), so
+    /// that deep links and bookmarks keep working. [`Assets::serve`] answers
+    /// requests for `old_path` with a `308 Permanent Redirect` to the
+    /// current (potentially hashed) path of `new_path`. The `Location` is
+    /// relative, so it works regardless of where the assets are mounted.
+    /// Each such request is logged (level `debug`, target `reinda`), to
+    /// find out when the old path is not used anymore.
+    ///
+    /// [`Assets::get`] does not return anything for `old_path`.
+    ///
+    /// **Panics** in [`Self::build`] if `new_path` is not an asset or if
+    /// `old_path` still is one.
+    pub fn add_renamed(
+        &mut self,
+        old_path: impl Into<String>,
+        new_path: impl Into<String>,
+    ) -> &mut Self {
+        self.renamed.push((old_path.into(), new_path.into()));
+        self
+    }
+
+    /// Serves ACME HTTP-01 challenges (e.g. created by certbot with
+    /// `--webroot`) from the directory `fs_dir` under
+    /// `/.well-known/acme-challenge/`. The directory is read on each request
+    /// by [`Assets::serve`] in both modes, so challenge files created after
+    /// building are found. They are served without hashing and with
+    /// `Cache-Control: no-store`. They are not assets, so [`Assets::get`]
+    /// does not return them.
+    pub fn add_acme_dir(&mut self, fs_dir: impl Into<PathBuf>) -> &mut Self {
+        self.http.acme_dir = Some(fs_dir.into());
+        self
+    }
+
+    /// Serves an HTML page under `http_path` (e.g. `"/__reinda"`) listing all
+    /// assets with their source, size, cache key, processing steps,
+    /// dependencies and the time their file was last modified. Useful to
+    /// debug why an asset does not look like expected. The page is generated
+    /// on each request by [`Assets::serve`] and sent with `Cache-Control:
+    /// no-store`.
+    ///
+    /// The same information is served as JSON under `http_path` with `.json`
+    /// appended (e.g. `"/__reinda.json"`), for editor plugins and scripts:
+    /// an object with the field `assets`, an array of objects with the
+    /// fields `path`, `source`, `internal`, `processing`, `dependencies`,
+    /// `size` (or `error` if loading the asset failed), `cache_key` and
+    /// `modified` (seconds since the Unix epoch). Unavailable values are
+    /// `null`.
+    ///
+    /// This only has an effect in dev mode. In prod mode, requests for
+    /// `http_path` are treated like all other requests, so the page is never
+    /// exposed in production.
+    pub fn enable_inspector(&mut self, http_path: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.http.inspector = Some(http_path.into());
+        self
+    }
+
+    /// Makes [`Assets::serve`] answer requests for missing assets under
+    /// `prefix` (e.g. `"/api/"`) with a JSON body instead of plain text, if
+    /// the request accepts JSON, i.e. its `Accept` header lists
+    /// `application/json` or a `+json` type. Useful for servers that serve
+    /// an SPA and an API, where API clients expect structured errors. Can be
+    /// called multiple times to add more prefixes; `"/"` applies to all
+    /// paths. Prefixes only match whole path segments, i.e. `"/api"` and
+    /// `"/api/"` both match `/api/users` but not `/apiv2/users`.
+    ///
+    /// The body is an object with the fields `error` (`"Not Found"`),
+    /// `status` (`404`) and `path`, plus `suggestions` with similar paths in
+    /// dev mode. Requests for other prefixes, or that don't accept JSON, get
+    /// the usual plain text 404.
+    pub fn enable_json_errors(&mut self, prefix: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.http.json_errors.push(prefix.into());
+        self
+    }
+
+    /// Sets the name of the environment variable that overrides the
+    /// `base_path` of all `embed!` invocations in dev mode. Default:
+    /// `REINDA_BASE_PATH` (see [`BASE_PATH_ENV_VAR`]).
+    ///
+    /// If the variable is set when calling [`Self::build`], embedded files
+    /// are loaded from there instead of from the paths specified at compile
+    /// time, allowing the same test binary to use different asset checkouts
+    /// without recompiling. Like `PATH`, it can contain multiple paths
+    /// (separated by `:` on Unix and `;` on Windows), searched in order.
+    /// Relative paths are relative to the working directory. The file list
+    /// of globs is still determined at compile time, but new files matching
+    /// a glob are found as usual.
+    ///
+    /// Has no effect in prod mode, where all files are embedded.
+    pub fn set_base_path_env_var(&mut self, name: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.base_path_env_var = Some(name.into());
+        self
+    }
+
+    /// Returns the base paths from the environment variable configured via
+    /// [`Self::set_base_path_env_var`], if it is set.
+    #[cfg(dev_mode)]
+    pub(crate) fn base_path_override(&self) -> Option<Vec<PathBuf>> {
+        let name = self.base_path_env_var.as_deref().unwrap_or(BASE_PATH_ENV_VAR);
+        let value = std::env::var_os(name).filter(|v| !v.is_empty())?;
+        Some(std::env::split_paths(&value).collect())
+    }
+
+    /// Runs `f` with this builder if `name` is the active profile, allowing
+    /// you to override settings or add entries only for some environments
+    /// (e.g. `dev`, `staging` or `prod`), while using the same binary for all
+    /// of them.
+    ///
+    /// The active profile is read from the environment variable
+    /// `REINDA_PROFILE` (see [`PROFILE_ENV_VAR`]) when creating the builder,
+    /// and can be overridden with [`Self::set_profile`].
+    ///
+    /// ```ignore
+    /// builder.profile("staging", |b| {
+    ///     b.add_embedded("debug.js", &EMBEDS["debug.js"]);
+    ///     b.set_source_map_policy(SourceMapPolicy::Always);
+    /// });
+    /// ```
+    pub fn profile(&mut self, name: &str, f: impl FnOnce(&mut Self)) -> &mut Self {
+        if self.profile.as_deref() == Some(name) {
+            f(self);
+        }
+        self
+    }
+
+    /// Sets the active profile, overriding the one from the environment
+    /// variable. Pass `None` to disable all profiles. See [`Self::profile`].
+    pub fn set_profile(&mut self, name: Option<&str>) -> &mut Self {
+        self.profile = name.map(ToOwned::to_owned);
+        self
+    }
+
+    /// Returns the active profile, if any. See [`Self::profile`].
+    pub fn active_profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Applies global settings to all entries that do not override them.
+    fn apply_global_settings(mut self) -> Result<Self, BuildError> {
+        let vars = Arc::new(std::mem::take(&mut self.vars));
+        for entry in &mut self.assets {
+            if entry.lazy {
+                let from_fs = entry.embedded.is_none() && match &entry.kind {
+                    EntryBuilderKind::Single { source, .. } => matches!(source, DataSource::File(_)),
+                    EntryBuilderKind::Glob { .. } | EntryBuilderKind::Dir { .. } => true,
+                };
+                assert!(
+                    from_fs,
+                    "entry {:?} is marked as lazy, but is not loaded from the file system",
+                    entry.http_paths(),
+                );
+                assert!(
+                    !entry.is_hashed(),
+                    "entry {:?} is marked as lazy, but also hashed",
+                    entry.http_paths(),
+                );
+            }
+            if entry.verbatim {
+                assert!(
+                    !entry.is_hashed()
+                        && !entry.has_modifier()
+                        && !entry.is_template()
+                        && !entry.transform.is_configured(),
+                    "entry {:?} is marked as verbatim, but configures processing of its content",
+                    entry.http_paths(),
+                );
+                continue;
+            }
+
+            let entry_vars = if entry.vars.is_empty() {
+                vars.clone()
+            } else {
+                let mut merged = (*vars).clone();
+                merged.append(&mut entry.vars);
+                Arc::new(merged)
+            };
+            if let Some(template) = &mut entry.template {
+                template.vars = entry_vars.clone();
+            }
+            entry.env = Arc::new(ModifierEnv {
+                vars: entry_vars,
+                public_url: self.public_url.clone(),
+            });
+            entry.steps = self.plugins.clone();
+            entry.transform_timeout = entry.transform_timeout.or(self.transform_timeout);
+            entry.transform.normalize_text |= self.normalize_text;
+            entry.transform.banner = self.banner.clone();
+            if entry.transform.needs_public_url() {
+                let Some(url) = &self.public_url else {
+                    let http_path = match &entry.kind {
+                        EntryBuilderKind::Single { http_path, .. } => http_path,
+                        EntryBuilderKind::Glob { http_prefix, .. }
+                        | EntryBuilderKind::Dir { http_prefix, .. } => http_prefix,
+                    };
+                    return Err(BuildError::MissingPublicUrl { http_path: http_path.to_string() });
+                };
+                entry.transform.public_url = Some(url.clone());
+                entry.transform.content_type = entry.serve.content_type.clone();
+            }
+        }
+
+        #[cfg(feature = "compress")]
+        for entry in &mut self.assets {
+            if entry.serve.no_compression || entry.lazy {
+                entry.compression = None;
+            } else if let Some(config) = &self.compression {
+                entry.compression.get_or_insert_with(|| config.clone());
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Removes all optional entries whose file does not exist, logging a
+    /// warning for each. Optional globs without any files are only warned
+    /// about.
+    async fn remove_missing_optional(mut self) -> Self {
+        let mut assets = Vec::with_capacity(self.assets.len());
+        for entry in self.assets {
+            if entry.optional {
+                match &entry.kind {
+                    EntryBuilderKind::Single { http_path, source } => {
+                        if let Some(path) = source.fs_path() {
+                            if !tokio::fs::try_exists(path).await.unwrap_or(false) {
+                                log::warn!(
+                                    target: "reinda",
+                                    "skipping optional asset '{http_path}': '{}' does not exist",
+                                    path.display(),
+                                );
+                                continue;
+                            }
                         }
-                    })
-                    .collect(),
-                glob: split_glob,
-                #[cfg(dev_mode)]
-                base_path: glob.base_path,
-            },
+                    }
+                    EntryBuilderKind::Glob { http_prefix, files, .. } => {
+                        if files.is_empty() {
+                            log::warn!(
+                                target: "reinda",
+                                "optional glob entry '{http_prefix}' did not match any files",
+                            );
+                        }
+                    }
+                    EntryBuilderKind::Dir { http_prefix, fs_dir, .. } => {
+                        if !tokio::fs::try_exists(fs_dir).await.unwrap_or(false) {
+                            log::warn!(
+                                target: "reinda",
+                                "skipping optional directory '{http_prefix}': '{}' does not exist",
+                                fs_dir.display(),
+                            );
+                            continue;
+                        }
+                    }
+                }
+            }
+            assets.push(entry);
+        }
+
+        self.assets = assets;
+        self
+    }
+
+    /// Removes the files of glob entries not passing their filter, see
+    /// [`EntryBuilder::with_filter`].
+    fn filter_globs(mut self) -> Self {
+        for entry in &mut self.assets {
+            if let EntryBuilderKind::Glob { files, filter, .. } = &mut entry.kind {
+                if !filter.is_empty() {
+                    files.retain(|f| filter.matches(&f.suffix.replace('\\', "/")));
+                }
+            }
+        }
+        self
+    }
+
+    /// Collects the files of all directory entries. In prod mode, failing to
+    /// read a directory is an error. In dev mode, it's only logged, as files
+    /// are looked up again on each request anyway.
+    async fn walk_dirs(mut self) -> Result<Self, BuildError> {
+        for entry in &mut self.assets {
+            let EntryBuilderKind::Dir { http_prefix, fs_dir, filter, files } = &mut entry.kind else {
+                continue;
+            };
+            match crate::dir::walk(fs_dir, filter).await {
+                Ok(found) => *files = found,
+                Err(err) if cfg!(prod_mode) => return Err(BuildError::Io {
+                    err,
+                    path: fs_dir.clone(),
+                    http_path: http_prefix.to_string(),
+                }),
+                Err(e) => log::warn!(
+                    target: "reinda",
+                    "failed to read directory '{}' (for '{http_prefix}'): {e}",
+                    fs_dir.display(),
+                ),
+            }
+        }
+        Ok(self)
+    }
+
+    fn push(&mut self, kind: EntryBuilderKind<'a>) -> &mut EntryBuilder<'a> {
+        self.assets.push(EntryBuilder {
+            kind,
             path_hash: PathHash::None,
-            modifier: Modifier::None,
+            modifiers: Modifiers::default(),
+            template: None,
+            vars: BTreeMap::new(),
+            internal: false,
+            optional: false,
+            verbatim: false,
+            lazy: false,
+            serve: ServeSettings::default(),
+            transform: TransformSettings::default(),
+            steps: Steps::default(),
+            env: Arc::default(),
+            transform_timeout: None,
+            extra_watch: vec![],
+            #[cfg(feature = "compress")]
+            compression: None,
+            embedded: None,
         });
-        self.assets.last_mut().unwrap()
+        let entry = self.assets.last_mut().unwrap();
+
+        let media_types = match &entry.kind {
+            EntryBuilderKind::Single { http_path, .. } => vec![crate::mime::guess(http_path)],
+            EntryBuilderKind::Glob { files, .. } => {
+                files.iter().map(|f| crate::mime::guess(f.suffix)).collect()
+            }
+            EntryBuilderKind::Dir { .. } => vec![],
+        };
+        for (pattern, apply) in &self.defaults.0 {
+            let matches = !media_types.is_empty()
+                && media_types.iter().all(|t| t.is_some_and(|t| media_type_matches(pattern, t)));
+            if matches {
+                apply(entry);
+            }
+        }
+        entry
     }
 
     /// Builds `Assets` from the configured assets. In prod mode, everything is
     /// loaded, processed, and assembled into a fast data structure. In dev
     /// mode, those steps are deferred to later.
+    ///
+    /// In prod mode, one `debug` level log record (target `reinda`) is emitted
+    /// per asset, describing its source, final path, sizes and modifier. Use
+    /// that to audit what the pipeline did, e.g. in CI logs.
+    ///
+    /// Fails with [`BuildError::MissingPublicUrl`] if
+    /// [`EntryBuilder::with_base_href`] or
+    /// [`EntryBuilder::with_canonical_link`] was used without
+    /// [`Self::set_public_url`].
+    ///
+    /// **Panics** if an entry marked as
+    /// [verbatim][EntryBuilder::verbatim] configures any processing, or if
+    /// a path passed to [`Self::add_renamed`] is invalid.
     pub async fn build(self) -> Result<Assets, BuildError> {
-        crate::imp::AssetsInner::build(self).await.map(Assets)
+        if let Some(&(pattern, path)) = self.invalid_glob_files.first() {
+            return Err(BuildError::InvalidGlobFile { pattern: pattern.into(), path: path.into() });
+        }
+
+        #[cfg(feature = "runtime-mutation")]
+        let runtime = crate::mutation::RuntimeAssets::new(self.vars.clone(), self.public_url.clone());
+        let mut builder = self.apply_global_settings()?.filter_globs().remove_missing_optional().await
+            .walk_dirs().await?;
+        let http = Arc::new(std::mem::take(&mut builder.http));
+        let sizes = crate::size::EntrySize::collect(&builder.assets).into();
+        let inner = crate::imp::AssetsInner::build(builder).await?;
+        Ok(Assets {
+            inner,
+            http,
+            sizes,
+            #[cfg(feature = "watch")]
+            watcher: Default::default(),
+            #[cfg(feature = "runtime-mutation")]
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Like [`Self::build`], but fails with [`BuildError::DeadlineExceeded`]
+    /// if building takes longer than `deadline`. Useful to make sure that a
+    /// misbehaving modifier (e.g. one calling an external tool) cannot hang
+    /// the startup of your application indefinitely.
+    ///
+    /// Building is cancellation safe: dropping the future returned by
+    /// [`Self::build`] (which is what happens when the deadline is exceeded)
+    /// stops all further loading and processing. Modifiers and pipeline
+    /// steps run on their own threads. One that is already running when the
+    /// deadline is hit cannot be interrupted and keeps running in the
+    /// background, but its result is discarded and nothing waits for it,
+    /// not even the shutdown of the tokio runtime.
+    pub async fn build_with_deadline(self, deadline: Duration) -> Result<Assets, BuildError> {
+        tokio::time::timeout(deadline, self.build())
+            .await
+            .map_err(|_| BuildError::DeadlineExceeded(deadline))?
     }
 }
 
@@ -195,13 +1198,371 @@ impl<'a> EntryBuilder<'a> {
 
     /// Replaces occurences of any of the given *unhashed HTTP paths* in this
     /// asset with the corresponding *hashed HTTP path*. This is a specialized
-    /// version of [`Self::with_modifier`].
+    /// version of [`Self::with_modifier`] and is added to the same chain of
+    /// modifiers.
     pub fn with_path_fixup<D, T>(&mut self, paths: D) -> &mut Self
     where
         D: IntoIterator<Item = T>,
         T: Into<Cow<'static, str>>,
     {
-        self.modifier = Modifier::PathFixup(paths.into_iter().map(Into::into).collect());
+        self.modifiers.0.push(Modifier::PathFixup(paths.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    /// Renders this asset as template. Fragments of the form
+    /// `{{: path:<unhashed HTTP path> :}}` are replaced by the *hashed HTTP
+    /// path* of the referenced asset. Unlike with [`Self::with_modifier`],
+    /// dependencies don't need to be declared, but are determined from the
+    /// template. Rendering happens before the modifiers are applied. A literal
+    /// `{{:` can be written as `\{{:`.
+    ///
+    /// `{{: include:<unhashed HTTP path> :}}` inserts the final content of
+    /// another asset, e.g. a shared header. `{{: for <name> in "<pattern>" :}}
+    /// ... {{: endfor :}}` repeats a section for all assets matching a glob
+    /// pattern.
+    ///
+    /// `{{: var:<name> :}}` inserts the value of a variable defined with
+    /// [`Builder::add_var`] or [`Self::add_var`]. Sections can be included
+    /// conditionally with `{{: if:<var> :}} ... {{: else :}} ... {{: endif
+    /// :}}`. See the [`template`][crate::template] module for
+    /// details.
+    ///
+    /// Content that looks binary (containing NUL bytes or having very high
+    /// entropy) is not treated as template and passed through unchanged, to
+    /// avoid corrupting binary files that happen to contain `{{:`. Use
+    /// [`Self::force_template`] to override that.
+    pub fn with_template(&mut self) -> &mut Self {
+        self.template = Some(TemplateSettings::default());
+        self
+    }
+
+    /// Like [`Self::with_template`], but renders the asset as template even
+    /// if it looks like binary content.
+    pub fn force_template(&mut self) -> &mut Self {
+        self.template = Some(TemplateSettings { force: true, ..Default::default() });
+        self
+    }
+
+    /// Defines a variable for the template and modifier of this entry (see
+    /// [`ModifierContext::var`]), overriding the value passed to
+    /// [`Builder::add_var`].
+    pub fn add_var(&mut self, name: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        self.vars.insert(name.into(), value.into());
+        self
+    }
+
+    /// Only mounts the files of a directory or glob entry (see
+    /// [`Builder::add_dir`] and [`Builder::add_embedded_glob`]) matching the
+    /// glob `pattern`, e.g. `"*.css"`. Patterns are matched against the path
+    /// relative to the directory or the fixed prefix of the glob (e.g. `b/c.js`
+    /// for `assets/b/c.js` of the glob `assets/**/*.js`), with `/` as
+    /// separator and `*` also matching `/`. Can be called multiple times,
+    /// files then have to match at least one of the patterns.
+    ///
+    /// **Panics** if `pattern` is not a valid glob or if this entry is
+    /// neither a directory nor a glob entry.
+    pub fn with_include(&mut self, pattern: &str) -> &mut Self {
+        self.path_filter().include.push(parse_filter_pattern(pattern));
+        self
+    }
+
+    /// Skips the files of a directory or glob entry matching the glob
+    /// `pattern`, e.g. `"*.map"` or `"drafts/*"`. Patterns are matched like
+    /// with [`Self::with_include`]; excludes take precedence over includes.
+    ///
+    /// **Panics** if `pattern` is not a valid glob or if this entry is
+    /// neither a directory nor a glob entry.
+    pub fn with_exclude(&mut self, pattern: &str) -> &mut Self {
+        self.path_filter().exclude.push(parse_filter_pattern(pattern));
+        self
+    }
+
+    /// Only mounts the files of a directory or glob entry for which `filter`
+    /// returns `true`. It's called with the same relative path that patterns
+    /// of [`Self::with_include`] are matched against, e.g.
+    /// `.with_filter(|path| !path.contains(".test."))`. Can be called
+    /// multiple times, files then have to pass all filters. In dev mode, it
+    /// is also called for files looked up at runtime.
+    ///
+    /// **Panics** if this entry is neither a directory nor a glob entry.
+    pub fn with_filter<F>(&mut self, filter: F) -> &mut Self
+    where
+        F: 'static + Send + Sync + Fn(&str) -> bool,
+    {
+        self.path_filter().predicates.push(Arc::new(filter));
+        self
+    }
+
+    fn path_filter(&mut self) -> &mut PathFilter {
+        match &mut self.kind {
+            EntryBuilderKind::Dir { filter, .. } | EntryBuilderKind::Glob { filter, .. } => filter,
+            _ => panic!("path filters are only supported for directory and glob entries"),
+        }
+    }
+
+    /// Marks this asset as internal: it is processed like all other assets
+    /// and can be referenced by templates and modifiers, but it cannot be
+    /// retrieved via [`Assets::get`] and is not part of [`Assets::iter`] and
+    /// friends. Useful for partials or source maps that should not be exposed.
+    pub fn internal(&mut self) -> &mut Self {
+        self.internal = true;
+        self
+    }
+
+    /// Marks this entry as optional: if its file does not exist when calling
+    /// [`Builder::build`], a warning is logged and the entry is skipped,
+    /// instead of failing the build (prod mode) or failing to load later
+    /// (dev mode). For globs, a warning is logged if no files matched. Useful
+    /// for overrides that might not exist in every deployment.
+    pub fn optional(&mut self) -> &mut Self {
+        self.optional = true;
+        self
+    }
+
+    /// Guarantees that all assets of this entry are served byte-identical to
+    /// their source file, in both modes: [`Asset::content`] and the body of
+    /// [`Assets::serve`] are exactly the bytes of the file (the compression
+    /// by `embed!` is lossless and undone when loading). Useful for signed
+    /// artifacts or downloads with published checksums.
+    ///
+    /// That's already the case for every entry without hash, modifier,
+    /// template and any of the content transformations (like
+    /// [`Self::prepend`]), as long as no global transformation (like
+    /// [`Builder::set_banner`] or [`Builder::normalize_text`]) is
+    /// configured. Calling this method additionally exempts the entry from
+    /// global transformations and makes [`Builder::build`] panic if any
+    /// processing is configured for this entry, so the guarantee cannot be
+    /// broken accidentally. Precompressed variants (feature `compress`) are
+    /// still created, as they decode to the identical bytes.
+    ///
+    /// [`Asset::content`]: crate::Asset::content
+    pub fn verbatim(&mut self) -> &mut Self {
+        self.verbatim = true;
+        self
+    }
+
+    /// Loads the files of this entry from the file system whenever their
+    /// content is requested, also in prod mode, instead of keeping them in
+    /// memory. Useful for huge files like videos, which should neither be
+    /// embedded nor loaded in [`Builder::build`]. Only the length and cache
+    /// key are determined while building, so the files must not change
+    /// afterwards. [`Asset::stream`] and range requests only read the
+    /// required parts. In dev mode, all files are loaded on request anyway.
+    ///
+    /// Implies [`Self::verbatim`]; no precompressed variants are created.
+    /// Including a lazy asset into a template or prepending it to another
+    /// asset still loads it once while building. [`Builder::build`]
+    /// panics if this entry is not loaded from the file system (i.e. not
+    /// added via [`Builder::add_file`] or [`Builder::add_dir`]), or if it
+    /// is also hashed (e.g. via `with_hash`), as hashing would
+    /// require reading the whole file while building.
+    ///
+    /// [`Asset::stream`]: crate::Asset::stream
+    pub fn lazy(&mut self) -> &mut Self {
+        self.lazy = true;
+        self.verbatim = true;
+        self
+    }
+
+    /// Limits how long the pipeline steps and the modifiers of this entry may
+    /// run, overriding [`Builder::set_transform_timeout`]. Like there, a
+    /// transform that timed out keeps running in the background.
+    pub fn with_transform_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.transform_timeout = Some(timeout);
+        self
+    }
+
+    /// Makes [`Assets::serve`] emit the header `X-Robots-Tag: noindex` for
+    /// this asset, asking search engines not to index it. Useful for staging
+    /// pages or internal tools.
+    pub fn with_noindex(&mut self) -> &mut Self {
+        self.serve.noindex = true;
+        self
+    }
+
+    /// Makes [`Assets::serve`] answer requests for this entry's assets with
+    /// the given status code instead of `200 OK`, e.g. `410` for a `gone.html`
+    /// mounted at removed paths, or `418` for an easter egg. Range requests
+    /// and `If-None-Match` are ignored for these assets, as `206` and `304`
+    /// only make sense for successful responses. Returned by
+    /// [`Asset::status`][crate::Asset::status] for custom handlers.
+    ///
+    /// **Panics** if `status` is not between 100 and 999.
+    pub fn with_status(&mut self, status: u16) -> &mut Self {
+        assert!((100..=999).contains(&status), "invalid HTTP status code {}", status);
+        self.serve.status = Some(status);
+        self
+    }
+
+    /// Sets the MIME type of this entry's assets (e.g. `application/wasm`),
+    /// overriding the one guessed from the file extension. Returned by
+    /// [`Asset::content_type`][crate::Asset::content_type] and sent as
+    /// `Content-Type` by [`Assets::serve`]. Include parameters like
+    /// `charset` if needed.
+    pub fn with_content_type(&mut self, content_type: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.serve.content_type = Some(content_type.into());
+        self
+    }
+
+    /// Makes [`Assets::serve`] emit `Content-Disposition: attachment` with
+    /// the given filename for this asset, so browsers download it (e.g. a
+    /// generated report) instead of displaying it. The filename is what the
+    /// browser suggests when saving, independent of the HTTP path.
+    pub fn with_download(&mut self, filename: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.serve.download = Some(filename.into());
+        self
+    }
+
+    /// Sets the caching directives [`Assets::serve`] sends for this asset,
+    /// e.g. `s-maxage` and `Surrogate-Control` for CDNs. See
+    /// [`CachePolicy`][crate::http::CachePolicy] for the defaults.
+    pub fn with_cache_policy(&mut self, policy: crate::http::CachePolicy) -> &mut Self {
+        self.serve.cache_policy = Some(policy);
+        self
+    }
+
+    /// Exempts this entry from compression: no precompressed variants are
+    /// created (overriding [`Builder::set_compression`] and
+    /// [`Self::with_compression_config`]), and responses of
+    /// [`Assets::serve`] are marked as not compressible (see
+    /// [`http::Response::is_compressible`][crate::http::Response::is_compressible])
+    /// and get `Cache-Control: no-transform`. Useful for files that are
+    /// already compressed, e.g. `.tar.gz` downloads or media.
+    pub fn with_no_compression(&mut self) -> &mut Self {
+        self.serve.no_compression = true;
+        self
+    }
+
+    /// Registers the asset with the *unhashed HTTP path* `http_path` as a
+    /// variant of this one, e.g. `hero.avif` for `hero.png`. [`Assets::serve`]
+    /// then serves the first registered variant whose media type (guessed
+    /// from the extension) the request's `Accept` header explicitly lists,
+    /// and the original asset otherwise. This can be customized with
+    /// [`Builder::set_variant_selector`]. The URL stays the same, and
+    /// `Vary: Accept` is set so that caches store the responses separately.
+    /// Register variants in order of preference, e.g. AVIF before WebP.
+    ///
+    /// The variant also stays reachable under its own path. This is meant
+    /// for single-file entries.
+    ///
+    /// [`Builder::build`] fails with [`BuildError::MissingDependency`] if no
+    /// asset with the given path exists.
+    pub fn with_variant(&mut self, http_path: impl Into<String>) -> &mut Self {
+        self.push_variant(http_path.into(), None)
+    }
+
+    /// Like [`Self::with_variant`], but with a label (e.g. `dark`) for use by
+    /// a custom [variant selector][Builder::set_variant_selector], e.g. to
+    /// pick a theme based on a cookie. Labeled variants are ignored by the
+    /// default `Accept` based selection.
+    pub fn with_labeled_variant(
+        &mut self,
+        label: impl Into<Cow<'static, str>>,
+        http_path: impl Into<String>,
+    ) -> &mut Self {
+        self.push_variant(http_path.into(), Some(label.into()))
+    }
+
+    /// Like [`Self::with_variant`], but for screens with the given device
+    /// pixel ratio, e.g. `2.0` for `hero@2x.png`. A DPR of at most 1 marks a
+    /// smaller version (e.g. lower quality). Only used if
+    /// [`Builder::use_client_hints`] is enabled.
+    pub fn with_dpr_variant(&mut self, dpr: f32, http_path: impl Into<String>) -> &mut Self {
+        self.push_variant(http_path.into(), None);
+        self.serve.variants.last_mut().unwrap().dpr = Some(dpr);
+        self
+    }
+
+    fn push_variant(&mut self, http_path: String, label: Option<Cow<'static, str>>) -> &mut Self {
+        let media_type = crate::mime::guess(&http_path)
+            .map_or("application/octet-stream", |t| t.split(';').next().unwrap_or(t));
+        self.serve.variants.push(crate::http::Variant { http_path, media_type, label, dpr: None });
+        self
+    }
+
+    /// Sets the `<base href>` of this HTML asset to the path of the URL passed
+    /// to [`Builder::set_public_url`] (e.g. `/docs/`), replacing an existing
+    /// `<base>` tag. This makes relative links work for apps deployed under
+    /// a sub-path. Applied after the modifiers, in both modes, but only to
+    /// assets with the content type `text/html` (guessed from the path or
+    /// set via [`Self::with_content_type`]). See
+    /// [`util::set_base_href`][crate::util::set_base_href].
+    pub fn with_base_href(&mut self) -> &mut Self {
+        self.transform.base_href = true;
+        self
+    }
+
+    /// Sets the `<link rel="canonical">` of this HTML asset to the public URL
+    /// (see [`Builder::set_public_url`]) followed by the *unhashed HTTP
+    /// path* of this asset, replacing an existing canonical link. Applied
+    /// after the modifiers, in both modes, but only to `text/html` assets
+    /// like [`Self::with_base_href`]. See
+    /// [`util::set_canonical_link`][crate::util::set_canonical_link].
+    pub fn with_canonical_link(&mut self) -> &mut Self {
+        self.transform.canonical_link = true;
+        self
+    }
+
+    /// Strips the UTF-8 BOM and converts all line endings (`\r\n` and `\r`)
+    /// to `\n` in text files of this entry. Binary files (see
+    /// [`Self::with_template`]) are not touched. This happens right after
+    /// loading, before templates and modifiers are applied. Useful as BOMs
+    /// can break strict CSS/JS parsers and different line endings (e.g. due
+    /// to git's `autocrlf` on Windows) change the hash of the asset.
+    pub fn normalize_text(&mut self) -> &mut Self {
+        self.transform.normalize_text = true;
+        self
+    }
+
+    /// Prepends `content` to all assets of this entry. Can be called multiple
+    /// times (also mixed with [`Self::prepend_file`] and
+    /// [`Self::prepend_asset`]), the contents are then prepended in the order
+    /// of the calls. Applied after the modifiers, in both modes, so the added
+    /// content is not seen by the modifiers.
+    pub fn prepend(&mut self, content: impl Into<Bytes>) -> &mut Self {
+        self.transform.prepend.push(Snippet::Source(DataSource::Loaded(content.into())));
+        self
+    }
+
+    /// Like [`Self::prepend`], but prepends the content of the file with the
+    /// given *FS path*. In prod mode, the file is loaded in
+    /// [`Builder::build`]; in dev mode, every time the asset is loaded, so
+    /// changes to the file are picked up immediately.
+    pub fn prepend_file(&mut self, fs_path: impl Into<PathBuf>) -> &mut Self {
+        self.transform.prepend.push(Snippet::Source(DataSource::File(fs_path.into())));
+        self
+    }
+
+    /// Like [`Self::prepend`], but prepends the final content of another
+    /// asset, given by *unhashed HTTP path*. That asset is a dependency of
+    /// this one, like with [`Self::with_modifier`], and is built first. It
+    /// can be [internal][Self::internal], e.g. for a shared license header.
+    /// In dev mode, it's loaded every time this asset is loaded, so changes
+    /// are picked up immediately.
+    ///
+    /// [`Builder::build`] fails with [`BuildError::MissingDependency`] if no
+    /// asset with that path exists.
+    pub fn prepend_asset(&mut self, http_path: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.transform.prepend.push(Snippet::Asset(http_path.into()));
+        self
+    }
+
+    /// Appends `content` to all assets of this entry. Like [`Self::prepend`]
+    /// otherwise.
+    pub fn append(&mut self, content: impl Into<Bytes>) -> &mut Self {
+        self.transform.append.push(Snippet::Source(DataSource::Loaded(content.into())));
+        self
+    }
+
+    /// Appends the content of a file, see [`Self::prepend_file`].
+    pub fn append_file(&mut self, fs_path: impl Into<PathBuf>) -> &mut Self {
+        self.transform.append.push(Snippet::Source(DataSource::File(fs_path.into())));
+        self
+    }
+
+    /// Appends the content of another asset, see [`Self::prepend_asset`].
+    pub fn append_asset(&mut self, http_path: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.transform.append.push(Snippet::Asset(http_path.into()));
         self
     }
 
@@ -213,16 +1574,76 @@ impl<'a> EntryBuilder<'a> {
     /// arbitrary logic with the asset's content. In prod mode, this is called
     /// once when you call [`Builder::build`]; in dev mode, it's called every
     /// time the asset is loaded.
+    ///
+    /// Calling this (or [`Self::with_path_fixup`]) multiple times chains the
+    /// modifiers: each one gets the output of the previous one, in the order
+    /// they were added. See [`Self::modifiers`] and [`Self::clear_modifiers`].
     pub fn with_modifier<F, D, T>(&mut self, dependencies: D, modifier: F) -> &mut Self
     where
         F: 'static + Send + Sync + Fn(Bytes, ModifierContext) -> Bytes,
         D: IntoIterator<Item = T>,
         T: Into<Cow<'static, str>>,
     {
-        self.modifier = Modifier::Custom {
+        self.with_fallible_modifier(dependencies, move |content, ctx| Ok(modifier(content, ctx)))
+    }
+
+    /// Like [`Self::with_modifier`], but the modifier can fail. An error
+    /// makes [`Builder::build`] fail with [`BuildError::Modifier`] in prod
+    /// mode, mentioning this asset and the assets depending on it. In dev
+    /// mode, loading the asset fails with an IO error wrapping it.
+    ///
+    /// ```ignore
+    /// builder.add_embedded("config.json", &EMBEDS["config.json"])
+    ///     .with_fallible_modifier([] as [&str; 0], |content, _| {
+    ///         let config = std::str::from_utf8(&content)
+    ///             .map_err(|e| ModifierError::with_source("config is not UTF-8", e))?;
+    ///         Ok(config.replace("$ENV", "prod").into())
+    ///     });
+    /// ```
+    pub fn with_fallible_modifier<F, D, T>(&mut self, dependencies: D, modifier: F) -> &mut Self
+    where
+        F: 'static + Send + Sync + Fn(Bytes, ModifierContext) -> Result<Bytes, ModifierError>,
+        D: IntoIterator<Item = T>,
+        T: Into<Cow<'static, str>>,
+    {
+        self.modifiers.0.push(Modifier::Custom {
             f: Arc::new(modifier),
             deps: dependencies.into_iter().map(Into::into).collect(),
-        };
+        });
+        self
+    }
+
+    /// Removes all modifiers registered for this entry, e.g. to replace ones
+    /// set by [`Builder::defaults_for`].
+    pub fn clear_modifiers(&mut self) -> &mut Self {
+        self.modifiers.0.clear();
+        self
+    }
+
+    /// Declares files that affect the content of this entry's assets without
+    /// being loaded by reinda, e.g. a `tailwind.config.js` or `.env` file
+    /// read by a modifier. In dev mode, a change of any of them is reported
+    /// for all assets of this entry by
+    /// [`Assets::subscribe_changes`][crate::Assets::subscribe_changes], and
+    /// these assets are never kept in the
+    /// [hot cache][Builder::enable_hot_cache]. Can be called multiple times.
+    /// Has no effect in prod mode, where all assets are processed once.
+    pub fn with_extra_watch<I, P>(&mut self, paths: I) -> &mut Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        self.extra_watch.extend(paths.into_iter().map(Into::into));
+        self
+    }
+
+    /// Enables precompression for this entry with the given settings,
+    /// overriding the ones passed to [`Builder::set_compression`].
+    ///
+    /// Method is only available if the crate feature `compress` is enabled.
+    #[cfg(feature = "compress")]
+    pub fn with_compression_config(&mut self, config: CompressionConfig) -> &mut Self {
+        self.compression = Some(config);
         self
     }
 
@@ -240,9 +1661,76 @@ impl<'a> EntryBuilder<'a> {
                 .iter()
                 .map(|f| f.http_path(http_prefix).into())
                 .collect(),
+            EntryBuilderKind::Dir { http_prefix, files, .. } => files
+                .iter()
+                .map(|f| f.http_path(http_prefix).into())
+                .collect(),
+        }
+    }
+
+    /// Returns `true` if this entry was added via glob or directory (and
+    /// thus might contain any number of files), `false` for single files.
+    pub fn is_glob(&self) -> bool {
+        matches!(self.kind, EntryBuilderKind::Glob { .. } | EntryBuilderKind::Dir { .. })
+    }
+
+    /// Returns the FS paths of all files of this entry that are loaded from
+    /// the file system (in the current mode). Embedded files are not
+    /// included.
+    pub fn fs_paths(&self) -> Vec<&Path> {
+        match &self.kind {
+            EntryBuilderKind::Single { source, .. } => source.fs_path().into_iter().collect(),
+            EntryBuilderKind::Glob { files, .. } => {
+                files.iter().filter_map(|f| f.source.fs_path()).collect()
+            }
+            EntryBuilderKind::Dir { files, .. } => files.iter().map(|f| &*f.fs_path).collect(),
         }
     }
 
+    /// Returns whether [`Self::with_hash`] was called for this entry.
+    pub fn is_hashed(&self) -> bool {
+        !matches!(self.path_hash, PathHash::None)
+    }
+
+    /// Returns whether a modifier was set for this entry, via
+    /// [`Self::with_modifier`] or [`Self::with_path_fixup`].
+    pub fn has_modifier(&self) -> bool {
+        !self.modifiers.is_empty()
+    }
+
+    /// Returns the modifiers of this entry, in the order they are applied.
+    pub fn modifiers(&self) -> Vec<ModifierInfo<'_>> {
+        self.modifiers.0.iter().map(Modifier::info).collect()
+    }
+
+    /// Returns the dependencies declared for all modifiers of this entry
+    /// (empty if there is no modifier). For [`Self::with_path_fixup`], these
+    /// are the paths to replace.
+    pub fn modifier_dependencies(&self) -> Vec<&Cow<'static, str>> {
+        self.modifiers.dependencies().collect()
+    }
+
+    /// Returns whether this entry is rendered as template, see
+    /// [`Self::with_template`].
+    pub fn is_template(&self) -> bool {
+        self.template.is_some()
+    }
+
+    /// Returns whether this entry is internal, see [`Self::internal`].
+    pub fn is_internal(&self) -> bool {
+        self.internal
+    }
+
+    /// Returns whether this entry is verbatim, see [`Self::verbatim`].
+    pub fn is_verbatim(&self) -> bool {
+        self.verbatim
+    }
+
+    /// Returns whether this entry is lazy, see [`Self::lazy`].
+    pub fn is_lazy(&self) -> bool {
+        self.lazy
+    }
+
     /// Like [`Self::http_paths`] but asserting that there is only one path
     /// added by this entry. If that's not the case, `None` is returned.
     pub fn single_http_path(&self) -> Option<Cow<'a, str>> {
@@ -257,10 +1745,26 @@ impl<'a> EntryBuilder<'a> {
                     None
                 }
             }
+            EntryBuilderKind::Dir { http_prefix, files, .. } => match &**files {
+                [file] => Some(file.http_path(http_prefix).into()),
+                _ => None,
+            },
         }
     }
 }
 
+fn parse_filter_pattern(pattern: &str) -> glob::Pattern {
+    glob::Pattern::new(pattern)
+        .unwrap_or_else(|e| panic!("invalid glob pattern '{}': {}", pattern, e))
+}
+
+/// Panics if the paths passed to [`Builder::add_renamed`] are invalid, given
+/// a function that returns whether an asset with a path exists.
+pub(crate) fn check_renamed(old: &str, new: &str, exists: impl Fn(&str) -> bool) {
+    assert!(!exists(old), "path '{}' was renamed to '{}', but is still an asset", old, new);
+    assert!(exists(new), "path '{}' was renamed to '{}', but that asset does not exist", old, new);
+}
+
 impl GlobFile {
     pub(crate) fn http_path(&self, http_prefix: &str) -> String {
         // suffix is split from EmbeddedFile::path
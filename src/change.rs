@@ -0,0 +1,141 @@
+//! Detecting changes of files in dev mode, for the hot cache and the
+//! watcher. See `Builder::set_change_detection`.
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::Hasher,
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+
+/// How changes of files are detected in dev mode, see
+/// [`Builder::set_change_detection`][crate::Builder::set_change_detection].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum ChangeDetection {
+    /// A file changed if its modification time or size changed. This is the
+    /// cheapest option and the default, but files touched by editors or
+    /// build tools count as changed even if their content is the same.
+    #[default]
+    Metadata,
+
+    /// A file changed if its content changed. The content is only read and
+    /// hashed if the modification time or size changed, so checking
+    /// untouched files is as cheap as with [`Self::Metadata`].
+    MetadataThenContent,
+
+    /// A file changed if its content changed, regardless of modification
+    /// time and size. Every check reads the whole file, but this also
+    /// catches changes keeping both, e.g. on file systems with coarse
+    /// timestamps.
+    Content,
+}
+
+/// Identifies a version of a file. If it changes, cached content is
+/// outdated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum FileVersion {
+    Metadata { modified: Option<SystemTime>, len: u64 },
+
+    /// Hash of the content.
+    Content(u64),
+}
+
+/// Determines `FileVersion`s as configured. For
+/// `ChangeDetection::MetadataThenContent`, the last hash of each file is
+/// remembered with its metadata.
+#[derive(Debug, Default)]
+pub(crate) struct ChangeDetector {
+    detection: ChangeDetection,
+    hashes: Mutex<Hashes>,
+}
+
+/// Modification time, length and content hash of files, by path.
+type Hashes = HashMap<PathBuf, (Option<SystemTime>, u64, u64)>;
+
+impl ChangeDetector {
+    pub(crate) fn new(detection: ChangeDetection) -> Self {
+        Self { detection, hashes: Mutex::default() }
+    }
+
+    /// Returns the current version of the file at `path`, reading it on the
+    /// blocking thread pool if its content has to be hashed.
+    pub(crate) async fn version(self: &Arc<Self>, path: &Path) -> Result<FileVersion, io::Error> {
+        if self.detection == ChangeDetection::Metadata {
+            let meta = tokio::fs::metadata(path).await?;
+            return Ok(FileVersion::Metadata { modified: meta.modified().ok(), len: meta.len() });
+        }
+
+        let (this, path) = (self.clone(), path.to_owned());
+        tokio::task::spawn_blocking(move || this.version_blocking(&path)).await?
+    }
+
+    /// Like `Self::version`, but blocks while reading the file.
+    pub(crate) fn version_blocking(&self, path: &Path) -> Result<FileVersion, io::Error> {
+        // The metadata is read before the content, so that a concurrent
+        // write at worst stores outdated metadata, causing another hash.
+        let meta = std::fs::metadata(path)?;
+        let (modified, len) = (meta.modified().ok(), meta.len());
+        let known_hash = match self.detection {
+            ChangeDetection::Metadata => return Ok(FileVersion::Metadata { modified, len }),
+            ChangeDetection::Content => None,
+            ChangeDetection::MetadataThenContent => self.lock()
+                .get(path)
+                .filter(|(m, l, _)| (*m, *l) == (modified, len))
+                .map(|(_, _, hash)| *hash),
+        };
+
+        let hash = match known_hash {
+            Some(hash) => hash,
+            None => {
+                let mut hasher = DefaultHasher::new();
+                hasher.write(&std::fs::read(path)?);
+                let hash = hasher.finish();
+                if self.detection == ChangeDetection::MetadataThenContent {
+                    self.lock().insert(path.to_owned(), (modified, len, hash));
+                }
+                hash
+            }
+        };
+        Ok(FileVersion::Content(hash))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Hashes> {
+        self.hashes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn touching_is_no_change() -> Result<(), io::Error> {
+        let path = std::env::temp_dir().join(format!("reinda-test-change-{}", std::process::id()));
+        std::fs::write(&path, "a")?;
+        let touch = |secs| std::fs::File::options().write(true).open(&path)?
+            .set_modified(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs));
+
+        let by_metadata = ChangeDetector::new(ChangeDetection::Metadata);
+        let by_content = ChangeDetector::new(ChangeDetection::MetadataThenContent);
+        let (meta_v1, content_v1) = (by_metadata.version_blocking(&path)?, by_content.version_blocking(&path)?);
+
+        touch(1_000_000)?;
+        assert_ne!(by_metadata.version_blocking(&path)?, meta_v1);
+        assert_eq!(by_content.version_blocking(&path)?, content_v1);
+
+        // Same size and modification time, different content.
+        let always_hashing = ChangeDetector::new(ChangeDetection::Content);
+        let hash_v1 = always_hashing.version_blocking(&path)?;
+        std::fs::write(&path, "b")?;
+        touch(1_000_000)?;
+        assert_ne!(always_hashing.version_blocking(&path)?, hash_v1);
+        assert_eq!(by_content.version_blocking(&path)?, content_v1);
+
+        std::fs::remove_file(&path)
+    }
+}
@@ -0,0 +1,295 @@
+//! Precompressing assets (e.g. with gzip or brotli) to serve them with a
+//! `Content-Encoding`.
+
+use std::{borrow::Cow, io::Write};
+
+use bytes::Bytes;
+
+
+/// A content encoding (compression algorithm) used for HTTP responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Encoding {
+    Gzip,
+    Brotli,
+
+    /// Only available if the crate feature `zstd` is enabled.
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Encoding {
+    /// All encodings supported by this library, in the default order of
+    /// preference: Brotli, zstd (if enabled) and gzip.
+    pub const ALL: &'static [Encoding] = &[
+        Encoding::Brotli,
+        #[cfg(feature = "zstd")]
+        Encoding::Zstd,
+        Encoding::Gzip,
+    ];
+
+    /// Returns the token used in the `Content-Encoding` and `Accept-Encoding`
+    /// headers, e.g. `br` for Brotli.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gzip",
+            Encoding::Brotli => "br",
+            #[cfg(feature = "zstd")]
+            Encoding::Zstd => "zstd",
+        }
+    }
+
+    /// Returns the file extension conventionally used for files compressed
+    /// with this encoding (without dot), e.g. `gz` for gzip.
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gz",
+            Encoding::Brotli => "br",
+            #[cfg(feature = "zstd")]
+            Encoding::Zstd => "zst",
+        }
+    }
+
+    /// Parses a token of the `Accept-Encoding` header (case-insensitively).
+    fn from_token(token: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|e| e.as_str().eq_ignore_ascii_case(token))
+    }
+}
+
+/// Settings controlling precompression of assets. See
+/// [`Builder::set_compression`][crate::Builder::set_compression] and
+/// [`EntryBuilder::with_compression_config`][crate::EntryBuilder::with_compression_config].
+///
+/// Create it via `CompressionConfig::default()` and then set the fields you
+/// want to change.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct CompressionConfig {
+    /// Compression level for gzip, from 0 to 9. Default: 9.
+    pub gzip_level: u32,
+
+    /// Compression quality for Brotli, from 0 to 11. Default: 11.
+    pub brotli_quality: u32,
+
+    /// Compression level for zstd, from 1 to 22. Default: 19.
+    ///
+    /// Field is only available if the crate feature `zstd` is enabled.
+    #[cfg(feature = "zstd")]
+    pub zstd_level: i32,
+
+    /// Assets smaller than this (in bytes) are not compressed, as the savings
+    /// would not be worth it. Default: 1024.
+    pub min_size: usize,
+
+    /// Only assets whose MIME type (guessed from the file extension) is in
+    /// this list are compressed. Entries are compared without parameters
+    /// (like `charset`). An entry ending in `/*` matches all subtypes, e.g.
+    /// `text/*`. If `None`, all assets are compressed. Default: text-like
+    /// formats (`text/*`, JS, JSON, SVG, XML, WASM).
+    pub content_type_allowlist: Option<Vec<Cow<'static, str>>>,
+
+    /// Assets whose filename ends with one of these extensions (compared
+    /// case-insensitively, without leading `.`) are never compressed. This is
+    /// meant for formats that are already compressed. Default: `png`, `jpg`,
+    /// `jpeg`, `gif`, `webp`, `avif`, `woff`, `woff2`, `zip`, `gz`, `br`,
+    /// `zst`, `mp3`, `mp4`, `webm`.
+    pub skip_extensions: Vec<Cow<'static, str>>,
+
+    /// A compressed variant is only kept if it is smaller than the original
+    /// by at least this fraction, e.g. `0.1` means it has to save at least
+    /// 10%. Otherwise serving the original is just as good and saves memory.
+    /// Default: `0.1`.
+    pub min_savings: f32,
+
+    /// Encodings to create variants for, in order of preference: if a client
+    /// accepts several of them equally, the first one is served. Set this
+    /// per entry (via
+    /// [`EntryBuilder::with_compression_config`][crate::EntryBuilder::with_compression_config])
+    /// to override the priority for some assets. Default: all supported
+    /// encodings, i.e. [`Encoding::ALL`].
+    pub encodings: Vec<Encoding>,
+
+    /// If `true`, only the smallest compressed variant is kept, instead of
+    /// one for each encoding. Saves memory, but clients not supporting that
+    /// encoding get the original. Default: `false`.
+    pub only_best_variant: bool,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            gzip_level: 9,
+            brotli_quality: 11,
+            #[cfg(feature = "zstd")]
+            zstd_level: 19,
+            min_size: 1024,
+            content_type_allowlist: Some(vec![
+                "text/*".into(),
+                "application/javascript".into(),
+                "application/json".into(),
+                "application/xml".into(),
+                "application/wasm".into(),
+                "image/svg+xml".into(),
+            ]),
+            skip_extensions: [
+                "png", "jpg", "jpeg", "gif", "webp", "avif", "woff", "woff2",
+                "zip", "gz", "br", "zst", "mp3", "mp4", "webm",
+            ].iter().map(|&ext| ext.into()).collect(),
+            min_savings: 0.1,
+            encodings: Encoding::ALL.to_vec(),
+            only_best_variant: false,
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// Returns whether the asset with the given path and content should be
+    /// compressed at all.
+    fn applies_to(&self, http_path: &str, content: &[u8]) -> bool {
+        if content.len() < self.min_size {
+            return false;
+        }
+
+        let filename = http_path.rsplit('/').next().unwrap_or(http_path);
+        let skipped = filename.rsplit_once('.').is_some_and(|(_, ext)| {
+            self.skip_extensions.iter().any(|skip| skip.eq_ignore_ascii_case(ext))
+        });
+        if skipped {
+            return false;
+        }
+
+        let Some(allowlist) = &self.content_type_allowlist else {
+            return true;
+        };
+        let Some(mime) = crate::mime::guess(http_path) else {
+            return false;
+        };
+        let mime = crate::mime::essence(mime);
+        allowlist.iter().any(|allowed| match allowed.strip_suffix("/*") {
+            Some(ty) => mime.split('/').next() == Some(ty),
+            None => allowed.eq_ignore_ascii_case(mime),
+        })
+    }
+}
+
+/// Returns all compressed variants of the given asset worth keeping,
+/// according to `config`.
+pub(crate) fn variants(
+    http_path: &str,
+    content: &[u8],
+    config: &CompressionConfig,
+) -> Vec<(Encoding, Bytes)> {
+    if !config.applies_to(http_path, content) {
+        return vec![];
+    }
+
+    let max_len = content.len() as f64 * (1.0 - config.min_savings as f64);
+    let mut out = config.encodings.iter()
+        .map(|&encoding| (encoding, compress(content, encoding, config)))
+        .filter(|(_, compressed)| (compressed.len() as f64) <= max_len)
+        .collect::<Vec<_>>();
+
+    if config.only_best_variant {
+        out.sort_by_key(|(_, compressed)| compressed.len());
+        out.truncate(1);
+    }
+
+    out
+}
+
+/// Returns the index of the encoding in `available` to use for a request with
+/// the given `Accept-Encoding` header, or `None` if the uncompressed content
+/// should be served. The encoding with the highest q-value wins, ties are
+/// broken by the order of `available`.
+pub(crate) fn negotiate(accept_encoding: &str, available: &[Encoding]) -> Option<usize> {
+    let mut explicit = Vec::new();
+    let mut wildcard = None;
+    for part in accept_encoding.split(',') {
+        let mut params = part.split(';');
+        let token = params.next().unwrap_or_default().trim();
+        let q = params
+            .filter_map(|p| p.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        if token == "*" {
+            wildcard = Some(q);
+        } else if let Some(encoding) = Encoding::from_token(token) {
+            explicit.push((encoding, q));
+        }
+    }
+
+    let q_of = |encoding: Encoding| explicit.iter()
+        .find(|(e, _)| *e == encoding)
+        .map(|(_, q)| *q)
+        .or(wildcard)
+        .unwrap_or(0.0);
+    let mut best: Option<(usize, f32)> = None;
+    for (i, &encoding) in available.iter().enumerate() {
+        let q = q_of(encoding);
+        if q > 0.0 && best.is_none_or(|(_, best_q)| q > best_q) {
+            best = Some((i, q));
+        }
+    }
+    best.map(|(i, _)| i)
+}
+
+/// Compresses `content` with the given encoding.
+pub(crate) fn compress(content: &[u8], encoding: Encoding, config: &CompressionConfig) -> Bytes {
+    let mut out = Vec::new();
+    match encoding {
+        Encoding::Gzip => {
+            let level = flate2::Compression::new(config.gzip_level.min(9));
+            let mut encoder = flate2::write::GzEncoder::new(&mut out, level);
+            encoder.write_all(content).expect("writing to Vec cannot fail");
+            encoder.finish().expect("writing to Vec cannot fail");
+        }
+        Encoding::Brotli => {
+            let params = brotli::enc::BrotliEncoderParams {
+                quality: config.brotli_quality.min(11) as i32,
+                ..Default::default()
+            };
+            brotli::BrotliCompress(&mut &*content, &mut out, &params)
+                .expect("unexpected error while compressing");
+        }
+        #[cfg(feature = "zstd")]
+        Encoding::Zstd => {
+            out = zstd::bulk::compress(content, config.zstd_level.clamp(1, 22))
+                .expect("unexpected error while compressing");
+        }
+    }
+
+    out.into()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiate_encoding() {
+        let all = [Encoding::Brotli, Encoding::Gzip];
+        assert_eq!(negotiate("gzip, deflate, br", &all), Some(0));
+        assert_eq!(negotiate("gzip, deflate, br", &[Encoding::Gzip, Encoding::Brotli]), Some(0));
+        assert_eq!(negotiate("gzip;q=1.0, br;q=0.5", &all), Some(1));
+        assert_eq!(negotiate("GZIP", &all), Some(1));
+        assert_eq!(negotiate("br;q=0, *", &all), Some(1));
+        assert_eq!(negotiate("*;q=0.1", &all), Some(0));
+        assert_eq!(negotiate("deflate", &all), None);
+        assert_eq!(negotiate("", &all), None);
+        assert_eq!(negotiate("br", &[Encoding::Gzip]), None);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn zstd() {
+        assert_eq!(Encoding::ALL, [Encoding::Brotli, Encoding::Zstd, Encoding::Gzip]);
+        assert_eq!(negotiate("gzip, zstd, br", Encoding::ALL), Some(0));
+        assert_eq!(negotiate("gzip, zstd", Encoding::ALL), Some(1));
+        assert_eq!(negotiate("gzip, zstd", &[Encoding::Gzip, Encoding::Zstd]), Some(0));
+
+        let content = "zstd ".repeat(100);
+        let compressed = compress(content.as_bytes(), Encoding::Zstd, &CompressionConfig::default());
+        assert_eq!(zstd::bulk::decompress(&compressed, content.len()).unwrap(), content.as_bytes());
+    }
+}
@@ -0,0 +1,163 @@
+//! Hashes of inline scripts and styles in HTML assets, for
+//! `Content-Security-Policy` headers. See [`Asset::csp_hashes`].
+//!
+//! Module is only available if the crate feature `hash` is enabled.
+
+use std::io;
+
+use crate::{http::ServeSettings, util::{sri_hash, SriAlgorithm}, Asset, Assets};
+
+
+/// Hashes of the inline `<script>` and `<style>` elements of an HTML asset,
+/// returned by [`Asset::csp_hashes`]. Each hash is a CSP source expression
+/// like `'sha256-<base64>'`, in document order, so they can be used in a
+/// `Content-Security-Policy` header as is:
+///
+/// ```ignore
+/// let hashes = asset.csp_hashes().await?;
+/// let csp = format!(
+///     "script-src 'self' {}; style-src 'self' {}",
+///     hashes.scripts.join(" "),
+///     hashes.styles.join(" "),
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CspHashes {
+    /// Hashes of inline `<script>` elements, for `script-src`. Scripts with
+    /// `src` attribute are not inline and thus not included.
+    pub scripts: Vec<String>,
+
+    /// Hashes of `<style>` elements, for `style-src`.
+    pub styles: Vec<String>,
+}
+
+impl CspHashes {
+    /// Returns `true` if there are neither inline scripts nor styles.
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty() && self.styles.is_empty()
+    }
+
+    /// Returns the hashes of the given asset, which are empty unless its
+    /// content type is HTML.
+    pub(crate) fn of_asset(serve: &ServeSettings, http_path: &str, content: &[u8]) -> Self {
+        let content_type = serve.content_type.as_deref().or_else(|| crate::mime::guess(http_path));
+        match content_type {
+            Some(ty) if ty.starts_with("text/html") => Self::of_html(content),
+            _ => Self::default(),
+        }
+    }
+
+    fn of_html(html: &[u8]) -> Self {
+        let hash = |content: &[u8]| format!("'{}'", sri_hash(content, SriAlgorithm::Sha256));
+        Self {
+            scripts: inline_contents(html, "script").filter(|c| !c.is_empty()).map(hash).collect(),
+            styles: inline_contents(html, "style").filter(|c| !c.is_empty()).map(hash).collect(),
+        }
+    }
+}
+
+impl Asset {
+    /// Returns the hashes of all inline `<script>` and `<style>` elements of
+    /// this asset's final content (i.e. after templates, plugins and
+    /// modifiers), so that servers can send a `Content-Security-Policy`
+    /// header allowing exactly those. For assets that are not HTML (judged
+    /// by [`Self::content_type`]), the result is empty.
+    ///
+    /// In prod mode, the hashes are computed in
+    /// [`Builder::build`][crate::Builder::build] and this never fails. In
+    /// dev mode, they are computed from the file on every call.
+    ///
+    /// Method is only available if the crate feature `hash` is enabled.
+    pub async fn csp_hashes(&self) -> Result<CspHashes, io::Error> {
+        self.0.csp_hashes().await
+    }
+}
+
+impl Assets {
+    /// Returns the [CSP hashes][Asset::csp_hashes] of the asset with the
+    /// given *hashed HTTP path*. Returns an error of kind
+    /// [`NotFound`][io::ErrorKind::NotFound] if there is no such asset.
+    ///
+    /// Method is only available if the crate feature `hash` is enabled.
+    pub async fn csp_hashes(&self, http_path: &str) -> Result<CspHashes, io::Error> {
+        match self.get(http_path) {
+            Some(asset) => asset.csp_hashes().await,
+            None => Err(io::Error::new(io::ErrorKind::NotFound, format!("no asset '{http_path}'"))),
+        }
+    }
+}
+
+/// Returns the contents of all elements with the given (lowercase) name that
+/// do not have a `src` attribute. Comments are skipped.
+fn inline_contents<'a>(html: &'a [u8], name: &'a str) -> impl 'a + Iterator<Item = &'a [u8]> {
+    let end_tag = format!("</{name}");
+    crate::util::find_tags(html, name)
+        .filter(move |tag| !in_comment(html, tag.start))
+        .filter(move |tag| !has_attribute(&html[tag.clone()], "src"))
+        .map(move |tag| {
+            let rest = &html[tag.end..];
+            let len = find_ignore_case(rest, end_tag.as_bytes()).unwrap_or(rest.len());
+            &rest[..len]
+        })
+}
+
+/// Returns whether `pos` is inside an HTML comment.
+fn in_comment(html: &[u8], pos: usize) -> bool {
+    let before = &html[..pos];
+    let last_open = before.windows(4).rposition(|w| w == b"<!--");
+    let last_close = before.windows(3).rposition(|w| w == b"-->");
+    match (last_open, last_close) {
+        (Some(open), Some(close)) => open > close,
+        (Some(_), None) => true,
+        _ => false,
+    }
+}
+
+fn has_attribute(tag: &[u8], name: &str) -> bool {
+    tag.windows(name.len() + 2).any(|w| {
+        w[0].is_ascii_whitespace()
+            && w[1..=name.len()].eq_ignore_ascii_case(name.as_bytes())
+            && matches!(w[name.len() + 1], b'=' | b'>' | b'/' | b' ' | b'\t' | b'\n' | b'\r')
+    })
+}
+
+fn find_ignore_case(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w.eq_ignore_ascii_case(needle))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(content: &str) -> String {
+        format!("'{}'", sri_hash(content.as_bytes(), SriAlgorithm::Sha256))
+    }
+
+    #[test]
+    fn inline_elements() {
+        let html = concat!(
+            "<html><head><style>body { color: red; }</style>",
+            "<script src=\"/app.js\"></script>",
+            "<SCRIPT type=module>import './a.js';</SCRIPT>",
+            "<!-- <script>commented()</script> -->",
+            "</head><body><scripts></scripts><script>\nrun();\n</script><script></script></body></html>",
+        );
+        let hashes = CspHashes::of_html(html.as_bytes());
+        assert_eq!(hashes.scripts, [hash("import './a.js';"), hash("\nrun();\n")]);
+        assert_eq!(hashes.styles, [hash("body { color: red; }")]);
+
+        assert!(CspHashes::of_html(b"<p>no scripts</p>").is_empty());
+        assert!(has_attribute(b"<script defer src='a.js'>", "src"));
+        assert!(!has_attribute(b"<script data-src='a.js'>", "src"));
+    }
+
+    #[test]
+    fn only_html() {
+        let serve = ServeSettings::default();
+        let html = b"<script>x()</script>";
+        assert_eq!(CspHashes::of_asset(&serve, "index.html", html).scripts, [hash("x()")]);
+        assert!(CspHashes::of_asset(&serve, "notes.txt", html).is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+//! Directories mounted via `Builder::add_dir`: walking them and filtering
+//! their files. The filter is also used for glob entries.
+
+use std::{
+    fmt, io,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+
+/// Filter callback, see `EntryBuilder::with_filter`.
+pub(crate) type PathPredicate = Arc<dyn Send + Sync + Fn(&str) -> bool>;
+
+/// Include and exclude patterns and filter callbacks of a directory or glob
+/// entry, see `EntryBuilder::with_include`, `EntryBuilder::with_exclude` and
+/// `EntryBuilder::with_filter`. Paths are relative to the directory or the
+/// fixed prefix of the glob, with `/` as separator. `*` also matches `/`, so
+/// `*.map` excludes source maps in all subdirectories.
+#[derive(Clone, Default)]
+pub(crate) struct PathFilter {
+    pub(crate) include: Vec<glob::Pattern>,
+    pub(crate) exclude: Vec<glob::Pattern>,
+    pub(crate) predicates: Vec<PathPredicate>,
+}
+
+impl PathFilter {
+    /// Returns whether the file with the given relative path is mounted: if
+    /// there are include patterns, it has to match one of them, it must not
+    /// match any exclude pattern and all predicates have to return `true`.
+    pub(crate) fn matches(&self, relative_path: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| p.matches(relative_path)))
+            && !self.exclude.iter().any(|p| p.matches(relative_path))
+            && self.predicates.iter().all(|f| f(relative_path))
+    }
+
+    /// Returns `true` if this filter lets all files through.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty() && self.predicates.is_empty()
+    }
+
+    /// Returns the file in `dir` that would be mounted under `relative_path`,
+    /// if it passes the filter and is a regular file. Paths leaving `dir`
+    /// (e.g. via `..`) are rejected. Like [`walk`], this does not follow
+    /// symlinks to directories, so they cannot be used to escape `dir`
+    /// either, but the file itself may be a symlink.
+    #[cfg_attr(prod_mode, allow(dead_code))]
+    pub(crate) fn find(&self, dir: &Path, relative_path: &str) -> Option<PathBuf> {
+        let stays_inside = Path::new(relative_path).components()
+            .all(|c| matches!(c, Component::Normal(_)));
+        if !stays_inside || !self.matches(relative_path) {
+            return None;
+        }
+
+        let mut path = dir.to_owned();
+        let mut components = Path::new(relative_path).components().peekable();
+        while let Some(component) = components.next() {
+            path.push(component);
+            let is_parent = components.peek().is_some();
+            if is_parent && path.symlink_metadata().ok()?.file_type().is_symlink() {
+                return None;
+            }
+        }
+        path.is_file().then_some(path)
+    }
+}
+
+impl fmt::Debug for PathFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PathFilter")
+            .field("include", &self.include)
+            .field("exclude", &self.exclude)
+            .field("predicates", &self.predicates.len())
+            .finish()
+    }
+}
+
+/// A file found in a mounted directory.
+#[derive(Debug, Clone)]
+pub(crate) struct DirFile {
+    /// Path relative to the directory, with `/` as separator.
+    pub(crate) relative_path: String,
+    pub(crate) fs_path: PathBuf,
+}
+
+impl DirFile {
+    pub(crate) fn http_path(&self, http_prefix: &str) -> String {
+        format!("{http_prefix}{}", self.relative_path)
+    }
+}
+
+/// Recursively collects all files in `dir` passing `filter`, sorted by
+/// relative path. Symlinks to files are followed, symlinks to directories
+/// are not, so that cycles are impossible.
+pub(crate) async fn walk(dir: &Path, filter: &PathFilter) -> Result<Vec<DirFile>, io::Error> {
+    let (dir, filter) = (dir.to_owned(), filter.clone());
+    tokio::task::spawn_blocking(move || walk_blocking(&dir, &filter))
+        .await
+        .map_err(io::Error::other)?
+}
+
+/// Like [`walk`], but blocking.
+pub(crate) fn walk_blocking(dir: &Path, filter: &PathFilter) -> Result<Vec<DirFile>, io::Error> {
+    let mut out = vec![];
+    let mut pending = vec![(dir.to_owned(), String::new())];
+    while let Some((fs_dir, prefix)) = pending.pop() {
+        for entry in std::fs::read_dir(&fs_dir)? {
+            let entry = entry?;
+            let Some(name) = entry.file_name().to_str().map(ToOwned::to_owned) else {
+                log::warn!(
+                    target: "reinda",
+                    "skipping '{}': file name is not valid UTF-8",
+                    entry.path().display(),
+                );
+                continue;
+            };
+            let relative_path = format!("{prefix}{name}");
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push((entry.path(), format!("{relative_path}/")));
+                continue;
+            }
+
+            let is_file = file_type.is_file()
+                || (file_type.is_symlink() && std::fs::metadata(entry.path())?.is_file());
+            if is_file && filter.matches(&relative_path) {
+                out.push(DirFile { relative_path, fs_path: entry.path() });
+            }
+        }
+    }
+
+    out.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    Ok(out)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(include: &[&str], exclude: &[&str]) -> PathFilter {
+        let patterns = |ps: &[&str]| ps.iter().map(|p| glob::Pattern::new(p).unwrap()).collect();
+        PathFilter { include: patterns(include), exclude: patterns(exclude), predicates: vec![] }
+    }
+
+    #[test]
+    fn filtering() {
+        let all = PathFilter::default();
+        assert!(all.matches("index.html"));
+        assert!(all.matches("a/b/c.js"));
+
+        let f = filter(&["*.js", "*.css"], &["*.map", "vendor/*"]);
+        assert!(f.matches("app.js"));
+        assert!(f.matches("deep/style.css"));
+        assert!(!f.matches("index.html"));
+        assert!(!f.matches("vendor/lib.js"));
+
+        let f = filter(&[], &["*.map"]);
+        assert!(f.matches("index.html"));
+        assert!(!f.matches("js/app.js.map"));
+
+        let mut f = filter(&["*.js"], &[]);
+        f.predicates.push(Arc::new(|path| !path.ends_with(".test.js")));
+        assert!(f.matches("app.js"));
+        assert!(!f.matches("app.test.js"));
+        assert!(!f.matches("style.css"));
+    }
+
+    #[test]
+    fn no_escaping() {
+        let all = PathFilter::default();
+        let dir = Path::new("tests/files");
+        assert!(all.find(dir, "peter.txt").is_some());
+        assert!(all.find(dir, "../files/peter.txt").is_none());
+        assert!(all.find(dir, "/etc/passwd").is_none());
+        assert!(all.find(dir, "img").is_none());
+        assert!(filter(&[], &["*.txt"]).find(dir, "peter.txt").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn no_escaping_via_symlinks() {
+        let dir = std::env::temp_dir().join(format!("reinda-dir-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let files = std::fs::canonicalize("tests/files").unwrap();
+        std::os::unix::fs::symlink(&files, dir.join("linked")).unwrap();
+        std::os::unix::fs::symlink(files.join("peter.txt"), dir.join("peter.txt")).unwrap();
+
+        let all = PathFilter::default();
+        assert!(all.find(&dir, "linked/peter.txt").is_none());
+        assert!(all.find(&dir, "peter.txt").is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
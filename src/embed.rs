@@ -37,11 +37,17 @@ pub struct EmbeddedGlob {
     #[doc(hidden)]
     pub files: &'static [EmbeddedFile],
 
-    /// Base path specified in the macro, only used to prefix the `pattern` for
-    /// loading files in dev mode.
+    /// Base paths specified in the macro (in order), only used to prefix the
+    /// `pattern` for loading files in dev mode.
     #[cfg(dev_mode)]
     #[doc(hidden)]
-    pub base_path: &'static str,
+    pub base_paths: &'static [&'static str],
+
+    /// Patterns specified with `except` in the macro, only used to skip
+    /// files in dev mode. In prod mode, `files` is already filtered.
+    #[cfg(dev_mode)]
+    #[doc(hidden)]
+    pub except: &'static [&'static str],
 }
 
 /// A single file embedded by [`embed!`][super::embed!].
@@ -61,10 +67,35 @@ pub struct EmbeddedFile {
     #[doc(hidden)]
     pub content: &'static [u8],
 
+    /// The file contents, if the file is marked with `always_embed` in the
+    /// macro. Otherwise, it's loaded from `full_path` in dev mode.
+    #[cfg(dev_mode)]
+    #[doc(hidden)]
+    pub content: Option<&'static [u8]>,
+
     /// Whether the `content` field is compressed.
     #[cfg(prod_mode)]
     #[doc(hidden)]
     pub compressed: bool,
+
+    /// If set, the content is not stored in `content` but in a sidecar file.
+    #[cfg(prod_mode)]
+    #[doc(hidden)]
+    pub sidecar: Option<SidecarLocation>,
+}
+
+/// Location of a file's content inside a sidecar file (see `sidecar` in
+/// [`embed!`][super::embed!]).
+#[cfg(prod_mode)]
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy)]
+pub struct SidecarLocation {
+    /// Path of the sidecar file relative to the executable's directory.
+    pub path: &'static str,
+    pub offset: usize,
+    pub len: usize,
+    /// Hex encoded SHA-256 of the stored (potentially compressed) content.
+    pub sha256: &'static str,
 }
 
 impl Embeds {
@@ -132,6 +163,12 @@ impl EmbeddedEntry {
             EmbeddedEntry::Glob(glob) => glob.files.iter(),
         }
     }
+
+    /// Returns the sum of [`EmbeddedFile::embedded_size`] of all files in
+    /// this entry.
+    pub fn embedded_size(&self) -> usize {
+        self.files().map(EmbeddedFile::embedded_size).sum()
+    }
 }
 
 impl From<EmbeddedGlob> for EmbeddedEntry {
@@ -152,7 +189,8 @@ impl EmbeddedGlob {
         self.pattern
     }
 
-    /// Iterator over all files matching the glob pattern found at build time.
+    /// Iterator over all files matching the glob pattern found at build time,
+    /// sorted by [path][EmbeddedFile::path] (byte-wise).
     pub fn files(&self) -> impl Iterator<Item = &'static EmbeddedFile> {
         self.files.iter()
     }
@@ -169,31 +207,50 @@ impl EmbeddedFile {
         self.path
     }
 
+    /// Returns the number of bytes this file occupies in the executable, i.e.
+    /// the size of the potentially compressed content. Always 0 for files
+    /// stored in a sidecar file and in dev mode, unless the file is marked
+    /// with `always_embed`.
+    pub fn embedded_size(&self) -> usize {
+        #[cfg(prod_mode)]
+        { self.content.len() }
+
+        #[cfg(dev_mode)]
+        { self.content.map_or(0, <[u8]>::len) }
+    }
+
     /// Returns the contents of the embedded file. This method might decompress
     /// data, so try calling it only once for each file to avoid doing
     /// duplicate work.
+    ///
+    /// If the file is stored in a sidecar file, that is loaded (once)
+    /// synchronously. **Panics** if that fails.
     #[cfg(prod_mode)]
     pub fn content(&self) -> std::borrow::Cow<'static, [u8]> {
-        #[cfg(feature = "compress")]
-        if self.compressed {
-            let mut decompressed = Vec::new();
-            brotli::BrotliDecompress(&mut &*self.content, &mut decompressed)
-                .expect("unexpected error while decompressing Brotli");
-            decompressed.into()
-        } else {
-            self.content.into()
+        #[cfg(feature = "sidecar")]
+        if let Some(location) = &self.sidecar {
+            let stored = crate::sidecar::load(location)
+                .unwrap_or_else(|e| panic!("failed to load '{}' from sidecar: {e}", self.path));
+            return maybe_decompress(&stored, self.compressed).into_owned().into();
         }
 
-        #[cfg(not(feature = "compress"))]
-        { self.content.into() }
+        maybe_decompress(self.content, self.compressed)
     }
 
     pub(crate) fn data_source(&self) -> DataSource {
         #[cfg(dev_mode)]
-        { DataSource::File(self.full_path.into()) }
+        match self.content {
+            Some(content) => DataSource::Loaded(bytes::Bytes::from_static(content)),
+            None => DataSource::File(self.full_path.into()),
+        }
 
         #[cfg(prod_mode)]
         {
+            #[cfg(feature = "sidecar")]
+            if let Some(location) = self.sidecar {
+                return DataSource::Sidecar { location, compressed: self.compressed };
+            }
+
             let bytes = match self.content() {
                 std::borrow::Cow::Borrowed(slice) => slice.into(),
                 std::borrow::Cow::Owned(vec) => vec.into(),
@@ -202,3 +259,20 @@ impl EmbeddedFile {
         }
     }
 }
+
+/// Decompresses data stored by the `embed!` macro, if `compressed` is true.
+#[cfg(prod_mode)]
+pub(crate) fn maybe_decompress(data: &[u8], compressed: bool) -> std::borrow::Cow<'_, [u8]> {
+    #[cfg(feature = "compress")]
+    if compressed {
+        let mut decompressed = Vec::new();
+        brotli::BrotliDecompress(&mut &*data, &mut decompressed)
+            .expect("unexpected error while decompressing Brotli");
+        return decompressed.into();
+    }
+
+    #[cfg(not(feature = "compress"))]
+    let _ = compressed;
+
+    data.into()
+}
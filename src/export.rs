@@ -0,0 +1,254 @@
+//! Writing built assets to a directory, e.g. to upload them to a CDN.
+
+use std::{
+    collections::HashSet,
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::Assets;
+
+
+/// Name of the file in the output directory listing all files written by
+/// exports to it, relative to the directory, one per line.
+const EXPORT_LIST: &str = ".reinda-export";
+
+/// Options for [`Assets::write_to_dir_with`].
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    #[cfg_attr(not(feature = "hash"), allow(dead_code))]
+    checksums: bool,
+    #[cfg_attr(not(feature = "compress"), allow(dead_code))]
+    precompressed: bool,
+    remove_stale: bool,
+}
+
+impl ExportOptions {
+    /// Returns the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also write a `SHA256SUMS` file into the root of the output directory,
+    /// listing the SHA-256 of all written assets, and a `<file>.sha256` file
+    /// next to each asset. Both use the format of `sha256sum`, so they can
+    /// be verified with `sha256sum -c`.
+    ///
+    /// Method is only available if the crate feature `hash` is enabled.
+    #[cfg(feature = "hash")]
+    pub fn with_checksums(mut self) -> Self {
+        self.checksums = true;
+        self
+    }
+
+    /// Also write the precompressed variants of each asset next to it, with
+    /// the extension of the encoding appended (e.g. `app.js.br` and
+    /// `app.js.gz`, see [`Encoding::file_extension`][crate::Encoding::file_extension]).
+    /// Servers like NGINX (`gzip_static`) and most CDNs can serve those
+    /// directly. Variants only exist for assets with precompression enabled,
+    /// see [`Builder::set_compression`][crate::Builder::set_compression].
+    ///
+    /// Method is only available if the crate feature `compress` is enabled.
+    #[cfg(feature = "compress")]
+    pub fn with_precompressed(mut self) -> Self {
+        self.precompressed = true;
+        self
+    }
+
+    /// Delete files written by previous exports into the output directory
+    /// that were not written by this export, e.g. assets whose hashed path
+    /// changed. Directories that become empty are deleted as well. Only files
+    /// listed in the `.reinda-export` file, which each export writes into the
+    /// output directory, are considered, so other files are never deleted.
+    pub fn remove_stale(mut self) -> Self {
+        self.remove_stale = true;
+        self
+    }
+}
+
+impl Assets {
+    /// Writes all assets into the directory `dir`, each under its *hashed HTTP
+    /// path*. Missing directories are created and existing files overwritten.
+    /// The written files are exactly what [`Asset::content`][crate::Asset::content]
+    /// returns, i.e. with all modifiers applied. Precompressed variants can
+    /// be written as well, see [`ExportOptions::with_precompressed`].
+    ///
+    /// The export is incremental: files that already exist with the exact same
+    /// content are not touched (keeping their modification time, which helps
+    /// `rsync`-like deploys and CI caches). The written files are listed in
+    /// `.reinda-export` in `dir`. Files of previous exports that were not
+    /// written again are only deleted with [`ExportOptions::remove_stale`].
+    ///
+    /// For assets included via glob, only files found at compile time are
+    /// written (see [`Self::iter`]).
+    pub async fn write_to_dir(&self, dir: impl AsRef<Path>) -> Result<(), io::Error> {
+        self.write_to_dir_with(dir, &ExportOptions::default()).await
+    }
+
+    /// Like [`Self::write_to_dir`], but with additional options.
+    pub async fn write_to_dir_with(
+        &self,
+        dir: impl AsRef<Path>,
+        options: &ExportOptions,
+    ) -> Result<(), io::Error> {
+        let dir = dir.as_ref();
+
+        // Sort to make the output (e.g. the checksum file) deterministic.
+        let mut assets = self.iter().collect::<Vec<_>>();
+        assets.sort_unstable_by_key(|(http_path, _)| *http_path);
+
+        #[cfg(feature = "hash")]
+        let mut checksums = options.checksums.then(String::new);
+
+        let mut written = HashSet::new();
+        let mut unchanged = 0;
+        for (http_path, asset) in assets {
+            let content = asset.content().await?;
+            let fs_path = fs_path_for(dir, http_path)?;
+            if let Some(parent) = fs_path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            unchanged += !write_if_changed(&fs_path, &content).await? as usize;
+
+            #[cfg(feature = "compress")]
+            if options.precompressed {
+                for (encoding, compressed) in asset.0.encoded_variants().await? {
+                    let mut path = fs_path.clone().into_os_string();
+                    path.push(format!(".{}", encoding.file_extension()));
+                    let path = PathBuf::from(path);
+                    unchanged += !write_if_changed(&path, &compressed).await? as usize;
+                    written.insert(path);
+                }
+            }
+
+            #[cfg(feature = "hash")]
+            if let Some(checksums) = &mut checksums {
+                let hash = crate::sha256_hex(&content);
+                let filename = http_path.rsplit('/').next().unwrap_or(http_path);
+                let mut sidecar = fs_path.clone().into_os_string();
+                sidecar.push(".sha256");
+                let sidecar = PathBuf::from(sidecar);
+                write_if_changed(&sidecar, format!("{hash}  {filename}\n").as_bytes()).await?;
+                written.insert(sidecar);
+
+                let relative = http_path.trim_start_matches('/');
+                checksums.push_str(&format!("{hash}  {relative}\n"));
+            }
+
+            written.insert(fs_path);
+        }
+
+        #[cfg(feature = "hash")]
+        if let Some(checksums) = checksums {
+            let path = dir.join("SHA256SUMS");
+            write_if_changed(&path, checksums.as_bytes()).await?;
+            written.insert(path);
+        }
+
+        // Files of previous exports are forgotten once they are removed.
+        let stale = read_export_list(dir).await?
+            .into_iter()
+            .filter(|path| !written.contains(path))
+            .collect::<Vec<_>>();
+        let removed = if options.remove_stale {
+            remove_files(dir, &stale).await?
+        } else {
+            written.extend(stale);
+            0
+        };
+        write_export_list(dir, &written).await?;
+
+        log::debug!(
+            target: "reinda",
+            "exported assets to '{}': {} files unchanged, {} stale files removed",
+            dir.display(),
+            unchanged,
+            removed,
+        );
+
+        Ok(())
+    }
+}
+
+/// Writes `content` to `path`, unless the file already has exactly that
+/// content. Returns whether the file was written.
+async fn write_if_changed(path: &Path, content: &[u8]) -> Result<bool, io::Error> {
+    let is_unchanged = match tokio::fs::metadata(path).await {
+        Ok(meta) if meta.is_file() && meta.len() == content.len() as u64 => {
+            tokio::fs::read(path).await? == content
+        }
+        _ => false,
+    };
+
+    if !is_unchanged {
+        tokio::fs::write(path, content).await?;
+    }
+    Ok(!is_unchanged)
+}
+
+/// Returns the files listed in the export list in `dir`, or nothing if
+/// there is none. Entries that would escape `dir` are ignored.
+async fn read_export_list(dir: &Path) -> Result<Vec<PathBuf>, io::Error> {
+    match tokio::fs::read_to_string(dir.join(EXPORT_LIST)).await {
+        Ok(list) => Ok(list.lines().filter_map(|line| fs_path_for(dir, line).ok()).collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(e),
+    }
+}
+
+/// Writes the export list in `dir`, listing all `files` (which are inside
+/// `dir`) sorted by path.
+async fn write_export_list(dir: &Path, files: &HashSet<PathBuf>) -> Result<(), io::Error> {
+    let mut relative = files.iter()
+        .filter_map(|path| path.strip_prefix(dir).ok())
+        .map(|path| {
+            path.components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .collect::<Vec<_>>();
+    relative.sort_unstable();
+    let list = relative.into_iter().map(|path| path + "\n").collect::<String>();
+    write_if_changed(&dir.join(EXPORT_LIST), list.as_bytes()).await?;
+    Ok(())
+}
+
+/// Deletes `files` (which are inside `dir`) and afterwards all of their
+/// parent directories that became empty by that, except `dir` itself. Files
+/// that do not exist (anymore) are skipped. Returns the number of deleted
+/// files.
+async fn remove_files(dir: &Path, files: &[PathBuf]) -> Result<usize, io::Error> {
+    let mut removed = 0;
+    for path in files {
+        match tokio::fs::remove_file(path).await {
+            Ok(()) => removed += 1,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        }
+
+        for parent in path.ancestors().skip(1).take_while(|p| *p != dir) {
+            match tokio::fs::read_dir(parent).await?.next_entry().await? {
+                None => tokio::fs::remove_dir(parent).await?,
+                Some(_) => break,
+            }
+        }
+    }
+
+    Ok(removed)
+}
+
+/// Returns the path to which the asset with the given HTTP path is written.
+/// Returns an error if the HTTP path would escape `dir`.
+fn fs_path_for(dir: &Path, http_path: &str) -> Result<PathBuf, io::Error> {
+    let relative = Path::new(http_path.trim_start_matches('/'));
+    let is_valid = relative.components().all(|c| matches!(c, Component::Normal(_)));
+    if !is_valid || http_path.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("HTTP path '{http_path}' cannot be used as file path"),
+        ));
+    }
+
+    Ok(dir.join(relative))
+}
@@ -0,0 +1,210 @@
+//! In-memory cache of the most requested assets loaded from the file system,
+//! see `Builder::enable_hot_cache`.
+
+use std::{
+    collections::HashMap,
+    sync::{atomic::{AtomicU64, Ordering}, Mutex},
+};
+
+use bytes::Bytes;
+
+use crate::change::FileVersion;
+#[cfg(feature = "compress")]
+use crate::Encoding;
+
+
+/// Metrics of the hot cache, returned by [`Assets::hot_cache_stats`][crate::Assets::hot_cache_stats].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct HotCacheStats {
+    /// Maximum number of cached assets, as passed to
+    /// [`Builder::enable_hot_cache`][crate::Builder::enable_hot_cache].
+    pub capacity: usize,
+
+    /// Number of currently cached assets.
+    pub entries: usize,
+
+    /// Total size of the cached content in bytes, including compressed
+    /// variants.
+    pub bytes: u64,
+
+    /// Number of times the content of an asset was taken from the cache.
+    pub hits: u64,
+
+    /// Number of times the content of a cacheable asset had to be loaded,
+    /// because it was not cached or its file had changed.
+    pub misses: u64,
+}
+
+#[derive(Debug)]
+pub(crate) struct HotCache {
+    capacity: usize,
+    state: Mutex<State>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Number of requests per *unhashed HTTP path*, also for assets that are
+    /// not cached (yet).
+    requests: HashMap<String, u64>,
+    entries: HashMap<String, Entry>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    version: FileVersion,
+    content: Bytes,
+    #[cfg(feature = "compress")]
+    variants: Option<Vec<(Encoding, Bytes)>>,
+}
+
+impl Entry {
+    fn size(&self) -> u64 {
+        #[allow(unused_mut)]
+        let mut size = self.content.len();
+        #[cfg(feature = "compress")]
+        {
+            size += self.variants.iter().flatten().map(|(_, v)| v.len()).sum::<usize>();
+        }
+        size as u64
+    }
+}
+
+impl HotCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::default(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Counts a request for `http_path` and returns its cached content, if
+    /// it's still of the given version.
+    pub(crate) fn get(&self, http_path: &str, version: FileVersion) -> Option<Bytes> {
+        let mut state = self.lock();
+        *state.requests.entry(http_path.to_owned()).or_default() += 1;
+        let out = match state.entries.get(http_path) {
+            Some(entry) if entry.version == version => Some(entry.content.clone()),
+            Some(_) => {
+                state.entries.remove(http_path);
+                None
+            }
+            None => None,
+        };
+
+        let counter = if out.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        out
+    }
+
+    /// Caches `content` if there is room or if `http_path` was requested more
+    /// often than the least requested cached asset, which is then evicted.
+    pub(crate) fn insert(&self, http_path: &str, version: FileVersion, content: Bytes) {
+        let mut state = self.lock();
+        let state = &mut *state;
+        if !state.entries.contains_key(http_path) && state.entries.len() >= self.capacity {
+            let requests = |path: &str| state.requests.get(path).copied().unwrap_or(0);
+            let Some(coldest) = state.entries.keys()
+                .min_by_key(|path| requests(path))
+                .filter(|coldest| requests(coldest) < requests(http_path))
+                .cloned()
+            else {
+                return;
+            };
+            log::trace!(target: "reinda", "evicting '{coldest}' from hot cache for '{http_path}'");
+            state.entries.remove(&coldest);
+        }
+
+        state.entries.insert(http_path.to_owned(), Entry {
+            version,
+            content,
+            #[cfg(feature = "compress")]
+            variants: None,
+        });
+    }
+
+    /// Returns the cached compressed variants of `http_path`, if the asset
+    /// is cached in the given version and they were stored before. Only
+    /// that counts as request, as otherwise the content is loaded via `get`.
+    #[cfg(feature = "compress")]
+    pub(crate) fn variants(&self, http_path: &str, version: FileVersion) -> Option<Vec<(Encoding, Bytes)>> {
+        let mut state = self.lock();
+        let variants = state.entries.get(http_path)
+            .filter(|entry| entry.version == version)
+            .and_then(|entry| entry.variants.clone())?;
+        *state.requests.entry(http_path.to_owned()).or_default() += 1;
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        Some(variants)
+    }
+
+    /// Stores compressed variants for an asset that is cached in the given
+    /// version. Does nothing otherwise.
+    #[cfg(feature = "compress")]
+    pub(crate) fn set_variants(
+        &self,
+        http_path: &str,
+        version: FileVersion,
+        variants: &[(Encoding, Bytes)],
+    ) {
+        if let Some(entry) = self.lock().entries.get_mut(http_path) {
+            if entry.version == version {
+                entry.variants = Some(variants.to_vec());
+            }
+        }
+    }
+
+    pub(crate) fn stats(&self) -> HotCacheStats {
+        let state = self.lock();
+        HotCacheStats {
+            capacity: self.capacity,
+            entries: state.entries.len(),
+            bytes: state.entries.values().map(Entry::size).sum(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_most_requested() {
+        let cache = HotCache::new(2);
+        let v = FileVersion::Content(1);
+        let request = |path: &str, n| {
+            for _ in 0..n {
+                if cache.get(path, v).is_none() {
+                    cache.insert(path, v, Bytes::from(path.to_owned()));
+                }
+            }
+        };
+
+        request("a", 3);
+        request("b", 2);
+        request("c", 1);
+        assert_eq!(cache.stats().entries, 2);
+        assert!(cache.get("c", v).is_none());
+        assert!(cache.get("c", v).is_none());
+
+        // `c` was now requested three times, more than `b`.
+        cache.insert("c", v, Bytes::from("c"));
+        assert_eq!(cache.get("c", v).as_deref(), Some(&b"c"[..]));
+        assert!(cache.get("b", v).is_none());
+
+        // Changed files are not served from the cache.
+        let changed = FileVersion::Content(2);
+        assert!(cache.get("a", changed).is_none());
+        assert_eq!(cache.stats().entries, 1);
+    }
+}
@@ -0,0 +1,1182 @@
+//! Serving assets via HTTP, independent of any specific web framework.
+//!
+//! [`Assets::serve`] turns a [`Request`] into a [`Response`] with the correct
+//! status code, headers and body. Integrating it into a web framework only
+//! requires converting between these types and the framework's own ones.
+//!
+//! ```ignore
+//! let req = reinda::http::Request::new(method.as_str(), uri.path())
+//!     .with_header("accept-encoding", accept_encoding);
+//! let response = assets.serve(&req).await;
+//! // Convert `response.status()`, `response.headers()` and
+//! // `response.body()` into your framework's response type.
+//! ```
+
+use std::{borrow::Cow, fmt, ops::Range, path::PathBuf, sync::Arc, time::Duration};
+
+use bytes::Bytes;
+
+use crate::{util::json_string, Assets};
+
+
+/// The parts of an HTTP request that are relevant for serving assets.
+#[derive(Debug, Clone)]
+pub struct Request<'a> {
+    method: &'a str,
+    path: &'a str,
+    headers: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Request<'a> {
+    /// Creates a request with the given method (e.g. `"GET"`) and path. The
+    /// path may start with `/` and may include a query string, both of which
+    /// are ignored for the lookup. It is percent-decoded; paths containing an
+    /// encoded `/` or a `..` segment are answered with `404 Not Found`.
+    pub fn new(method: &'a str, path: &'a str) -> Self {
+        Self { method, path, headers: vec![] }
+    }
+
+    /// Shorthand for `Request::new("GET", path)`.
+    pub fn get(path: &'a str) -> Self {
+        Self::new("GET", path)
+    }
+
+    /// Adds a header to this request. Header names are case-insensitive.
+    pub fn with_header(mut self, name: &'a str, value: &'a str) -> Self {
+        self.headers.push((name, value));
+        self
+    }
+
+    /// Returns the request method.
+    pub fn method(&self) -> &'a str {
+        self.method
+    }
+
+    /// Returns the request path, as passed to [`Self::new`].
+    pub fn path(&self) -> &'a str {
+        self.path
+    }
+
+    /// Returns the value of the first header with the given name (compared
+    /// case-insensitively).
+    pub fn header(&self, name: &str) -> Option<&'a str> {
+        self.headers.iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| *v)
+    }
+
+    /// Returns a copy of this request with a different path.
+    pub(crate) fn with_path(&self, path: &'a str) -> Self {
+        Self { path, ..self.clone() }
+    }
+
+    /// Returns the path used for the asset lookup, i.e. percent-decoded,
+    /// without leading `/` and without query string. Returns `None` if the
+    /// path is not valid UTF-8 after decoding, contains an encoded `/` or a
+    /// `..` segment.
+    pub(crate) fn lookup_path(&self) -> Option<Cow<'a, str>> {
+        let path = self.path.split_once('?').map_or(self.path, |(path, _)| path);
+        let path = percent_decode(path.trim_start_matches('/'))?;
+        if path.split('/').any(|segment| segment == "..") {
+            return None;
+        }
+        Some(path)
+    }
+}
+
+/// Decodes `%XX` escapes. Returns `None` for invalid escapes, encoded `/`
+/// and results that are not valid UTF-8.
+fn percent_decode(s: &str) -> Option<Cow<'_, str>> {
+    if !s.contains('%') {
+        return Some(Cow::Borrowed(s));
+    }
+
+    let mut out = Vec::with_capacity(s.len());
+    let mut bytes = s.bytes();
+    while let Some(b) = bytes.next() {
+        if b != b'%' {
+            out.push(b);
+            continue;
+        }
+        let hex = [bytes.next()?, bytes.next()?];
+        let decoded = u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?;
+        if decoded == b'/' {
+            return None;
+        }
+        out.push(decoded);
+    }
+    String::from_utf8(out).ok().map(Cow::Owned)
+}
+
+
+/// A response to a [`Request`], created by [`Assets::serve`].
+#[derive(Debug, Clone)]
+pub struct Response {
+    status: u16,
+    headers: Vec<(Cow<'static, str>, String)>,
+    body: Bytes,
+    compressible: bool,
+}
+
+impl Response {
+    pub(crate) fn new(status: u16, body: impl Into<Bytes>) -> Self {
+        Self { status, headers: vec![], body: body.into(), compressible: true }
+    }
+
+    pub(crate) fn with_header(mut self, name: impl Into<Cow<'static, str>>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub(crate) fn text(status: u16, body: impl Into<Bytes>) -> Self {
+        Self::new(status, body).with_header("content-type", "text/plain; charset=utf-8")
+    }
+
+    /// Returns the HTTP status code.
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// Returns all headers. Header names are lowercase.
+    pub fn headers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.headers.iter().map(|(n, v)| (&**n, &**v))
+    }
+
+    /// Returns the value of the first header with the given name (compared
+    /// case-insensitively).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v)
+    }
+
+    /// Returns `false` if the served asset opted out of compression via
+    /// [`EntryBuilder::with_no_compression`][crate::EntryBuilder::with_no_compression].
+    /// Integrations compressing responses on the fly should skip those.
+    pub fn is_compressible(&self) -> bool {
+        self.compressible
+    }
+
+    /// Returns the body.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Splits this response into status, headers and body.
+    pub fn into_parts(self) -> (u16, Vec<(Cow<'static, str>, String)>, Bytes) {
+        (self.status, self.headers, self.body)
+    }
+}
+
+
+/// Controls whether source maps (files ending in `.map`) are served by
+/// [`Assets::serve`]. See [`Builder::set_source_map_policy`][crate::Builder::set_source_map_policy].
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub enum SourceMapPolicy {
+    /// Source maps are served like all other assets. The default.
+    #[default]
+    Always,
+
+    /// Source maps are never served, requests for them get a 404.
+    Never,
+
+    /// Source maps are only served to requests that have a header `name`
+    /// with exactly the value `value`, e.g. a secret token configured in the
+    /// browser's dev tools. All other requests get a 404.
+    WithHeader {
+        name: Cow<'static, str>,
+        value: Cow<'static, str>,
+    },
+}
+
+impl SourceMapPolicy {
+    fn allows(&self, req: &Request<'_>) -> bool {
+        match self {
+            SourceMapPolicy::Always => true,
+            SourceMapPolicy::Never => false,
+            SourceMapPolicy::WithHeader { name, value } => req.header(name) == Some(&**value),
+        }
+    }
+}
+
+/// Caching directives for a single entry, set with
+/// [`EntryBuilder::with_cache_policy`][crate::EntryBuilder::with_cache_policy].
+/// Mostly useful for CDN-fronted deployments, where edge caches should use a
+/// different policy than browsers.
+///
+/// Create it via `CachePolicy::default()` and then set the fields you want
+/// to change. Durations are sent in whole seconds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CachePolicy {
+    /// The `max-age` of the `Cache-Control` header. If `None`, the default
+    /// is used: one year (plus `immutable`) for assets with hashed filename,
+    /// `no-cache` for all others. Default: `None`.
+    pub max_age: Option<Duration>,
+
+    /// The `s-maxage` of the `Cache-Control` header, i.e. the `max-age` for
+    /// shared caches like CDNs. Default: `None`.
+    pub s_maxage: Option<Duration>,
+
+    /// The `stale-while-revalidate` of the `Cache-Control` header, i.e. how
+    /// long a stale response may still be served while it's revalidated in
+    /// the background. Default: `None`.
+    pub stale_while_revalidate: Option<Duration>,
+
+    /// If set, a `Surrogate-Control: max-age=...` header is sent. It's
+    /// understood (and removed) by surrogates like Varnish or Fastly, so it
+    /// sets the edge policy without affecting browsers. Default: `None`.
+    pub surrogate_max_age: Option<Duration>,
+}
+
+impl CachePolicy {
+    /// Returns the value of the `Cache-Control` header for `asset`.
+    fn of_asset(asset: &crate::Asset) -> Cow<'static, str> {
+        let settings = asset.0.serve_settings();
+        let mut out = Self::cache_control(settings.cache_policy.as_ref(), asset.is_filename_hashed());
+        if settings.no_compression {
+            // Asks proxies to not compress the response either.
+            out.to_mut().push_str(", no-transform");
+        }
+        out
+    }
+
+    /// Returns the value of the `Cache-Control` header for an asset with the
+    /// given policy.
+    fn cache_control(policy: Option<&Self>, is_filename_hashed: bool) -> Cow<'static, str> {
+        let default = if is_filename_hashed {
+            "public, max-age=31536000, immutable"
+        } else {
+            "no-cache"
+        };
+        let Some(policy) = policy else {
+            return Cow::Borrowed(default);
+        };
+
+        let mut out = match policy.max_age {
+            Some(max_age) => format!("public, max-age={}", max_age.as_secs()),
+            None => default.to_owned(),
+        };
+        if let Some(s_maxage) = policy.s_maxage {
+            out.push_str(&format!(", s-maxage={}", s_maxage.as_secs()));
+        }
+        if let Some(swr) = policy.stale_while_revalidate {
+            out.push_str(&format!(", stale-while-revalidate={}", swr.as_secs()));
+        }
+        Cow::Owned(out)
+    }
+}
+
+/// The headers [`Assets::serve`] sends for an asset, returned by
+/// [`Assets::head`]. Useful to answer `HEAD` requests or to build custom
+/// responses without loading the content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AssetHead {
+    /// The status code, see [`crate::Asset::status`].
+    pub status: u16,
+
+    /// Value of the `Content-Type` header.
+    pub content_type: String,
+
+    /// Length of the uncompressed content in bytes.
+    pub content_length: u64,
+
+    /// Value of the `ETag` header of the uncompressed content. Only `Some`
+    /// if the crate feature `hash` is enabled.
+    pub etag: Option<String>,
+
+    /// Value of the `Cache-Control` header.
+    pub cache_control: String,
+}
+
+/// An alternative representation of an asset, e.g. an AVIF or WebP version
+/// of a PNG image. Registered with
+/// [`EntryBuilder::with_variant`][crate::EntryBuilder::with_variant].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub(crate) http_path: String,
+    pub(crate) media_type: &'static str,
+    pub(crate) label: Option<Cow<'static, str>>,
+    pub(crate) dpr: Option<f32>,
+}
+
+impl Variant {
+    /// Returns the HTTP path of the asset serving this variant. That's the
+    /// *hashed HTTP path* once the assets are built.
+    pub fn http_path(&self) -> &str {
+        &self.http_path
+    }
+
+    /// Returns the media type of this variant (without parameters), guessed
+    /// from the file extension, e.g. `image/avif`.
+    pub fn media_type(&self) -> &str {
+        self.media_type
+    }
+
+    /// Returns the label this variant was registered with (e.g. `dark`), see
+    /// [`EntryBuilder::with_labeled_variant`][crate::EntryBuilder::with_labeled_variant].
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+    /// Returns the device pixel ratio this variant was registered for, see
+    /// [`EntryBuilder::with_dpr_variant`][crate::EntryBuilder::with_dpr_variant].
+    pub fn dpr(&self) -> Option<f32> {
+        self.dpr
+    }
+}
+
+/// Configuration of the HTTP layer for a single entry.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ServeSettings {
+    pub(crate) noindex: bool,
+    pub(crate) variants: Vec<Variant>,
+    pub(crate) cache_policy: Option<CachePolicy>,
+    pub(crate) no_compression: bool,
+    pub(crate) download: Option<Cow<'static, str>>,
+    pub(crate) content_type: Option<Cow<'static, str>>,
+
+    /// See `EntryBuilder::with_status`.
+    pub(crate) status: Option<u16>,
+}
+
+impl ServeSettings {
+    /// Returns the *unhashed HTTP paths* of all variants.
+    pub(crate) fn dependencies(&self) -> impl Iterator<Item = &str> {
+        self.variants.iter().map(|v| v.http_path())
+    }
+}
+
+/// HTTP path prefix of ACME HTTP-01 challenges, see
+/// [`Builder::add_acme_dir`][crate::Builder::add_acme_dir].
+const ACME_PREFIX: &str = ".well-known/acme-challenge/";
+
+/// Function rewriting request paths, see
+/// [`Builder::set_request_rewriter`][crate::Builder::set_request_rewriter].
+pub(crate) type RequestRewriter = Arc<dyn Send + Sync + for<'a> Fn(&'a str) -> Cow<'a, str>>;
+
+/// Function choosing the variant to serve, see
+/// [`Builder::set_variant_selector`][crate::Builder::set_variant_selector].
+pub(crate) type VariantSelector = Arc<dyn Send + Sync + Fn(&Request<'_>, &[Variant]) -> Option<usize>>;
+
+/// Configuration of the HTTP layer that applies to all assets.
+#[derive(Clone, Default)]
+pub(crate) struct HttpConfig {
+    pub(crate) source_maps: Option<SourceMapPolicy>,
+    pub(crate) rewriter: Option<RequestRewriter>,
+    pub(crate) acme_dir: Option<PathBuf>,
+
+    /// Value of the `Vary` header and the selector.
+    pub(crate) variant_selector: Option<(Cow<'static, str>, VariantSelector)>,
+
+    /// See `Builder::use_client_hints`.
+    pub(crate) client_hints: bool,
+
+    /// See `Builder::set_save_data_preload_limit`.
+    pub(crate) save_data_preload_limit: Option<u64>,
+
+    /// See `Builder::set_cache_key_header`.
+    #[cfg_attr(not(feature = "hash"), allow(dead_code))]
+    pub(crate) cache_key_header: Option<Cow<'static, str>>,
+
+    /// See `Builder::enable_inspector`.
+    #[cfg_attr(prod_mode, allow(dead_code))]
+    pub(crate) inspector: Option<Cow<'static, str>>,
+
+    /// Path prefixes (without leading `/`) for which 404s are answered with
+    /// JSON, see `Builder::enable_json_errors`.
+    pub(crate) json_errors: Vec<Cow<'static, str>>,
+}
+
+impl fmt::Debug for HttpConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpConfig")
+            .field("source_maps", &self.source_maps)
+            .field("rewriter", &self.rewriter.as_ref().map(|_| "<closure>"))
+            .field("acme_dir", &self.acme_dir)
+            .field("variant_selector", &self.variant_selector.as_ref().map(|(vary, _)| vary))
+            .field("client_hints", &self.client_hints)
+            .field("save_data_preload_limit", &self.save_data_preload_limit)
+            .field("cache_key_header", &self.cache_key_header)
+            .field("inspector", &self.inspector)
+            .field("json_errors", &self.json_errors)
+            .finish()
+    }
+}
+
+
+impl Assets {
+    /// Answers the given HTTP request. Requests for paths without asset get a
+    /// `404 Not Found` (in dev mode, with suggestions for similar paths in
+    /// the body). If configured, the request rewriter is applied to the path
+    /// before the lookup. Only `GET` and `HEAD` requests are supported:
+    /// `OPTIONS` requests for assets get a `204 No Content` with `Allow: GET,
+    /// HEAD` (e.g. for CORS preflights), all other methods get a `405 Method
+    /// Not Allowed`.
+    ///
+    /// Successful responses have the headers `Content-Type` (see
+    /// [`Asset::content_type`][crate::Asset::content_type]) and
+    /// `Cache-Control` set. Assets with hashed filename
+    /// are cached indefinitely, all others need to be revalidated. This can
+    /// be changed per entry, see
+    /// [`EntryBuilder::with_cache_policy`][crate::EntryBuilder::with_cache_policy].
+    ///
+    /// If configured, ACME challenges are served from the file system before
+    /// anything else, see [`Builder::add_acme_dir`][crate::Builder::add_acme_dir].
+    /// In dev mode, the inspector page is served next, if enabled via
+    /// [`Builder::enable_inspector`][crate::Builder::enable_inspector].
+    /// Requests for renamed paths are redirected, see
+    /// [`Builder::add_renamed`][crate::Builder::add_renamed]. 404s have a JSON
+    /// body for some requests, see
+    /// [`Builder::enable_json_errors`][crate::Builder::enable_json_errors].
+    /// For assets with variants, the variant is chosen based on the `Accept`
+    /// header, see [`EntryBuilder::with_variant`][crate::EntryBuilder::with_variant].
+    /// With the feature `hash`, an `ETag` is sent and requests with a matching
+    /// `If-None-Match` get a `304 Not Modified` without body.
+    /// If precompression is enabled (feature `compress`), the best variant
+    /// accepted by the client is served with a `Content-Encoding`, see
+    /// [`Asset::negotiate_content`][crate::Asset::negotiate_content].
+    /// `GET` requests with a single byte range in the `Range` header get a
+    /// `206 Partial Content` with that part of the uncompressed content (see
+    /// [`Asset::content_range`][crate::Asset::content_range]), or a `416
+    /// Range Not Satisfiable`. `HEAD` requests get the same headers as `GET`
+    /// requests, plus `Content-Length`, but the content is not loaded if
+    /// possible (see [`Self::head`]). Assets with a custom status (see
+    /// [`EntryBuilder::with_status`][crate::EntryBuilder::with_status]) are
+    /// served with that status, and never with `206` or `304`.
+    pub async fn serve(&self, req: &Request<'_>) -> Response {
+        let is_head = match req.method() {
+            "GET" => false,
+            "HEAD" => true,
+            _ => return self.serve_other_method(req),
+        };
+
+        let Some(lookup_path) = req.lookup_path() else {
+            return Response::text(404, "Not Found");
+        };
+        if let (Some(dir), Some(token)) = (&self.http.acme_dir, lookup_path.strip_prefix(ACME_PREFIX)) {
+            return serve_acme_challenge(dir, token, is_head).await;
+        }
+
+        #[cfg(dev_mode)]
+        if let Some(inspector) = &self.http.inspector {
+            match lookup_path.strip_prefix(inspector.trim_start_matches('/')) {
+                Some("") => return crate::inspector::serve(self, false, is_head).await,
+                Some(".json") => return crate::inspector::serve(self, true, is_head).await,
+                _ => {}
+            }
+        }
+
+        let (path, asset) = match self.lookup(req, &lookup_path) {
+            Ok(found) => found,
+            Err(response) => return response,
+        };
+        let path = &*path;
+        let settings = asset.0.serve_settings();
+
+        // Serve the variant chosen by the selector, or the asset itself.
+        let variant_index = match &self.http.variant_selector {
+            _ if settings.variants.is_empty() => None,
+            Some((_, select)) => select(req, &settings.variants),
+            None if self.http.client_hints => {
+                self.negotiate_with_hints(req, &asset, &settings.variants).await
+            }
+            None => negotiate_accept(req, &settings.variants),
+        };
+        let variant = variant_index
+            .and_then(|i| settings.variants.get(i))
+            .and_then(|v| Some((v, self.get(v.http_path())?)));
+        let (served, served_path) = match &variant {
+            Some((v, variant_asset)) => (variant_asset, v.http_path()),
+            None => (&asset, path),
+        };
+        let content_type = served.content_type();
+
+        // Range requests are answered with the uncompressed content. With
+        // `If-Range`, the whole content is served, which is always allowed.
+        // Assets with a custom status are always served completely.
+        let range_header = req.header("range")
+            .filter(|_| !is_head && req.header("if-range").is_none() && settings.status.is_none());
+        let range = match range_header {
+            Some(header) => match served.content_len().await {
+                Ok(total) => parse_range(header, total).map(|range| (range, total)),
+                Err(e) => {
+                    log::error!(target: "reinda", "failed to load asset '{served_path}': {e}");
+                    return Response::text(500, "Internal Server Error");
+                }
+            },
+            None => None,
+        };
+        let loaded = match &range {
+            None => load_content(served, req, is_head).await,
+            Some((Ok(range), _)) => served.content_range(range.clone()).await
+                .map(|content| Loaded { len: content.len() as u64, content, encoding: None, varies: false }),
+            Some((Err(()), total)) => {
+                return Response::text(416, "Range Not Satisfiable")
+                    .with_header("content-range", format!("bytes */{total}"));
+            }
+        };
+        let mut loaded = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                log::error!(target: "reinda", "failed to load asset '{served_path}': {e}");
+                return Response::text(500, "Internal Server Error");
+            }
+        };
+
+        // With `Save-Data`, heavy preloads are removed from HTML. The result
+        // gets its own ETag and cache key, like an encoding.
+        let preload_limit = self.http.save_data_preload_limit
+            .filter(|_| content_type.starts_with("text/html"));
+        let mut key_suffix = loaded.encoding;
+        if let Some(limit) = preload_limit.filter(|_| range.is_none() && wants_save_data(req)) {
+            let html = match served.content().await {
+                Ok(html) => html,
+                Err(e) => {
+                    log::error!(target: "reinda", "failed to load asset '{served_path}': {e}");
+                    return Response::text(500, "Internal Server Error");
+                }
+            };
+            if let Some(html) = self.without_heavy_preloads(served_path, &html, limit).await {
+                let len = html.len() as u64;
+                let content = if is_head { Bytes::new() } else { html.into() };
+                loaded = Loaded { content, len, encoding: None, varies: loaded.varies };
+                key_suffix = Some("save-data");
+            }
+        }
+
+        let cache_policy = settings.cache_policy.as_ref();
+        let cache_control = CachePolicy::of_asset(&asset);
+        let mut response = Response::new(settings.status.unwrap_or(200), loaded.content)
+            .with_header("content-type", content_type)
+            .with_header("cache-control", cache_control)
+            .with_header("accept-ranges", "bytes");
+        if is_head {
+            // The length the body of a `GET` would have, as the body is empty.
+            response = response.with_header("content-length", loaded.len.to_string());
+        }
+        response.compressible = !settings.no_compression;
+        if let Some((Ok(range), total)) = &range {
+            response.status = 206;
+            response = response.with_header(
+                "content-range",
+                format!("bytes {}-{}/{total}", range.start, range.end - 1),
+            );
+        }
+
+        if let Some(encoding) = loaded.encoding {
+            response = response.with_header("content-encoding", encoding);
+        }
+        // In dev mode, the ETag would require loading the whole file.
+        let etag = match range {
+            Some(_) if cfg!(dev_mode) => None,
+            _ => entity_tag(served, key_suffix).await,
+        };
+        if let Some(etag) = &etag {
+            response = response.with_header("etag", etag.clone());
+        }
+        if let Some(max_age) = cache_policy.and_then(|p| p.surrogate_max_age) {
+            response = response.with_header("surrogate-control", format!("max-age={}", max_age.as_secs()));
+        }
+        if !settings.variants.is_empty() {
+            let vary = match &self.http.variant_selector {
+                Some((vary, _)) => vary,
+                None if self.http.client_hints => "accept, save-data, sec-ch-dpr, dpr",
+                None => "accept",
+            };
+            response = response.with_header("vary", vary.to_owned());
+        }
+        if loaded.varies {
+            response = response.with_header("vary", "accept-encoding");
+        }
+        if preload_limit.is_some() {
+            response = response.with_header("vary", "save-data");
+        }
+        // The key of the served content, so it also differs between variants
+        // and encodings.
+        #[cfg(feature = "hash")]
+        if let Some(name) = &self.http.cache_key_header {
+            match served.cache_key().await {
+                Ok(key) => {
+                    response = response.with_header(name.clone(), with_encoding(key, key_suffix));
+                }
+                Err(e) => log::error!(target: "reinda", "failed to compute cache key of '{served_path}': {e}"),
+            }
+        }
+        if self.http.client_hints && content_type.starts_with("text/html") {
+            response = response.with_header("accept-ch", "Sec-CH-DPR");
+        }
+        if settings.noindex {
+            response = response.with_header("x-robots-tag", "noindex");
+        }
+        if let Some(filename) = &settings.download {
+            response = response.with_header("content-disposition", content_disposition(filename));
+        }
+
+        let if_none_match = req.header("if-none-match").filter(|_| settings.status.is_none());
+        if let (Some(etag), Some(if_none_match)) = (&etag, if_none_match) {
+            if etag_matches(if_none_match, etag) {
+                response.status = 304;
+                response.body = Bytes::new();
+            }
+        }
+
+        response
+    }
+
+    /// Returns the headers [`Self::serve`] would send for the asset with the
+    /// given *hashed HTTP path*, without its content. Returns an error of
+    /// kind [`NotFound`][std::io::ErrorKind::NotFound] if there is no such
+    /// asset.
+    ///
+    /// Like for `HEAD` requests answered by [`Self::serve`], the content is
+    /// not loaded if possible: in prod mode, this never loads anything. In
+    /// dev mode, only the file's metadata is read if no processing is
+    /// configured for the asset (see [`crate::Asset::content_len`]), and the
+    /// `ETag` requires loading the content.
+    pub async fn head(&self, http_path: &str) -> Result<AssetHead, std::io::Error> {
+        let Some(asset) = self.get(http_path) else {
+            let msg = format!("no asset '{http_path}'");
+            return Err(std::io::Error::new(std::io::ErrorKind::NotFound, msg));
+        };
+        Ok(AssetHead {
+            status: asset.status(),
+            content_type: asset.content_type().to_owned(),
+            content_length: asset.content_len().await?,
+            etag: entity_tag(&asset, None).await,
+            cache_control: CachePolicy::of_asset(&asset).into_owned(),
+        })
+    }
+
+    /// Finds the asset for a request with the given lookup path, which all
+    /// methods have to use: applies the request rewriter and hides source
+    /// maps not allowed by the `SourceMapPolicy`. Returns the path and the
+    /// asset, or the response to send if there is none, i.e. a redirect for
+    /// renamed paths or a `404 Not Found`.
+    fn lookup<'r>(
+        &self,
+        req: &Request<'_>,
+        lookup_path: &'r str,
+    ) -> Result<(Cow<'r, str>, crate::Asset), Response> {
+        let path = match self.rewritten_path(lookup_path) {
+            Cow::Borrowed(s) => Cow::Borrowed(s.trim_start_matches('/')),
+            Cow::Owned(s) => Cow::Owned(s.trim_start_matches('/').to_owned()),
+        };
+        let source_maps = self.http.source_maps.as_ref().unwrap_or(&SourceMapPolicy::Always);
+        if path.ends_with(".map") && !source_maps.allows(req) {
+            return Err(Response::text(404, "Not Found"));
+        }
+
+        match self.get(&path) {
+            Some(asset) => Ok((path, asset)),
+            None => match self.inner.redirect(&path) {
+                Some(target) => {
+                    log::debug!(target: "reinda", "request for renamed path '{path}', redirecting to '{target}'");
+                    Err(Response::new(308, Bytes::new())
+                        .with_header("location", relative_location(lookup_path, target)))
+                }
+                None => Err(self.not_found(req, lookup_path, &path)),
+            },
+        }
+    }
+
+    /// Returns the lookup path of a request after applying the request
+    /// rewriter.
+    fn rewritten_path<'r>(&self, lookup_path: &'r str) -> Cow<'r, str> {
+        match &self.http.rewriter {
+            Some(rewrite) => rewrite(lookup_path),
+            None => Cow::Borrowed(lookup_path),
+        }
+    }
+
+    /// Answers requests with methods other than `GET` and `HEAD`: `OPTIONS`
+    /// gets a `204 No Content` listing the allowed methods, all others a
+    /// `405 Method Not Allowed`. Both only for assets found by
+    /// [`Self::lookup`], otherwise its response is sent.
+    fn serve_other_method(&self, req: &Request<'_>) -> Response {
+        let Some(lookup_path) = req.lookup_path() else {
+            return Response::text(404, "Not Found");
+        };
+        if let Err(response) = self.lookup(req, &lookup_path) {
+            return response;
+        }
+
+        let response = match req.method() {
+            "OPTIONS" => Response::new(204, Bytes::new()),
+            _ => Response::text(405, "Method Not Allowed"),
+        };
+        response.with_header("allow", "GET, HEAD")
+    }
+
+    /// The variant selection with client hints, see
+    /// [`Builder::use_client_hints`][crate::Builder::use_client_hints].
+    async fn negotiate_with_hints(
+        &self,
+        req: &Request<'_>,
+        original: &crate::Asset,
+        variants: &[Variant],
+    ) -> Option<usize> {
+        let format = negotiate_accept(req, variants);
+
+        // With `Save-Data`, the smallest acceptable variant not meant for
+        // high density screens is served.
+        if wants_save_data(req) {
+            let mut best = (None, original.content_len().await.ok()?);
+            for (i, v) in variants.iter().enumerate() {
+                let eligible = format == Some(i) || v.dpr.is_some_and(|dpr| dpr <= 1.0);
+                if !eligible {
+                    continue;
+                }
+                let Some(asset) = self.get(v.http_path()) else { continue };
+                match asset.content_len().await {
+                    Ok(len) if len < best.1 => best = (Some(i), len),
+                    _ => {}
+                }
+            }
+            return best.0;
+        }
+
+        // Otherwise, for high density screens, the variant with the smallest
+        // DPR that is at least the requested one is served (or the one with
+        // the largest DPR, if none is large enough).
+        let dpr = req.header("sec-ch-dpr")
+            .or_else(|| req.header("dpr"))
+            .and_then(|v| v.trim().parse::<f32>().ok())
+            .filter(|&dpr| dpr > 1.0);
+        if let Some(dpr) = dpr {
+            let candidates = variants.iter()
+                .enumerate()
+                .filter_map(|(i, v)| Some((i, v.dpr.filter(|&d| d > 1.0)?)))
+                .collect::<Vec<_>>();
+            let large_enough = candidates.iter()
+                .filter(|(_, d)| *d >= dpr)
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            let largest = candidates.iter().max_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((i, _)) = large_enough.or(largest) {
+                return Some(*i);
+            }
+        }
+
+        format
+    }
+
+    /// Returns `html` (of the asset with the given path) without the
+    /// preloads of assets larger than `limit`, or `None` if there are none,
+    /// see [`Builder::set_save_data_preload_limit`][crate::Builder::set_save_data_preload_limit].
+    async fn without_heavy_preloads(&self, http_path: &str, html: &[u8], limit: u64) -> Option<Vec<u8>> {
+        let mut heavy = vec![];
+        for (range, href) in crate::util::find_preloads(html) {
+            let Some(resolved) = crate::links::resolve(http_path, href) else { continue };
+            let lookup = self.rewritten_path(&resolved);
+            let Some(asset) = self.get(lookup.trim_start_matches('/')) else { continue };
+            if asset.content_len().await.is_ok_and(|len| len > limit) {
+                heavy.push(range);
+            }
+        }
+        if heavy.is_empty() {
+            return None;
+        }
+
+        let mut out = Vec::with_capacity(html.len());
+        let mut pos = 0;
+        for range in heavy {
+            out.extend_from_slice(&html[pos..range.start]);
+            pos = range.end;
+        }
+        out.extend_from_slice(&html[pos..]);
+        Some(out)
+    }
+
+    /// `lookup_path` is the requested path, `path` the one after rewriting.
+    fn not_found(&self, req: &Request<'_>, lookup_path: &str, path: &str) -> Response {
+        let similar = if cfg!(dev_mode) { self.similar_paths(path) } else { vec![] };
+
+        let wants_json = self.http.json_errors.iter()
+            .any(|prefix| {
+                let prefix = prefix.trim_matches('/');
+                prefix.is_empty() || lookup_path.strip_prefix(prefix)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+            && accepted_media_types(req).iter().any(|t| is_json_media_type(t));
+        if wants_json {
+            let mut body = format!(
+                "{{\"error\":\"Not Found\",\"status\":404,\"path\":{}",
+                json_string(&format!("/{lookup_path}")),
+            );
+            if !similar.is_empty() {
+                let similar = similar.iter()
+                    .map(|p| json_string(&format!("/{p}")))
+                    .collect::<Vec<_>>();
+                body.push_str(&format!(",\"suggestions\":[{}]", similar.join(",")));
+            }
+            body.push_str("}\n");
+            return Response::new(404, body).with_header("content-type", "application/json");
+        }
+
+        let mut body = "Not Found".to_owned();
+        if !similar.is_empty() {
+            body.push_str("\n\nDid you mean one of these?\n");
+            for p in similar {
+                body.push_str(&format!("- /{p}\n"));
+            }
+        }
+
+        Response::text(404, body)
+    }
+}
+
+/// The default variant selection of [`Assets::serve`]: returns the index of
+/// the first of `variants` without label and DPR whose media type is explicitly
+/// accepted by the request, i.e. listed in the `Accept` header with a
+/// non-zero quality. Wildcards like `image/*` are ignored, as browsers send
+/// them regardless of which formats they support.
+///
+/// Useful as fallback in a custom
+/// [variant selector][crate::Builder::set_variant_selector].
+pub fn negotiate_accept(req: &Request<'_>, variants: &[Variant]) -> Option<usize> {
+    let accepted = accepted_media_types(req);
+    variants.iter().position(|v| {
+        v.label.is_none()
+            && v.dpr.is_none()
+            && accepted.iter().any(|t| t.eq_ignore_ascii_case(v.media_type))
+    })
+}
+
+/// Returns the media types listed in the `Accept` header with a non-zero
+/// quality, without parameters.
+fn accepted_media_types<'a>(req: &Request<'a>) -> Vec<&'a str> {
+    let Some(accept) = req.header("accept") else {
+        return vec![];
+    };
+    accept.split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';').map(str::trim);
+            let media_type = parts.next()?;
+            let rejected = parts.any(|param| {
+                param.strip_prefix("q=").and_then(|q| q.parse::<f32>().ok()) == Some(0.0)
+            });
+            (!rejected).then_some(media_type)
+        })
+        .collect()
+}
+
+/// Returns whether `media_type` is `application/json` or a JSON based type
+/// like `application/problem+json`.
+fn is_json_media_type(media_type: &str) -> bool {
+    let media_type = media_type.to_ascii_lowercase();
+    media_type == "application/json" || media_type.ends_with("+json")
+}
+
+/// Content of an asset loaded by `load_content`.
+struct Loaded {
+    /// Empty for `HEAD` requests.
+    content: Bytes,
+
+    /// Length of the content that would be served for a `GET` request.
+    len: u64,
+
+    /// Value of the `Content-Encoding` header, `None` if uncompressed.
+    encoding: Option<&'static str>,
+
+    /// Whether the response depends on the `Accept-Encoding` header.
+    varies: bool,
+}
+
+/// Loads the content of `asset` to serve for `req`: the best precompressed
+/// variant accepted by the client (feature `compress`) or the uncompressed
+/// content. For `HEAD` requests, the uncompressed content is not loaded if
+/// its length can be determined without, see [`crate::Asset::content_len`].
+#[cfg_attr(not(feature = "compress"), allow(unused_variables))]
+async fn load_content(
+    asset: &crate::Asset,
+    req: &Request<'_>,
+    is_head: bool,
+) -> Result<Loaded, std::io::Error> {
+    let uncompressed = |varies| async move {
+        let (content, len) = if is_head {
+            (Bytes::new(), asset.content_len().await?)
+        } else {
+            let content = asset.content().await?;
+            let len = content.len() as u64;
+            (content, len)
+        };
+        Ok(Loaded { content, len, encoding: None, varies })
+    };
+
+    #[cfg(feature = "compress")]
+    if let Some(accept) = req.header("accept-encoding") {
+        let variants = asset.0.encoded_variants().await?;
+        let encodings = variants.iter().map(|(e, _)| *e).collect::<Vec<_>>();
+        if let Some(i) = crate::compress::negotiate(accept, &encodings) {
+            let (encoding, content) = &variants[i];
+            return Ok(Loaded {
+                content: if is_head { Bytes::new() } else { content.clone() },
+                len: content.len() as u64,
+                encoding: Some(encoding.as_str()),
+                varies: true,
+            });
+        }
+        return uncompressed(!variants.is_empty()).await;
+    }
+
+    uncompressed(false).await
+}
+
+/// Parses the value of a `Range` header for content of length `total`.
+/// Returns `None` if the header should be ignored, i.e. if it's invalid or
+/// has multiple ranges (which are not supported), and `Err` if the range is
+/// not satisfiable.
+fn parse_range(header: &str, total: u64) -> Option<Result<Range<u64>, ()>> {
+    let spec = header.trim().strip_prefix("bytes=")?;
+    if spec.contains(',') {
+        return None;
+    }
+
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
+    if start.is_empty() {
+        // Suffix range, i.e. the last `n` bytes.
+        let n = end.parse::<u64>().ok()?;
+        if n == 0 || total == 0 {
+            return Some(Err(()));
+        }
+        return Some(Ok(total.saturating_sub(n)..total));
+    }
+
+    let start = start.parse::<u64>().ok()?;
+    let end = match end {
+        "" => total,
+        end => {
+            let last = end.parse::<u64>().ok()?;
+            if last < start {
+                return None;
+            }
+            last.saturating_add(1).min(total)
+        }
+    };
+    if start >= total {
+        return Some(Err(()));
+    }
+    Some(Ok(start..end))
+}
+
+/// Returns the strong `ETag` of the served content: its cache key, plus the
+/// content encoding (if any), in quotes. `None` without the feature `hash`.
+#[cfg_attr(not(feature = "hash"), allow(unused_variables))]
+async fn entity_tag(asset: &crate::Asset, encoding: Option<&str>) -> Option<String> {
+    #[cfg(feature = "hash")]
+    match asset.cache_key().await {
+        Ok(key) => return Some(format!("\"{}\"", with_encoding(key, encoding))),
+        Err(e) => log::error!(target: "reinda", "failed to compute ETag: {e}"),
+    }
+
+    None
+}
+
+/// Returns whether the request has the client hint `Save-Data: on`.
+fn wants_save_data(req: &Request<'_>) -> bool {
+    req.header("save-data").is_some_and(|v| v.trim().eq_ignore_ascii_case("on"))
+}
+
+/// Appends the content encoding (if any) to a cache key, so that different
+/// encodings of the same content have different keys.
+#[cfg(feature = "hash")]
+fn with_encoding(key: String, encoding: Option<&str>) -> String {
+    match encoding {
+        Some(encoding) => format!("{key}-{encoding}"),
+        None => key,
+    }
+}
+
+/// Returns whether the value of an `If-None-Match` header matches `etag`,
+/// using the weak comparison (i.e. ignoring `W/`).
+pub(crate) fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match.trim() == "*" || if_none_match.split(',').any(|tag| {
+        let tag = tag.trim();
+        tag.strip_prefix("W/").unwrap_or(tag) == etag
+    })
+}
+
+/// Returns the value of a `Content-Disposition: attachment` header with the
+/// given filename. Non-ASCII filenames are additionally passed as
+/// `filename*` (RFC 6266), with an ASCII fallback.
+fn content_disposition(filename: &str) -> String {
+    let fallback = filename.chars()
+        .map(|c| match c {
+            '"' | '\\' => '_',
+            c if c.is_ascii() && !c.is_ascii_control() => c,
+            _ => '_',
+        })
+        .collect::<String>();
+    let mut out = format!("attachment; filename=\"{fallback}\"");
+    if !filename.is_ascii() {
+        out.push_str("; filename*=UTF-8''");
+        for b in filename.bytes() {
+            if b.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&b) {
+                out.push(b as char);
+            } else {
+                out.push_str(&format!("%{b:02X}"));
+            }
+        }
+    }
+    out
+}
+
+/// Returns the URL of `to`, relative to `from`. Both are paths without
+/// leading `/`.
+fn relative_location(from: &str, to: &str) -> String {
+    let from_dirs = from.rsplit_once('/').map_or(vec![], |(dir, _)| dir.split('/').collect());
+    let to_segments = to.split('/').collect::<Vec<_>>();
+    let common = from_dirs.iter()
+        .zip(&to_segments[..to_segments.len() - 1])
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut out = "../".repeat(from_dirs.len() - common);
+    out.push_str(&to_segments[common..].join("/"));
+    out
+}
+
+/// Serves the ACME challenge file `token` from `dir`. Tokens are base64url,
+/// so everything else (in particular path separators) is rejected.
+async fn serve_acme_challenge(dir: &std::path::Path, token: &str, is_head: bool) -> Response {
+    let is_valid = !token.is_empty()
+        && token.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+    if !is_valid {
+        return Response::text(404, "Not Found");
+    }
+
+    match tokio::fs::read(dir.join(token)).await {
+        Ok(content) => {
+            let body = if is_head { Bytes::new() } else { content.into() };
+            Response::new(200, body)
+                .with_header("content-type", "text/plain")
+                .with_header("cache-control", "no-store")
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Response::text(404, "Not Found"),
+        Err(e) => {
+            log::error!(target: "reinda", "failed to read ACME challenge '{token}': {e}");
+            Response::text(500, "Internal Server Error")
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_parsing() {
+        assert_eq!(parse_range("bytes=0-499", 1000), Some(Ok(0..500)));
+        assert_eq!(parse_range("bytes=500-", 1000), Some(Ok(500..1000)));
+        assert_eq!(parse_range("bytes=-200", 1000), Some(Ok(800..1000)));
+        assert_eq!(parse_range("bytes=-2000", 1000), Some(Ok(0..1000)));
+        assert_eq!(parse_range("bytes=900-1999", 1000), Some(Ok(900..1000)));
+        assert_eq!(parse_range("bytes=1000-", 1000), Some(Err(())));
+        assert_eq!(parse_range("bytes=-0", 1000), Some(Err(())));
+        assert_eq!(parse_range("bytes=0-", 0), Some(Err(())));
+        assert_eq!(parse_range("bytes=5-3", 1000), None);
+        assert_eq!(parse_range("bytes=0-1,5-6", 1000), None);
+        assert_eq!(parse_range("items=0-1", 1000), None);
+        assert_eq!(parse_range("bytes=a-b", 1000), None);
+    }
+
+    #[test]
+    fn variant_negotiation() {
+        let variant = |http_path: &str, media_type, label| Variant {
+            http_path: http_path.into(),
+            media_type,
+            label,
+            dpr: None,
+        };
+        let variants = [
+            variant("a-dark.png", "image/png", Some("dark".into())),
+            variant("a.avif", "image/avif", None),
+            variant("a.webp", "image/webp", None),
+        ];
+        let negotiate = |accept| {
+            let req = Request::get("a.png").with_header("Accept", accept);
+            negotiate_accept(&req, &variants).map(|i| variants[i].http_path())
+        };
+
+        assert_eq!(negotiate("image/avif,image/webp,image/apng,*/*;q=0.8"), Some("a.avif"));
+        assert_eq!(negotiate("image/webp,*/*"), Some("a.webp"));
+        assert_eq!(negotiate("image/avif;q=0, image/webp;q=0.5"), Some("a.webp"));
+        assert_eq!(negotiate("image/*,*/*;q=0.8"), None);
+        assert_eq!(negotiate("image/png"), None);
+        assert_eq!(negotiate_accept(&Request::get("a.png"), &variants), None);
+    }
+
+    #[test]
+    fn relative_locations() {
+        assert_eq!(relative_location("old.js", "new.js"), "new.js");
+        assert_eq!(relative_location("old.js", "static/new.js"), "static/new.js");
+        assert_eq!(relative_location("static/old.js", "new.js"), "../new.js");
+        assert_eq!(relative_location("a/b/old.js", "a/c/new.js"), "../c/new.js");
+        assert_eq!(relative_location("a/b/old.js", "a/b/new.js"), "new.js");
+    }
+
+    #[test]
+    fn request() {
+        let req = Request::get("/foo/bar.js?v=3").with_header("X-Token", "abc");
+        assert_eq!(req.method(), "GET");
+        assert_eq!(req.lookup_path().as_deref(), Some("foo/bar.js"));
+        assert_eq!(Request::get("/caf%C3%A9%20au%20lait.txt?a=%2F").lookup_path().as_deref(), Some("café au lait.txt"));
+        assert_eq!(Request::get("/a%2Fb.txt").lookup_path(), None);
+        assert_eq!(Request::get("/a/%2E%2E/b.txt").lookup_path(), None);
+        assert_eq!(Request::get("/a/../b.txt").lookup_path(), None);
+        assert_eq!(Request::get("/a%2").lookup_path(), None);
+        assert_eq!(Request::get("/%FF").lookup_path(), None);
+        assert_eq!(req.header("x-token"), Some("abc"));
+        assert_eq!(req.header("x-other"), None);
+    }
+
+    #[test]
+    fn source_map_policy() {
+        let with_token = Request::get("a.js.map").with_header("x-sourcemap-token", "secret");
+        let without = Request::get("a.js.map");
+        let policy = SourceMapPolicy::WithHeader {
+            name: "X-Sourcemap-Token".into(),
+            value: "secret".into(),
+        };
+        assert!(policy.allows(&with_token));
+        assert!(!policy.allows(&without));
+        assert!(SourceMapPolicy::Always.allows(&without));
+        assert!(!SourceMapPolicy::Never.allows(&with_token));
+    }
+
+    #[test]
+    fn cache_policy() {
+        let cc = |policy: &CachePolicy, hashed| CachePolicy::cache_control(Some(policy), hashed);
+        assert_eq!(CachePolicy::cache_control(None, false), "no-cache");
+        assert_eq!(cc(&CachePolicy::default(), true), "public, max-age=31536000, immutable");
+
+        let mut policy = CachePolicy {
+            s_maxage: Some(Duration::from_secs(600)),
+            stale_while_revalidate: Some(Duration::from_secs(30)),
+            ..Default::default()
+        };
+        assert_eq!(cc(&policy, false), "no-cache, s-maxage=600, stale-while-revalidate=30");
+        policy.max_age = Some(Duration::from_secs(60));
+        assert_eq!(cc(&policy, true), "public, max-age=60, s-maxage=600, stale-while-revalidate=30");
+    }
+
+    #[test]
+    fn download_disposition() {
+        assert_eq!(content_disposition("report.csv"), r#"attachment; filename="report.csv""#);
+        assert_eq!(content_disposition(r#"a "b".txt"#), r#"attachment; filename="a _b_.txt""#);
+        assert_eq!(
+            content_disposition("bericht-für-mai.pdf"),
+            r#"attachment; filename="bericht-f_r-mai.pdf"; filename*=UTF-8''bericht-f%C3%BCr-mai.pdf"#,
+        );
+    }
+
+    #[test]
+    fn if_none_match() {
+        assert!(etag_matches(r#""abc""#, r#""abc""#));
+        assert!(etag_matches(r#""x", W/"abc""#, r#""abc""#));
+        assert!(etag_matches(" * ", r#""abc""#));
+        assert!(!etag_matches(r#""abc-br""#, r#""abc""#));
+        assert!(!etag_matches("abc", r#""abc""#));
+    }
+}
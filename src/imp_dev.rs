@@ -1,12 +1,32 @@
-use std::{io, marker::PhantomData, path::{Path, PathBuf}, sync::Arc};
+use std::{
+    borrow::Cow,
+    io,
+    marker::PhantomData,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-use ahash::{HashMap, HashMapExt};
+use ahash::{HashMap, HashMapExt, HashSet};
 use bytes::Bytes;
+use tokio::time::Instant;
 
 use crate::{
-    builder::EntryBuilderKind,
-    Asset, BuildError, Builder, DataSource, Modifier, ModifierContext, SplitGlob,
+    builder::{EntryBuilder, EntryBuilderKind},
+    dir::PathFilter,
+    change::{ChangeDetector, FileVersion},
+    hot_cache::HotCache,
+    http::ServeSettings,
+    inspector::Row,
+    pipeline::Steps,
+    template::{self, Fragment, Template, TemplateSettings},
+    transform::{Snippet, TransformSettings},
+    Asset, BuildError, Builder, ContentReader, DataSource, Modifier, ModifierContext, ModifierEnv,
+    Modifiers, SplitGlob,
 };
+#[cfg(feature = "compress")]
+use crate::{CompressionConfig, Encoding};
 
 
 #[derive(Debug, Clone)]
@@ -15,7 +35,7 @@ pub(crate) struct AssetsInner(Arc<AssetsEvenMoreInner>);
 #[derive(Debug, Clone)]
 pub(crate) struct AssetsEvenMoreInner {
     /// All specified assets, but not yet loaded.
-    assets: HashMap<String, (DataSource, Modifier)>,
+    assets: HashMap<String, (DataSource, EntrySettings)>,
 
     /// List of glob patterns that were added. This is only relevant for the dev
     /// mode where we want to be able to load files dynamically in `get` that
@@ -23,90 +43,526 @@ pub(crate) struct AssetsEvenMoreInner {
     ///
     /// Sorted by the length of `http_prefix`, starting with the longest.
     globs: Vec<DevGlobEntry>,
+
+    /// All directory entries, sorted like `globs`.
+    dirs: Vec<DevDirEntry>,
+
+    /// All keys of `assets` of non-internal assets, sorted. Used for prefix
+    /// queries.
+    sorted_paths: Vec<String>,
+
+    /// Renamed paths mapped to the path of the new asset, see
+    /// `Builder::add_renamed`.
+    redirects: HashMap<String, String>,
+
+    /// See `Builder::enable_hot_cache`.
+    hot_cache: Option<Arc<HotCache>>,
+
+    /// See `Builder::set_change_detection`.
+    change_detector: Arc<ChangeDetector>,
+
+    /// Assets whose transforms exceeded their time limit, with the version
+    /// of their file at that time (`None` if not loaded from a file). As
+    /// the transform keeps running, it's not started again until the file
+    /// changes.
+    timed_out: Arc<Mutex<HashMap<String, Option<FileVersion>>>>,
 }
 
 #[derive(Debug, Clone)]
 struct DevGlobEntry {
     http_prefix: String,
     glob: SplitGlob,
-    modifier: Modifier,
-    base_path: &'static Path,
+    filter: PathFilter,
+    settings: EntrySettings,
+
+    /// Searched in order, the first one containing the file wins.
+    base_paths: Vec<PathBuf>,
+}
+
+/// A directory mounted via `Builder::add_dir`, to look up files that were
+/// added after building.
+#[derive(Debug, Clone)]
+struct DevDirEntry {
+    http_prefix: String,
+    fs_dir: PathBuf,
+    filter: PathFilter,
+    settings: EntrySettings,
+}
+
+/// Settings of an entry that apply to all of its assets.
+#[derive(Debug, Clone)]
+struct EntrySettings {
+    /// All registered pipeline steps, not yet filtered for the asset.
+    steps: Steps,
+    modifiers: Modifiers,
+    template: Option<TemplateSettings>,
+    internal: bool,
+    serve: ServeSettings,
+    transform: TransformSettings,
+    #[cfg(feature = "compress")]
+    compression: Option<CompressionConfig>,
+
+    /// Whether the entry was added from `embed!`. Only files marked with
+    /// `always_embed` are then actually embedded in dev mode.
+    embedded: bool,
+    env: Arc<ModifierEnv>,
+    transform_timeout: Option<Duration>,
+
+    /// See `EntryBuilder::with_extra_watch`.
+    extra_watch: Arc<[PathBuf]>,
+}
+
+impl EntrySettings {
+    fn of(entry: &EntryBuilder<'_>) -> Self {
+        Self {
+            steps: entry.steps.clone(),
+            modifiers: entry.modifiers.clone(),
+            template: entry.template.clone(),
+            internal: entry.internal,
+            serve: entry.serve.clone(),
+            transform: entry.transform.clone(),
+            #[cfg(feature = "compress")]
+            compression: entry.compression.clone(),
+            embedded: entry.embedded.is_some(),
+            env: entry.env.clone(),
+            transform_timeout: entry.transform_timeout,
+            extra_watch: entry.extra_watch.clone().into(),
+        }
+    }
+
+    /// Settings of assets added by post-build hooks, which are served as is.
+    fn unprocessed(#[cfg(feature = "compress")] compression: Option<CompressionConfig>) -> Self {
+        Self {
+            steps: Steps::default(),
+            modifiers: Modifiers::default(),
+            template: None,
+            internal: false,
+            serve: ServeSettings::default(),
+            transform: TransformSettings::default(),
+            #[cfg(feature = "compress")]
+            compression,
+            embedded: false,
+            env: Arc::default(),
+            transform_timeout: None,
+            extra_watch: Arc::new([]),
+        }
+    }
 }
 
 impl AssetsInner {
     pub(crate) async fn build(builder: Builder<'_>) -> Result<Self, BuildError> {
+        let base_override = builder.base_path_override();
+        if let Some(paths) = &base_override {
+            log::info!(target: "reinda", "overriding base paths of embedded files with {paths:?}");
+        }
+
         // Collect all glob entries we have.
         let globs = builder.assets.iter().filter_map(|ab| {
-            if let EntryBuilderKind::Glob { http_prefix, glob, base_path, .. } = &ab.kind {
+            if let EntryBuilderKind::Glob { http_prefix, glob, filter, base_paths, .. } = &ab.kind {
                 Some(DevGlobEntry {
                     http_prefix: http_prefix.clone().into_owned(),
                     glob: glob.clone(),
-                    modifier: ab.modifier.clone(),
-                    base_path: Path::new(*base_path),
+                    filter: filter.clone(),
+                    settings: EntrySettings::of(ab),
+                    base_paths: base_override.clone()
+                        .unwrap_or_else(|| base_paths.iter().map(PathBuf::from).collect()),
                 })
             } else {
                 None
             }
-        }).collect();
+        }).collect::<Vec<_>>();
+        let mut dirs = builder.assets.iter().filter_map(|ab| match &ab.kind {
+            EntryBuilderKind::Dir { http_prefix, fs_dir, filter, .. } => Some(DevDirEntry {
+                http_prefix: http_prefix.clone().into_owned(),
+                fs_dir: fs_dir.clone(),
+                filter: filter.clone(),
+                settings: EntrySettings::of(ab),
+            }),
+            _ => None,
+        }).collect::<Vec<_>>();
+        dirs.sort_by_key(|dir| std::cmp::Reverse(dir.http_prefix.len()));
 
         // Collect all files we know about.
         let mut assets = HashMap::with_capacity(builder.assets.len());
         for ab in builder.assets {
+            let settings = EntrySettings::of(&ab);
             match ab.kind {
                 EntryBuilderKind::Single { http_path, source } => {
-                    assets.insert(http_path.into_owned(), (source, ab.modifier));
+                    // Files embedded with `always_embed` are not overridden.
+                    let source = match (&base_override, ab.embedded) {
+                        (Some(bases), Some((path, _))) if matches!(source, DataSource::File(_)) => {
+                            DataSource::File(find_in(bases, path))
+                        }
+                        _ => source,
+                    };
+                    assets.insert(http_path.into_owned(), (source, settings));
                 }
-                EntryBuilderKind::Glob { http_prefix, files, .. } => {
+                EntryBuilderKind::Glob { http_prefix, files, glob, .. } => {
                     for file in files {
-                        assets.insert(
-                            file.http_path(&http_prefix),
-                            (file.source, ab.modifier.clone()),
-                        );
+                        let source = match &base_override {
+                            Some(bases) if matches!(file.source, DataSource::File(_)) => DataSource::File(
+                                find_in(bases, Path::new(glob.prefix).join(file.suffix)),
+                            ),
+                            _ => file.source.clone(),
+                        };
+                        assets.insert(file.http_path(&http_prefix), (source, settings.clone()));
+                    }
+                }
+                kind @ EntryBuilderKind::Dir { .. } => {
+                    for (http_path, source) in kind.into_files() {
+                        assets.insert(http_path, (source, settings.clone()));
                     }
                 }
             }
         }
 
-        Ok(Self(Arc::new(AssetsEvenMoreInner { assets, globs })))
+        let mut sorted_paths = assets.iter()
+            .filter(|(_, (_, settings))| !settings.internal)
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        sorted_paths.sort_unstable();
+
+        let redirects = builder.renamed.into_iter().collect();
+        let hot_cache = builder.hot_cache.map(|capacity| Arc::new(HotCache::new(capacity)));
+        let change_detection = builder.change_detection.unwrap_or_default();
+        let mut inner = Arc::new(AssetsEvenMoreInner {
+            assets,
+            globs,
+            dirs,
+            sorted_paths,
+            redirects,
+            hot_cache,
+            change_detector: Arc::new(ChangeDetector::new(change_detection)),
+            timed_out: Default::default(),
+        });
+        for (old, new) in &inner.redirects {
+            crate::builder::check_renamed(old, new, |path| AssetInner::lookup(&inner, path).is_some());
+        }
+
+        // Like in prod mode, prepended and appended assets, variants and
+        // dependencies of pipeline steps have to exist.
+        for (http_path, (_, settings)) in &inner.assets {
+            let step_deps = settings.steps.for_path(http_path).dependencies();
+            let deps = settings.transform.dependencies()
+                .chain(settings.serve.dependencies())
+                .chain(step_deps.iter().map(|dep| &**dep));
+            for dep in deps {
+                if AssetInner::lookup(&inner, dep).is_none() {
+                    return Err(BuildError::MissingDependency {
+                        asset: http_path.clone(),
+                        dependency: dep.to_owned(),
+                    });
+                }
+            }
+        }
+
+        // Run post-build hooks, which requires loading all assets once. Their
+        // outputs are not updated afterwards.
+        if !builder.post_build.is_empty() {
+            let mut view = Vec::with_capacity(inner.sorted_paths.len());
+            for http_path in &inner.sorted_paths {
+                let asset = AssetInner::lookup(&inner, http_path).expect("known asset is missing");
+                let content = asset.content().await.map_err(|err| BuildError::Io {
+                    err,
+                    path: asset.source.fs_path().map(ToOwned::to_owned).unwrap_or_default(),
+                    http_path: http_path.clone(),
+                })?;
+                view.push((http_path.clone(), http_path.clone(), content));
+            }
+
+            let internal = inner.assets.iter()
+                .filter(|(_, (_, settings))| settings.internal)
+                .map(|(path, _)| path.clone())
+                .collect::<Vec<_>>();
+            let outputs = builder.post_build.run(view, internal, builder.public_url)?;
+            let inner = Arc::make_mut(&mut inner);
+            for (path, content) in outputs {
+                log::debug!(target: "reinda", "post-build hook added asset '{path}'");
+                let settings = EntrySettings::unprocessed(
+                    #[cfg(feature = "compress")]
+                    builder.compression.clone(),
+                );
+                inner.sorted_paths.push(path.clone());
+                inner.assets.insert(path, (DataSource::Loaded(content), settings));
+            }
+            inner.sorted_paths.sort_unstable();
+        }
+
+        Ok(Self(inner))
     }
 
     pub(crate) fn get(&self, http_path: &str) -> Option<Asset> {
-        self.0.assets.get(http_path)
-            .cloned()
-            // In dev mode, we also check if the requested file matches a glob
-            // and if so, we check the file system.
-            .or_else(|| {
-                self.0.match_globs(http_path)
-                    .filter(|(path, _)| path.exists())
-                    .map(|(path, modifier)| (DataSource::File(path), modifier))
-            })
-            .map(|(source, modifier)| Asset(AssetInner {
-                source,
-                modifier,
-                assets: self.0.clone(),
-            }))
+        let out = self.get_impl(http_path);
+        if out.is_none() {
+            let suggestions = crate::suggest::similar(http_path, self.0.sorted_paths.iter().map(|k| &**k));
+            if !suggestions.is_empty() {
+                log::debug!(
+                    target: "reinda",
+                    "no asset '{http_path}', did you mean one of: {suggestions:?}",
+                );
+            }
+        }
+        out
+    }
+
+    fn get_impl(&self, http_path: &str) -> Option<Asset> {
+        AssetInner::lookup(&self.0, http_path)
+            .filter(|asset| !asset.settings.internal)
+            .map(Asset)
     }
 
     pub(crate) fn len(&self) -> usize {
-        self.0.assets.len()
+        self.0.sorted_paths.len()
     }
 
     pub(crate) fn iter(&self) -> impl '_ + Iterator<Item = (&str, Asset)> {
-        self.0.assets.keys().flat_map(move |key| self.get(key).map(|a| (&**key, a)))
+        self.0.sorted_paths.iter().flat_map(move |key| self.get(key).map(|a| (&**key, a)))
+    }
+
+    pub(crate) async fn unreferenced(&self) -> Vec<&str> {
+        let mut referenced = HashSet::<String>::default();
+        let known_paths = self.0.all_paths();
+        for (http_path, (source, settings)) in &self.0.assets {
+            referenced.extend(settings.modifiers.dependencies().map(|dep| dep.to_string()));
+            let step_deps = settings.steps.for_path(http_path).dependencies();
+            referenced.extend(step_deps.into_iter().map(Cow::into_owned));
+            referenced.extend(settings.transform.dependencies().map(ToOwned::to_owned));
+            referenced.extend(settings.serve.dependencies().map(ToOwned::to_owned));
+
+            // Templates that can't be loaded or parsed are ignored here, they
+            // will result in errors when loading them anyway.
+            if let Some(template) = &settings.template {
+                let Ok(bytes) = source.load().await else { continue };
+                if let Some(Ok(t)) = template.applies_to(&bytes).then(|| Template::parse(&bytes)) {
+                    referenced.extend(t.dependencies(|var| template.var(var), &known_paths));
+                }
+            }
+        }
+
+        self.0.sorted_paths.iter()
+            .filter(|path| !referenced.contains(*path))
+            .map(|path| &**path)
+            .collect()
+    }
+
+    /// Returns information about all assets known at build time for the
+    /// inspector page. Every asset is loaded and processed to get its size.
+    pub(crate) async fn inspect(&self) -> Vec<Row> {
+        let known_paths = self.0.all_paths();
+        let mut out = vec![];
+        for http_path in &known_paths {
+            let Some(asset) = AssetInner::lookup(&self.0, http_path) else { continue };
+            let settings = &asset.settings;
+            let steps = settings.steps.for_path(http_path);
+
+            let mut processing = vec![];
+            let mut dependencies = vec![];
+            if let Some(template) = &settings.template {
+                processing.push("template".to_owned());
+                if let Ok(bytes) = asset.source.load().await {
+                    if let Some(Ok(t)) = template.applies_to(&bytes).then(|| Template::parse(&bytes)) {
+                        dependencies.extend(t.dependencies(|var| template.var(var), &known_paths));
+                    }
+                }
+            }
+            processing.extend(steps.0.iter().map(|step| format!("plugin '{}'", step.name())));
+            dependencies.extend(steps.dependencies().into_iter().map(Cow::into_owned));
+            processing.extend(settings.modifiers.0.iter().map(|modifier| match modifier {
+                Modifier::PathFixup(_) => "path fixup".to_owned(),
+                Modifier::Custom { .. } => "custom modifier".to_owned(),
+            }));
+            dependencies.extend(settings.modifiers.dependencies().map(|dep| dep.to_string()));
+            if settings.transform.is_configured() {
+                processing.push("transform".to_owned());
+            }
+            dependencies.extend(settings.transform.dependencies().map(ToOwned::to_owned));
+            dependencies.extend(settings.serve.dependencies().map(ToOwned::to_owned));
+            dependencies.sort();
+            dependencies.dedup();
+
+            let modified = match &asset.source {
+                DataSource::File(path) => tokio::fs::metadata(path).await
+                    .and_then(|meta| meta.modified())
+                    .ok(),
+                _ => None,
+            };
+
+            #[cfg(feature = "hash")]
+            let cache_key = asset.cache_key().await.ok();
+            #[cfg(not(feature = "hash"))]
+            let cache_key = None;
+
+            out.push(Row {
+                http_path: http_path.to_string(),
+                source: asset.source.to_string(),
+                internal: settings.internal,
+                processing,
+                dependencies,
+                size: asset.content_len().await.map_err(|e| e.to_string()),
+                cache_key,
+                modified,
+            });
+        }
+        out
+    }
+
+    #[cfg(feature = "compress")]
+    pub(crate) fn deduplicated_bytes(&self) -> usize {
+        0
+    }
+
+    pub(crate) fn hot_cache_stats(&self) -> Option<crate::HotCacheStats> {
+        self.0.hot_cache.as_ref().map(|cache| cache.stats())
+    }
+
+    pub(crate) fn redirect(&self, http_path: &str) -> Option<&str> {
+        self.0.redirects.get(http_path).map(|s| &**s)
+    }
+
+    pub(crate) fn paths_under<'s>(&'s self, prefix: &'s str) -> impl 's + Iterator<Item = &'s str> {
+        paths_with_prefix(&self.0.sorted_paths, prefix)
+    }
+}
+
+impl AssetsInner {
+    /// See `Builder::set_change_detection`.
+    #[cfg(feature = "watch")]
+    pub(crate) fn change_detector(&self) -> &ChangeDetector {
+        &self.0.change_detector
+    }
+
+    /// Returns all files assets are currently loaded from as `(http_path,
+    /// fs_path)`: the files of single entries, all files currently matching
+    /// glob entries or in directory entries, and the files prepended or
+    /// appended to them. Blocks while evaluating the globs and walking the
+    /// directories.
+    #[cfg(feature = "watch")]
+    pub(crate) fn watched_files(&self) -> Vec<(String, PathBuf)> {
+        let mut out = vec![];
+        let mut add = |http_path: &str, source: &DataSource, settings: &EntrySettings| {
+            let transform = &settings.transform;
+            let snippets = transform.prepend.iter().chain(&transform.append).filter_map(|s| match s {
+                Snippet::Source(source) => Some(source),
+                Snippet::Asset(_) => None,
+            });
+            for source in std::iter::once(source).chain(snippets) {
+                if let DataSource::File(path) = source {
+                    out.push((http_path.to_owned(), path.clone()));
+                }
+            }
+            for path in settings.extra_watch.iter() {
+                out.push((http_path.to_owned(), path.clone()));
+            }
+        };
+
+        for (http_path, (source, settings)) in &self.0.assets {
+            add(http_path, source, settings);
+        }
+        for entry in &self.0.globs {
+            let searches = entry.base_paths.iter()
+                .flat_map(|base| entry.glob.suffixes.iter().map(move |suffix| (base, suffix)));
+            for (base, suffix) in searches {
+                let dir = base.join(entry.glob.prefix);
+                let pattern = format!(
+                    "{}/{}",
+                    glob::Pattern::escape(&dir.to_string_lossy()),
+                    suffix.as_str(),
+                );
+                let Ok(paths) = glob::glob(&pattern) else { continue };
+                for path in paths.flatten().filter(|path| path.is_file()) {
+                    let Ok(suffix) = path.strip_prefix(&dir) else { continue };
+                    let suffix = suffix.components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    if !entry.filter.matches(&suffix) {
+                        continue;
+                    }
+                    let http_path = format!("{}{suffix}", entry.http_prefix);
+                    if !self.0.assets.contains_key(&http_path) {
+                        add(&http_path, &DataSource::File(path), &entry.settings);
+                    }
+                }
+            }
+        }
+
+        // Like with `match_dirs`, the first directory containing a path wins.
+        let mut from_dirs = HashSet::default();
+        for entry in &self.0.dirs {
+            let Ok(files) = crate::dir::walk_blocking(&entry.fs_dir, &entry.filter) else { continue };
+            for file in files {
+                let http_path = file.http_path(&entry.http_prefix);
+                if !self.0.assets.contains_key(&http_path) && from_dirs.insert(http_path.clone()) {
+                    add(&http_path, &DataSource::File(file.fs_path), &entry.settings);
+                }
+            }
+        }
+        out
+    }
+
+    /// Returns the directories to watch for changes of `Self::watched_files`
+    /// as `(path, recursive)`: the directories of the watched files and,
+    /// recursively, the fixed prefix directories of glob entries and the
+    /// directories of directory entries.
+    #[cfg(feature = "watch")]
+    pub(crate) fn watched_dirs(&self) -> Vec<(PathBuf, bool)> {
+        let files = self.watched_files().into_iter()
+            .filter_map(|(_, path)| Some((path.parent()?.to_owned(), false)));
+        let globs = self.0.globs.iter().flat_map(|entry| {
+            entry.base_paths.iter().map(move |base| (base.join(entry.glob.prefix), true))
+        });
+        let dirs = self.0.dirs.iter().map(|entry| (entry.fs_dir.clone(), true));
+        let mut out = files.chain(globs).chain(dirs).collect::<Vec<_>>();
+        out.sort();
+        out.dedup();
+        out
     }
 }
 
 impl AssetsEvenMoreInner {
-    fn match_globs(&self, http_path: &str) -> Option<(PathBuf, Modifier)> {
+    /// Returns the *unhashed HTTP paths* of all assets (including internal
+    /// ones) known at build time, sorted. Used for `for` loops in templates.
+    fn all_paths(&self) -> Vec<&str> {
+        let mut out = self.assets.keys().map(|p| &**p).collect::<Vec<_>>();
+        out.sort_unstable();
+        out
+    }
+
+    fn exists(&self, http_path: &str) -> bool {
+        self.assets.contains_key(http_path) || self.match_globs(http_path).is_some()
+    }
+
+    /// Returns the file system path and settings of the glob matching
+    /// `http_path`. See `find_in` for which base path is used.
+    fn match_globs(&self, http_path: &str) -> Option<(PathBuf, EntrySettings)> {
         self.globs.iter().find_map(|item| {
             http_path.strip_prefix(&item.http_prefix)
-                .filter(|suffix| item.glob.suffix.matches(suffix))
+                .filter(|suffix| item.glob.matches(suffix) && item.filter.matches(suffix))
                 .map(|suffix| (
-                    item.base_path.join(item.glob.prefix).join(suffix),
-                    item.modifier.clone(),
+                    find_in(&item.base_paths, Path::new(item.glob.prefix).join(suffix)),
+                    item.settings.clone(),
                 ))
         })
     }
+
+    /// Returns the file and settings of the directory entry `http_path`
+    /// belongs to, if that file exists.
+    fn match_dirs(&self, http_path: &str) -> Option<(PathBuf, EntrySettings)> {
+        self.dirs.iter().find_map(|dir| {
+            let relative = http_path.strip_prefix(&dir.http_prefix)?;
+            Some((dir.filter.find(&dir.fs_dir, relative)?, dir.settings.clone()))
+        })
+    }
+}
+
+/// Returns `path` joined to the first of `bases` containing it, or to the
+/// first one if none does.
+fn find_in(bases: &[PathBuf], path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    bases.iter()
+        .map(|base| base.join(path))
+        .find(|full| full.exists())
+        .unwrap_or_else(|| bases.first().map_or_else(|| path.to_owned(), |base| base.join(path)))
 }
 
 
@@ -116,43 +572,316 @@ impl AssetsEvenMoreInner {
 /// matters).
 #[derive(Debug, Clone)]
 pub(crate) struct AssetInner {
+    http_path: String,
     source: DataSource,
-    modifier: Modifier,
+    settings: EntrySettings,
     assets: Arc<AssetsEvenMoreInner>,
 }
 
 impl AssetInner {
+    /// Returns the asset with the given path, including internal ones.
+    fn lookup(assets: &Arc<AssetsEvenMoreInner>, http_path: &str) -> Option<Self> {
+        assets.assets.get(http_path)
+            .cloned()
+            // In dev mode, we also check if the requested file matches a glob
+            // and if so, we check the file system.
+            .or_else(|| {
+                assets.match_globs(http_path)
+                    .filter(|(path, _)| path.exists())
+                    .or_else(|| assets.match_dirs(http_path))
+                    .map(|(path, settings)| (DataSource::File(path), settings))
+            })
+            .map(|(source, settings)| AssetInner {
+                http_path: http_path.to_owned(),
+                source,
+                settings,
+                assets: assets.clone(),
+            })
+    }
+
     /// Returns the contents of this asset. Will be loaded from the file system
     /// in dev mode, potentially returning IO errors. In prod mode, the file
     /// contents are already loaded and this method always returns `Ok(_)`.
     pub(crate) async fn content(&self) -> Result<Bytes, io::Error> {
+        let Some((cache, version)) = self.hot_cache_version().await? else {
+            return self.content_impl(&[]).await;
+        };
+        if let Some(content) = cache.get(&self.http_path, version) {
+            return Ok(content);
+        }
+        let content = self.content_impl(&[]).await?;
+        cache.insert(&self.http_path, version, content.clone());
+        Ok(content)
+    }
+
+    /// Returns the hot cache and the current version of this asset's file,
+    /// if the cache is enabled and this asset can be cached. That's not the
+    /// case if the content depends on other files, including extra watched
+    /// ones.
+    async fn hot_cache_version(&self) -> Result<Option<(&HotCache, FileVersion)>, io::Error> {
+        let Some(cache) = &self.assets.hot_cache else {
+            return Ok(None);
+        };
+        let DataSource::File(path) = &self.source else {
+            return Ok(None);
+        };
+        let transform = &self.settings.transform;
+        if self.settings.template.is_some()
+            || !transform.prepend.is_empty()
+            || !transform.append.is_empty()
+            || !self.settings.extra_watch.is_empty()
+        {
+            return Ok(None);
+        }
+        Ok(Some((cache, self.assets.change_detector.version(path).await?)))
+    }
+
+    /// Like `Self::content`, but `including` contains the assets that
+    /// (transitively) include this one via templates or prepend/append it,
+    /// to detect cycles.
+    async fn content_impl(&self, including: &[String]) -> Result<Bytes, io::Error> {
         let bytes = self.source.load().await.map_err(|(e, _)| e)?;
+        let bytes = self.settings.transform.normalize(bytes);
 
-        // Apply modifications, if specified.
-        let modified =  match &self.modifier {
-            Modifier::None => bytes,
-
-            // Since in dev mode, hashed paths are not used, no
-            // modifications are necessary.
-            Modifier::PathFixup(_) => bytes,
-
-            // The `PathMap::empty()` might allocate but we are in dev mode,
-            // we don't care.
-            Modifier::Custom { f, deps } => f(bytes, ModifierContext {
-                declared_deps: &deps,
-                inner: ModifierContextInner {
-                    assets: self.assets.clone(),
-                    _dummy: PhantomData,
-                },
-            }),
+        // Render template. There are no hashed paths in dev mode, so we only
+        // check that the referenced assets exist. Included assets are loaded
+        // first, as rendering is synchronous.
+        let bytes = match &self.settings.template {
+            Some(settings) if settings.applies_to(&bytes) => {
+                let template_err = |message| io::Error::other(BuildError::Template {
+                    http_path: self.http_path.clone(),
+                    error: template::Error(message),
+                });
+                let template = Template::parse(&bytes).map_err(template_err)?;
+                let known_paths = self.assets.all_paths();
+                let mut including = including.to_vec();
+                including.push(self.http_path.clone());
+                let mut included = HashMap::new();
+                for path in template.includes(|var| settings.var(var), &known_paths) {
+                    if let Some(pos) = including.iter().position(|p| *p == path) {
+                        let cycle = including[pos..].to_vec();
+                        return Err(io::Error::other(BuildError::CyclicDependencies(cycle)));
+                    }
+                    let Some(dep) = Self::lookup(&self.assets, &path) else { continue };
+                    let content = Box::pin(dep.content_impl(&including)).await?;
+                    included.insert(path, content);
+                }
+
+                template
+                    .render(Some(&known_paths), |fragment| match fragment {
+                        Fragment::Path(path) => self.assets.exists(path).then(|| path.to_owned()),
+                        Fragment::Include(path) => included.get(path)
+                            .and_then(|content| std::str::from_utf8(content).ok())
+                            .map(ToOwned::to_owned),
+                        #[cfg(feature = "hash")]
+                        Fragment::Sri(path) => included.get(path)
+                            .map(|content| crate::util::sri_hash(content, Default::default())),
+                        #[cfg(not(feature = "hash"))]
+                        Fragment::Sri(_) => None,
+                        Fragment::Var(var) => settings.var(var).map(ToOwned::to_owned),
+                    })
+                    .map_err(template_err)?
+                    .into()
+            }
+            _ => bytes,
         };
 
+        // With a time limit, steps and modifier run on a detached thread, so
+        // that a runaway one blocks neither this task nor the runtime
+        // shutdown. It cannot be cancelled though and keeps running.
+        let modified = match self.settings.transform_timeout {
+            None => self.run_transforms(bytes)?,
+            Some(limit) => {
+                let version = match &self.source {
+                    DataSource::File(path) => Some(self.assets.change_detector.version(path).await?),
+                    _ => None,
+                };
+                let timed_out = self.assets.timed_out.lock().unwrap().get(&self.http_path) == Some(&version);
+                if timed_out {
+                    return Err(io::Error::other(BuildError::TransformTimeout {
+                        http_path: self.http_path.clone(),
+                        timeout: limit,
+                    }));
+                }
+
+                let this = self.clone();
+                let task = crate::run_detached(move || this.run_transforms(bytes));
+                match crate::with_time_limit(Instant::now(), Some(limit), &self.http_path, task).await {
+                    Ok(result) => result.map_err(io::Error::other)??,
+                    Err(e) => {
+                        self.assets.timed_out.lock().unwrap().insert(self.http_path.clone(), version);
+                        return Err(io::Error::other(e));
+                    }
+                }
+            }
+        };
+
+        let transform = &self.settings.transform;
+        let prepend = self.load_snippets(&transform.prepend, including).await?;
+        let append = self.load_snippets(&transform.append, including).await?;
+        Ok(transform.apply(&self.http_path, modified, &prepend, &append))
+    }
+
+    /// Runs the pipeline steps and the modifiers on `bytes`. Synchronous, as
+    /// both are user code that might block.
+    fn run_transforms(&self, bytes: Bytes) -> Result<Bytes, io::Error> {
+        let bytes = self.settings.steps.for_path(&self.http_path)
+            .apply(&self.http_path, bytes, &self.settings.env, || ModifierContextInner {
+                assets: self.assets.clone(),
+                _dummy: PhantomData,
+            })
+            .map_err(io::Error::other)?;
+
+        // Apply modifications, if specified, in order.
+        let mut modified = bytes;
+        for modifier in &self.settings.modifiers.0 {
+            modified = match modifier {
+                // Since in dev mode, hashed paths are not used, no
+                // modifications are necessary.
+                Modifier::PathFixup(_) => modified,
+
+                // Panics inside the modifier are reported as IO error, so that
+                // the application keeps running and the developer can fix it.
+                Modifier::Custom { f, deps } => {
+                    let ctx = ModifierContext {
+                        declared_deps: deps,
+                        env: &self.settings.env,
+                        inner: ModifierContextInner {
+                            assets: self.assets.clone(),
+                            _dummy: PhantomData,
+                        },
+                    };
+                    crate::call_modifier(&**f, modified, ctx, &self.http_path)
+                        .map_err(io::Error::other)?
+                }
+            };
+        }
         Ok(modified)
     }
 
+    /// Returns the file backing this asset if its content is exactly the
+    /// file's content, i.e. if no processing is configured. Parts of it can
+    /// then be read without loading the whole file.
+    fn unprocessed_file(&self) -> Option<&Path> {
+        let unprocessed = self.settings.template.is_none()
+            && self.settings.steps.for_path(&self.http_path).is_empty()
+            && self.settings.modifiers.0.iter().all(|m| matches!(m, Modifier::PathFixup(_)))
+            && !self.settings.transform.is_configured();
+        match &self.source {
+            DataSource::File(path) if unprocessed => Some(path),
+            _ => None,
+        }
+    }
+
+    pub(crate) async fn stream(&self) -> Result<ContentReader, io::Error> {
+        match self.unprocessed_file() {
+            Some(path) => Ok(ContentReader::file(tokio::fs::File::open(path).await?)),
+            None => Ok(ContentReader::memory(self.content().await?)),
+        }
+    }
+
+    pub(crate) async fn content_len(&self) -> Result<u64, io::Error> {
+        match self.unprocessed_file() {
+            Some(path) => Ok(tokio::fs::metadata(path).await?.len()),
+            None => Ok(self.content().await?.len() as u64),
+        }
+    }
+
+    pub(crate) async fn content_range(&self, range: Range<u64>) -> Result<Bytes, io::Error> {
+        match self.unprocessed_file() {
+            Some(path) => crate::read_file_range(path, range).await,
+            None => Ok(crate::slice_range(&self.content().await?, range)),
+        }
+    }
+
+    /// Loads the contents of the given snippets. Files and other assets are
+    /// loaded every time, so changes are picked up immediately. `including`
+    /// is passed on like in `Self::content_impl`.
+    async fn load_snippets(&self, snippets: &[Snippet], including: &[String]) -> Result<Vec<Bytes>, io::Error> {
+        let mut out = Vec::with_capacity(snippets.len());
+        for snippet in snippets {
+            let content = match snippet {
+                Snippet::Source(source) => source.load().await.map_err(|(e, _)| e)?,
+                Snippet::Asset(path) => {
+                    let mut including = including.to_vec();
+                    including.push(self.http_path.clone());
+                    if let Some(pos) = including.iter().position(|p| p == path) {
+                        let cycle = including[pos..].to_vec();
+                        return Err(io::Error::other(BuildError::CyclicDependencies(cycle)));
+                    }
+                    let dep = Self::lookup(&self.assets, path).ok_or_else(|| io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("asset '{path}' prepended/appended to '{}' does not exist", self.http_path),
+                    ))?;
+                    Box::pin(dep.content_impl(&including)).await?
+                }
+            };
+            out.push(content);
+        }
+        Ok(out)
+    }
+
+    #[cfg(feature = "compress")]
+    pub(crate) async fn encoded_content(&self, encoding: Encoding) -> Result<Option<Bytes>, io::Error> {
+        let variant = self.encoded_variants().await?
+            .into_iter()
+            .find(|(e, _)| *e == encoding)
+            .map(|(_, bytes)| bytes);
+        Ok(variant)
+    }
+
+    #[cfg(feature = "compress")]
+    pub(crate) async fn encoded_variants(&self) -> Result<Vec<(Encoding, Bytes)>, io::Error> {
+        let Some(config) = &self.settings.compression else {
+            return Ok(vec![]);
+        };
+
+        let cached = self.hot_cache_version().await?;
+        if let Some((cache, version)) = cached {
+            if let Some(variants) = cache.variants(&self.http_path, version) {
+                return Ok(variants);
+            }
+        }
+
+        let content = self.content().await?;
+        let variants = crate::compress::variants(&self.http_path, &content, config);
+        if let Some((cache, version)) = cached {
+            cache.set_variants(&self.http_path, version, &variants);
+        }
+        Ok(variants)
+    }
+
+    #[cfg(feature = "hash")]
+    pub(crate) async fn cache_key(&self) -> Result<String, io::Error> {
+        let content = self.content().await?;
+        Ok(crate::util::format_cache_key(&crate::util::cache_key(&content)))
+    }
+
+    #[cfg(feature = "hash")]
+    pub(crate) async fn csp_hashes(&self) -> Result<crate::CspHashes, io::Error> {
+        let content = self.content().await?;
+        Ok(crate::CspHashes::of_asset(&self.settings.serve, &self.http_path, &content))
+    }
+
     pub(crate) fn is_filename_hashed(&self) -> bool {
         false
     }
+
+    pub(crate) fn is_embedded(&self) -> bool {
+        self.settings.embedded && matches!(self.source, DataSource::Loaded(_))
+    }
+
+    pub(crate) fn serve_settings(&self) -> &ServeSettings {
+        &self.settings.serve
+    }
+
+    pub(crate) fn http_path(&self) -> &str {
+        &self.http_path
+    }
+
+    pub(crate) fn unhashed_http_path(&self) -> &str {
+        &self.http_path
+    }
 }
 
 
@@ -164,10 +893,34 @@ pub(crate) struct ModifierContextInner<'a> {
 
 impl<'a> ModifierContextInner<'a> {
     pub(crate) fn resolve_path<'b>(&'b self, path: &'b str) -> Option<&'b str> {
-        if self.assets.assets.contains_key(path) || self.assets.match_globs(path).is_some() {
+        if self.assets.exists(path) {
             Some(path)
         } else {
             None
         }
     }
 }
+
+/// Returns all paths in `sorted` that start with `prefix`.
+fn paths_with_prefix<'a>(sorted: &'a [String], prefix: &'a str) -> impl 'a + Iterator<Item = &'a str> {
+    let start = sorted.partition_point(|p| p.as_str() < prefix);
+    sorted[start..].iter().map(|p| p.as_str()).take_while(move |p| p.starts_with(prefix))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_query() {
+        let sorted = ["a.css", "fonts/a.woff2", "fonts/b.woff2", "fontsy.txt", "z.js"]
+            .iter().map(|&s| s.to_owned()).collect::<Vec<_>>();
+        let query = |prefix| paths_with_prefix(&sorted, prefix).collect::<Vec<_>>();
+        assert_eq!(query("fonts/"), ["fonts/a.woff2", "fonts/b.woff2"]);
+        assert_eq!(query("fonts"), ["fonts/a.woff2", "fonts/b.woff2", "fontsy.txt"]);
+        assert_eq!(query("").len(), 5);
+        assert!(query("nope/").is_empty());
+        assert!(query("zz").is_empty());
+    }
+}
@@ -1,126 +1,463 @@
-use std::{borrow::Cow, fmt, io};
+use std::{borrow::Cow, fmt, io, ops::Range, path::Path, sync::Arc, time::Duration};
 
-use ahash::{HashMap, HashMapExt};
+use ahash::{HashMap, HashMapExt, HashSet};
 use bytes::Bytes;
 
 use crate::{
-    builder::EntryBuilderKind, Asset, BuildError, Builder, DataSource, Modifier,
-    ModifierContext, EntryBuilder, PathHash,
+    Asset, BuildError, Builder, ContentReader, DataSource, Modifier, ModifierContext, ModifierError,
+    EntryBuilder, ModifierEnv, Modifiers, PathHash,
     dep_graph::DepGraph,
     hash::PathMap,
+    http::ServeSettings,
+    pipeline::Steps,
+    table::{AssetId, BuiltAsset, LazyFile, Table},
+    template::{self, Fragment, Template, TemplateSettings},
+    transform::{Snippet, TransformSettings},
 };
+#[cfg(feature = "compress")]
+use crate::{CompressionConfig, Encoding};
 
 
 #[derive(Clone)]
 pub(crate) struct AssetsInner {
-    assets: HashMap<String, Asset>,
+    table: Arc<Table>,
+
+    /// Renamed *unhashed HTTP paths* mapped to the *hashed HTTP path* of the
+    /// new asset, see `Builder::add_renamed`.
+    redirects: Arc<HashMap<String, String>>,
 }
 
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub(crate) struct AssetInner {
-    content: Bytes,
-    hashed_filename: bool,
+    table: Arc<Table>,
+    id: AssetId,
 }
 
 impl AssetsInner {
     pub(crate) async fn build(builder: Builder<'_>) -> Result<Self, BuildError> {
+        let warn_unreferenced = builder.warn_unreferenced;
+        let renamed = builder.renamed;
+        let post_build = builder.post_build;
+        let public_url = builder.public_url;
+        #[cfg(feature = "compress")]
+        let global_compression = builder.compression;
+
         // First we flatten our entries into a list of files to be loaded/resolved.
         let mut unresolved = HashMap::with_capacity(builder.assets.len());
-        for EntryBuilder { kind, path_hash, modifier } in builder.assets {
-            match kind {
-                EntryBuilderKind::Single { http_path, source } => {
-                    unresolved.insert(http_path.into_owned(), UnresolvedAsset {
-                        source,
-                        modifier,
-                        path_hash,
-                    });
-                }
-                EntryBuilderKind::Glob { http_prefix, files, .. } => {
-                    for file in files {
-                        let key = file.http_path(http_prefix.as_ref());
-                        let value = UnresolvedAsset {
-                            source: file.source,
-                            modifier: modifier.clone(),
-                            path_hash,
-                        };
-                        unresolved.insert(key, value);
-                    }
+        for entry in builder.assets {
+            let EntryBuilder {
+                kind, path_hash, modifiers, template, internal, lazy, serve, transform, steps, env,
+                transform_timeout, ..
+            } = entry;
+            #[cfg(feature = "compress")]
+            let compression = entry.compression;
+            for (key, source) in kind.into_files() {
+                let file_steps = steps.for_path(&key);
+                let value = UnresolvedAsset {
+                    embedded: entry.embedded.is_some() && !matches!(source, DataSource::File(_)),
+                    source,
+                    step_deps: file_steps.dependencies(),
+                    steps: file_steps,
+                    env: env.clone(),
+                    transform_timeout,
+                    modifiers: modifiers.clone(),
+                    path_hash,
+                    template: template.clone(),
+                    template_deps: None,
+                    template_includes: vec![],
+                    preloaded: None,
+                    internal,
+                    lazy,
+                    serve: serve.clone(),
+                    transform: transform.clone(),
+                    #[cfg(feature = "compress")]
+                    compression: compression.clone(),
+                };
+                unresolved.insert(key, value);
+            }
+        }
+
+        for (old, new) in &renamed {
+            crate::builder::check_renamed(old, new, |path| unresolved.contains_key(path));
+        }
+
+        // Templates have to be loaded already, as we only know their
+        // dependencies after parsing them. `for` loops iterate over all
+        // assets, in order.
+        let mut known_paths = unresolved.keys().cloned().collect::<Vec<_>>();
+        known_paths.sort_unstable();
+        let known_paths = known_paths.iter().map(|p| p.as_str()).collect::<Vec<_>>();
+        for (http_path, asset) in &mut unresolved {
+            if let Some(settings) = &asset.template {
+                let raw = asset.source.load().await.map_err(|(err, path)| BuildError::Io {
+                    err,
+                    path,
+                    http_path: http_path.clone(),
+                })?;
+                if settings.applies_to(&raw) {
+                    let template = Template::parse(&raw).map_err(|message| BuildError::Template {
+                        http_path: http_path.clone(),
+                        error: template::Error(message),
+                    })?;
+                    let var = |var: &str| settings.var(var);
+                    asset.template_deps = Some(template.dependencies(var, &known_paths));
+                    asset.template_includes = template.includes(var, &known_paths);
                 }
-            };
+                asset.preloaded = Some(raw);
+            }
         }
 
         // Next: build the dep graph.
         let mut dep_graph = DepGraph::new();
+        let mut referenced = HashSet::default();
+        let mut snippet_deps = HashSet::default();
         for (unhashed_http_path, asset) in &unresolved {
             dep_graph.add_asset(&unhashed_http_path);
-            if let Some(deps) = asset.modifier.dependencies() {
-                for dep in deps {
-                    if !unresolved.contains_key(dep.as_ref()) {
-                        panic!(
-                            "Asset '{}' specified dependency '{}' but that asset does not exist",
-                            unhashed_http_path, dep,
-                        );
-                    }
-                    dep_graph.add_dependency(&unhashed_http_path, &dep);
+            let template_deps = asset.template_deps.iter().flatten().map(|s| s.as_str());
+            let modifier_deps = asset.modifiers.dependencies().map(|s| &**s);
+            let step_deps = asset.step_deps.iter().map(|s| &**s);
+            referenced.extend(template_deps.chain(modifier_deps).chain(asset.transform.dependencies()));
+            referenced.extend(asset.serve.dependencies());
+            referenced.extend(step_deps.clone());
+            for dep in asset.template_deps.iter().flatten() {
+                if !unresolved.contains_key(dep) {
+                    return Err(BuildError::Template {
+                        http_path: unhashed_http_path.clone(),
+                        error: template::Error(format!("no asset with path '{dep}' exists")),
+                    });
                 }
+                dep_graph.add_dependency(unhashed_http_path, dep);
             }
+            let modifier_deps = asset.modifiers.dependencies().map(|s| &**s);
+            let other_deps = asset.transform.dependencies().chain(asset.serve.dependencies());
+            for dep in modifier_deps.chain(other_deps).chain(step_deps) {
+                if !unresolved.contains_key(dep) {
+                    return Err(BuildError::MissingDependency {
+                        asset: unhashed_http_path.clone(),
+                        dependency: dep.to_owned(),
+                    });
+                }
+                dep_graph.add_dependency(&unhashed_http_path, &dep);
+            }
+            snippet_deps.extend(asset.transform.dependencies());
+            snippet_deps.extend(asset.template_includes.iter().map(|s| s.as_str()));
         }
 
         // Finally go over all assets, load and prepare them.
         let sorting = dep_graph.topological_sort().map_err(|cycle| {
             BuildError::CyclicDependencies(cycle.into_iter().map(|s| s.to_owned()).collect())
         })?;
-        let mut assets = HashMap::new();
+        let mut assets = Vec::with_capacity(unresolved.len());
         let mut path_map = PathMap::new();
+        let mut snippet_contents = HashMap::<&str, Bytes>::new();
+        let mut internal_paths = Vec::new();
         for path in sorting {
             let asset = unresolved.get(path).unwrap();
 
-            // Apply modifier
-            let raw = asset.source.load().await
-                .map_err(|(err, path)| BuildError::Io { err, path: path.to_owned() })?;
-            let content = match &asset.modifier {
-                Modifier::None => raw,
-                Modifier::PathFixup(paths) => path_fixup(raw, paths, &path_map),
-                Modifier::Custom { f, deps } => {
-                    f(raw, ModifierContext {
-                        declared_deps: &deps,
-                        inner: ModifierContextInner {
-                            path_map: &path_map,
-                            unresolved: &unresolved,
-                        },
-                    })
-                },
+            // Lazy assets are not processed, so only their file is inspected.
+            if asset.lazy {
+                let DataSource::File(fs_path) = &asset.source else {
+                    unreachable!("lazy asset not loaded from file system");
+                };
+                let io_err = |err| BuildError::Io {
+                    err,
+                    path: fs_path.clone(),
+                    http_path: path.to_owned(),
+                };
+                let file = lazy_file(fs_path).await.map_err(io_err)?;
+                if snippet_deps.contains(path) {
+                    let content = tokio::fs::read(fs_path).await.map_err(io_err)?;
+                    snippet_contents.insert(path, content.into());
+                }
+                log::debug!(
+                    target: "reinda",
+                    "built lazy asset: path={path} source={} size={}",
+                    asset.source,
+                    file.len,
+                );
+                if asset.internal {
+                    internal_paths.push(path.to_owned());
+                    continue;
+                }
+                let is_referenced = referenced.contains(path);
+                if warn_unreferenced && !is_referenced {
+                    log::warn!(target: "reinda", "asset '{path}' is not referenced by any other asset");
+                }
+
+                let mut serve = asset.serve.clone();
+                for variant in &mut serve.variants {
+                    if let Some(hashed) = path_map.get(&variant.http_path) {
+                        variant.http_path = hashed.to_owned();
+                    }
+                }
+                assets.push(BuiltAsset {
+                    path: path.to_owned(),
+                    unhashed_path: None,
+                    content: Bytes::new(),
+                    file: Some(file),
+                    hashed_filename: false,
+                    embedded: false,
+                    serve,
+                    referenced: is_referenced,
+                    #[cfg(feature = "compress")]
+                    variants: vec![],
+                });
+                continue;
+            }
+
+            let raw = match &asset.preloaded {
+                Some(raw) => raw.clone(),
+                None => asset.source.load().await.map_err(|(err, fs_path)| BuildError::Io {
+                    err,
+                    path: fs_path,
+                    http_path: path.to_owned(),
+                })?,
             };
+            let loaded_size = raw.len();
+            let raw = asset.transform.normalize(raw);
+
+            // Render template
+            let raw = match (&asset.template_deps, &asset.template) {
+                (Some(_), Some(settings)) => {
+                    let rendered = Template::parse(&raw)
+                        .and_then(|t| t.render(Some(&known_paths), |fragment| match fragment {
+                            Fragment::Path(dep) => Some(path_map.get(dep).unwrap_or(dep).to_owned()),
+                            Fragment::Include(dep) => snippet_contents.get(dep)
+                                .and_then(|content| std::str::from_utf8(content).ok())
+                                .map(ToOwned::to_owned),
+                            #[cfg(feature = "hash")]
+                            Fragment::Sri(dep) => snippet_contents.get(dep)
+                                .map(|content| crate::util::sri_hash(content, Default::default())),
+                            #[cfg(not(feature = "hash"))]
+                            Fragment::Sri(_) => None,
+                            Fragment::Var(var) => settings.var(var).map(ToOwned::to_owned),
+                        }))
+                        .map_err(|message| BuildError::Template {
+                            http_path: path.to_owned(),
+                            error: template::Error(message),
+                        })?;
+                    rendered.into()
+                }
+                _ => raw,
+            };
+
+            // Run pipeline steps. Like the modifiers, they run on a detached
+            // thread, and panics are caught inside. All together have to
+            // finish within the transform timeout, if any.
+            let (started, limit) = (tokio::time::Instant::now(), asset.transform_timeout);
+            let raw = if asset.steps.is_empty() {
+                raw
+            } else {
+                let resolved = resolve_deps(&asset.step_deps, &path_map);
+                let (steps, env, http_path) = (asset.steps.clone(), asset.env.clone(), path.to_owned());
+                let task = crate::run_detached(move || {
+                    steps.apply(&http_path, raw, &env, || ModifierContextInner { resolved: &resolved })
+                });
+                let result = crate::with_time_limit(started, limit, path, task).await?;
+                result.map_err(|e| BuildError::Pipeline {
+                    http_path: path.to_owned(),
+                    step: asset.steps.names(),
+                    message: format!("task panicked: {e}"),
+                })??
+            };
+
+            // Apply modifiers, in order
+            let mut content = raw;
+            for modifier in &asset.modifiers.0 {
+                content = match modifier {
+                    Modifier::PathFixup(paths) => path_fixup(content, paths, &path_map),
+                    Modifier::Custom { f, deps } => {
+                        // The modifier is run on a detached thread, as it
+                        // might take a long time or even never return. That
+                        // way, this future stays responsive and can be
+                        // dropped (e.g. by `build_with_deadline`) without
+                        // anything waiting for the modifier. All
+                        // dependencies have been checked above, so they can
+                        // be resolved already.
+                        let resolved = resolve_deps(deps, &path_map);
+                        let (f, deps, env) = (f.clone(), deps.clone(), asset.env.clone());
+                        let http_path = path.to_owned();
+                        let task = crate::run_detached(move || {
+                            let ctx = ModifierContext {
+                                declared_deps: &deps,
+                                env: &env,
+                                inner: ModifierContextInner { resolved: &resolved },
+                            };
+                            crate::call_modifier(&*f, content, ctx, &http_path)
+                        });
+                        let result = crate::with_time_limit(started, limit, path, task).await?;
+                        result.map_err(|e| BuildError::ModifierPanicked {
+                            http_path: path.to_owned(),
+                            error: ModifierError::with_source(e.to_string(), e),
+                        })?.map_err(|mut e| {
+                            if let BuildError::Modifier { dependents, .. } = &mut e {
+                                *dependents = dependent_chain(&unresolved, path);
+                            }
+                            e
+                        })?
+                    },
+                };
+            }
+
+            let prepend = load_snippets(&asset.transform.prepend, path, &snippet_contents).await?;
+            let append = load_snippets(&asset.transform.append, path, &snippet_contents).await?;
+            let content = asset.transform.apply(path, content, &prepend, &append);
+            if snippet_deps.contains(path) {
+                snippet_contents.insert(path, content.clone());
+            }
 
             // Potentially hash filename
-            let final_path = crate::hash::path_of(asset.path_hash, &path, &content, &mut path_map);
+            let final_path = crate::hash::path_of(asset.path_hash, path, &content, &mut path_map);
+
+            log::debug!(
+                target: "reinda",
+                "built asset: path={final_path} unhashed_path={path} source={} \
+                    steps={:?} modifiers={:?} size_loaded={} size_final={} hashed={}",
+                asset.source,
+                asset.steps,
+                asset.modifiers,
+                loaded_size,
+                content.len(),
+                !matches!(asset.path_hash, PathHash::None),
+            );
+
+            // Internal assets are not served, they only needed to be built to
+            // resolve their path in other assets.
+            if asset.internal {
+                internal_paths.extend([path.to_owned(), final_path]);
+                continue;
+            }
 
-            assets.insert(final_path, Asset(AssetInner {
+            let is_referenced = referenced.contains(path);
+            if warn_unreferenced && !is_referenced {
+                log::warn!(target: "reinda", "asset '{final_path}' is not referenced by any other asset");
+            }
+
+            #[cfg(feature = "compress")]
+            let variants = compress(&final_path, &content, asset.compression.clone()).await;
+
+            // Variants were built already, so their final paths are known.
+            let mut serve = asset.serve.clone();
+            for variant in &mut serve.variants {
+                if let Some(hashed) = path_map.get(&variant.http_path) {
+                    variant.http_path = hashed.to_owned();
+                }
+            }
+
+            assets.push(BuiltAsset {
+                unhashed_path: (final_path != path).then(|| path.to_owned()),
+                path: final_path,
                 content,
+                file: None,
                 hashed_filename: !matches!(asset.path_hash, PathHash::None),
-            }));
+                embedded: asset.embedded,
+                serve,
+                referenced: is_referenced,
+                #[cfg(feature = "compress")]
+                variants,
+            });
+        }
+
+        // Run post-build hooks on all served assets and add the assets they
+        // produce. Lazy assets have to be loaded for that.
+        if !post_build.is_empty() {
+            let mut view = Vec::with_capacity(assets.len());
+            for asset in &assets {
+                let unhashed = asset.unhashed_path.clone().unwrap_or_else(|| asset.path.clone());
+                let content = match &asset.file {
+                    None => asset.content.clone(),
+                    Some(file) => tokio::fs::read(&file.path).await
+                        .map_err(|err| BuildError::Io {
+                            err,
+                            path: file.path.clone(),
+                            http_path: unhashed.clone(),
+                        })?
+                        .into(),
+                };
+                view.push((unhashed, asset.path.clone(), content));
+            }
+
+            for (path, content) in post_build.run(view, internal_paths, public_url)? {
+                log::debug!(target: "reinda", "post-build hook added asset '{path}'");
+                #[cfg(feature = "compress")]
+                let variants = compress(&path, &content, global_compression.clone()).await;
+                assets.push(BuiltAsset {
+                    unhashed_path: None,
+                    path,
+                    content,
+                    file: None,
+                    hashed_filename: false,
+                    embedded: false,
+                    serve: Default::default(),
+                    referenced: true,
+                    #[cfg(feature = "compress")]
+                    variants,
+                });
+            }
         }
 
-        Ok(Self { assets })
+        let redirects = renamed.into_iter()
+            .map(|(old, new)| {
+                let hashed = path_map.get(&new).map_or(new.clone(), ToOwned::to_owned);
+                (old, hashed)
+            })
+            .collect();
+
+        let table = Table::new(assets)?;
+        #[cfg(feature = "compress")]
+        if table.deduplicated_bytes() > 0 {
+            log::debug!(
+                target: "reinda",
+                "sharing identical compressed variants saved {} bytes",
+                table.deduplicated_bytes(),
+            );
+        }
+
+        Ok(Self { table: Arc::new(table), redirects: Arc::new(redirects) })
     }
 
     pub(crate) fn get(&self, http_path: &str) -> Option<Asset> {
-        self.assets.get(http_path).cloned()
+        self.table.get(http_path).map(|id| self.asset(id))
     }
 
     pub(crate) fn len(&self) -> usize {
-        self.assets.len()
+        self.table.len()
     }
 
     pub(crate) fn iter(&self) -> impl '_ + Iterator<Item = (&str, Asset)> {
-        self.assets.iter().map(|(k, v)| (&**k, v.clone()))
+        self.table.ids().map(move |id| (self.table.path(id), self.asset(id)))
+    }
+
+    pub(crate) fn paths_under<'s>(&'s self, prefix: &'s str) -> impl 's + Iterator<Item = &'s str> {
+        self.table.ids_with_prefix(prefix).map(move |id| self.table.path(id))
+    }
+
+    pub(crate) async fn unreferenced(&self) -> Vec<&str> {
+        self.table.ids().filter(|&id| !self.table.is_referenced(id)).map(|id| self.table.path(id)).collect()
+    }
+
+    #[cfg(feature = "compress")]
+    pub(crate) fn deduplicated_bytes(&self) -> usize {
+        self.table.deduplicated_bytes()
+    }
+
+    pub(crate) fn hot_cache_stats(&self) -> Option<crate::HotCacheStats> {
+        None
+    }
+
+    pub(crate) fn redirect(&self, http_path: &str) -> Option<&str> {
+        self.redirects.get(http_path).map(|s| &**s)
+    }
+
+    fn asset(&self, id: AssetId) -> Asset {
+        Asset(AssetInner { table: self.table.clone(), id })
     }
 }
 
 impl fmt::Debug for AssetsInner {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.assets.keys().fmt(f)
+        self.table.fmt(f)
     }
 }
 
@@ -129,11 +466,80 @@ impl AssetInner {
     /// in dev mode, potentially returning IO errors. In prod mode, the file
     /// contents are already loaded and this method always returns `Ok(_)`.
     pub(crate) async fn content(&self) -> Result<Bytes, io::Error> {
-        Ok(self.content.clone())
+        match self.table.lazy_file(self.id) {
+            Some(file) => Ok(tokio::fs::read(&file.path).await?.into()),
+            None => Ok(self.table.content(self.id).clone()),
+        }
+    }
+
+    pub(crate) async fn stream(&self) -> Result<ContentReader, io::Error> {
+        match self.table.lazy_file(self.id) {
+            Some(file) => Ok(ContentReader::file(tokio::fs::File::open(&file.path).await?)),
+            None => Ok(ContentReader::memory(self.table.content(self.id).clone())),
+        }
+    }
+
+    pub(crate) async fn content_len(&self) -> Result<u64, io::Error> {
+        match self.table.lazy_file(self.id) {
+            Some(file) => Ok(file.len),
+            None => Ok(self.table.content(self.id).len() as u64),
+        }
+    }
+
+    pub(crate) async fn content_range(&self, range: Range<u64>) -> Result<Bytes, io::Error> {
+        match self.table.lazy_file(self.id) {
+            Some(file) => crate::read_file_range(&file.path, range).await,
+            None => Ok(crate::slice_range(self.table.content(self.id), range)),
+        }
+    }
+
+    #[cfg(feature = "compress")]
+    pub(crate) async fn encoded_content(&self, encoding: Encoding) -> Result<Option<Bytes>, io::Error> {
+        let variant = self.table.variants(self.id).iter()
+            .find(|(e, _)| *e == encoding)
+            .map(|(_, b)| b.clone());
+        Ok(variant)
+    }
+
+    #[cfg(feature = "compress")]
+    pub(crate) async fn encoded_variants(&self) -> Result<Vec<(Encoding, Bytes)>, io::Error> {
+        Ok(self.table.variants(self.id).to_vec())
+    }
+
+    #[cfg(feature = "hash")]
+    pub(crate) async fn cache_key(&self) -> Result<String, io::Error> {
+        Ok(crate::util::format_cache_key(self.table.cache_key(self.id)))
+    }
+
+    #[cfg(feature = "hash")]
+    pub(crate) async fn csp_hashes(&self) -> Result<crate::CspHashes, io::Error> {
+        Ok(self.table.csp_hashes(self.id))
     }
 
     pub(crate) fn is_filename_hashed(&self) -> bool {
-        self.hashed_filename
+        self.table.is_filename_hashed(self.id)
+    }
+
+    pub(crate) fn is_embedded(&self) -> bool {
+        self.table.is_embedded(self.id)
+    }
+
+    pub(crate) fn serve_settings(&self) -> &ServeSettings {
+        self.table.serve_settings(self.id)
+    }
+
+    pub(crate) fn http_path(&self) -> &str {
+        self.table.path(self.id)
+    }
+
+    pub(crate) fn unhashed_http_path(&self) -> &str {
+        self.table.unhashed_path(self.id)
+    }
+}
+
+impl fmt::Debug for AssetInner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Asset").field("path", &self.table.path(self.id)).finish()
     }
 }
 
@@ -141,26 +547,157 @@ impl AssetInner {
 #[derive(Debug)]
 struct UnresolvedAsset<'a> {
     source: DataSource,
-    modifier: Modifier,
+
+    /// The registered pipeline steps applying to this asset, and their
+    /// dependencies.
+    steps: Steps,
+    step_deps: Vec<Cow<'static, str>>,
+    env: Arc<ModifierEnv>,
+    transform_timeout: Option<Duration>,
+    modifiers: Modifiers,
     path_hash: PathHash<'a>,
+    template: Option<TemplateSettings>,
+
+    /// Dependencies of the template, if this asset is rendered as template.
+    template_deps: Option<Vec<String>>,
+
+    /// The subset of `template_deps` whose content is needed (`include` and
+    /// `sri` fragments).
+    template_includes: Vec<String>,
+
+    /// Content, if it was already loaded before the main loop.
+    preloaded: Option<Bytes>,
+    internal: bool,
+    lazy: bool,
+
+    /// Whether the content is stored in the executable (or its sidecar).
+    embedded: bool,
+    serve: ServeSettings,
+    transform: TransformSettings,
+    #[cfg(feature = "compress")]
+    compression: Option<CompressionConfig>,
 }
 
 #[derive(Debug)]
 pub(crate) struct ModifierContextInner<'a> {
-    path_map: &'a PathMap<'a>,
-    unresolved: &'a HashMap<String, UnresolvedAsset<'a>>,
+    /// Maps all declared dependencies to their hashed HTTP path.
+    resolved: &'a HashMap<String, String>,
 }
 
 impl<'a> ModifierContextInner<'a> {
     pub(crate) fn resolve_path<'b>(&'b self, unhashed_http_path: &'b str) -> Option<&'b str> {
-        self.path_map.get(unhashed_http_path).or_else(|| {
-            if self.unresolved.contains_key(unhashed_http_path) {
-                Some(unhashed_http_path)
-            } else {
-                None
+        self.resolved.get(unhashed_http_path).map(|s| &**s)
+    }
+}
+
+impl UnresolvedAsset<'_> {
+    /// Returns the *unhashed HTTP paths* of all assets this asset depends on.
+    fn dependencies(&self) -> impl '_ + Iterator<Item = &str> {
+        self.template_deps.iter().flatten().map(|s| s.as_str())
+            .chain(self.modifiers.dependencies().map(|s| &**s))
+            .chain(self.step_deps.iter().map(|s| &**s))
+            .chain(self.transform.dependencies())
+            .chain(self.serve.dependencies())
+    }
+}
+
+/// Returns a chain of assets depending on `path`: an asset depending on it,
+/// then one depending on that and so on. If there are several, the
+/// alphabetically first one is picked. Only used for error messages.
+fn dependent_chain(unresolved: &HashMap<String, UnresolvedAsset<'_>>, path: &str) -> Vec<String> {
+    let mut chain = Vec::<String>::new();
+    let mut current = path;
+    loop {
+        let next = unresolved.iter()
+            .filter(|(_, asset)| asset.dependencies().any(|dep| dep == current))
+            .map(|(p, _)| p)
+            .filter(|p| p.as_str() != path && !chain.contains(p))
+            .min();
+        match next {
+            Some(next) => {
+                chain.push(next.clone());
+                current = next;
             }
+            None => return chain,
+        }
+    }
+}
+
+/// Creates compressed variants, if configured. This can take a while, so
+/// it's done on the blocking thread pool.
+#[cfg(feature = "compress")]
+async fn compress(
+    http_path: &str,
+    content: &Bytes,
+    config: Option<CompressionConfig>,
+) -> Vec<(Encoding, Bytes)> {
+    let Some(config) = config else {
+        return vec![];
+    };
+    let (http_path, content) = (http_path.to_owned(), content.clone());
+    tokio::task::spawn_blocking(move || crate::compress::variants(&http_path, &content, &config))
+        .await
+        .expect("compression task panicked")
+}
+
+/// Determines length and cache key of the file of a lazy asset. The file is
+/// read in chunks on the blocking thread pool, so it's never fully in memory.
+async fn lazy_file(path: &Path) -> Result<LazyFile, io::Error> {
+    let path = path.to_owned();
+    tokio::task::spawn_blocking(move || {
+        let file = std::fs::File::open(&path)?;
+
+        #[cfg(feature = "hash")]
+        {
+            use sha2::{Digest, Sha256};
+
+            let mut hasher = Sha256::new();
+            let len = io::copy(&mut &file, &mut hasher)?;
+            let mut cache_key = [0; 16];
+            cache_key.copy_from_slice(&hasher.finalize()[..16]);
+            Ok(LazyFile { path, len, cache_key })
+        }
+
+        #[cfg(not(feature = "hash"))]
+        {
+            let len = file.metadata()?.len();
+            Ok(LazyFile { path, len })
+        }
+    }).await.expect("hashing task panicked")
+}
+
+/// Maps the given dependencies to their *hashed HTTP paths*. All
+/// dependencies were built already.
+fn resolve_deps(deps: &[Cow<'static, str>], path_map: &PathMap) -> HashMap<String, String> {
+    deps.iter()
+        .map(|dep| {
+            let hashed = path_map.get(dep).unwrap_or(dep);
+            (dep.to_string(), hashed.to_owned())
         })
+        .collect()
+}
+
+/// Loads the contents of the given snippets of the asset `http_path`. All
+/// assets referenced by snippets were built before (see the dep graph) and
+/// are stored in `built`.
+async fn load_snippets(
+    snippets: &[Snippet],
+    http_path: &str,
+    built: &HashMap<&str, Bytes>,
+) -> Result<Vec<Bytes>, BuildError> {
+    let mut out = Vec::with_capacity(snippets.len());
+    for snippet in snippets {
+        let content = match snippet {
+            Snippet::Source(source) => source.load().await.map_err(|(err, path)| BuildError::Io {
+                err,
+                path,
+                http_path: http_path.to_owned(),
+            })?,
+            Snippet::Asset(dep) => built[&**dep].clone(),
+        };
+        out.push(content);
     }
+    Ok(out)
 }
 
 fn path_fixup(original: Bytes, paths: &[Cow<'static, str>], path_map: &PathMap) -> Bytes {
@@ -0,0 +1,215 @@
+//! An HTML page (and a JSON version of it) listing all assets with their
+//! processing, for debugging the asset pipeline in dev mode. See
+//! `Builder::enable_inspector`.
+
+use std::{fmt::Write, time::{SystemTime, UNIX_EPOCH}};
+
+use bytes::Bytes;
+
+use crate::{http::Response, util::json_string, Assets};
+
+
+/// Information about one asset shown on the inspector page.
+pub(crate) struct Row {
+    pub(crate) http_path: String,
+    pub(crate) source: String,
+    pub(crate) internal: bool,
+
+    /// Human readable names of all configured processing steps, in order.
+    pub(crate) processing: Vec<String>,
+    pub(crate) dependencies: Vec<String>,
+
+    /// Size of the final content, or the error that occured loading it.
+    pub(crate) size: Result<u64, String>,
+    pub(crate) cache_key: Option<String>,
+
+    /// Modification time of the file the asset is loaded from.
+    pub(crate) modified: Option<SystemTime>,
+}
+
+/// Answers a request for the inspector page, as JSON if `json` is set.
+pub(crate) async fn serve(assets: &Assets, json: bool, is_head: bool) -> Response {
+    let rows = assets.inner.inspect().await;
+    let (body, content_type) = if json {
+        (render_json(&rows), "application/json")
+    } else {
+        (render(&rows, SystemTime::now()), "text/html; charset=utf-8")
+    };
+    let body = if is_head { Bytes::new() } else { body.into() };
+    Response::new(200, body)
+        .with_header("content-type", content_type)
+        .with_header("cache-control", "no-store")
+}
+
+fn render(rows: &[Row], now: SystemTime) -> String {
+    let mut out = String::from(concat!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>reinda assets</title>",
+        "<style>body{font-family:sans-serif}table{border-collapse:collapse}",
+        "td,th{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}",
+        ".internal{color:#888}.error{color:#c00}</style></head><body>\n",
+    ));
+    let _ = writeln!(out, "<h1>{} assets</h1>", rows.len());
+    out.push_str(concat!(
+        "<table><tr><th>Path</th><th>Source</th><th>Size</th><th>Cache key</th>",
+        "<th>Processing</th><th>Dependencies</th><th>Modified</th></tr>\n",
+    ));
+    for row in rows {
+        let class = if row.internal { " class=\"internal\"" } else { "" };
+        let path = escape(&row.http_path);
+        let _ = write!(out, "<tr{class}><td>");
+        if row.internal {
+            let _ = write!(out, "/{path} (internal)");
+        } else {
+            let _ = write!(out, "<a href=\"/{path}\">/{path}</a>");
+        }
+        let _ = write!(out, "</td><td>{}</td>", escape(&row.source));
+        let _ = match &row.size {
+            Ok(size) => write!(out, "<td>{size} B</td>"),
+            Err(e) => write!(out, "<td class=\"error\">{}</td>", escape(e)),
+        };
+        let _ = write!(out, "<td><code>{}</code></td>", row.cache_key.as_deref().unwrap_or("-"));
+        let _ = write!(out, "<td>{}</td>", list(&row.processing));
+        let _ = write!(out, "<td>{}</td>", list(&row.dependencies));
+        let _ = match row.modified.and_then(|t| now.duration_since(t).ok()) {
+            Some(age) => write!(out, "<td>{}s ago</td>", age.as_secs()),
+            None => write!(out, "<td>-</td>"),
+        };
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table></body></html>\n");
+    out
+}
+
+/// Renders the same information as `render` as JSON object with the field
+/// `assets`. Times are given as seconds since the Unix epoch.
+fn render_json(rows: &[Row]) -> String {
+    let strings = |items: &[String]| {
+        items.iter().map(|item| json_string(item)).collect::<Vec<_>>().join(",")
+    };
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "null".into());
+
+    let mut out = String::from("{\"assets\":[");
+    for (i, row) in rows.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let (size, error) = match &row.size {
+            Ok(size) => (Some(size.to_string()), None),
+            Err(e) => (None, Some(json_string(e))),
+        };
+        let modified = row.modified
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs().to_string());
+        let _ = write!(
+            out,
+            concat!(
+                "{{\"path\":{},\"source\":{},\"internal\":{},\"processing\":[{}],",
+                "\"dependencies\":[{}],\"size\":{},\"error\":{},\"cache_key\":{},",
+                "\"modified\":{}}}",
+            ),
+            json_string(&row.http_path),
+            json_string(&row.source),
+            row.internal,
+            strings(&row.processing),
+            strings(&row.dependencies),
+            optional(size),
+            optional(error),
+            optional(row.cache_key.as_deref().map(json_string)),
+            optional(modified),
+        );
+    }
+    out.push_str("]}\n");
+    out
+}
+
+fn list(items: &[String]) -> String {
+    if items.is_empty() {
+        return "-".into();
+    }
+    items.iter().map(|item| escape(item)).collect::<Vec<_>>().join("<br>")
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn rows(now: SystemTime) -> [Row; 2] {
+        [
+            Row {
+                http_path: "index.html".into(),
+                source: "assets/index.html".into(),
+                internal: false,
+                processing: vec!["template".into(), "modifier".into()],
+                dependencies: vec!["<style>.css".into()],
+                size: Ok(2048),
+                cache_key: Some("abc".into()),
+                modified: Some(now - Duration::from_secs(3)),
+            },
+            Row {
+                http_path: "header.html".into(),
+                source: "<embedded>".into(),
+                internal: true,
+                processing: vec![],
+                dependencies: vec![],
+                size: Err("not found".into()),
+                cache_key: None,
+                modified: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn rendering() {
+        let now = SystemTime::now();
+        let rows = rows(now);
+        let html = render(&rows, now);
+        assert!(html.contains("<h1>2 assets</h1>"));
+        assert!(html.contains("<a href=\"/index.html\">/index.html</a>"));
+        assert!(html.contains("<td>2048 B</td>"));
+        assert!(html.contains("template<br>modifier"));
+        assert!(html.contains("&lt;style&gt;.css"));
+        assert!(html.contains("<td>3s ago</td>"));
+        assert!(html.contains("/header.html (internal)"));
+        assert!(html.contains("<td class=\"error\">not found</td>"));
+    }
+
+    #[test]
+    fn rendering_json() {
+        let now = UNIX_EPOCH + Duration::from_secs(1000);
+        let json = render_json(&rows(now));
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value, serde_json::json!({
+            "assets": [
+                {
+                    "path": "index.html",
+                    "source": "assets/index.html",
+                    "internal": false,
+                    "processing": ["template", "modifier"],
+                    "dependencies": ["<style>.css"],
+                    "size": 2048,
+                    "error": null,
+                    "cache_key": "abc",
+                    "modified": 997,
+                },
+                {
+                    "path": "header.html",
+                    "source": "<embedded>",
+                    "internal": true,
+                    "processing": [],
+                    "dependencies": [],
+                    "size": null,
+                    "error": "not found",
+                    "cache_key": null,
+                    "modified": null,
+                },
+            ],
+        }));
+
+    }
+}
@@ -70,6 +70,12 @@
 //! Reinda operates in one of two modes: *prod* or *dev*. Prod mode is enabled
 //! if you are building in release mode (e.g. `cargo build --release`) or if
 //! you enabled the crate feature `always-prod`. Otherwise, dev mode is enabled.
+//! To choose the mode independent of the optimization level, enable
+//! `always-prod` (e.g. for a production build with debug assertions) or
+//! `always-dev` (e.g. for local development with `--release`). If both are
+//! enabled, e.g. by different crates in the dependency graph, `always-prod`
+//! wins and the build emits a warning. At runtime, the mode is returned by
+//! [`Assets::mode`].
 //!
 //! The mode influences the behavior of reinda significantly. The following
 //! table describes those differences, though you likely don't need to worry
@@ -85,6 +91,17 @@
 //! | `Asset::content` | Just returns the already loaded `Bytes` | Loads file from file system, applies modifier |
 //!
 //!
+//! Individual entries can deviate from their mode: files marked with
+//! `always_embed` in `embed!` are embedded even in dev mode, and entries
+//! marked with [`EntryBuilder::lazy`] are read from the file system even in
+//! prod mode, e.g. for huge videos. Check with [`Asset::is_embedded`].
+//!
+//! Independent of the mode, assets are served byte-identical to their source
+//! file unless you configure processing (hash, modifier, template or one of
+//! the content transformations). Use [`EntryBuilder::verbatim`] to make sure
+//! that this stays the case.
+//!
+//!
 //! # Glossary: kinds of paths
 //!
 //! This library is dealing with different kind of paths, which could be
@@ -108,15 +125,61 @@
 //!
 //! - **`compress`** (enabled by default): if enabled, embedded files are
 //!   compressed. This often noticably reduces the binary size of the
-//!   executable. This feature adds the `brotli` dependency.
+//!   executable. Also enables precompressing assets for serving them with a
+//!   `Content-Encoding` (see [`Builder::set_compression`]). This feature adds
+//!   the `brotli` and `flate2` dependencies.
+//!
+//! - **`zstd`**: adds zstd to the encodings used for precompression (see
+//!   [`Encoding`]). Implies `compress`. This feature adds the `zstd`
+//!   dependency.
 //!
 //! - **`hash`** (enabled by default): is required for support of filename
 //!   hashing (see above). This feature adds the `base64` and `sha2`
 //!   dependencies.
 //!
+//! - **`sidecar`**: enables the `sidecar` option of [`embed!`], to store
+//!   file contents next to the executable instead of inside it. This feature
+//!   adds the `sha2` dependency.
+//!
+//! - **`testing`**: enables the [`testing`] module with an HTTP server to
+//!   check for broken references in integration tests. This feature adds
+//!   the `hyper`, `hyper-util` and `http-body-util` dependencies.
+//!
+//! - **`minify`**: enables the [`modifiers`] module with modifiers to minify
+//!   JavaScript, CSS and HTML in prod mode. This feature adds the
+//!   `minify-js`, `lightningcss` and `minify-html` dependencies.
+//!
+//! - **`watch`**: enables [`Assets::subscribe_changes`] to get notified when
+//!   the files of assets change in dev mode, e.g. for live reloading. Adds
+//!   the `notify` dependency.
+//!
+//! - **`reload`**: enables the [`reload`] module to rebuild assets at
+//!   runtime and swap them in, e.g. on `SIGHUP`.
+//!
+//! - **`runtime-mutation`**: enables [`Assets::insert`] and
+//!   [`Assets::remove`] to add assets after building, e.g. user uploads.
+//!
+//! - **`serde`**: derives `Serialize` for [`Manifest`] and enables
+//!   [`Manifest::to_json`] and [`Assets::write_manifest_json`]. This feature
+//!   adds the `serde` and `serde_json` dependencies.
+//!
+//! - **`url`**: enables [`Builder::add_url`] to fetch assets from remote
+//!   origins. This feature adds the `reqwest` dependency.
+//!
+//! - **`actix`**: enables the [`actix`] module and
+//!   [`Assets::into_actix_service`] to serve assets from an actix-web app.
+//!   This feature adds the `actix-web` dependency.
+//!
+//! - **`axum`**: enables the [`axum`][mod@axum] module and
+//!   [`Assets::into_axum_router`] to serve assets from an axum app. This
+//!   feature adds the `axum` dependency.
+//!
 //! - **`always-prod`**: enabled *prod* mode even when compiled in debug mode.
 //!   See the section about "prod" and "dev" mode above.
 //!
+//! - **`always-dev`**: enables *dev* mode even when compiled in release mode.
+//!   Ignored (with a build warning) if `always-prod` is enabled as well.
+//!
 //!
 //! # Notes, Requirements and Limitations
 //!
@@ -130,17 +193,70 @@
 
 #![deny(missing_debug_implementations)]
 
-use std::{borrow::Cow, fmt, io, path::{Path, PathBuf}, sync::Arc};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fmt, io,
+    future::Future,
+    ops::Range,
+    path::{Component, Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
 
 use bytes::Bytes;
 
+#[cfg(feature = "actix")]
+pub mod actix;
+#[cfg(feature = "axum")]
+pub mod axum;
 mod builder;
+#[cfg_attr(prod_mode, allow(dead_code))]
+mod change;
+#[cfg(feature = "compress")]
+mod compress;
+#[cfg(feature = "hash")]
+mod csp;
+mod dir;
 mod embed;
+mod export;
+#[cfg(dev_mode)]
+mod inspector;
 #[cfg(prod_mode)]
 mod hash;
+#[cfg_attr(prod_mode, allow(dead_code))]
+mod hot_cache;
 #[cfg(prod_mode)]
 mod dep_graph;
+pub mod http;
+mod links;
+mod manifest;
+mod mime;
+#[cfg(feature = "minify")]
+pub mod modifiers;
+#[cfg(feature = "runtime-mutation")]
+mod mutation;
+mod pipeline;
+mod post_build;
+#[cfg(feature = "reload")]
+pub mod reload;
+#[cfg(feature = "url")]
+mod remote;
+mod router;
+#[cfg(all(prod_mode, feature = "sidecar"))]
+mod sidecar;
+mod size;
+mod suggest;
+#[cfg(prod_mode)]
+mod table;
+pub mod template;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod transform;
 pub mod util;
+#[cfg(feature = "watch")]
+pub mod watch;
 
 #[cfg_attr(prod_mode, path = "imp_prod.rs")]
 #[cfg_attr(dev_mode, path = "imp_dev.rs")]
@@ -149,10 +265,29 @@ mod imp;
 
 
 pub use self::{
-    builder::{Builder, EntryBuilder},
+    builder::{Builder, EntryBuilder, BASE_PATH_ENV_VAR, PROFILE_ENV_VAR},
+    change::ChangeDetection,
     embed::{EmbeddedEntry, EmbeddedFile, EmbeddedGlob, Embeds},
+    export::ExportOptions,
+    hot_cache::HotCacheStats,
+    links::BrokenLink,
+    manifest::{Manifest, ManifestEntry},
+    pipeline::Pipeline,
+    post_build::{BuildView, ViewedAsset},
+    router::AssetsRouter,
+    size::EntrySize,
 };
 
+#[cfg(feature = "compress")]
+pub use self::compress::{CompressionConfig, Encoding};
+
+#[cfg(feature = "hash")]
+pub use self::{csp::CspHashes, util::SriAlgorithm};
+
+#[cfg(prod_mode)]
+#[doc(hidden)]
+pub use self::embed::SidecarLocation;
+
 
 
 /// Embeds files into the executable.
@@ -169,12 +304,32 @@ pub use self::{
 /// The following fields can be specified, with only `files` being mandatory:
 ///
 /// - **`files`** (array of strings): list of paths or patterns of files that
-///   should be embedded.
+///   should be embedded. A pattern can be followed by `except` and one or an
+///   array of patterns of files to skip, e.g.
+///   `"assets/**/*.js" except ["assets/**/*.test.js", "assets/vendor/*"]`.
+///   These are matched against the whole path, like the pattern itself. The
+///   entry is still referred to by the first pattern, i.e.
+///   `EMBEDS["assets/**/*.js"]`.
 ///
-/// - **`base_path`** (string): a base path that is prefixed to all values in
-///   `files`. Relative to `Cargo.toml`. Empty if unspecified. For a path `path`
-///   in `files`, the following file is loaded:
-///   `${CARGO_MANIFEST_DIR}/${base_dir}/${path}`.
+///   A path or pattern can also be followed by `always_embed` (before or
+///   after `except`), e.g. `"templates/*.html" always_embed`. Those files
+///   are embedded even in dev mode, so they are available even if the
+///   source directory is not. Changes are then only picked up on recompile.
+///
+///   Patterns use the syntax of the [`glob`](https://docs.rs/glob) crate,
+///   plus `{a,b}` alternatives (e.g. `"assets/*.{js,css}"`, can be nested)
+///   and `**` at the start of a segment (`"**.js"` is the same as
+///   `"**/*.js"`). Other uses of `**` and unbalanced braces are compile
+///   errors. Literal braces in file names have to be escaped as `[{]` and
+///   `[}]`.
+///
+/// - **`base_path`** (string or array of strings): a base path that is
+///   prefixed to all values in `files`. Relative to `Cargo.toml`. Empty if
+///   unspecified. For a path `path` in `files`, the following file is loaded:
+///   `${CARGO_MANIFEST_DIR}/${base_dir}/${path}`. If multiple base paths are
+///   given (e.g. `["frontend/dist", "frontend/public"]`), they are searched in
+///   order and the first one containing a file wins. Globs match files in all
+///   base paths. This also applies to files loaded at runtime in dev mode.
 ///
 /// - **`print_stats`** (bool): if set to true, reinda will print stats about
 ///   embedded files at compile time. Default: `false`.
@@ -190,6 +345,18 @@ pub use self::{
 /// - **`compression_quality`** (int): sets the Brotli compression quality (from
 ///   1 to 11). Default: `9`.
 ///
+/// - **`sidecar`** (string): if set, the file contents are not embedded into
+///   the executable, but written to a single "sidecar" file at this path
+///   (relative to `Cargo.toml`) at compile time. At runtime, this file is
+///   loaded from the same relative path, but relative to the directory of the
+///   executable (or relative to the directory in the environment variable
+///   `REINDA_SIDECAR_DIR`, if set). The SHA-256 of each file's content is
+///   stored in the executable and verified when loading. As the sidecar
+///   file is deterministic, executables for different targets built from the
+///   same assets can share one sidecar file, instead of each embedding all
+///   assets. Only has an effect in prod mode and requires the crate feature
+///   `sidecar`. Don't use the same path in multiple `embed!` invocations.
+///
 /// For compression to be used at all, the `compress` feature needs to be
 /// enabled.
 ///
@@ -216,26 +383,99 @@ pub use reinda_macros::embed;
 /// You create an instance of this by using [`Self::builder`] and eventually
 /// call [`Builder::build`].
 #[derive(Debug, Clone)]
-pub struct Assets(imp::AssetsInner);
+pub struct Assets {
+    inner: imp::AssetsInner,
+    http: Arc<http::HttpConfig>,
+    sizes: Arc<[EntrySize]>,
+    #[cfg(feature = "watch")]
+    watcher: Arc<watch::Watcher>,
+    #[cfg(feature = "runtime-mutation")]
+    runtime: Arc<mutation::RuntimeAssets>,
+}
 
 impl Assets {
     /// Returns a builder, allowing you to add and configure assets.
     pub fn builder<'a>() -> Builder<'a> {
-        Builder { assets: vec![] }
+        Builder {
+            assets: vec![],
+            #[cfg(feature = "compress")]
+            compression: None,
+            http: Default::default(),
+            profile: std::env::var(builder::PROFILE_ENV_VAR).ok(),
+            public_url: None,
+            normalize_text: false,
+            banner: None,
+            warn_unreferenced: false,
+            hot_cache: None,
+            change_detection: None,
+            transform_timeout: None,
+            vars: BTreeMap::new(),
+            plugins: Default::default(),
+            post_build: Default::default(),
+            renamed: vec![],
+            invalid_glob_files: vec![],
+            base_path_env_var: None,
+            defaults: Default::default(),
+        }
     }
 
     /// Retrieves an asset by *hashed HTTP path*. In prod mode, this is just a
     /// fast hash map lookup. In dev mode, the asset is loaded from the file
     /// system.
     pub fn get(&self, http_path: &str) -> Option<Asset> {
-        self.0.get(http_path)
+        #[cfg(feature = "runtime-mutation")]
+        if let Some(asset) = self.runtime.get(http_path) {
+            return Some(asset);
+        }
+        self.inner.get(http_path)
     }
 
     /// Returns the number of assets. For glob patterns, see [`Self::iter`] for
     /// details. This method always returns the same number as
     /// `self.iter().count()` (but faster).
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.inner.len()
+    }
+
+    /// Returns all *hashed HTTP paths* starting with `prefix`, in
+    /// lexicographical order. To list a "directory", pass a prefix ending in
+    /// `/`. This uses a sorted index, so it does not scan all assets. Like
+    /// [`Self::iter`], this only returns files of glob entries that were
+    /// found at compile time.
+    pub fn paths_under<'s>(&'s self, prefix: &'s str) -> impl 's + Iterator<Item = &'s str> {
+        self.inner.paths_under(prefix)
+    }
+
+    /// Returns the *hashed HTTP paths* of all assets that are not referenced
+    /// by any other asset, i.e. that are neither a dependency of a modifier
+    /// nor referenced in a template. This helps finding unused files that
+    /// just bloat the executable. Naturally, entry points like `index.html`
+    /// are also returned. The result is sorted.
+    ///
+    /// In dev mode, this needs to load all templates to find their
+    /// references, which is why this is `async`.
+    pub async fn unreferenced(&self) -> Vec<&str> {
+        self.inner.unreferenced().await
+    }
+
+    /// Returns up to five *hashed HTTP paths* of existing assets that are
+    /// similar to `http_path` (e.g. due to a typo or a missing hash), best
+    /// match first. Useful for debugging failed lookups, e.g. on a 404 page
+    /// in development. Like [`Self::iter`], this only considers files of
+    /// glob entries that were found at compile time.
+    pub fn similar_paths(&self, http_path: &str) -> Vec<&str> {
+        suggest::similar(http_path, self.iter().map(|(path, _)| path))
+    }
+
+    /// Returns the mode this crate was compiled in, see [`Mode`].
+    pub fn mode(&self) -> Mode {
+        Mode::CURRENT
+    }
+
+    /// Returns the current metrics of the hot cache, or `None` if it is not
+    /// enabled (see [`Builder::enable_hot_cache`]) or in prod mode.
+    pub fn hot_cache_stats(&self) -> Option<HotCacheStats> {
+        self.inner.hot_cache_stats()
     }
 
     /// Returns an iterator over all assets and their *hashed HTTP paths*.
@@ -244,7 +484,26 @@ impl Assets {
     ///  those found at compile time. This does *not* perform a glob walk over
     ///  directories.
     pub fn iter(&self) -> impl '_ + Iterator<Item = (&str, Asset)> {
-        self.0.iter()
+        self.inner.iter()
+    }
+
+    /// Stops all background work of this `Assets` and all its clones, e.g.
+    /// at the end of a test or when a server shuts down gracefully. Returns
+    /// once everything is stopped. Assets can still be retrieved and served
+    /// afterwards.
+    ///
+    /// Currently, the only background work is the task watching files for
+    /// changes in dev mode (see `Assets::subscribe_changes` with the crate
+    /// feature `watch`). It is stopped and all receivers of changes are
+    /// closed. The task is also stopped (without waiting for it) when the
+    /// last clone of this `Assets` is dropped, so calling this is optional.
+    pub async fn shutdown(&self) {
+        #[cfg(feature = "watch")]
+        if let Some(task) = self.watcher.shutdown() {
+            // The task was aborted, so this always returns a cancellation
+            // error, unless it had already stopped.
+            let _ = task.await;
+        }
     }
 }
 
@@ -260,17 +519,207 @@ impl Asset {
     /// Returns the contents of this asset. Will be loaded from the file system
     /// in dev mode, potentially returning IO errors. In prod mode, the file
     /// contents are already loaded and this method always returns `Ok(_)` and
-    /// never yield.
+    /// never yield, except for [lazy][EntryBuilder::lazy] assets, which are
+    /// always read from the file system.
     pub async fn content(&self) -> Result<Bytes, io::Error> {
         self.0.content().await
     }
 
+    /// Returns a reader for the contents of this asset, e.g. to send large
+    /// files as streaming response body (for example via
+    /// `tokio_util::io::ReaderStream`). In dev mode, the file is read
+    /// incrementally, without loading it into memory, if no processing (e.g.
+    /// modifier or template) is configured for the asset; otherwise, the
+    /// processed content is loaded first. In prod mode, this never fails and
+    /// reads from the content in memory, except for
+    /// [lazy][EntryBuilder::lazy] assets, which are streamed from their file.
+    pub async fn stream(&self) -> Result<ContentReader, io::Error> {
+        self.0.stream().await
+    }
+
+    /// Returns the length of the content of this asset in bytes. In dev
+    /// mode, this only reads the file's metadata if no processing (e.g.
+    /// modifier or template) is configured for the asset.
+    pub async fn content_len(&self) -> Result<u64, io::Error> {
+        self.0.content_len().await
+    }
+
+    /// Returns the part of the content of this asset in `range`, e.g. to
+    /// answer HTTP `Range` requests. The range is clamped to the content, so
+    /// the result is shorter than requested if it extends past the end. In
+    /// prod mode, this does not copy (lazy assets are read partially like in
+    /// dev mode). In dev mode, only the requested part
+    /// of the file is read if no processing (e.g. modifier or template) is
+    /// configured for the asset.
+    pub async fn content_range(&self, range: Range<u64>) -> Result<Bytes, io::Error> {
+        self.0.content_range(range).await
+    }
+
+    /// Returns the content of this asset compressed with the given encoding,
+    /// or `None` if there is no such variant. Variants only exist if
+    /// precompression was enabled for this asset (see
+    /// [`Builder::set_compression`]) and the asset qualified for it. In prod
+    /// mode, all variants are created during [`Builder::build`]; in dev mode,
+    /// this compresses on the fly.
+    ///
+    /// Method is only available if the crate feature `compress` is enabled.
+    #[cfg(feature = "compress")]
+    pub async fn encoded_content(&self, encoding: Encoding) -> Result<Option<Bytes>, io::Error> {
+        self.0.encoded_content(encoding).await
+    }
+
+    /// Returns the best content for a request with the given `Accept-Encoding`
+    /// header value, and the encoding to send as `Content-Encoding` (`None`
+    /// for the uncompressed content). The compressed variant with the highest
+    /// q-value is chosen; if several are accepted equally, the order of
+    /// [`CompressionConfig::encodings`] decides. This is what
+    /// [`Assets::serve`] uses.
+    ///
+    /// Method is only available if the crate feature `compress` is enabled.
+    #[cfg(feature = "compress")]
+    pub async fn negotiate_content(&self, accept_encoding: &str) -> Result<(Bytes, Option<Encoding>), io::Error> {
+        let variants = self.0.encoded_variants().await?;
+        let encodings = variants.iter().map(|(e, _)| *e).collect::<Vec<_>>();
+        match crate::compress::negotiate(accept_encoding, &encodings) {
+            Some(i) => Ok((variants[i].1.clone(), Some(variants[i].0))),
+            None => Ok((self.content().await?, None)),
+        }
+    }
+
+    /// Returns a key identifying the content of this asset: the first 128
+    /// bits of its SHA-256, hex encoded. As it only depends on the content
+    /// (not on e.g. build time or memory addresses), it is the same on all
+    /// replicas serving the same assets, so reverse proxies (e.g. Varnish or
+    /// NGINX) can use it as cache key. See also
+    /// [`Builder::set_cache_key_header`]. In prod mode, it is computed in
+    /// [`Builder::build`] and this never fails.
+    ///
+    /// Method is only available if the crate feature `hash` is enabled.
+    #[cfg(feature = "hash")]
+    pub async fn cache_key(&self) -> Result<String, io::Error> {
+        self.0.cache_key().await
+    }
+
+    /// Returns the strong ETag of this asset's (uncompressed) content, i.e.
+    /// its [cache key][Self::cache_key] in quotes. In prod mode, it is
+    /// computed in [`Builder::build`]; in dev mode, it is recomputed from the
+    /// file on every call, so it changes whenever the file does.
+    ///
+    /// Method is only available if the crate feature `hash` is enabled.
+    #[cfg(feature = "hash")]
+    pub async fn etag(&self) -> Result<String, io::Error> {
+        Ok(format!("\"{}\"", self.cache_key().await?))
+    }
+
+    /// Returns the [Subresource Integrity][crate::util::sri_hash] hash of
+    /// this asset's final content (i.e. after templates, plugins and
+    /// modifiers), e.g. `sha384-<base64>`, for `integrity` attributes. In
+    /// templates, `{{: sri:<unhashed HTTP path> :}}` inserts the SHA-384
+    /// variant. The hash is computed on every call.
+    ///
+    /// Method is only available if the crate feature `hash` is enabled.
+    #[cfg(feature = "hash")]
+    pub async fn sri_hash(&self, algorithm: SriAlgorithm) -> Result<String, io::Error> {
+        Ok(crate::util::sri_hash(&self.content().await?, algorithm))
+    }
+
+    /// Returns whether the given value of an `If-None-Match` header matches
+    /// the [ETag][Self::etag] of this asset, i.e. whether a handler can
+    /// answer with `304 Not Modified`. In prod mode, this does not touch the
+    /// content and never fails.
+    ///
+    /// Method is only available if the crate feature `hash` is enabled.
+    #[cfg(feature = "hash")]
+    pub async fn matches_etag(&self, if_none_match: &str) -> Result<bool, io::Error> {
+        Ok(crate::http::etag_matches(if_none_match, &self.etag().await?))
+    }
+
+    /// Returns the MIME type of this asset, e.g. `text/css; charset=utf-8`.
+    /// That's the type set via [`EntryBuilder::with_content_type`] or, if
+    /// none was set, the one guessed from the file extension (text types
+    /// include `charset=utf-8`). Falls back to `application/octet-stream`
+    /// for unknown extensions. [`Assets::serve`] sends this as
+    /// `Content-Type`.
+    pub fn content_type(&self) -> &str {
+        self.0.serve_settings().content_type.as_deref()
+            .or_else(|| crate::mime::guess(self.0.http_path()))
+            .unwrap_or("application/octet-stream")
+    }
+
+    /// Returns the HTTP status code this asset is served with, `200` unless
+    /// set via [`EntryBuilder::with_status`].
+    pub fn status(&self) -> u16 {
+        self.0.serve_settings().status.unwrap_or(200)
+    }
+
     /// Returns whether this asset's filename contains a hash. Specifically, it
     /// returns true iff [`EntryBuilder::with_hash`] was called *and* you are
     /// compiling in prod mode.
     pub fn is_filename_hashed(&self) -> bool {
         self.0.is_filename_hashed()
     }
+
+    /// Returns whether the content of this asset is stored in the executable
+    /// (or the sidecar file), instead of being read from the file system. In
+    /// prod mode, this is true for all assets of `embed!` entries, except
+    /// [lazy][EntryBuilder::lazy] ones. In dev mode, it's only true for files
+    /// marked with `always_embed` in `embed!`. Assets added with
+    /// [`Builder::add_bytes`] are never considered embedded.
+    pub fn is_embedded(&self) -> bool {
+        self.0.is_embedded()
+    }
+}
+
+/// The mode this crate was compiled in, returned by [`Assets::mode`]. See
+/// [the crate docs](crate#prod-vs-dev-mode) for what differs between them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Mode {
+    /// Assets are embedded and processed once in [`Builder::build`].
+    Prod,
+
+    /// Assets are loaded from the file system and processed on every access.
+    Dev,
+}
+
+impl Mode {
+    #[cfg(prod_mode)]
+    const CURRENT: Self = Self::Prod;
+    #[cfg(dev_mode)]
+    const CURRENT: Self = Self::Dev;
+}
+
+/// Reader for the content of an asset, returned by [`Asset::stream`].
+#[derive(Debug)]
+pub struct ContentReader(ReaderInner);
+
+#[derive(Debug)]
+enum ReaderInner {
+    File(tokio::fs::File),
+    Memory(io::Cursor<Bytes>),
+}
+
+impl ContentReader {
+    pub(crate) fn file(file: tokio::fs::File) -> Self {
+        Self(ReaderInner::File(file))
+    }
+
+    pub(crate) fn memory(content: Bytes) -> Self {
+        Self(ReaderInner::Memory(io::Cursor::new(content)))
+    }
+}
+
+impl tokio::io::AsyncRead for ContentReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match &mut self.get_mut().0 {
+            ReaderInner::File(file) => Pin::new(file).poll_read(cx, buf),
+            ReaderInner::Memory(cursor) => Pin::new(cursor).poll_read(cx, buf),
+        }
+    }
 }
 
 /// Passed to the modifier closure, e.g. allowing you to resolve *unhashed HTTP
@@ -278,9 +727,19 @@ impl Asset {
 #[derive(Debug)]
 pub struct ModifierContext<'a> {
     declared_deps: &'a [Cow<'static, str>],
+    env: &'a ModifierEnv,
     inner: imp::ModifierContextInner<'a>,
 }
 
+/// Settings of the builder that are visible to modifiers, see
+/// `ModifierContext::var` and `ModifierContext::public_url`.
+#[derive(Debug, Default)]
+pub(crate) struct ModifierEnv {
+    /// Variables of the builder merged with the ones of the entry.
+    pub(crate) vars: Arc<BTreeMap<String, String>>,
+    pub(crate) public_url: Option<Arc<str>>,
+}
+
 impl<'a> ModifierContext<'a> {
     /// Resolves an *unhashed HTTP path* to the *hashed HTTP path*.
     ///
@@ -310,6 +769,19 @@ impl<'a> ModifierContext<'a> {
     pub fn dependencies(&self) -> &'a [Cow<'static, str>] {
         self.declared_deps
     }
+
+    /// Returns the value of the variable defined with [`Builder::add_var`]
+    /// or [`EntryBuilder::add_var`] (which takes precedence), or `None` if it
+    /// is not defined.
+    pub fn var(&self, name: &str) -> Option<&str> {
+        self.env.vars.get(name).map(|v| &**v)
+    }
+
+    /// Returns the public URL passed to [`Builder::set_public_url`] (always
+    /// ending in `/`), or `None` if it was not set.
+    pub fn public_url(&self) -> Option<&str> {
+        self.env.public_url.as_deref()
+    }
 }
 
 // =========================================================================================
@@ -317,27 +789,281 @@ impl<'a> ModifierContext<'a> {
 // =========================================================================================
 
 /// Errors that might happen during [`Builder::build`], when loading and resolving files.
+///
+/// Use [`Self::http_path`] and [`Self::fs_path`] to find out which asset
+/// caused the error, and [`std::error::Error::source`] to get the underlying
+/// error, if any.
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum BuildError {
+    /// Loading an asset from the file system (or sidecar file) failed.
     Io {
         err: std::io::Error,
         path: PathBuf,
+        http_path: String,
     },
+
+    /// The dependencies between assets (via modifiers or templates) form a
+    /// cycle. Contains the *unhashed HTTP paths* forming the cycle.
     CyclicDependencies(Vec<String>),
+
+    /// The modifier of an asset panicked. The message of `error` is the
+    /// panic message. If the task running the modifier failed in another
+    /// way, the underlying error is the source of `error`.
+    ModifierPanicked {
+        http_path: String,
+        error: ModifierError,
+    },
+
+    /// The modifier of an asset returned an error, see
+    /// [`EntryBuilder::with_fallible_modifier`]. `dependents` contains the
+    /// *unhashed HTTP paths* of the assets that (transitively) depend on the
+    /// failed one and thus could not be built either, nearest first. It is
+    /// always empty in dev mode.
+    Modifier {
+        http_path: String,
+        dependents: Vec<String>,
+        error: ModifierError,
+    },
+
+    /// [`Builder::build_with_deadline`] did not finish in time.
+    DeadlineExceeded(std::time::Duration),
+
+    /// The pipeline steps and modifier of an asset did not finish within the
+    /// limit set via [`Builder::set_transform_timeout`] or
+    /// [`EntryBuilder::with_transform_timeout`].
+    TransformTimeout {
+        http_path: String,
+        timeout: std::time::Duration,
+    },
+
+    /// An asset could not be rendered as template.
+    Template {
+        http_path: String,
+        error: template::Error,
+    },
+
+    /// A step registered via [`Builder::register_plugin`] failed or panicked
+    /// while processing an asset.
+    Pipeline {
+        http_path: String,
+        step: String,
+        message: String,
+    },
+
+    /// The assets exceed an internal limit.
+    LimitExceeded(String),
+
+    /// A file embedded via a glob pattern does not start with the fixed
+    /// prefix of the pattern, so its HTTP path cannot be determined. This
+    /// indicates a bug in `embed!`.
+    InvalidGlobFile {
+        pattern: String,
+        path: String,
+    },
+
+    /// An asset inserted via `Assets::insert_with` (crate feature
+    /// `runtime-mutation`) is marked as [internal][EntryBuilder::internal].
+    /// That's not supported, as inserted assets cannot be referenced by
+    /// other assets.
+    InternalInsert {
+        http_path: String,
+    },
+
+    /// [`EntryBuilder::with_base_href`] or
+    /// [`EntryBuilder::with_canonical_link`] was used without
+    /// [`Builder::set_public_url`]. For glob and directory entries,
+    /// `http_path` is their prefix.
+    MissingPublicUrl {
+        http_path: String,
+    },
+
+    /// A post-build hook added an asset via
+    /// [`BuildView::add`][crate::BuildView::add] under the *unhashed* or
+    /// *hashed HTTP path* of a built asset.
+    DuplicatePath {
+        http_path: String,
+    },
+
+    /// The asset `asset` depends on the asset `dependency` (both *unhashed
+    /// HTTP paths*), which does not exist. Dependencies are e.g. variants
+    /// (see [`EntryBuilder::with_variant`]) or assets prepended via
+    /// [`EntryBuilder::prepend_asset`].
+    MissingDependency {
+        asset: String,
+        dependency: String,
+    },
+}
+
+impl BuildError {
+    /// Returns `true` if this error was caused by a file that does not exist.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, BuildError::Io { err, .. } if err.kind() == io::ErrorKind::NotFound)
+    }
+
+    /// Returns the *unhashed HTTP path* of the asset that caused this error,
+    /// if the error is specific to one asset. For cyclic dependencies, one
+    /// of the assets in the cycle is returned.
+    pub fn http_path(&self) -> Option<&str> {
+        match self {
+            BuildError::Io { http_path, .. }
+            | BuildError::ModifierPanicked { http_path, .. }
+            | BuildError::Modifier { http_path, .. }
+            | BuildError::TransformTimeout { http_path, .. }
+            | BuildError::Template { http_path, .. }
+            | BuildError::Pipeline { http_path, .. }
+            | BuildError::InternalInsert { http_path }
+            | BuildError::MissingPublicUrl { http_path }
+            | BuildError::DuplicatePath { http_path }
+            | BuildError::MissingDependency { asset: http_path, .. } => Some(http_path),
+            BuildError::CyclicDependencies(cycle) => cycle.first().map(|s| &**s),
+            BuildError::DeadlineExceeded(_)
+            | BuildError::LimitExceeded(_)
+            | BuildError::InvalidGlobFile { .. } => None,
+        }
+    }
+
+    /// Returns the *FS path* of the file that caused this error, if the error
+    /// was caused by loading a file.
+    pub fn fs_path(&self) -> Option<&Path> {
+        match self {
+            BuildError::Io { path, .. } => Some(path),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for BuildError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            BuildError::Io { err, path }
-                => write!(f, "IO error while accessing '{}': '{}'", path.display(), err),
+            BuildError::Io { err, path, http_path } => write!(
+                f,
+                "IO error while loading '{}' (for asset '{}'): '{}'",
+                path.display(),
+                http_path,
+                err,
+            ),
             BuildError::CyclicDependencies(cycle) => write!(f, "cyclic dependencies: {:?}", cycle),
+            BuildError::ModifierPanicked { http_path, error }
+                => write!(f, "modifier of asset '{}' panicked: {}", http_path, error),
+            BuildError::Modifier { http_path, dependents, error } => {
+                write!(f, "modifier of asset '{}' failed: {}", http_path, error)?;
+                if !dependents.is_empty() {
+                    write!(f, " (required by '{}')", dependents.join("' <- '"))?;
+                }
+                Ok(())
+            }
+            BuildError::DeadlineExceeded(deadline)
+                => write!(f, "building assets took longer than {:?}", deadline),
+            BuildError::TransformTimeout { http_path, timeout } => write!(
+                f,
+                "processing asset '{}' took longer than {:?}",
+                http_path,
+                timeout,
+            ),
+            BuildError::Template { http_path, error }
+                => write!(f, "error in template '{}': {}", http_path, error),
+            BuildError::Pipeline { http_path, step, message }
+                => write!(f, "step '{}' failed for asset '{}': {}", step, http_path, message),
+            BuildError::LimitExceeded(msg) => write!(f, "limit exceeded: {}", msg),
+            BuildError::InvalidGlobFile { pattern, path } => write!(
+                f,
+                "embedded file '{}' does not start with the prefix of its glob '{}'",
+                path,
+                pattern,
+            ),
+            BuildError::InternalInsert { http_path }
+                => write!(f, "asset '{}' inserted at runtime must not be internal", http_path),
+            BuildError::MissingPublicUrl { http_path } => write!(
+                f,
+                "asset '{}' uses `with_base_href` or `with_canonical_link`, but no public URL \
+                    was set via `Builder::set_public_url`",
+                http_path,
+            ),
+            BuildError::DuplicatePath { http_path }
+                => write!(f, "post-build hook added asset '{}', but that path is taken", http_path),
+            BuildError::MissingDependency { asset, dependency } => write!(
+                f,
+                "asset '{}' specified dependency '{}', but that asset does not exist",
+                asset,
+                dependency,
+            ),
         }
     }
 }
 
-impl std::error::Error for BuildError {}
+impl std::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuildError::Io { err, .. } => Some(err),
+            BuildError::Modifier { error, .. }
+            | BuildError::ModifierPanicked { error, .. } => Some(error),
+            BuildError::Template { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+
+
+/// Error returned by a modifier passed to
+/// [`EntryBuilder::with_fallible_modifier`], making the build fail with
+/// [`BuildError::Modifier`]. Can be created from a message, optionally with
+/// the underlying error as [source][std::error::Error::source]:
+///
+/// ```ignore
+/// let parsed = serde_json::from_slice::<Config>(&content)
+///     .map_err(|e| ModifierError::with_source("invalid config", e))?;
+/// ```
+#[derive(Debug)]
+pub struct ModifierError {
+    message: String,
+    source: Option<Box<dyn std::error::Error + Send + Sync>>,
+}
+
+impl ModifierError {
+    /// Creates an error with the given message.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into(), source: None }
+    }
+
+    /// Creates an error with the given message, caused by `source`.
+    pub fn with_source(
+        message: impl Into<String>,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Self { message: message.into(), source: Some(source.into()) }
+    }
+
+    /// Returns the message of this error, without its source.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ModifierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ModifierError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source.as_deref().map(|e| e as _)
+    }
+}
+
+impl From<String> for ModifierError {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<&str> for ModifierError {
+    fn from(message: &str) -> Self {
+        Self::new(message)
+    }
+}
 
 
 
@@ -356,51 +1082,258 @@ enum PathHash<'a> {
     },
 }
 
-#[derive(Debug, Clone)]
+/// Async function producing the content of an asset, see
+/// `Builder::add_with`.
+type Generator = Arc<
+    dyn Send + Sync + Fn() -> Pin<Box<dyn Send + Future<Output = Result<Bytes, io::Error>>>>
+>;
+
+#[derive(Clone)]
 enum DataSource {
     File(PathBuf),
-    #[cfg_attr(dev_mode, allow(dead_code))]
     Loaded(Bytes),
+    Generated(Generator),
+    #[cfg(all(prod_mode, feature = "sidecar"))]
+    Sidecar {
+        location: SidecarLocation,
+        compressed: bool,
+    },
+
+    /// Fetched once on first load, and then cached.
+    #[cfg(feature = "url")]
+    Url {
+        url: String,
+        cache: std::sync::Arc<tokio::sync::OnceCell<Bytes>>,
+    },
+}
+
+impl fmt::Debug for DataSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataSource::File(path) => f.debug_tuple("File").field(path).finish(),
+            DataSource::Loaded(bytes) => f.debug_tuple("Loaded").field(bytes).finish(),
+            DataSource::Generated(_) => f.write_str("Generated(<closure>)"),
+            #[cfg(all(prod_mode, feature = "sidecar"))]
+            DataSource::Sidecar { location, compressed } => f.debug_struct("Sidecar")
+                .field("location", location)
+                .field("compressed", compressed)
+                .finish(),
+            #[cfg(feature = "url")]
+            DataSource::Url { url, cache } => f.debug_struct("Url")
+                .field("url", url)
+                .field("cache", cache)
+                .finish(),
+        }
+    }
+}
+
+impl fmt::Display for DataSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataSource::File(path) => path.display().fmt(f),
+            DataSource::Loaded(_) => f.write_str("<embedded>"),
+            DataSource::Generated(_) => f.write_str("<generated>"),
+            #[cfg(all(prod_mode, feature = "sidecar"))]
+            DataSource::Sidecar { location, .. } => write!(f, "<sidecar '{}'>", location.path),
+            #[cfg(feature = "url")]
+            DataSource::Url { url, .. } => f.write_str(url),
+        }
+    }
 }
 
 impl DataSource {
-    async fn load(&self) -> Result<Bytes, (io::Error, &Path)> {
+    fn fs_path(&self) -> Option<&Path> {
+        match self {
+            DataSource::File(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    async fn load(&self) -> Result<Bytes, (io::Error, PathBuf)> {
         match self {
             DataSource::File(path) => tokio::fs::read(path).await
                 .map(Into::into)
-                .map_err(|err| (err, &**path)),
+                .map_err(|err| (err, path.clone())),
             DataSource::Loaded(bytes) => Ok(bytes.clone()),
+            DataSource::Generated(generate) => generate().await
+                .map_err(|err| (err, PathBuf::from("<generated>"))),
+
+            // The sidecar file is only read once and then cached, so doing
+            // that synchronously is fine.
+            #[cfg(all(prod_mode, feature = "sidecar"))]
+            DataSource::Sidecar { location, compressed } => {
+                let path = || crate::sidecar::path_of(location).unwrap_or_default();
+                let stored = crate::sidecar::load(location).map_err(|e| (e, path()))?;
+                if *compressed {
+                    Ok(crate::embed::maybe_decompress(&stored, true).into_owned().into())
+                } else {
+                    Ok(stored)
+                }
+            }
+
+            #[cfg(feature = "url")]
+            DataSource::Url { url, cache } => cache.get_or_try_init(|| crate::remote::fetch(url))
+                .await
+                .cloned()
+                .map_err(|err| (err, PathBuf::from(url))),
         }
     }
 }
 
+#[cfg(any(feature = "hash", all(prod_mode, feature = "sidecar")))]
+fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    use std::fmt::Write;
+
+    let hash = Sha256::digest(data);
+    let mut out = String::with_capacity(64);
+    for byte in hash {
+        write!(out, "{byte:02x}").unwrap();
+    }
+    out
+}
+
+
+/// A modifier closure. Infallible ones are wrapped to always return `Ok`.
+type ModifierFn = dyn Send + Sync + Fn(Bytes, ModifierContext) -> Result<Bytes, ModifierError>;
 
 #[derive(Clone)]
 enum Modifier {
-    None,
     #[cfg_attr(dev_mode, allow(dead_code))]
     PathFixup(Vec<Cow<'static, str>>),
     Custom {
-        f: Arc<dyn Send + Sync + Fn(Bytes, ModifierContext) -> Bytes>,
+        f: Arc<ModifierFn>,
         deps: Vec<Cow<'static, str>>,
     },
 }
 
 impl Modifier {
-    #[cfg(prod_mode)]
-    fn dependencies(&self) -> Option<&[Cow<'static, str>]> {
+    fn dependencies(&self) -> &[Cow<'static, str>] {
         match self {
-            Modifier::None => None,
-            Modifier::PathFixup(deps) => Some(deps),
-            Modifier::Custom { deps, .. } => Some(deps),
+            Modifier::PathFixup(deps) => deps,
+            Modifier::Custom { deps, .. } => deps,
         }
     }
+
+    fn info(&self) -> ModifierInfo<'_> {
+        let kind = match self {
+            Modifier::PathFixup(_) => ModifierKind::PathFixup,
+            Modifier::Custom { .. } => ModifierKind::Custom,
+        };
+        ModifierInfo { kind, dependencies: self.dependencies() }
+    }
+}
+
+/// The modifiers of an entry, applied in registration order.
+#[derive(Debug, Clone, Default)]
+struct Modifiers(Vec<Modifier>);
+
+impl Modifiers {
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the dependencies of all modifiers.
+    fn dependencies(&self) -> impl '_ + Iterator<Item = &Cow<'static, str>> {
+        self.0.iter().flat_map(Modifier::dependencies)
+    }
+}
+
+/// A modifier registered for an entry, returned by
+/// [`EntryBuilder::modifiers`].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct ModifierInfo<'a> {
+    /// How the modifier was registered.
+    pub kind: ModifierKind,
+
+    /// The declared dependencies. For path fixups, these are the paths to
+    /// replace.
+    pub dependencies: &'a [Cow<'static, str>],
+}
+
+/// How a modifier was registered, see [`ModifierInfo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ModifierKind {
+    /// Via [`EntryBuilder::with_path_fixup`].
+    PathFixup,
+
+    /// Via [`EntryBuilder::with_modifier`] or
+    /// [`EntryBuilder::with_fallible_modifier`].
+    Custom,
+}
+
+/// Calls a user-supplied modifier closure, catching any panic and turning it
+/// and errors into a `BuildError` mentioning the asset. That way, one bad
+/// modifier does not take down the whole application (e.g. on a dev mode
+/// reload). `dependents` of the error are left empty.
+fn call_modifier(
+    f: &ModifierFn,
+    input: Bytes,
+    ctx: ModifierContext<'_>,
+    http_path: &str,
+) -> Result<Bytes, BuildError> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(input, ctx)))
+        .map_err(|payload| BuildError::ModifierPanicked {
+            http_path: http_path.to_owned(),
+            error: ModifierError::new(panic_message(payload)),
+        })?
+        .map_err(|error| BuildError::Modifier {
+            http_path: http_path.to_owned(),
+            dependents: vec![],
+            error,
+        })
+}
+
+/// Runs `f`, i.e. user code like modifiers or pipeline steps, on a new
+/// detached thread and returns a future resolving to its result. Unlike with
+/// `spawn_blocking`, neither dropping that future nor shutting down the
+/// runtime waits for the thread, so code that never returns cannot block
+/// either. The future fails if `f` panics.
+fn run_detached<T: Send + 'static>(
+    f: impl 'static + Send + FnOnce() -> T,
+) -> impl Future<Output = Result<T, tokio::sync::oneshot::error::RecvError>> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        // The receiver is gone if nobody waits for the result anymore.
+        let _ = tx.send(f());
+    });
+    rx
+}
+
+/// Awaits `task`, which runs transforms of the asset `http_path` started at
+/// `started`, failing with `BuildError::TransformTimeout` once `limit` has
+/// passed since then.
+async fn with_time_limit<T>(
+    started: tokio::time::Instant,
+    limit: Option<std::time::Duration>,
+    http_path: &str,
+    task: impl Future<Output = T>,
+) -> Result<T, BuildError> {
+    let Some(limit) = limit else {
+        return Ok(task.await);
+    };
+    tokio::time::timeout_at(started + limit, task).await.map_err(|_| BuildError::TransformTimeout {
+        http_path: http_path.to_owned(),
+        timeout: limit,
+    })
+}
+
+/// Extracts the message of a panic caught with `catch_unwind`.
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_owned()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "<non-string panic payload>".to_owned()
+    }
 }
 
 impl std::fmt::Debug for Modifier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Modifier::None => write!(f, "None"),
             Modifier::PathFixup(_) => write!(f, "PathFixup"),
             Modifier::Custom { .. } => write!(f, "Custom"),
         }
@@ -416,9 +1349,9 @@ struct SplitGlob {
     prefix: &'static str,
 
     /// The second part of the glob, starting with a segment having glob meta
-    /// characters.
+    /// characters, expanded to plain patterns (see `reinda_shared::glob_syntax::expand`).
     #[cfg_attr(prod_mode, allow(dead_code))]
-    suffix: glob::Pattern,
+    suffixes: Vec<glob::Pattern>,
 }
 
 impl SplitGlob {
@@ -430,7 +1363,7 @@ impl SplitGlob {
 
             // We know it came from a `str` so this unwrap is fine.
             let seg = seg.to_str().unwrap();
-            if seg.contains(&['*', '?', '[', ']']) {
+            if seg.contains(&['*', '?', '[', ']', '{', '}']) {
                 return Some(seg.as_ptr() as usize - glob.as_ptr() as usize);
             }
 
@@ -439,12 +1372,56 @@ impl SplitGlob {
 
         let (prefix, suffix) = glob.split_at(offset);
 
-        Self {
-            prefix,
-            // The `expect` is fine as the glob was already parsed at compile time.
-            suffix: glob::Pattern::new(suffix).expect("invalid glob"),
+        // The `expect`s are fine as the glob was already parsed at compile time.
+        let suffixes = reinda_shared::glob_syntax::expand(suffix).expect("invalid glob")
+            .iter()
+            .map(|s| glob::Pattern::new(s).expect("invalid glob"))
+            .collect();
+        Self { prefix, suffixes }
+    }
+
+    /// Returns whether `suffix` (the path without `prefix`) matches the glob.
+    #[cfg_attr(prod_mode, allow(dead_code))]
+    fn matches(&self, suffix: &str) -> bool {
+        self.suffixes.iter().any(|p| p.matches(suffix))
+    }
+}
+
+/// Returns the part of `content` in `range`, clamped to the content.
+fn slice_range(content: &Bytes, range: Range<u64>) -> Bytes {
+    let len = content.len() as u64;
+    let end = range.end.min(len);
+    let start = range.start.min(end);
+    content.slice(start as usize..end as usize)
+}
+
+/// Reads the part of the file at `path` in `range`, clamped to its length.
+async fn read_file_range(path: &Path, range: Range<u64>) -> Result<Bytes, io::Error> {
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+    let mut file = tokio::fs::File::open(path).await?;
+    file.seek(io::SeekFrom::Start(range.start)).await?;
+    let mut out = Vec::new();
+    file.take(range.end.saturating_sub(range.start)).read_to_end(&mut out).await?;
+    Ok(out.into())
+}
+
+/// Returns `path` without the leading `prefix` of a glob (see `SplitGlob`),
+/// or `None` if `path` does not start with it. Paths are compared by
+/// component, so `.` segments and repeated or trailing separators are
+/// ignored.
+fn strip_glob_prefix<'p>(path: &'p str, prefix: &str) -> Option<&'p str> {
+    let not_cur_dir = |c: &Component<'_>| *c != Component::CurDir;
+    let mut rest = Path::new(path).components();
+    for expected in Path::new(prefix).components().filter(not_cur_dir) {
+        if rest.find(not_cur_dir)? != expected {
+            return None;
         }
     }
+    while rest.clone().next() == Some(Component::CurDir) {
+        rest.next();
+    }
+    rest.as_path().to_str()
 }
 
 
@@ -458,7 +1435,7 @@ mod tests {
             ($whole:literal => $prefix:literal + $suffix:literal) => {
                 assert_eq!(
                     SplitGlob::new($whole),
-                    SplitGlob { prefix: $prefix, suffix: glob::Pattern::new($suffix).unwrap() },
+                    SplitGlob { prefix: $prefix, suffixes: vec![glob::Pattern::new($suffix).unwrap()] },
                 );
             };
         }
@@ -466,5 +1443,73 @@ mod tests {
         check!("frontend/build/fonts/*.woff2" => "frontend/build/fonts/" + "*.woff2");
         check!("frontend/**/banana.txt" => "frontend/" + "**/banana.txt");
         check!("../foo/bar*/*.svg" => "../foo/" + "bar*/*.svg");
+        check!("assets/**.js" => "assets/" + "**/*.js");
+
+        let split = SplitGlob::new("assets/{js,css}/*.min.*");
+        assert_eq!(split.prefix, "assets/");
+        assert!(split.matches("js/app.min.js"));
+        assert!(split.matches("css/app.min.css"));
+        assert!(!split.matches("img/logo.min.svg"));
+    }
+
+    #[test]
+    fn strip_prefix() {
+        assert_eq!(strip_glob_prefix("fonts/a.woff2", "fonts/"), Some("a.woff2"));
+        assert_eq!(strip_glob_prefix("./fonts//a.woff2", "fonts"), Some("a.woff2"));
+        assert_eq!(strip_glob_prefix("../fonts/sub/a.woff2", "../fonts/"), Some("sub/a.woff2"));
+        assert_eq!(strip_glob_prefix("a.txt", ""), Some("a.txt"));
+        assert_eq!(strip_glob_prefix("fontsy/a.woff2", "fonts/"), None);
+        assert_eq!(strip_glob_prefix("a.woff2", "fonts/"), None);
+    }
+
+    /// A path segment without glob meta characters and separators, including
+    /// non-ASCII characters.
+    const SEGMENT: &str = "[a-zA-Z0-9_\\-äöü日本語 ]{1,8}(\\.[a-z]{1,4})?";
+
+    proptest::proptest! {
+        #[test]
+        fn split_glob_prop(
+            fixed in proptest::collection::vec(SEGMENT, 0..4),
+            pattern in "(\\*|\\*\\*/\\*|[a-z]{1,4}\\*)(\\.[a-z]{1,4})?",
+            trailing_slash: bool,
+        ) {
+            let mut prefix = fixed.iter().map(|s| format!("{s}/")).collect::<String>();
+            let whole = format!("{prefix}{pattern}");
+            let split = SplitGlob::new(Box::leak(whole.into_boxed_str()));
+            proptest::prop_assert_eq!(split.prefix, &*prefix);
+            proptest::prop_assert_eq!(split.suffixes.len(), 1);
+            proptest::prop_assert_eq!(split.suffixes[0].as_str(), &*pattern);
+
+            if !trailing_slash {
+                prefix.pop();
+            }
+            let file = format!("{}x/y.txt", split.prefix);
+            proptest::prop_assert_eq!(strip_glob_prefix(&file, &prefix), Some("x/y.txt"));
+        }
+
+        #[test]
+        fn strip_prefix_prop(
+            prefix in proptest::collection::vec(SEGMENT, 0..4),
+            rest in proptest::collection::vec(SEGMENT, 1..4),
+            cur_dir: bool,
+        ) {
+            let prefix = prefix.iter().map(|s| format!("{s}/")).collect::<String>();
+            let rest = rest.join("/");
+            let path = format!("{}{prefix}{rest}", if cur_dir { "./" } else { "" });
+            proptest::prop_assert_eq!(strip_glob_prefix(&path, &prefix), Some(&*rest));
+
+            let file = crate::builder::GlobFile {
+                suffix: Box::leak(rest.replace('/', "\\").into_boxed_str()),
+                source: DataSource::File(path.clone().into()),
+            };
+            proptest::prop_assert_eq!(file.http_path("static/"), format!("static/{rest}"));
+        }
+
+        #[test]
+        fn strip_prefix_never_panics(path in "\\PC{0,20}", prefix in "\\PC{0,20}") {
+            if let Some(rest) = strip_glob_prefix(&path, &prefix) {
+                proptest::prop_assert!(Path::new(&path).ends_with(rest));
+            }
+        }
     }
 }
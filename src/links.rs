@@ -0,0 +1,193 @@
+//! Finding references to other assets in HTML and CSS files.
+
+use std::{borrow::Cow, io};
+
+use crate::Assets;
+
+
+/// An internal reference that does not resolve to any asset, returned by
+/// [`Assets::check_links`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BrokenLink {
+    /// *Hashed HTTP path* of the asset containing the reference.
+    pub asset: String,
+
+    /// The reference, exactly as written in the asset.
+    pub reference: String,
+
+    /// The path the reference resolved to, which does not exist.
+    pub resolved: String,
+}
+
+impl Assets {
+    /// Searches all HTML and CSS assets for internal references (`src` and
+    /// `href` attributes and `url(...)` in CSS) and returns all that do not
+    /// resolve to an asset. Relative references are resolved against the
+    /// referencing asset, and the request rewriter (if configured) is
+    /// applied like in [`Self::serve`]. External URLs and references to
+    /// directories (like `/`) are ignored.
+    ///
+    /// As the final contents are searched (i.e. after fixups and templates
+    /// are applied), this catches broken `<img src>` paths and missing
+    /// [`EntryBuilder::with_path_fixup`][crate::EntryBuilder::with_path_fixup]
+    /// calls in tests, instead of in production. Note that in dev mode,
+    /// filenames are never hashed, so the latter are only found in prod
+    /// mode. Only fails if loading an asset fails, which can only happen
+    /// in dev mode.
+    pub async fn check_links(&self) -> Result<Vec<BrokenLink>, io::Error> {
+        let mut broken = Vec::new();
+        for (path, asset) in self.iter() {
+            if !may_contain_references(path) {
+                continue;
+            }
+
+            let content = asset.content().await?;
+            for reference in references(path, &content) {
+                let Some(resolved) = resolve(path, reference) else { continue };
+                let lookup = match &self.http.rewriter {
+                    Some(rewrite) => rewrite(&resolved),
+                    None => Cow::Borrowed(&*resolved),
+                };
+                if self.get(lookup.trim_start_matches('/')).is_none() {
+                    broken.push(BrokenLink {
+                        asset: path.to_owned(),
+                        reference: reference.to_owned(),
+                        resolved,
+                    });
+                }
+            }
+        }
+
+        Ok(broken)
+    }
+}
+
+/// Returns whether `references` searches the asset with the given path, i.e.
+/// whether it's an HTML or CSS file.
+fn may_contain_references(http_path: &str) -> bool {
+    is_html(http_path) || http_path.ends_with(".css")
+}
+
+fn is_html(http_path: &str) -> bool {
+    http_path.ends_with(".html") || http_path.ends_with(".htm")
+}
+
+/// Returns all references (values of `src` and `href` attributes and CSS
+/// `url(...)`s) in the given asset, exactly as written. Only HTML and CSS
+/// files (judged by the extension of `http_path`) are searched, for all
+/// other files, an empty list is returned.
+pub(crate) fn references<'a>(http_path: &str, content: &'a [u8]) -> Vec<&'a str> {
+    if !may_contain_references(http_path) {
+        return vec![];
+    }
+    let Ok(s) = std::str::from_utf8(content) else {
+        return vec![];
+    };
+
+    let mut out = vec![];
+    if is_html(http_path) {
+        for attr in ["src=", "href="] {
+            for (i, _) in s.match_indices(attr) {
+                // Only match whole attribute names, not e.g. `data-src`.
+                if !s[..i].ends_with(|c: char| c.is_ascii_whitespace()) {
+                    continue;
+                }
+                let value = &s[i + attr.len()..];
+                let value = match value.chars().next() {
+                    Some(q @ ('"' | '\'')) => value[1..].split(q).next(),
+                    _ => value.split(|c: char| c.is_ascii_whitespace() || c == '>').next(),
+                };
+                out.extend(value);
+            }
+        }
+    }
+
+    // CSS can also appear inline in HTML files.
+    for (i, _) in s.match_indices("url(") {
+        let value = s[i + 4..].trim_start();
+        let value = match value.chars().next() {
+            Some(q @ ('"' | '\'')) => value[1..].split(q).next(),
+            _ => value.split(')').next().map(str::trim_end),
+        };
+        out.extend(value);
+    }
+
+    out
+}
+
+/// Resolves `reference` found in the asset with path `base` to the HTTP path
+/// (without leading `/`) it refers to. Returns `None` for references that
+/// do not point to an asset, e.g. external URLs, fragments, `data:` URLs or
+/// directories (like `/`), which are usually routes of the application.
+/// Query and fragment are removed.
+pub(crate) fn resolve(base: &str, reference: &str) -> Option<String> {
+    let reference = reference.trim();
+    let reference = reference.split(['?', '#']).next().unwrap_or_default();
+    if reference.is_empty()
+        || reference.ends_with('/')
+        || reference.starts_with("//")
+        || has_scheme(reference)
+    {
+        return None;
+    }
+
+    let (dir, reference) = match reference.strip_prefix('/') {
+        Some(absolute) => ("", absolute),
+        None => (base.rsplit_once('/').map_or("", |(dir, _)| dir), reference),
+    };
+    let mut segments = Vec::new();
+    for segment in dir.split('/').chain(reference.split('/')) {
+        match segment {
+            "" | "." => {}
+            ".." => { segments.pop(); }
+            s => segments.push(s),
+        }
+    }
+    Some(segments.join("/")).filter(|path| !path.is_empty())
+}
+
+/// Returns whether `s` starts with a URL scheme like `https:` or `data:`.
+fn has_scheme(s: &str) -> bool {
+    s.split_once(':').is_some_and(|(scheme, _)| {
+        !scheme.is_empty() && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_references() {
+        let html = br#"<link rel="stylesheet" href="/style.css">
+            <img data-src="nope.png" src='logo.svg' alt="x">
+            <a href=foo/bar.html>x</a>
+            <div style="background: url( 'bg.png' )"></div>"#;
+        assert_eq!(
+            references("index.html", html),
+            ["logo.svg", "/style.css", "foo/bar.html", "bg.png"],
+        );
+
+        let css = b"@font-face { src: url(fonts/a.woff2) } a { background: url(\"b.png\") }";
+        assert_eq!(references("style.css", css), ["fonts/a.woff2", "b.png"]);
+        assert!(references("bundle.js", css).is_empty());
+    }
+
+    #[test]
+    fn resolve_references() {
+        assert_eq!(resolve("index.html", "style.css").as_deref(), Some("style.css"));
+        assert_eq!(resolve("a/b/index.html", "../c.css?v=1").as_deref(), Some("a/c.css"));
+        assert_eq!(resolve("a/b/index.html", "/c.css#x").as_deref(), Some("c.css"));
+        assert_eq!(resolve("a/index.html", "./img/x.png").as_deref(), Some("a/img/x.png"));
+        assert_eq!(resolve("a/index.html", "docs/"), None);
+        assert_eq!(resolve("index.html", "/"), None);
+        assert_eq!(resolve("index.html", "?page=2"), None);
+        assert_eq!(resolve("index.html", "https://example.com/x.css"), None);
+        assert_eq!(resolve("index.html", "//cdn.example.com/x.css"), None);
+        assert_eq!(resolve("index.html", "data:image/png;base64,AAAA"), None);
+        assert_eq!(resolve("index.html", "mailto:a@b.c"), None);
+        assert_eq!(resolve("index.html", "#top"), None);
+    }
+}
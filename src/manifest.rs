@@ -0,0 +1,143 @@
+//! A machine-readable list of all assets, e.g. for tooling outside of Rust.
+//! See [`Assets::manifest`].
+
+use std::{collections::BTreeMap, io};
+
+use crate::Assets;
+
+
+/// Maps the *unhashed HTTP path* of each asset to information about it,
+/// returned by [`Assets::manifest`]. With the crate feature `serde`, it can
+/// be serialized (as the map `entries`), e.g. with [`Self::to_json`], to
+/// pass it to other tools like a Node SSR layer or a CDN upload script.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(transparent))]
+#[non_exhaustive]
+pub struct Manifest {
+    /// All assets by *unhashed HTTP path*.
+    pub entries: BTreeMap<String, ManifestEntry>,
+}
+
+/// Information about one asset in a [`Manifest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct ManifestEntry {
+    /// The *hashed HTTP path*, under which the asset is served.
+    pub path: String,
+
+    /// Size of the (uncompressed) content in bytes.
+    pub size: u64,
+
+    /// Hex encoded SHA-256 of the content. Only `Some` if the crate feature
+    /// `hash` is enabled.
+    pub sha256: Option<String>,
+
+    /// MIME type, see [`Asset::content_type`][crate::Asset::content_type].
+    pub content_type: String,
+}
+
+impl Manifest {
+    /// Returns the entry of the asset with the given *unhashed HTTP path*.
+    pub fn get(&self, unhashed_http_path: &str) -> Option<&ManifestEntry> {
+        self.entries.get(unhashed_http_path)
+    }
+
+    /// Serializes this manifest as pretty-printed JSON object mapping each
+    /// *unhashed HTTP path* to an object with the fields `path`, `size`,
+    /// `sha256` (`null` without the crate feature `hash`) and `content_type`:
+    ///
+    /// ```text
+    /// {
+    ///   "app.js": {
+    ///     "path": "app.H8BRh8j48O9o.js",
+    ///     "size": 1234,
+    ///     …
+    ///   },
+    ///   …
+    /// }
+    /// ```
+    ///
+    /// Method is only available if the crate feature `serde` is enabled.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("serializing the manifest cannot fail")
+    }
+}
+
+impl Assets {
+    /// Returns a [`Manifest`] of all assets, mapping their *unhashed HTTP
+    /// paths* to the *hashed* ones, their size, hash and MIME type. Internal
+    /// assets are not included and, like with [`Self::iter`], only files of
+    /// glob entries that were found at compile time.
+    ///
+    /// In prod mode, this never fails. In dev mode, all assets are loaded
+    /// to determine their size and hash, which might fail.
+    pub async fn manifest(&self) -> Result<Manifest, io::Error> {
+        let mut entries = BTreeMap::new();
+        for (http_path, asset) in self.iter() {
+            let content = asset.content().await?;
+            entries.insert(asset.0.unhashed_http_path().to_owned(), ManifestEntry {
+                path: http_path.to_owned(),
+                size: content.len() as u64,
+                #[cfg(feature = "hash")]
+                sha256: Some(crate::sha256_hex(&content)),
+                #[cfg(not(feature = "hash"))]
+                sha256: None,
+                content_type: asset.content_type().to_owned(),
+            });
+        }
+        Ok(Manifest { entries })
+    }
+
+    /// Writes the [manifest][Self::manifest] as JSON (see
+    /// [`Manifest::to_json`]) to the file `fs_path`, overwriting it if it
+    /// exists.
+    ///
+    /// Method is only available if the crate feature `serde` is enabled.
+    #[cfg(feature = "serde")]
+    pub async fn write_manifest_json(
+        &self,
+        fs_path: impl AsRef<std::path::Path>,
+    ) -> Result<(), io::Error> {
+        let json = self.manifest().await?.to_json();
+        tokio::fs::write(fs_path, json).await
+    }
+}
+
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json() {
+        let mut manifest = Manifest::default();
+        assert_eq!(manifest.to_json(), "{}");
+
+        manifest.entries.insert("app.js".into(), ManifestEntry {
+            path: "app.1234.js".into(),
+            size: 3,
+            sha256: Some("abcd".into()),
+            content_type: "text/javascript; charset=utf-8".into(),
+        });
+        manifest.entries.insert("a \"b\".txt".into(), ManifestEntry {
+            path: "a \"b\".txt".into(),
+            size: 0,
+            sha256: None,
+            content_type: "text/plain".into(),
+        });
+
+        let json = manifest.to_json();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value, serde_json::json!({
+            "a \"b\".txt": { "path": "a \"b\".txt", "size": 0, "sha256": null, "content_type": "text/plain" },
+            "app.js": {
+                "path": "app.1234.js",
+                "size": 3,
+                "sha256": "abcd",
+                "content_type": "text/javascript; charset=utf-8",
+            },
+        }));
+    }
+}
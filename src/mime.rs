@@ -0,0 +1,49 @@
+//! Guessing MIME types from file extensions.
+
+
+/// Returns the MIME type for the file extension of `path`, or `None` if the
+/// extension is unknown.
+pub(crate) fn guess(path: &str) -> Option<&'static str> {
+    let filename = path.rsplit('/').next().unwrap_or(path);
+    let (_, ext) = filename.rsplit_once('.')?;
+    let mime = match &*ext.to_ascii_lowercase() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "json" | "map" => "application/json",
+        "txt" => "text/plain; charset=utf-8",
+        "md" => "text/markdown; charset=utf-8",
+        "csv" => "text/csv; charset=utf-8",
+        "xml" => "application/xml",
+        "wasm" => "application/wasm",
+        "pdf" => "application/pdf",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "mp3" => "audio/mpeg",
+        "ogg" => "audio/ogg",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        _ => return None,
+    };
+
+    Some(mime)
+}
+
+/// Returns the essence of the MIME type, i.e. without parameters like
+/// `charset`.
+#[cfg_attr(not(feature = "compress"), allow(dead_code))]
+pub(crate) fn essence(mime: &str) -> &str {
+    mime.split(';').next().unwrap_or(mime).trim()
+}
@@ -0,0 +1,93 @@
+//! Ready-made modifiers to minify JavaScript, CSS and HTML. Pass them to
+//! [`EntryBuilder::with_modifier`][crate::EntryBuilder::with_modifier]:
+//!
+//! ```ignore
+//! builder.add_embedded("app.js", &EMBEDS["app.js"])
+//!     .with_modifier([], reinda::modifiers::minify_js());
+//! builder.add_embedded("style.css", &EMBEDS["style.css"])
+//!     .with_modifier([], reinda::modifiers::minify_css());
+//! ```
+//!
+//! The modifiers only minify in prod mode. In dev mode, they return the
+//! content unchanged, so that it stays readable and fast to load. If the
+//! content cannot be parsed, a warning is logged and it is served
+//! unminified.
+//!
+//! Module is only available if the crate feature `minify` is enabled.
+
+use bytes::Bytes;
+
+use crate::ModifierContext;
+
+
+/// Minifies JavaScript code parsed as classic script (not as ES module), so
+/// top-level declarations keep their names. Use [`minify_js_module`] for ES
+/// modules.
+pub fn minify_js() -> impl Fn(Bytes, ModifierContext<'_>) -> Bytes + Send + Sync + 'static {
+    |content, _| minify("JS", content, |src| js(src, minify_js::TopLevelMode::Global))
+}
+
+/// Minifies JavaScript code parsed as ES module, which may contain `import`
+/// and `export` statements.
+pub fn minify_js_module() -> impl Fn(Bytes, ModifierContext<'_>) -> Bytes + Send + Sync + 'static {
+    |content, _| minify("JS", content, |src| js(src, minify_js::TopLevelMode::Module))
+}
+
+/// Minifies CSS.
+pub fn minify_css() -> impl Fn(Bytes, ModifierContext<'_>) -> Bytes + Send + Sync + 'static {
+    |content, _| minify("CSS", content, css)
+}
+
+/// Minifies HTML, including the CSS and JavaScript in `<style>` and
+/// `<script>` tags. Closing tags, `<html>`/`<head>` tags and DOCTYPEs are
+/// kept, so the output stays spec-compliant.
+pub fn minify_html() -> impl Fn(Bytes, ModifierContext<'_>) -> Bytes + Send + Sync + 'static {
+    |content, _| minify("HTML", content, |src| {
+        let cfg = minify_html::Cfg {
+            keep_closing_tags: true,
+            keep_html_and_head_opening_tags: true,
+            minify_css: true,
+            minify_js: true,
+            ..minify_html::Cfg::spec_compliant()
+        };
+        Ok(minify_html::minify(src, &cfg))
+    })
+}
+
+/// Applies `f` in prod mode, falling back to the original content if it
+/// fails.
+fn minify(
+    language: &str,
+    content: Bytes,
+    f: impl FnOnce(&[u8]) -> Result<Vec<u8>, String>,
+) -> Bytes {
+    if cfg!(dev_mode) {
+        return content;
+    }
+
+    match f(&content) {
+        Ok(minified) => minified.into(),
+        Err(e) => {
+            log::warn!(target: "reinda", "failed to minify {language}, keeping it unminified: {e}");
+            content
+        }
+    }
+}
+
+fn js(src: &[u8], mode: minify_js::TopLevelMode) -> Result<Vec<u8>, String> {
+    let session = minify_js::Session::new();
+    let mut out = Vec::new();
+    minify_js::minify(&session, mode, src, &mut out).map_err(|e| format!("{e:?}"))?;
+    Ok(out)
+}
+
+fn css(src: &[u8]) -> Result<Vec<u8>, String> {
+    use lightningcss::stylesheet::{MinifyOptions, ParserOptions, PrinterOptions, StyleSheet};
+
+    let src = std::str::from_utf8(src).map_err(|e| e.to_string())?;
+    let mut sheet = StyleSheet::parse(src, ParserOptions::default()).map_err(|e| e.to_string())?;
+    sheet.minify(MinifyOptions::default()).map_err(|e| e.to_string())?;
+    let printed = sheet.to_css(PrinterOptions { minify: true, ..PrinterOptions::default() })
+        .map_err(|e| e.to_string())?;
+    Ok(printed.code.into_bytes())
+}
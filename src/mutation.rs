@@ -0,0 +1,115 @@
+//! Adding and removing assets after [`Builder::build`][crate::Builder::build].
+//! See [`Assets::insert`].
+//!
+//! Module is only available if the crate feature `runtime-mutation` is
+//! enabled.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, RwLock},
+};
+
+use bytes::Bytes;
+
+use crate::{Asset, Assets, BuildError, EntryBuilder};
+
+
+/// Assets inserted at runtime, by *hashed HTTP path*. Shared by all clones
+/// of an [`Assets`].
+#[derive(Debug)]
+pub(crate) struct RuntimeAssets {
+    assets: RwLock<HashMap<String, Asset>>,
+
+    /// Variables and public URL of the builder, applied to inserted assets.
+    vars: BTreeMap<String, String>,
+    public_url: Option<Arc<str>>,
+}
+
+impl RuntimeAssets {
+    pub(crate) fn new(vars: BTreeMap<String, String>, public_url: Option<Arc<str>>) -> Self {
+        Self { assets: RwLock::default(), vars, public_url }
+    }
+
+    pub(crate) fn get(&self, http_path: &str) -> Option<Asset> {
+        self.assets.read().unwrap_or_else(|e| e.into_inner()).get(http_path).cloned()
+    }
+}
+
+impl Assets {
+    /// Adds an asset with the given content under `http_path`, e.g. a file
+    /// uploaded by a user, so that it can be retrieved with [`Self::get`] and
+    /// served by [`Self::serve`] like all other assets. Returns its *hashed
+    /// HTTP path*, which, without hashing, is just `http_path`. An asset
+    /// inserted under the same path before is replaced. Inserted assets take
+    /// precedence over assets with the same path added via the builder.
+    ///
+    /// The change is visible in all clones of this `Assets`. Inserted assets
+    /// are not returned by [`Self::iter`] and are not part of the
+    /// [manifest][Self::manifest].
+    ///
+    /// Method is only available if the crate feature `runtime-mutation` is
+    /// enabled.
+    pub async fn insert(
+        &self,
+        http_path: &str,
+        content: impl Into<Bytes>,
+    ) -> Result<String, BuildError> {
+        self.insert_with(http_path, content, |_| {}).await
+    }
+
+    /// Like [`Self::insert`], but `configure` can set up the asset like with
+    /// [`Builder::add_bytes`][crate::Builder::add_bytes], e.g. enable
+    /// filename hashing with
+    /// [`EntryBuilder::with_hash`][crate::EntryBuilder::with_hash] or set a
+    /// cache policy. Fails if processing the content fails, e.g. if a
+    /// modifier panics, or with [`BuildError::InternalInsert`] if the asset
+    /// is marked as [internal][crate::EntryBuilder::internal]. The asset is
+    /// processed on its own, so templates and modifiers cannot refer to other
+    /// assets (variants and prepended assets fail with
+    /// [`BuildError::MissingDependency`]), but see the variables and public
+    /// URL of the builder this `Assets` was built with.
+    ///
+    /// Method is only available if the crate feature `runtime-mutation` is
+    /// enabled.
+    pub async fn insert_with(
+        &self,
+        http_path: &str,
+        content: impl Into<Bytes>,
+        configure: impl FnOnce(&mut EntryBuilder<'_>),
+    ) -> Result<String, BuildError> {
+        let http_path = http_path.trim_start_matches('/');
+        let mut builder = Assets::builder();
+        builder.vars = self.runtime.vars.clone();
+        builder.public_url = self.runtime.public_url.clone();
+        let entry = builder.add_bytes(http_path, content);
+        configure(entry);
+        if entry.internal {
+            return Err(BuildError::InternalInsert { http_path: http_path.to_owned() });
+        }
+        // Configured dependencies (e.g. variants) do not exist in this
+        // builder, so building fails for them. Only internal assets are not
+        // returned by `iter`, but they are rejected above already.
+        let built = builder.build().await?;
+        let (hashed_path, asset) = built.iter()
+            .next()
+            .map(|(path, asset)| (path.to_owned(), asset))
+            .ok_or_else(|| BuildError::InternalInsert { http_path: http_path.to_owned() })?;
+
+        log::debug!(target: "reinda", "inserted asset '{hashed_path}' at runtime");
+        let mut assets = self.runtime.assets.write().unwrap_or_else(|e| e.into_inner());
+        assets.retain(|_, a| a.0.unhashed_http_path() != http_path);
+        assets.insert(hashed_path.clone(), asset);
+        Ok(hashed_path)
+    }
+
+    /// Removes the asset inserted via [`Self::insert`] with the given *hashed
+    /// HTTP path* and returns it. Assets added via the builder cannot be
+    /// removed.
+    ///
+    /// Method is only available if the crate feature `runtime-mutation` is
+    /// enabled.
+    pub fn remove(&self, http_path: &str) -> Option<Asset> {
+        let http_path = http_path.trim_start_matches('/');
+        self.runtime.assets.write().unwrap_or_else(|e| e.into_inner()).remove(http_path)
+    }
+}
@@ -0,0 +1,117 @@
+//! Reusable processing steps registered via `Builder::register_plugin`.
+
+use std::{borrow::Cow, fmt, sync::Arc};
+
+use bytes::Bytes;
+
+use crate::{imp::ModifierContextInner, BuildError, ModifierContext, ModifierEnv};
+
+
+/// A reusable processing step for assets, e.g. compiling TypeScript or
+/// optimizing images. Crates can publish implementations of this trait,
+/// which users then enable for all matching assets with
+/// [`Builder::register_plugin`][crate::Builder::register_plugin].
+///
+/// Steps run after the template is rendered and before the modifiers of the
+/// asset (see [`EntryBuilder::with_modifier`][crate::EntryBuilder::with_modifier])
+/// are applied. Like modifiers, they run once in [`Builder::build`][crate::Builder::build]
+/// in prod mode, and every time the asset is loaded in dev mode.
+///
+/// ```ignore
+/// struct Uppercase;
+///
+/// impl reinda::Pipeline for Uppercase {
+///     fn name(&self) -> &str {
+///         "uppercase"
+///     }
+///
+///     fn applies_to(&self, http_path: &str) -> bool {
+///         http_path.ends_with(".txt")
+///     }
+///
+///     fn transform(&self, content: Bytes, _: ModifierContext) -> Result<Bytes, String> {
+///         Ok(content.to_ascii_uppercase().into())
+///     }
+/// }
+/// ```
+pub trait Pipeline: Send + Sync + 'static {
+    /// Name of this step, used in log messages and errors, e.g. `"tailwind"`.
+    fn name(&self) -> &str;
+
+    /// Returns whether this step is applied to the asset with the given
+    /// *unhashed HTTP path*.
+    fn applies_to(&self, http_path: &str) -> bool;
+
+    /// *Unhashed HTTP paths* of assets this step refers to, which can then be
+    /// resolved via [`ModifierContext::resolve_path`]. These assets are built
+    /// first, just like dependencies passed to
+    /// [`EntryBuilder::with_modifier`][crate::EntryBuilder::with_modifier].
+    /// Defaults to none. If one does not exist, [`Builder::build`][crate::Builder::build]
+    /// fails with [`BuildError::MissingDependency`].
+    fn dependencies(&self) -> Vec<Cow<'static, str>> {
+        vec![]
+    }
+
+    /// Transforms the content of an asset. Returning an error (or panicking)
+    /// makes the build fail with [`BuildError::Pipeline`] in prod mode, and
+    /// loading the asset fail in dev mode.
+    fn transform(&self, content: Bytes, ctx: ModifierContext) -> Result<Bytes, String>;
+}
+
+/// The registered pipeline steps, in registration order.
+#[derive(Clone, Default)]
+pub(crate) struct Steps(pub(crate) Vec<Arc<dyn Pipeline>>);
+
+impl fmt::Debug for Steps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.0.iter().map(|step| step.name())).finish()
+    }
+}
+
+impl Steps {
+    /// Returns the steps applying to the given asset.
+    pub(crate) fn for_path(&self, http_path: &str) -> Self {
+        Self(self.0.iter().filter(|step| step.applies_to(http_path)).cloned().collect())
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the names of all steps, separated by commas.
+    #[cfg_attr(dev_mode, allow(dead_code))]
+    pub(crate) fn names(&self) -> String {
+        self.0.iter().map(|step| step.name()).collect::<Vec<_>>().join(", ")
+    }
+
+    /// Returns the dependencies of all steps.
+    pub(crate) fn dependencies(&self) -> Vec<Cow<'static, str>> {
+        self.0.iter().flat_map(|step| step.dependencies()).collect()
+    }
+
+    /// Runs all steps on `content`, in order. `inner` creates the context to
+    /// resolve paths, which has to contain all `Self::dependencies`.
+    pub(crate) fn apply<'i>(
+        &self,
+        http_path: &str,
+        mut content: Bytes,
+        env: &ModifierEnv,
+        inner: impl Fn() -> ModifierContextInner<'i>,
+    ) -> Result<Bytes, BuildError> {
+        for step in &self.0 {
+            let deps = step.dependencies();
+            let ctx = ModifierContext { declared_deps: &deps, env, inner: inner() };
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                step.transform(content, ctx)
+            }));
+            content = result
+                .unwrap_or_else(|payload| Err(crate::panic_message(payload)))
+                .map_err(|message| BuildError::Pipeline {
+                    http_path: http_path.to_owned(),
+                    step: step.name().to_owned(),
+                    message,
+                })?;
+        }
+        Ok(content)
+    }
+}
@@ -0,0 +1,140 @@
+//! Hooks running once after all assets are built, see
+//! `Builder::with_post_build`.
+
+use std::{collections::HashSet, fmt, sync::Arc};
+
+use bytes::Bytes;
+
+use crate::BuildError;
+
+
+/// A hook registered via `Builder::with_post_build`.
+pub(crate) type PostBuildFn = Arc<dyn Send + Sync + Fn(&mut BuildView)>;
+
+/// All registered hooks, in registration order.
+#[derive(Clone, Default)]
+pub(crate) struct PostBuildHooks(pub(crate) Vec<PostBuildFn>);
+
+impl fmt::Debug for PostBuildHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PostBuildHooks({})", self.0.len())
+    }
+}
+
+impl PostBuildHooks {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Runs all hooks in order on the given assets (as `(unhashed HTTP path,
+    /// hashed HTTP path, content)`) and returns the assets they added.
+    /// `internal` contains the paths of internal assets, which hooks cannot
+    /// see, but must not add assets under either.
+    pub(crate) fn run(
+        &self,
+        assets: Vec<(String, String, Bytes)>,
+        internal: impl IntoIterator<Item = String>,
+        public_url: Option<Arc<str>>,
+    ) -> Result<Vec<(String, Bytes)>, BuildError> {
+        let mut taken = assets.iter()
+            .flat_map(|(unhashed, hashed, _)| [unhashed.clone(), hashed.clone()])
+            .collect::<HashSet<_>>();
+        taken.extend(internal);
+        let mut view = BuildView { assets, taken, added: vec![], public_url, error: None };
+        view.assets.sort_by(|a, b| a.0.cmp(&b.0));
+        for hook in &self.0 {
+            hook(&mut view);
+        }
+        match view.error {
+            Some(e) => Err(e),
+            None => Ok(view.added),
+        }
+    }
+}
+
+/// Read access to all built assets and a way to add new ones, passed to the
+/// hooks registered via
+/// [`Builder::with_post_build`][crate::Builder::with_post_build].
+#[derive(Debug)]
+pub struct BuildView {
+    /// `(unhashed HTTP path, hashed HTTP path, content)`, sorted.
+    assets: Vec<(String, String, Bytes)>,
+
+    /// All paths of built assets, hashed and unhashed, including internal
+    /// ones.
+    taken: HashSet<String>,
+    added: Vec<(String, Bytes)>,
+    public_url: Option<Arc<str>>,
+
+    /// The first failed `Self::add`, returned by `Builder::build`.
+    error: Option<BuildError>,
+}
+
+/// An asset in a [`BuildView`].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct ViewedAsset<'a> {
+    /// The *unhashed HTTP path*.
+    pub unhashed_http_path: &'a str,
+
+    /// The *hashed HTTP path*, under which the asset is served.
+    pub http_path: &'a str,
+
+    /// The final content, after all processing.
+    pub content: &'a Bytes,
+}
+
+impl BuildView {
+    /// Returns all built assets sorted by *unhashed HTTP path*, followed by
+    /// the assets added by hooks so far. Internal assets are not included.
+    pub fn iter(&self) -> impl '_ + Iterator<Item = ViewedAsset<'_>> {
+        let built = self.assets.iter().map(|(unhashed, hashed, content)| ViewedAsset {
+            unhashed_http_path: unhashed,
+            http_path: hashed,
+            content,
+        });
+        let added = self.added.iter().map(|(path, content)| ViewedAsset {
+            unhashed_http_path: path,
+            http_path: path,
+            content,
+        });
+        built.chain(added)
+    }
+
+    /// Returns the asset with the given *unhashed HTTP path*, if any.
+    pub fn get(&self, unhashed_http_path: &str) -> Option<ViewedAsset<'_>> {
+        self.iter().find(|asset| asset.unhashed_http_path == unhashed_http_path)
+    }
+
+    /// Returns the URL passed to
+    /// [`Builder::set_public_url`][crate::Builder::set_public_url], e.g. to
+    /// write absolute URLs into a sitemap.
+    pub fn public_url(&self) -> Option<&str> {
+        self.public_url.as_deref()
+    }
+
+    /// Adds an asset with the given content, e.g. a sitemap or the precache
+    /// list of a service worker. It is served under `http_path` without any
+    /// processing or filename hashing, but is compressed if configured via
+    /// [`Builder::set_compression`][crate::Builder::set_compression]. It is
+    /// visible to hooks running later. An asset added before under the same
+    /// path is replaced.
+    ///
+    /// If a built asset (including internal ones) has the same *unhashed* or
+    /// *hashed HTTP path*, the asset is not added and
+    /// [`Builder::build`][crate::Builder::build] fails with
+    /// [`BuildError::DuplicatePath`].
+    pub fn add(&mut self, http_path: impl Into<String>, content: impl Into<Bytes>) -> &mut Self {
+        let mut http_path = http_path.into();
+        if http_path.starts_with('/') {
+            http_path = http_path.trim_start_matches('/').to_owned();
+        }
+        if self.taken.contains(&http_path) {
+            self.error.get_or_insert(BuildError::DuplicatePath { http_path });
+            return self;
+        }
+        self.added.retain(|(path, _)| *path != http_path);
+        self.added.push((http_path, content.into()));
+        self
+    }
+}
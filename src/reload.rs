@@ -0,0 +1,125 @@
+//! Rebuilding assets at runtime and swapping them in without restarting, e.g.
+//! on `SIGHUP` like nginx. See [`ReloadableAssets`].
+//!
+//! Module is only available if the crate feature `reload` is enabled.
+
+use std::{future::Future, sync::{Arc, RwLock}};
+
+use crate::{http::{Request, Response}, Assets, Builder};
+
+
+/// Shared handle to [`Assets`] that can be replaced at runtime. Cheap to
+/// clone; all clones refer to the same assets.
+///
+/// Handlers should call [`Self::current`] (or [`Self::serve`]) for each
+/// request instead of holding on to an `Assets`, so that they pick up
+/// reloaded assets. Requests already being answered finish with the old
+/// assets.
+///
+/// ```ignore
+/// let assets = ReloadableAssets::new(builder().build().await?);
+/// tokio::spawn({
+///     let assets = assets.clone();
+///     async move { assets.reload_on_sighup(builder).await }
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReloadableAssets(Arc<RwLock<Assets>>);
+
+impl ReloadableAssets {
+    /// Wraps already built assets.
+    pub fn new(assets: Assets) -> Self {
+        Self(Arc::new(RwLock::new(assets)))
+    }
+
+    /// Returns the current assets. This is a cheap clone that stays valid
+    /// (and unchanged) when the assets are reloaded.
+    pub fn current(&self) -> Assets {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replaces the assets, returning the old ones.
+    pub fn replace(&self, assets: Assets) -> Assets {
+        let mut guard = self.0.write().unwrap_or_else(|e| e.into_inner());
+        std::mem::replace(&mut *guard, assets)
+    }
+
+    /// Answers the request with the current assets, see [`Assets::serve`].
+    pub async fn serve(&self, req: &Request<'_>) -> Response {
+        self.current().serve(req).await
+    }
+
+    /// Builds new assets with the builder returned by `builder` and, if that
+    /// succeeds, swaps them in. If building fails, the error is returned and
+    /// the current assets stay in place.
+    pub async fn reload<F>(&self, builder: F) -> Result<(), crate::BuildError>
+    where
+        F: FnOnce() -> Builder<'static>,
+    {
+        let assets = builder().build().await?;
+        log::info!(target: "reinda", "reloaded {} assets", assets.len());
+        self.replace(assets);
+        Ok(())
+    }
+
+    /// Reloads the assets (see [`Self::reload`]) every time `trigger`
+    /// completes, until it returns `false`. The builder is usually created
+    /// from a config file or runtime directory, so that changed settings
+    /// are applied as well. Errors while building, and panics due to an
+    /// invalid configuration, are logged and the current assets are kept.
+    ///
+    /// ```ignore
+    /// // Reload every minute.
+    /// assets.reload_on(builder, || async {
+    ///     tokio::time::sleep(Duration::from_secs(60)).await;
+    ///     true
+    /// }).await;
+    /// ```
+    pub async fn reload_on<B, T, F>(&self, mut builder: B, mut trigger: T)
+    where
+        B: FnMut() -> Builder<'static>,
+        T: FnMut() -> F,
+        F: Future<Output = bool>,
+    {
+        while trigger().await {
+            self.reload_or_log(&mut builder).await;
+        }
+    }
+
+    /// Reloads the assets every time the process receives `SIGHUP`, like
+    /// nginx and other static servers do. Like with [`Self::reload_on`],
+    /// errors and panics while building are logged. Only returns if registering the
+    /// signal handler fails. Must be called from within a Tokio runtime.
+    ///
+    /// Method is only available on Unix.
+    #[cfg(unix)]
+    pub async fn reload_on_sighup<B>(&self, mut builder: B) -> Result<(), std::io::Error>
+    where
+        B: FnMut() -> Builder<'static>,
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = signal(SignalKind::hangup())?;
+        while hangup.recv().await.is_some() {
+            log::info!(target: "reinda", "received SIGHUP, reloading assets");
+            self.reload_or_log(&mut builder).await;
+        }
+        Ok(())
+    }
+
+    async fn reload_or_log(&self, builder: impl FnOnce() -> Builder<'static>) {
+        // Building panics for some invalid configurations, e.g. a lazy entry
+        // that is not loaded from the file system. That must not end the
+        // reload loop, so building runs in its own task.
+        let (this, builder) = (self.clone(), builder());
+        match tokio::spawn(async move { this.reload(|| builder).await }).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                log::error!(target: "reinda", "failed to reload assets, keeping the old ones: {e}");
+            }
+            Err(e) => {
+                log::error!(target: "reinda", "reloading assets panicked, keeping the old ones: {e}");
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+//! Fetching assets from remote origins, see `Builder::add_url`.
+//!
+//! Module is only available if the crate feature `url` is enabled.
+
+use std::{io, time::Duration};
+
+use bytes::Bytes;
+
+
+/// How long fetching a single asset may take, including the connection.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Fetches `url` with a `GET` request and returns the response body. Non-2xx
+/// responses are errors; a `404` results in [`io::ErrorKind::NotFound`].
+pub(crate) async fn fetch(url: &str) -> Result<Bytes, io::Error> {
+    let client = reqwest::Client::builder()
+        .timeout(TIMEOUT)
+        .build()
+        .map_err(io::Error::other)?;
+
+    log::debug!(target: "reinda", "fetching '{url}'");
+    let response = client.get(url).send().await.map_err(to_io_error)?;
+    let status = response.status();
+    if !status.is_success() {
+        let kind = match status.as_u16() {
+            404 | 410 => io::ErrorKind::NotFound,
+            _ => io::ErrorKind::Other,
+        };
+        return Err(io::Error::new(kind, format!("server responded with status {status}")));
+    }
+
+    response.bytes().await.map_err(to_io_error)
+}
+
+fn to_io_error(err: reqwest::Error) -> io::Error {
+    let kind = if err.is_timeout() { io::ErrorKind::TimedOut } else { io::ErrorKind::Other };
+    io::Error::new(kind, err)
+}
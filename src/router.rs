@@ -0,0 +1,110 @@
+//! Serving several independent [`Assets`] sets under different path
+//! prefixes. See [`AssetsRouter`].
+
+use std::borrow::Cow;
+
+use crate::{http::{Request, Response}, Asset, Assets};
+
+
+/// Dispatches lookups and requests by path prefix to several independent
+/// [`Assets`], e.g. the embedded app assets under `/` and runtime uploads
+/// under `/uploads/`, so that the application only deals with one of them.
+///
+/// Each set sees paths with its prefix removed, i.e. an asset mounted as
+/// `logo.png` in a set under `uploads/` is served at `/uploads/logo.png`.
+/// Sets are not aware of each other, so templates and path fixup only refer
+/// to assets of the same set. Cheap to clone, like [`Assets`].
+///
+/// ```ignore
+/// let mut router = AssetsRouter::new();
+/// router.mount("/", app_assets).mount("/uploads/", upload_assets);
+/// let response = router.serve(&Request::get("/uploads/logo.png")).await;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AssetsRouter {
+    /// Prefixes without leading `/`, sorted by descending length so that the
+    /// most specific one matches first.
+    mounts: Vec<(Cow<'static, str>, Assets)>,
+}
+
+impl AssetsRouter {
+    /// Creates a router without any assets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mounts `assets` under the path `prefix`, e.g. `"/uploads/"`, replacing
+    /// the set previously mounted there. `"/"` (or `""`) matches all paths,
+    /// but more specific prefixes always win. Prefixes are compared as
+    /// strings, so they should usually end with `/`.
+    pub fn mount(&mut self, prefix: impl Into<Cow<'static, str>>, assets: Assets) -> &mut Self {
+        let prefix = match prefix.into() {
+            Cow::Borrowed(s) => Cow::Borrowed(s.trim_start_matches('/')),
+            Cow::Owned(s) => Cow::Owned(s.trim_start_matches('/').to_owned()),
+        };
+        self.mounts.retain(|(p, _)| *p != prefix);
+        self.mounts.push((prefix, assets));
+        self.mounts.sort_by_key(|(p, _)| std::cmp::Reverse(p.len()));
+        self
+    }
+
+    /// Returns all mounted sets with their prefix (without leading `/`), most
+    /// specific first.
+    pub fn mounts(&self) -> impl '_ + Iterator<Item = (&str, &Assets)> {
+        self.mounts.iter().map(|(prefix, assets)| (&**prefix, assets))
+    }
+
+    /// Returns the set responsible for `http_path` and the path within it.
+    fn route<'p>(&self, http_path: &'p str) -> Option<(&Assets, &'p str)> {
+        let http_path = http_path.trim_start_matches('/');
+        self.mounts.iter()
+            .find_map(|(prefix, assets)| Some((assets, http_path.strip_prefix(&**prefix)?)))
+    }
+
+    /// Returns the asset with the given *hashed HTTP path*, including the
+    /// prefix of its set. Like [`Assets::get`], but for all sets.
+    pub fn get(&self, http_path: &str) -> Option<Asset> {
+        let (assets, path) = self.route(http_path)?;
+        assets.get(path)
+    }
+
+    /// Returns an iterator over the assets of all sets and their *hashed HTTP
+    /// paths*, including the prefix of their set.
+    pub fn iter(&self) -> impl '_ + Iterator<Item = (String, Asset)> {
+        self.mounts.iter().flat_map(|(prefix, assets)| {
+            assets.iter().map(move |(path, asset)| (format!("{prefix}{path}"), asset))
+        })
+    }
+
+    /// Answers the request with the set responsible for its path, see
+    /// [`Assets::serve`]. The set gets the request with the prefix removed
+    /// from its path, but with the query string kept. Requests for paths not
+    /// matching any prefix get a `404 Not Found`.
+    pub async fn serve(&self, req: &Request<'_>) -> Response {
+        let Some(lookup_path) = req.lookup_path() else {
+            return Response::text(404, "Not Found");
+        };
+        let Some((assets, path)) = self.route(&lookup_path) else {
+            return Response::text(404, "Not Found");
+        };
+
+        // The set decodes the path again, so the raw path without the prefix
+        // is forwarded. If the prefix itself was encoded, the decoded path is
+        // escaped again instead.
+        let prefix_len = lookup_path.len() - path.len();
+        let (raw, query) = match req.path().split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (req.path(), None),
+        };
+        let raw = raw.trim_start_matches('/');
+        let forwarded = match raw.get(prefix_len..) {
+            Some(rest) if raw.get(..prefix_len) == Some(&lookup_path[..prefix_len]) => Cow::Borrowed(rest),
+            _ => Cow::Owned(path.replace('%', "%25").replace('?', "%3F")),
+        };
+        let forwarded = match query {
+            Some(query) => Cow::Owned(format!("{forwarded}?{query}")),
+            None => forwarded,
+        };
+        assets.serve(&req.with_path(&forwarded)).await
+    }
+}
@@ -0,0 +1,60 @@
+//! Loading file contents from sidecar files (see `sidecar` in `embed!`).
+
+use std::{io, path::PathBuf, sync::Mutex};
+
+use bytes::Bytes;
+
+use crate::SidecarLocation;
+
+
+/// Name of the environment variable that overrides the directory in which
+/// sidecar files are searched.
+pub(crate) const DIR_ENV_VAR: &str = "REINDA_SIDECAR_DIR";
+
+/// All sidecar files loaded so far. There are usually very few, so a `Vec` is
+/// just fine.
+static LOADED: Mutex<Vec<(&'static str, Bytes)>> = Mutex::new(Vec::new());
+
+/// Returns the stored (potentially compressed) content for the given location.
+/// The sidecar file is only read once and then cached.
+pub(crate) fn load(location: &SidecarLocation) -> Result<Bytes, io::Error> {
+    let mut loaded = LOADED.lock().unwrap_or_else(|e| e.into_inner());
+    let file = match loaded.iter().find(|(path, _)| *path == location.path) {
+        Some((_, data)) => data.clone(),
+        None => {
+            let data = Bytes::from(std::fs::read(path_of(location)?)?);
+            loaded.push((location.path, data.clone()));
+            data
+        }
+    };
+    drop(loaded);
+
+    let invalid = |msg: &str| io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("sidecar '{}' does not match this executable: {msg}", location.path),
+    );
+
+    let end = location.offset.checked_add(location.len)
+        .filter(|&end| end <= file.len())
+        .ok_or_else(|| invalid("file too short"))?;
+    let stored = file.slice(location.offset..end);
+    if crate::sha256_hex(&stored) != location.sha256 {
+        return Err(invalid("integrity check failed"));
+    }
+
+    Ok(stored)
+}
+
+/// Returns the full path of the sidecar file: its relative path joined to the
+/// directory of the executable (or the value of `REINDA_SIDECAR_DIR`).
+pub(crate) fn path_of(location: &SidecarLocation) -> Result<PathBuf, io::Error> {
+    let dir = match std::env::var_os(DIR_ENV_VAR) {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            let exe = std::env::current_exe()?;
+            exe.parent().map(|p| p.to_owned()).unwrap_or_default()
+        }
+    };
+
+    Ok(dir.join(location.path))
+}
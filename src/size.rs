@@ -0,0 +1,82 @@
+//! Attributing the size of the executable to embedded entries.
+
+use crate::{builder::EntryBuilderKind, Assets, EntryBuilder};
+
+
+/// Number of bytes an embedded entry adds to the executable. Returned by
+/// [`Assets::embedded_size_by_entry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntrySize {
+    http_path: String,
+    embed_pattern: &'static str,
+    files: usize,
+    bytes: usize,
+}
+
+impl EntrySize {
+    /// The HTTP path (for single files) or HTTP prefix (for globs) the entry
+    /// was added with.
+    pub fn http_path(&self) -> &str {
+        &self.http_path
+    }
+
+    /// The *embed pattern*, i.e. the string specified in the `embed!` macro.
+    pub fn embed_pattern(&self) -> &'static str {
+        self.embed_pattern
+    }
+
+    /// Number of files in this entry.
+    pub fn files(&self) -> usize {
+        self.files
+    }
+
+    /// Number of bytes stored in the executable for this entry, i.e. the
+    /// size after compression by `embed!`. See
+    /// [`EmbeddedFile::embedded_size`][crate::EmbeddedFile::embedded_size].
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Collects the sizes of all embedded entries, largest first.
+    pub(crate) fn collect(entries: &[EntryBuilder<'_>]) -> Vec<Self> {
+        let mut out = entries.iter()
+            .filter_map(|entry| {
+                let (embed_pattern, bytes) = entry.embedded?;
+                let (http_path, files) = match &entry.kind {
+                    EntryBuilderKind::Single { http_path, .. } => (http_path, 1),
+                    EntryBuilderKind::Glob { http_prefix, files, .. } => (http_prefix, files.len()),
+                    EntryBuilderKind::Dir { http_prefix, files, .. } => (http_prefix, files.len()),
+                };
+                Some(Self { http_path: http_path.to_string(), embed_pattern, files, bytes })
+            })
+            .collect::<Vec<_>>();
+        out.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.http_path.cmp(&b.http_path)));
+        out
+    }
+}
+
+impl Assets {
+    /// Returns how many bytes each embedded entry adds to the executable,
+    /// largest first. Helps finding out which glob accidentally pulled in
+    /// lots of large files. Entries added via [`Builder::add_file`] are
+    /// not included, as they are not embedded.
+    ///
+    /// In dev mode, nothing is embedded, so all sizes are 0. The `embed!`
+    /// macro prints the same breakdown at compile time with `print_stats:
+    /// true`.
+    ///
+    /// [`Builder::add_file`]: crate::Builder::add_file
+    pub fn embedded_size_by_entry(&self) -> &[EntrySize] {
+        &self.sizes
+    }
+
+    /// Returns the number of bytes saved by storing identical compressed
+    /// variants (e.g. of duplicated vendor chunks) only once. Always 0 in
+    /// dev mode, where variants are created on the fly.
+    ///
+    /// Method is only available if the crate feature `compress` is enabled.
+    #[cfg(feature = "compress")]
+    pub fn deduplicated_bytes(&self) -> usize {
+        self.inner.deduplicated_bytes()
+    }
+}
@@ -0,0 +1,79 @@
+//! Finding paths similar to a given one, to help debugging failed lookups.
+
+/// Maximum number of suggestions returned by [`similar`].
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Returns the paths from `candidates` most similar to `needle`, best match
+/// first. Candidates that are too different are not returned at all.
+pub(crate) fn similar<'a>(needle: &str, candidates: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let mut matches = candidates
+        .filter_map(|candidate| score(needle, candidate).map(|score| (score, candidate)))
+        .collect::<Vec<_>>();
+    matches.sort_unstable();
+    matches.into_iter().take(MAX_SUGGESTIONS).map(|(_, path)| path).collect()
+}
+
+/// Lower is better. Returns `None` if `a` and `b` are not similar enough.
+fn score(a: &str, b: &str) -> Option<usize> {
+    let a = a.trim_start_matches('/');
+    let b = b.trim_start_matches('/');
+
+    // If one is a prefix of the other (e.g. missing extension), that's a
+    // strong hint, regardless of the length difference.
+    if a.starts_with(b) || b.starts_with(a) {
+        return Some(a.len().abs_diff(b.len()) / 4);
+    }
+
+    // Hashed filenames differ from the unhashed ones by a long hash, so we
+    // allow the distance to be up to half the length of the longer string.
+    let distance = levenshtein(a.as_bytes(), b.as_bytes());
+    let max_distance = a.len().max(b.len()) / 2;
+    (distance <= max_distance).then_some(distance)
+}
+
+fn levenshtein(a: &[u8], b: &[u8]) -> usize {
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + (ca != cb) as usize;
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance() {
+        assert_eq!(levenshtein(b"", b""), 0);
+        assert_eq!(levenshtein(b"abc", b""), 3);
+        assert_eq!(levenshtein(b"kitten", b"sitting"), 3);
+        assert_eq!(levenshtein(b"style.css", b"style.css"), 0);
+    }
+
+    #[test]
+    fn suggestions() {
+        let paths = [
+            "index.html",
+            "style.css",
+            "bundle.8f29ad31.js",
+            "fonts/latin-400.woff2",
+            "fonts/latin-700.woff2",
+            "robots.txt",
+        ];
+        let similar = |needle| similar(needle, paths.iter().copied());
+
+        assert_eq!(similar("styles.css"), ["style.css"]);
+        assert_eq!(similar("/index.htm"), ["index.html"]);
+        assert_eq!(similar("bundle.js"), ["bundle.8f29ad31.js"]);
+        assert_eq!(similar("fonts/latin-500.woff2"), ["fonts/latin-400.woff2", "fonts/latin-700.woff2"]);
+        assert!(similar("completely/unrelated.png").is_empty());
+    }
+}
@@ -0,0 +1,415 @@
+//! Compact storage for all assets in prod mode.
+//!
+//! Instead of one heap allocation (or more) per asset, all assets are stored
+//! in a few contiguous buffers that are sized once at build time: all paths
+//! are concatenated into one string, all compressed variants are stored in
+//! one list and the entries themselves only refer to those via offsets. This
+//! reduces pointer chasing and improves cache locality for large asset sets.
+//! Compressed variants are stored content-addressed: identical outputs (e.g.
+//! from duplicated vendor chunks) share one buffer.
+
+use std::{convert::TryFrom, fmt, ops::Range, path::PathBuf};
+
+use ahash::{HashMap, HashMapExt, RandomState};
+use bytes::Bytes;
+
+use crate::{http::ServeSettings, BuildError};
+#[cfg(feature = "compress")]
+use ahash::HashSet;
+#[cfg(feature = "compress")]
+use crate::Encoding;
+#[cfg(feature = "hash")]
+use crate::CspHashes;
+
+
+/// Index of an asset in the [`Table`].
+pub(crate) type AssetId = u32;
+
+/// A built asset, before being inserted into the table.
+pub(crate) struct BuiltAsset {
+    pub(crate) path: String,
+
+    /// The *unhashed HTTP path*, if it differs from `path`.
+    pub(crate) unhashed_path: Option<String>,
+    pub(crate) content: Bytes,
+
+    /// Set for lazy assets, whose `content` is empty.
+    pub(crate) file: Option<LazyFile>,
+    pub(crate) hashed_filename: bool,
+    pub(crate) embedded: bool,
+    pub(crate) serve: ServeSettings,
+    pub(crate) referenced: bool,
+    #[cfg(feature = "compress")]
+    pub(crate) variants: Vec<(Encoding, Bytes)>,
+}
+
+/// File of a lazy asset, loaded on each request (see `EntryBuilder::lazy`).
+/// Length and cache key are determined in `Builder::build`.
+#[derive(Debug)]
+pub(crate) struct LazyFile {
+    pub(crate) path: PathBuf,
+    pub(crate) len: u64,
+    #[cfg(feature = "hash")]
+    pub(crate) cache_key: [u8; 16],
+}
+
+pub(crate) struct Table {
+    /// All assets, sorted by path.
+    entries: Vec<Entry>,
+
+    /// All paths concatenated, referred to by `Entry::path`.
+    paths: String,
+
+    /// All compressed variants, referred to by `Entry::variants`.
+    #[cfg(feature = "compress")]
+    variants: Vec<(Encoding, Bytes)>,
+
+    /// Number of bytes saved by sharing identical compressed variants.
+    #[cfg(feature = "compress")]
+    deduplicated_bytes: usize,
+
+    /// Maps the hash of a path to the asset with that path.
+    index: HashMap<u64, AssetId>,
+
+    /// Assets whose path hash collides with another asset's. Practically
+    /// always empty.
+    overflow: Vec<AssetId>,
+
+    hasher: RandomState,
+}
+
+struct Entry {
+    path: Span,
+    unhashed_path: Option<Span>,
+    content: Bytes,
+    file: Option<Box<LazyFile>>,
+    hashed_filename: bool,
+    embedded: bool,
+    serve: ServeSettings,
+    referenced: bool,
+    #[cfg(feature = "compress")]
+    variants: Span,
+    #[cfg(feature = "hash")]
+    cache_key: [u8; 16],
+
+    /// `None` if there are no inline scripts or styles, which is the case
+    /// for almost all assets.
+    #[cfg(feature = "hash")]
+    csp_hashes: Option<Box<CspHashes>>,
+}
+
+#[derive(Clone, Copy)]
+struct Span {
+    start: u32,
+    end: u32,
+}
+
+impl Span {
+    fn range(self) -> Range<usize> {
+        self.start as usize..self.end as usize
+    }
+}
+
+impl Table {
+    /// Creates the table. Returns an error if the assets exceed the limits
+    /// of the compact representation (2^32 assets, path bytes or variants).
+    pub(crate) fn new(mut assets: Vec<BuiltAsset>) -> Result<Self, BuildError> {
+        let total_path_len = assets.iter()
+            .map(|a| a.path.len() + a.unhashed_path.as_ref().map_or(0, |p| p.len()))
+            .sum();
+        check_limit("assets", assets.len())?;
+        check_limit("bytes of HTTP paths", total_path_len)?;
+        #[cfg(feature = "compress")]
+        let total_variants = assets.iter().map(|a| a.variants.len()).sum();
+        #[cfg(feature = "compress")]
+        check_limit("compressed variants", total_variants)?;
+
+        assets.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+        // All casts to `u32` below are fine, as we checked the limits above.
+        let span = |start: usize, end: usize| Span { start: start as u32, end: end as u32 };
+
+        let mut entries = Vec::with_capacity(assets.len());
+        let mut paths = String::with_capacity(total_path_len);
+        #[cfg(feature = "compress")]
+        let mut variants = Vec::with_capacity(total_variants);
+        #[cfg(feature = "compress")]
+        let mut unique_variants = HashSet::<(Encoding, Bytes)>::default();
+        #[cfg(feature = "compress")]
+        let mut deduplicated_bytes = 0;
+        let hasher = RandomState::new();
+        let mut index = HashMap::with_capacity(assets.len());
+        let mut overflow = Vec::new();
+        for (i, asset) in assets.into_iter().enumerate() {
+            let id = i as AssetId;
+            match index.entry(hasher.hash_one(&asset.path)) {
+                std::collections::hash_map::Entry::Vacant(e) => { e.insert(id); }
+                std::collections::hash_map::Entry::Occupied(_) => overflow.push(id),
+            }
+
+            let path_start = paths.len();
+            paths.push_str(&asset.path);
+            let path_end = paths.len();
+            let unhashed_path = asset.unhashed_path.map(|unhashed| {
+                paths.push_str(&unhashed);
+                span(path_end, paths.len())
+            });
+            #[cfg(feature = "compress")]
+            let variants_start = variants.len();
+            #[cfg(feature = "compress")]
+            for variant in asset.variants {
+                match unique_variants.get(&variant) {
+                    Some(existing) => {
+                        deduplicated_bytes += existing.1.len();
+                        variants.push(existing.clone());
+                    }
+                    None => {
+                        unique_variants.insert(variant.clone());
+                        variants.push(variant);
+                    }
+                }
+            }
+            entries.push(Entry {
+                path: span(path_start, path_end),
+                unhashed_path,
+                #[cfg(feature = "hash")]
+                cache_key: match &asset.file {
+                    Some(file) => file.cache_key,
+                    None => crate::util::cache_key(&asset.content),
+                },
+                #[cfg(feature = "hash")]
+                csp_hashes: Some(CspHashes::of_asset(&asset.serve, &asset.path, &asset.content))
+                    .filter(|hashes| !hashes.is_empty())
+                    .map(Box::new),
+                content: asset.content,
+                file: asset.file.map(Box::new),
+                hashed_filename: asset.hashed_filename,
+                embedded: asset.embedded,
+                serve: asset.serve,
+                referenced: asset.referenced,
+                #[cfg(feature = "compress")]
+                variants: span(variants_start, variants.len()),
+            });
+        }
+
+        Ok(Self {
+            entries,
+            paths,
+            #[cfg(feature = "compress")]
+            variants,
+            #[cfg(feature = "compress")]
+            deduplicated_bytes,
+            index,
+            overflow,
+            hasher,
+        })
+    }
+
+    pub(crate) fn get(&self, http_path: &str) -> Option<AssetId> {
+        let hash = self.hasher.hash_one(http_path);
+        self.index.get(&hash)
+            .copied()
+            .filter(|&id| self.path(id) == http_path)
+            .or_else(|| self.overflow.iter().copied().find(|&id| self.path(id) == http_path))
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns all asset IDs, sorted by path.
+    pub(crate) fn ids(&self) -> impl Iterator<Item = AssetId> {
+        // `new` made sure that all indices fit.
+        0..self.entries.len() as AssetId
+    }
+
+    /// Returns the IDs of all assets whose path starts with `prefix`.
+    pub(crate) fn ids_with_prefix<'s>(&'s self, prefix: &'s str) -> impl 's + Iterator<Item = AssetId> {
+        let start = self.entries.partition_point(|e| &self.paths[e.path.range()] < prefix);
+        (start as AssetId..self.entries.len() as AssetId)
+            .take_while(move |&id| self.path(id).starts_with(prefix))
+    }
+
+    pub(crate) fn path(&self, id: AssetId) -> &str {
+        &self.paths[self.entry(id).path.range()]
+    }
+
+    pub(crate) fn unhashed_path(&self, id: AssetId) -> &str {
+        let entry = self.entry(id);
+        &self.paths[entry.unhashed_path.unwrap_or(entry.path).range()]
+    }
+
+    pub(crate) fn content(&self, id: AssetId) -> &Bytes {
+        &self.entry(id).content
+    }
+
+    /// Returns the file of a lazy asset, whose content is not stored.
+    pub(crate) fn lazy_file(&self, id: AssetId) -> Option<&LazyFile> {
+        self.entry(id).file.as_deref()
+    }
+
+    pub(crate) fn is_filename_hashed(&self, id: AssetId) -> bool {
+        self.entry(id).hashed_filename
+    }
+
+    pub(crate) fn is_embedded(&self, id: AssetId) -> bool {
+        self.entry(id).embedded
+    }
+
+    pub(crate) fn is_referenced(&self, id: AssetId) -> bool {
+        self.entry(id).referenced
+    }
+
+    pub(crate) fn serve_settings(&self, id: AssetId) -> &ServeSettings {
+        &self.entry(id).serve
+    }
+
+    #[cfg(feature = "compress")]
+    pub(crate) fn variants(&self, id: AssetId) -> &[(Encoding, Bytes)] {
+        &self.variants[self.entry(id).variants.range()]
+    }
+
+    #[cfg(feature = "compress")]
+    pub(crate) fn deduplicated_bytes(&self) -> usize {
+        self.deduplicated_bytes
+    }
+
+    #[cfg(feature = "hash")]
+    pub(crate) fn cache_key(&self, id: AssetId) -> &[u8; 16] {
+        &self.entry(id).cache_key
+    }
+
+    #[cfg(feature = "hash")]
+    pub(crate) fn csp_hashes(&self, id: AssetId) -> CspHashes {
+        self.entry(id).csp_hashes.as_deref().cloned().unwrap_or_default()
+    }
+
+    fn entry(&self, id: AssetId) -> &Entry {
+        &self.entries[id as usize]
+    }
+}
+
+/// Returns an error if `count` does not fit into the `u32` offsets and IDs
+/// used by the table.
+fn check_limit(what: &str, count: usize) -> Result<(), BuildError> {
+    if u32::try_from(count).is_err() {
+        return Err(BuildError::LimitExceeded(
+            format!("{count} {what}, but at most {} are supported", u32::MAX),
+        ));
+    }
+    Ok(())
+}
+
+impl fmt::Debug for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.ids().map(|id| self.path(id))).finish()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(paths: &[&str]) -> Table {
+        Table::new(paths.iter().map(|&path| BuiltAsset {
+            path: path.to_owned(),
+            unhashed_path: None,
+            content: Bytes::from(path.to_uppercase()),
+            file: None,
+            hashed_filename: false,
+            embedded: false,
+            serve: Default::default(),
+            referenced: false,
+            #[cfg(feature = "compress")]
+            variants: vec![],
+        }).collect()).unwrap()
+    }
+
+    #[test]
+    fn lookup() {
+        let table = table(&["b.css", "a.js", "fonts/x.woff2", "index.html"]);
+        assert_eq!(table.len(), 4);
+        let paths = table.ids().map(|id| table.path(id)).collect::<Vec<_>>();
+        assert_eq!(paths, ["a.js", "b.css", "fonts/x.woff2", "index.html"]);
+
+        for path in paths {
+            let id = table.get(path).unwrap();
+            assert_eq!(table.path(id), path);
+            assert_eq!(table.content(id), path.to_uppercase().as_bytes());
+        }
+        assert_eq!(table.get("c.css"), None);
+        assert_eq!(table.get(""), None);
+    }
+
+    #[test]
+    fn unhashed_paths() {
+        let asset = |path: &str, unhashed_path: Option<&str>| BuiltAsset {
+            path: path.to_owned(),
+            unhashed_path: unhashed_path.map(ToOwned::to_owned),
+            content: Bytes::new(),
+            file: None,
+            hashed_filename: unhashed_path.is_some(),
+            embedded: false,
+            serve: Default::default(),
+            referenced: false,
+            #[cfg(feature = "compress")]
+            variants: vec![],
+        };
+        let table = Table::new(vec![asset("app.1234.js", Some("app.js")), asset("b.css", None)]).unwrap();
+        let app = table.get("app.1234.js").unwrap();
+        assert_eq!((table.path(app), table.unhashed_path(app)), ("app.1234.js", "app.js"));
+        let b = table.get("b.css").unwrap();
+        assert_eq!(table.unhashed_path(b), "b.css");
+        assert_eq!(table.get("app.js"), None);
+    }
+
+    #[test]
+    fn limits() {
+        assert!(check_limit("assets", 0).is_ok());
+        assert!(check_limit("assets", u32::MAX as usize).is_ok());
+        if let Some(too_many) = (u32::MAX as usize).checked_add(1) {
+            let err = check_limit("assets", too_many).unwrap_err();
+            assert!(matches!(err, BuildError::LimitExceeded(_)));
+            assert!(err.to_string().contains("4294967296 assets"));
+        }
+    }
+
+    #[test]
+    fn hash_collisions() {
+        // Force everything into the overflow list by faking collisions.
+        let mut table = table(&["a", "b", "c"]);
+        table.overflow = table.ids().collect();
+        table.index.values_mut().for_each(|id| *id = 0);
+        assert_eq!(table.get("a"), Some(0));
+        assert_eq!(table.get("b"), Some(1));
+        assert_eq!(table.get("c"), Some(2));
+        assert_eq!(table.get("d"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "compress")]
+    fn dedup_variants() {
+        let asset = |path: &str, br: &'static [u8]| BuiltAsset {
+            path: path.to_owned(),
+            unhashed_path: None,
+            content: Bytes::new(),
+            file: None,
+            hashed_filename: false,
+            embedded: false,
+            serve: Default::default(),
+            referenced: false,
+            variants: vec![(Encoding::Brotli, Bytes::from(br.to_vec()))],
+        };
+        let table = Table::new(vec![
+            asset("a.js", b"vendor"),
+            asset("b.js", b"vendor"),
+            asset("c.js", b"other"),
+        ]).unwrap();
+
+        assert_eq!(table.deduplicated_bytes(), 6);
+        let a = &table.variants(table.get("a.js").unwrap())[0].1;
+        let b = &table.variants(table.get("b.js").unwrap())[0].1;
+        assert_eq!(a.as_ptr(), b.as_ptr());
+    }
+}
@@ -0,0 +1,698 @@
+//! A small template engine for assets.
+//!
+//! Templates are arbitrary files containing *fragments* of the form
+//! `{{: kind:argument :}}`. These kinds exist:
+//!
+//! - `path`: replaced by the *hashed HTTP path* of the asset with the given
+//!   *unhashed HTTP path*, e.g. `{{: path:static/main.js :}}`.
+//! - `include`: replaced by the final content of the asset with the given
+//!   *unhashed HTTP path*, e.g. `{{: include:partials/header.html :}}`. The
+//!   included asset is built first and is usually
+//!   [internal][crate::EntryBuilder::internal]. Assets including each other
+//!   form a cycle, which is an error. The content has to be valid UTF-8.
+//! - `sri`: replaced by the [Subresource Integrity][crate::util::sri_hash]
+//!   hash (SHA-384) of the final content of the asset with the given
+//!   *unhashed HTTP path*, e.g. `<script src="/{{: path:app.js :}}"
+//!   integrity="{{: sri:app.js :}}">`. Like with `include`, the asset is
+//!   built first. Requires the crate feature `hash`.
+//! - `var`: replaced by the value of a variable (see
+//!   [`Builder::add_var`][crate::Builder::add_var]), e.g. `{{: var:version :}}`.
+//!   Using an undefined variable is an error.
+//!
+//! Parts of a template can be included conditionally, depending on a
+//! variable (see [`Builder::add_var`][crate::Builder::add_var]):
+//!
+//! ```text
+//! {{: if:debug :}}<script src="/{{: path:debug.js :}}"></script>{{: endif :}}
+//! {{: if:!debug :}}...{{: else :}}...{{: endif :}}
+//! ```
+//!
+//! A variable is true unless its value is empty, `false` or `0`. Using an
+//! undefined variable is an error. Conditions can be nested, and fragments
+//! in sections that are not included are not resolved.
+//!
+//! A section can be repeated for all assets whose *unhashed HTTP path*
+//! matches a glob pattern (in lexicographic order), e.g. to list all files
+//! added by a glob entry. Like in `embed!`, `*` does not match `/`, but `**`
+//! does. Inside the loop, `path` and `include` fragments with the loop
+//! variable as argument refer to the current asset:
+//!
+//! ```text
+//! {{: for sound in "sounds/*.mp3" :}}<source src="/{{: path:sound :}}">{{: endfor :}}
+//! ```
+//!
+//! To include a literal `{{:` in the output, escape it with a backslash:
+//! `\{{:`. The backslash is removed. Braces that are not followed by `:` are
+//! always emitted as is (including a backslash before them) and don't need
+//! escaping.
+//!
+//! Assets are rendered via [`EntryBuilder::with_template`][crate::EntryBuilder::with_template].
+//! To use the same engine for other things (e.g. emails or config files),
+//! use [`render`], which does not support `for` loops.
+//!
+//! ```
+//! use reinda::template::{self, Fragment};
+//!
+//! let out = template::render(b"<script src=\"/{{: path:app.js :}}\">", |fragment| {
+//!     match fragment {
+//!         Fragment::Path("app.js") => Some("app.1234.js".to_owned()),
+//!         _ => None,
+//!     }
+//! }).unwrap();
+//! assert_eq!(out, b"<script src=\"/app.1234.js\">");
+//! ```
+
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+
+/// Renders the template `template`, calling `resolver` for each fragment to
+/// get its replacement (or the value of a variable). If `resolver` returns
+/// `None`, rendering fails.
+pub fn render(
+    template: &[u8],
+    resolver: impl FnMut(Fragment<'_>) -> Option<String>,
+) -> Result<Vec<u8>, Error> {
+    Template::parse(template)
+        .and_then(|t| t.render(None, resolver))
+        .map_err(Error)
+}
+
+/// A fragment in a template that needs to be replaced, passed to the resolver
+/// of [`render`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Fragment<'a> {
+    /// `{{: path:<path> :}}`, for assets: the *unhashed HTTP path*.
+    Path(&'a str),
+
+    /// `{{: include:<path> :}}`, for assets: the *unhashed HTTP path* of the
+    /// asset whose content is inserted.
+    Include(&'a str),
+
+    /// `{{: sri:<path> :}}`, for assets: the *unhashed HTTP path* of the
+    /// asset whose Subresource Integrity hash is inserted.
+    Sri(&'a str),
+
+    /// A variable inserted with `{{: var:<name> :}}` or used in a condition,
+    /// e.g. `debug` in `{{: if:debug :}}`. The resolver returns its value.
+    Var(&'a str),
+}
+
+/// Error returned by [`render`], e.g. for a syntax error or a fragment that
+/// the resolver could not resolve.
+#[derive(Debug, Clone)]
+pub struct Error(pub(crate) String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for Error {}
+
+
+const FRAGMENT_START: &[u8] = b"{{:";
+const FRAGMENT_END: &[u8] = b":}}";
+const OPEN_BRACES: &[u8] = b"{{";
+const ESCAPE: u8 = b'\\';
+
+/// Settings for entries with templating enabled.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TemplateSettings {
+    /// Whether to treat the asset as template even if it looks binary.
+    pub(crate) force: bool,
+
+    /// The variables passed to `Builder::add_var`, merged with the ones of
+    /// the entry (see `EntryBuilder::add_var`) in `Builder::build`.
+    pub(crate) vars: Arc<BTreeMap<String, String>>,
+}
+
+impl TemplateSettings {
+    /// Returns whether `content` should be rendered as template.
+    pub(crate) fn applies_to(&self, content: &[u8]) -> bool {
+        self.force || !is_binary(content)
+    }
+
+    /// Returns the value of the given variable.
+    pub(crate) fn var(&self, name: &str) -> Option<&str> {
+        self.vars.get(name).map(|v| &**v)
+    }
+}
+
+/// A parsed template.
+#[derive(Debug)]
+pub(crate) struct Template<'a> {
+    parts: Vec<Part<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Part<'a> {
+    Raw(&'a [u8]),
+    Path(&'a str),
+    Include(&'a str),
+    Sri(&'a str),
+    Var(&'a str),
+    If {
+        var: &'a str,
+        negated: bool,
+        then: Vec<Part<'a>>,
+        otherwise: Vec<Part<'a>>,
+    },
+    For {
+        var: &'a str,
+        pattern: glob::Pattern,
+        body: Vec<Part<'a>>,
+    },
+}
+
+/// A parsed fragment.
+enum Token<'a> {
+    Part(Part<'a>),
+    Open(Block<'a>),
+    Else,
+    EndIf,
+    EndFor,
+}
+
+/// A block whose end was not parsed yet.
+enum Block<'a> {
+    If {
+        var: &'a str,
+        negated: bool,
+
+        /// The parts of the `if` branch, once `else` was parsed.
+        then: Option<Vec<Part<'a>>>,
+    },
+    For {
+        var: &'a str,
+        pattern: glob::Pattern,
+    },
+}
+
+/// Options for matching `for` patterns against *unhashed HTTP paths*: like
+/// in `embed!`, `*` does not match `/`.
+const MATCH_OPTIONS: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// The loop variables bound while walking a template, innermost last.
+type Scope<'s> = Vec<(&'s str, &'s str)>;
+
+/// Returns the path bound to the loop variable `name`, or `name` itself if
+/// it's not a loop variable.
+fn bound<'s>(scope: &Scope<'s>, name: &'s str) -> &'s str {
+    scope.iter().rev().find(|(var, _)| *var == name).map_or(name, |(_, path)| path)
+}
+
+impl<'a> Template<'a> {
+    pub(crate) fn parse(src: &'a [u8]) -> Result<Self, String> {
+        fn push_raw<'a>(parts: &mut Vec<Part<'a>>, raw: &'a [u8]) {
+            if !raw.is_empty() {
+                parts.push(Part::Raw(raw));
+            }
+        }
+
+        let mut parts = Vec::new();
+
+        // Open blocks with their byte offset and the parts before them.
+        let mut open_blocks = Vec::<(Block<'a>, usize, Vec<Part<'a>>)>::new();
+        let mut rest = src;
+        while let Some(start) = find(rest, OPEN_BRACES) {
+            // Braces not starting a fragment are just normal text. Only skip
+            // one brace, as in `{{{:`, the fragment starts at the second one.
+            if !rest[start..].starts_with(FRAGMENT_START) {
+                let end = start + 1;
+                push_raw(&mut parts, &rest[..end]);
+                rest = &rest[end..];
+                continue;
+            }
+
+            // Escaped fragment start: emit it literally and drop the backslash.
+            if start > 0 && rest[start - 1] == ESCAPE {
+                push_raw(&mut parts, &rest[..start - 1]);
+                push_raw(&mut parts, FRAGMENT_START);
+                rest = &rest[start + FRAGMENT_START.len()..];
+                continue;
+            }
+
+            push_raw(&mut parts, &rest[..start]);
+            let fragment_offset = offset(src, rest) + start;
+            let after_start = &rest[start + FRAGMENT_START.len()..];
+            let end = find(after_start, FRAGMENT_END)
+                .ok_or_else(|| format!("unclosed fragment at byte {fragment_offset}"))?;
+            let inner = std::str::from_utf8(&after_start[..end])
+                .map_err(|_| "fragment contains invalid UTF-8".to_owned())?;
+            match parse_fragment(inner)? {
+                Token::Part(part) => parts.push(part),
+                Token::Open(block) => {
+                    open_blocks.push((block, fragment_offset, std::mem::take(&mut parts)));
+                }
+                Token::Else => match open_blocks.last_mut() {
+                    Some((Block::If { then: then @ None, .. }, ..)) => {
+                        *then = Some(std::mem::take(&mut parts));
+                    }
+                    Some((Block::If { .. }, ..)) => {
+                        return Err(format!("second 'else' for the same 'if' at byte {fragment_offset}"));
+                    }
+                    _ => return Err(format!("'else' without 'if' at byte {fragment_offset}")),
+                },
+                Token::EndIf => match open_blocks.pop() {
+                    Some((Block::If { var, negated, then }, _, parent)) => {
+                        let branch = std::mem::replace(&mut parts, parent);
+                        let (then, otherwise) = match then {
+                            Some(then) => (then, branch),
+                            None => (branch, vec![]),
+                        };
+                        parts.push(Part::If { var, negated, then, otherwise });
+                    }
+                    _ => return Err(format!("'endif' without 'if' at byte {fragment_offset}")),
+                },
+                Token::EndFor => match open_blocks.pop() {
+                    Some((Block::For { var, pattern }, _, parent)) => {
+                        let body = std::mem::replace(&mut parts, parent);
+                        parts.push(Part::For { var, pattern, body });
+                    }
+                    _ => return Err(format!("'endfor' without 'for' at byte {fragment_offset}")),
+                },
+            }
+            rest = &after_start[end + FRAGMENT_END.len()..];
+        }
+        if let Some((block, offset, _)) = open_blocks.first() {
+            let (kind, end) = match block {
+                Block::If { .. } => ("if", "endif"),
+                Block::For { .. } => ("for", "endfor"),
+            };
+            return Err(format!("'{kind}' at byte {offset} is missing its '{end}'"));
+        }
+        push_raw(&mut parts, rest);
+
+        Ok(Self { parts })
+    }
+
+    /// Returns all *unhashed HTTP paths* this template refers to (via `path`
+    /// or `include`), in the sections included with the variables from
+    /// `var`. Sections depending on undefined variables are included, as
+    /// rendering fails anyway. `for` loops iterate over `known_paths`, which
+    /// should be sorted.
+    pub(crate) fn dependencies<'s>(
+        &self,
+        var: impl Fn(&str) -> Option<&'s str>,
+        known_paths: &[&str],
+    ) -> Vec<String> {
+        let mut out = Vec::new();
+        collect_paths(&self.parts, &var, known_paths, true, &mut vec![], &mut out);
+        out
+    }
+
+    /// Like `Self::dependencies`, but only returns paths of assets whose
+    /// content is needed, i.e. of `include` and `sri` fragments.
+    pub(crate) fn includes<'s>(
+        &self,
+        var: impl Fn(&str) -> Option<&'s str>,
+        known_paths: &[&str],
+    ) -> Vec<String> {
+        let mut out = Vec::new();
+        collect_paths(&self.parts, &var, known_paths, false, &mut vec![], &mut out);
+        out
+    }
+
+    /// Renders this template, using `resolve` to map *unhashed HTTP paths*
+    /// to *hashed* ones, to get the content of included assets and to get
+    /// the values of variables. `for` loops iterate over `known_paths`
+    /// (which should be sorted); if that's `None`, they are an error.
+    pub(crate) fn render(
+        &self,
+        known_paths: Option<&[&str]>,
+        mut resolve: impl FnMut(Fragment<'_>) -> Option<String>,
+    ) -> Result<Vec<u8>, String> {
+        let mut out = Vec::new();
+        render_parts(&self.parts, known_paths, &mut resolve, &mut vec![], &mut out)?;
+        Ok(out)
+    }
+}
+
+/// Collects the paths referred to by `parts` into `out`, see
+/// `Template::dependencies`. With `all` set to `false`, only includes are
+/// collected.
+fn collect_paths<'s, 'v>(
+    parts: &'s [Part<'s>],
+    var: &dyn Fn(&str) -> Option<&'v str>,
+    known_paths: &[&'s str],
+    all: bool,
+    scope: &mut Scope<'s>,
+    out: &mut Vec<String>,
+) {
+    for part in parts {
+        match part {
+            Part::Raw(_) | Part::Var(_) => {}
+            Part::Path(path) => if all {
+                out.push(bound(scope, path).to_owned());
+            },
+            Part::Include(path) | Part::Sri(path) => out.push(bound(scope, path).to_owned()),
+            Part::If { var: name, negated, then, otherwise } => match var(name) {
+                Some(value) if is_true(value) != *negated => {
+                    collect_paths(then, var, known_paths, all, scope, out);
+                }
+                Some(_) => collect_paths(otherwise, var, known_paths, all, scope, out),
+                None => {
+                    collect_paths(then, var, known_paths, all, scope, out);
+                    collect_paths(otherwise, var, known_paths, all, scope, out);
+                }
+            },
+            Part::For { var: name, pattern, body } => {
+                for path in known_paths.iter().filter(|p| pattern.matches_with(p, MATCH_OPTIONS)) {
+                    scope.push((name, path));
+                    collect_paths(body, var, known_paths, all, scope, out);
+                    scope.pop();
+                }
+            }
+        }
+    }
+}
+
+fn render_parts<'s>(
+    parts: &'s [Part<'s>],
+    known_paths: Option<&[&'s str]>,
+    resolve: &mut dyn FnMut(Fragment<'_>) -> Option<String>,
+    scope: &mut Scope<'s>,
+    out: &mut Vec<u8>,
+) -> Result<(), String> {
+    for part in parts {
+        match part {
+            Part::Raw(raw) => out.extend_from_slice(raw),
+            Part::Path(path) => {
+                let path = bound(scope, path);
+                let resolved = resolve(Fragment::Path(path))
+                    .ok_or_else(|| format!("cannot resolve '{{{{: path:{path} :}}}}'"))?;
+                out.extend_from_slice(resolved.as_bytes());
+            }
+            Part::Include(path) => {
+                let path = bound(scope, path);
+                let content = resolve(Fragment::Include(path))
+                    .ok_or_else(|| format!("cannot resolve '{{{{: include:{path} :}}}}'"))?;
+                out.extend_from_slice(content.as_bytes());
+            }
+            Part::Sri(path) => {
+                let path = bound(scope, path);
+                let hash = resolve(Fragment::Sri(path))
+                    .ok_or_else(|| format!("cannot resolve '{{{{: sri:{path} :}}}}'"))?;
+                out.extend_from_slice(hash.as_bytes());
+            }
+            Part::Var(var) => {
+                let value = resolve(Fragment::Var(var))
+                    .ok_or_else(|| format!("undefined variable '{var}'"))?;
+                out.extend_from_slice(value.as_bytes());
+            }
+            Part::If { var, negated, then, otherwise } => {
+                let value = resolve(Fragment::Var(var))
+                    .ok_or_else(|| format!("undefined variable '{var}' in 'if'"))?;
+                let branch = if is_true(&value) != *negated { then } else { otherwise };
+                render_parts(branch, known_paths, resolve, scope, out)?;
+            }
+            Part::For { var, pattern, body } => {
+                let paths = known_paths.ok_or("'for' loops are only supported in assets")?;
+                for path in paths.iter().filter(|p| pattern.matches_with(p, MATCH_OPTIONS)) {
+                    scope.push((var, path));
+                    render_parts(body, known_paths, resolve, scope, out)?;
+                    scope.pop();
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns whether the value of a variable counts as true in conditions.
+fn is_true(value: &str) -> bool {
+    !matches!(value.trim(), "" | "false" | "0")
+}
+
+fn parse_fragment(inner: &str) -> Result<Token<'_>, String> {
+    if let Some(loop_header) = inner.trim_start().strip_prefix("for ") {
+        return parse_for(loop_header);
+    }
+
+    let Some((kind, arg)) = inner.split_once(':') else {
+        return match inner.trim() {
+            "else" => Ok(Token::Else),
+            "endif" => Ok(Token::EndIf),
+            "endfor" => Ok(Token::EndFor),
+            _ => Err(format!("fragment '{inner}' is missing a kind (e.g. 'path:')")),
+        };
+    };
+    match kind.trim() {
+        "path" => Ok(Token::Part(Part::Path(arg.trim()))),
+        "include" => Ok(Token::Part(Part::Include(arg.trim()))),
+        "sri" => Ok(Token::Part(Part::Sri(arg.trim()))),
+        "var" => match arg.trim() {
+            "" => Err("'var' is missing a variable name".into()),
+            var => Ok(Token::Part(Part::Var(var))),
+        },
+        "if" => {
+            let arg = arg.trim();
+            let (var, negated) = match arg.strip_prefix('!') {
+                Some(var) => (var.trim(), true),
+                None => (arg, false),
+            };
+            if var.is_empty() {
+                return Err("'if' is missing a variable name".into());
+            }
+            Ok(Token::Open(Block::If { var, negated, then: None }))
+        }
+        other => Err(format!("unknown fragment kind '{other}'")),
+    }
+}
+
+/// Parses `<var> in "<pattern>"`, the part after `for `.
+fn parse_for(header: &str) -> Result<Token<'_>, String> {
+    let invalid = || format!("invalid 'for {header}', expected 'for <name> in \"<pattern>\"'");
+    let (var, pattern) = header.split_once(" in ").ok_or_else(invalid)?;
+    let var = var.trim();
+    let pattern = pattern.trim()
+        .strip_prefix('"')
+        .and_then(|p| p.strip_suffix('"'))
+        .ok_or_else(invalid)?;
+    if var.is_empty() || var.contains(char::is_whitespace) {
+        return Err(invalid());
+    }
+    let pattern = glob::Pattern::new(pattern)
+        .map_err(|e| format!("invalid pattern '{pattern}' in 'for': {e}"))?;
+    Ok(Token::Open(Block::For { var, pattern }))
+}
+
+/// Heuristically determines whether `data` is binary (i.e. not text), by
+/// checking for NUL bytes and a very high byte entropy (as found in
+/// compressed data) in the first few KiB.
+pub(crate) fn is_binary(data: &[u8]) -> bool {
+    const SAMPLE_LEN: usize = 8 * 1024;
+    const MIN_ENTROPY_SAMPLE: usize = 512;
+
+    let sample = &data[..data.len().min(SAMPLE_LEN)];
+    if sample.contains(&0) {
+        return true;
+    }
+    if sample.len() < MIN_ENTROPY_SAMPLE {
+        return false;
+    }
+
+    let mut counts = [0usize; 256];
+    for &b in sample {
+        counts[b as usize] += 1;
+    }
+    let len = sample.len() as f64;
+    let entropy: f64 = counts.iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / len;
+            -p * p.log2()
+        })
+        .sum();
+
+    // Natural language and code are usually way below 6 bits per byte.
+    entropy > 7.0
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn offset(whole: &[u8], part: &[u8]) -> usize {
+    part.as_ptr() as usize - whole.as_ptr() as usize
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KNOWN_PATHS: [&str; 5] = [
+        "foo.js",
+        "header.html",
+        "sounds/a.mp3",
+        "sounds/b.mp3",
+        "sounds/sub/c.mp3",
+    ];
+
+    fn render(src: &str) -> Result<String, String> {
+        let template = Template::parse(src.as_bytes())?;
+        let out = template.render(Some(&KNOWN_PATHS), |fragment| match fragment {
+            Fragment::Path("foo.js") => Some("foo.abc.js".into()),
+            Fragment::Path(p) if p.starts_with("sounds/") => Some(p.replace(".mp3", ".123.mp3")),
+            Fragment::Include("header.html") => Some("<h1>Hi</h1>".into()),
+            Fragment::Sri("foo.js") => Some("sha384-abc".into()),
+            Fragment::Var("yes") => Some("true".into()),
+            Fragment::Var("no") => Some("0".into()),
+            _ => None,
+        })?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn render_paths() {
+        assert_eq!(render("").unwrap(), "");
+        assert_eq!(render("no fragments {{ here }}").unwrap(), "no fragments {{ here }}");
+        assert_eq!(render("{{: path:foo.js :}}").unwrap(), "foo.abc.js");
+        assert_eq!(
+            render("<script src=\"/{{:path: foo.js:}}\"></script>").unwrap(),
+            "<script src=\"/foo.abc.js\"></script>",
+        );
+
+        assert!(render("{{: path:bar.js :}}").is_err());
+        assert!(render("{{: path:foo.js").is_err());
+        assert!(render("{{: foo.js :}}").is_err());
+        assert!(render("{{: banana:foo.js :}}").is_err());
+    }
+
+    #[test]
+    fn render_includes() {
+        assert_eq!(render("{{: include:header.html :}}<p>").unwrap(), "<h1>Hi</h1><p>");
+        assert!(render("{{: include:footer.html :}}").is_err());
+    }
+
+    #[test]
+    fn render_sri() {
+        assert_eq!(render("integrity=\"{{: sri: foo.js :}}\"").unwrap(), "integrity=\"sha384-abc\"");
+        assert!(render("{{: sri:missing.js :}}").is_err());
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(render(r"\{{: path:foo.js :}}").unwrap(), "{{: path:foo.js :}}");
+        assert_eq!(render(r"a \{{ b").unwrap(), r"a \{{ b");
+        assert_eq!(render(r"\\{{").unwrap(), r"\\{{");
+        assert_eq!(render(r"\{{{: path:foo.js :}}").unwrap(), r"\{foo.abc.js");
+        assert_eq!(render(r"{{{: path:foo.js :}}}").unwrap(), "{foo.abc.js}");
+        assert_eq!(render(r"\x {{ \}} }}").unwrap(), r"\x {{ \}} }}");
+        assert_eq!(
+            render(r"\{{: path:foo.js :}} = {{: path:foo.js :}}").unwrap(),
+            "{{: path:foo.js :}} = foo.abc.js",
+        );
+    }
+
+    #[test]
+    fn conditionals() {
+        assert_eq!(render("a{{: if:yes :}}b{{: endif :}}c").unwrap(), "abc");
+        assert_eq!(render("a{{: if:no :}}b{{: endif :}}c").unwrap(), "ac");
+        assert_eq!(render("{{: if:!no :}}x{{: else :}}y{{: endif :}}").unwrap(), "x");
+        assert_eq!(render("{{: if: yes :}}x{{:else:}}y{{: endif :}}").unwrap(), "x");
+        assert_eq!(
+            render("{{: if:yes :}}1{{: if:no :}}2{{: else :}}3{{: endif :}}4{{: endif :}}").unwrap(),
+            "134",
+        );
+
+        // Fragments in sections not included are not resolved.
+        assert_eq!(render("{{: if:no :}}{{: path:missing.js :}}{{: endif :}}").unwrap(), "");
+        assert!(render("{{: if:yes :}}{{: path:missing.js :}}{{: endif :}}").is_err());
+
+        assert!(render("{{: if:undefined :}}x{{: endif :}}").is_err());
+        assert!(render("{{: if:yes :}}x").is_err());
+        assert!(render("x{{: endif :}}").is_err());
+        assert!(render("{{: else :}}").is_err());
+        assert!(render("{{: if:yes :}}{{: else :}}{{: else :}}{{: endif :}}").is_err());
+        assert!(render("{{: if: :}}{{: endif :}}").is_err());
+
+        assert_eq!(render("v{{: var:yes :}}/{{: var: no :}}").unwrap(), "vtrue/0");
+        assert!(render("{{: var:undefined :}}").is_err());
+        assert!(render("{{: var: :}}").is_err());
+
+        let t = Template::parse(b"{{: if:a :}}{{: path:x :}}{{: else :}}{{: path:y :}}{{: endif :}}").unwrap();
+        assert_eq!(t.dependencies(|_| Some("1"), &[]), ["x"]);
+        assert_eq!(t.dependencies(|_| Some("false"), &[]), ["y"]);
+        assert_eq!(t.dependencies(|_| None, &[]), ["x", "y"]);
+    }
+
+    #[test]
+    fn loops() {
+        assert_eq!(
+            render(r#"{{: for s in "sounds/*.mp3" :}}<{{: path:s :}}>{{: endfor :}}"#).unwrap(),
+            "<sounds/a.123.mp3><sounds/b.123.mp3>",
+        );
+        assert_eq!(
+            render(r#"{{: for s in "sounds/**/*.mp3" :}}{{: path:s :}} {{: endfor :}}"#).unwrap(),
+            "sounds/a.123.mp3 sounds/b.123.mp3 sounds/sub/c.123.mp3 ",
+        );
+        assert_eq!(render(r#"{{:for x in "*.png":}}x{{:endfor:}}"#).unwrap(), "");
+
+        // Nesting, shadowing and conditions.
+        assert_eq!(
+            render(concat!(
+                r#"{{: for s in "sounds/a.mp3" :}}{{: for s in "foo.js" :}}{{: path:s :}}"#,
+                r#"{{: endfor :}} {{: path:s :}}{{: if:yes :}}!{{: endif :}}{{: endfor :}}"#,
+            )).unwrap(),
+            "foo.abc.js sounds/a.123.mp3!",
+        );
+
+        assert!(render(r#"{{: for s in "*.js" :}}"#).is_err());
+        assert!(render(r#"{{: endfor :}}"#).is_err());
+        assert!(render(r#"{{: for s in "*.js" :}}{{: endif :}}"#).is_err());
+        assert!(render(r#"{{: if:yes :}}{{: endfor :}}"#).is_err());
+        assert!(render(r#"{{: for s in "*.js" :}}{{: else :}}{{: endfor :}}"#).is_err());
+        assert!(render(r#"{{: for s in *.js :}}{{: endfor :}}"#).is_err());
+        assert!(render(r#"{{: for in "*.js" :}}{{: endfor :}}"#).is_err());
+        assert!(render(r#"{{: for s in "[" :}}{{: endfor :}}"#).is_err());
+
+        let t = Template::parse(br#"{{: for s in "sounds/*" :}}{{: include:s :}}{{: endfor :}}"#).unwrap();
+        assert_eq!(t.includes(|_| None, &KNOWN_PATHS), ["sounds/a.mp3", "sounds/b.mp3"]);
+
+        // Not supported without known paths.
+        assert!(super::render(br#"{{: for s in "*" :}}{{: endfor :}}"#, |_| None).is_err());
+    }
+
+    #[test]
+    fn public_render() {
+        let out = super::render(b"a {{: path:x :}} {{: path:y :}}", |fragment| match fragment {
+            Fragment::Path(p) => Some(p.to_uppercase()),
+            _ => None,
+        });
+        assert_eq!(out.unwrap(), b"a X Y");
+
+        let err = super::render(b"{{: path:x :}}", |_| None).unwrap_err();
+        assert_eq!(err.to_string(), "cannot resolve '{{: path:x :}}'");
+    }
+
+    #[test]
+    fn dependencies() {
+        let t = Template::parse(b"a {{: path:x.css :}} b {{: path: y/z.js :}}").unwrap();
+        assert_eq!(t.dependencies(|_| None, &[]), ["x.css", "y/z.js"]);
+
+        let t = Template::parse(b"{{: include:a.html :}} {{: path:x.css :}}").unwrap();
+        assert_eq!(t.dependencies(|_| None, &[]), ["a.html", "x.css"]);
+        assert_eq!(t.includes(|_| None, &[]), ["a.html"]);
+
+        let t = Template::parse(b"{{: path:x.js :}} {{: sri:x.js :}}").unwrap();
+        assert_eq!(t.dependencies(|_| None, &[]), ["x.js", "x.js"]);
+        assert_eq!(t.includes(|_| None, &[]), ["x.js"]);
+    }
+
+    #[test]
+    fn binary_detection() {
+        assert!(!is_binary(b""));
+        assert!(!is_binary(include_bytes!("../examples/assets/style.css")));
+        assert!(!is_binary(include_bytes!("../examples/assets/bundle.8f29ad31.js")));
+        assert!(is_binary(b"foo\0bar {{: path:x :}}"));
+        assert!(is_binary(include_bytes!("../examples/assets/fonts/latin-400.woff2")));
+    }
+}
@@ -0,0 +1,216 @@
+//! Utilities to test built [`Assets`] over real HTTP, e.g. in CI.
+//!
+//! [`TestServer`] serves assets via [`Assets::serve`] on a local port and
+//! [`TestServer::check_references`] crawls pages from there, making sure that
+//! all referenced assets (in particular ones with hashed filenames) can
+//! actually be fetched.
+//!
+//! ```ignore
+//! #[tokio::test]
+//! async fn no_broken_references() {
+//!     let assets = my_app::assets().await.unwrap();
+//!     let server = reinda::testing::TestServer::start(assets).await.unwrap();
+//!     let broken = server.check_references(&["index.html"]).await.unwrap();
+//!     assert!(broken.is_empty(), "broken references: {broken:#?}");
+//! }
+//! ```
+//!
+//! Module is only available if the crate feature `testing` is enabled.
+
+use std::{collections::VecDeque, convert::Infallible, io, net::SocketAddr};
+
+use ahash::HashMap;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Empty, Full};
+use hyper::{body::Incoming, server::conn::http1, service::service_fn};
+use hyper_util::rt::TokioIo;
+use tokio::{net::{TcpListener, TcpStream}, task::JoinHandle};
+
+use crate::{http::{Request, Response}, links, Assets};
+
+
+/// HTTP server serving [`Assets`] on a random local port. The server is
+/// stopped when this is dropped.
+#[derive(Debug)]
+pub struct TestServer {
+    addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+/// A reference found by [`TestServer::check_references`] that could not be
+/// fetched successfully.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BrokenReference {
+    /// Path of the page containing the reference, or `None` if this is one
+    /// of the start pages.
+    pub page: Option<String>,
+
+    /// The reference, exactly as written in the page.
+    pub reference: String,
+
+    /// Status code of the response.
+    pub status: u16,
+}
+
+impl TestServer {
+    /// Starts serving `assets` on `127.0.0.1` with a random port.
+    pub async fn start(assets: Assets) -> io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let task = tokio::spawn(async move {
+            loop {
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        log::error!(target: "reinda", "test server failed to accept connection: {e}");
+                        return;
+                    }
+                };
+
+                let assets = assets.clone();
+                tokio::spawn(async move {
+                    let service = service_fn(move |req| {
+                        let assets = assets.clone();
+                        async move { Ok::<_, Infallible>(serve(&assets, req).await) }
+                    });
+                    let conn = http1::Builder::new().serve_connection(TokioIo::new(stream), service);
+                    if let Err(e) = conn.await {
+                        log::warn!(target: "reinda", "test server connection failed: {e}");
+                    }
+                });
+            }
+        });
+
+        Ok(Self { addr, task })
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the full URL of the given path, e.g. to pass it to an
+    /// external tool.
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}/{}", self.addr, path.trim_start_matches('/'))
+    }
+
+    /// Sends a `GET` request for `path` to the server and returns the
+    /// response.
+    pub async fn get(&self, path: &str) -> io::Result<Response> {
+        let stream = TcpStream::connect(self.addr).await?;
+        let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .map_err(io::Error::other)?;
+        tokio::spawn(conn);
+
+        let req = hyper::Request::get(format!("/{}", encode_path(path.trim_start_matches('/'))))
+            .header("host", self.addr.to_string())
+            .body(Empty::<Bytes>::new())
+            .map_err(io::Error::other)?;
+        let (parts, body) = sender.send_request(req).await.map_err(io::Error::other)?.into_parts();
+        let body = body.collect().await.map_err(io::Error::other)?.to_bytes();
+
+        let mut out = Response::new(parts.status.as_u16(), body);
+        for (name, value) in &parts.headers {
+            out = out.with_header(name.as_str().to_owned(), String::from_utf8_lossy(value.as_bytes()));
+        }
+        Ok(out)
+    }
+
+    /// Fetches all `start` pages and, recursively, all assets referenced by
+    /// HTML and CSS files (via `src` and `href` attributes and `url(...)`).
+    /// Returns all references that did not result in a `200 OK`. External
+    /// URLs and references to directories (like `/`) are not followed.
+    ///
+    /// As assets are fetched over HTTP, all fixups, templates and the
+    /// request rewriter are applied, exactly like in production. This
+    /// catches broken hashed links, e.g. due to a missing
+    /// [`EntryBuilder::with_path_fixup`][crate::EntryBuilder::with_path_fixup].
+    pub async fn check_references(&self, start: &[&str]) -> io::Result<Vec<BrokenReference>> {
+        let mut broken = Vec::new();
+        let mut fetched = HashMap::<String, u16>::default();
+        let mut pending = start.iter()
+            .map(|page| (None, (*page).to_owned()))
+            .collect::<VecDeque<(Option<String>, String)>>();
+
+        while let Some((page, reference)) = pending.pop_front() {
+            let path = match &page {
+                None => reference.trim_start_matches('/').to_owned(),
+                Some(page) => match links::resolve(page, &reference) {
+                    Some(path) => path,
+                    None => continue,
+                },
+            };
+
+            let status = match fetched.get(&path) {
+                Some(&status) => status,
+                None => {
+                    let response = self.get(&path).await?;
+                    if response.status() == 200 {
+                        let references = links::references(&path, response.body());
+                        pending.extend(references.into_iter().map(|r| (Some(path.clone()), r.to_owned())));
+                    }
+                    fetched.insert(path, response.status());
+                    response.status()
+                }
+            };
+
+            let reference = BrokenReference { page, reference, status };
+            if status != 200 && !broken.contains(&reference) {
+                broken.push(reference);
+            }
+        }
+
+        Ok(broken)
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn serve(assets: &Assets, req: hyper::Request<Incoming>) -> hyper::Response<Full<Bytes>> {
+    let path = req.uri().path_and_query().map_or("/", |pq| pq.as_str());
+    let mut request = Request::new(req.method().as_str(), path);
+    for (name, value) in req.headers() {
+        if let Ok(value) = value.to_str() {
+            request = request.with_header(name.as_str(), value);
+        }
+    }
+
+    let (status, headers, body) = assets.serve(&request).await.into_parts();
+    let mut response = hyper::Response::builder().status(status);
+    for (name, value) in headers {
+        response = response.header(&*name, value);
+    }
+    match response.body(Full::new(body)) {
+        Ok(response) => response,
+
+        // E.g. for a content type containing a line break. Panicking here
+        // would only surface as an unrelated connection error in the test.
+        Err(e) => {
+            let message = format!("invalid response created by `Assets::serve`: {e}");
+            log::error!(target: "reinda", "{message}");
+            let mut response = hyper::Response::new(Full::new(Bytes::from(message)));
+            *response.status_mut() = hyper::StatusCode::INTERNAL_SERVER_ERROR;
+            response
+        }
+    }
+}
+
+/// Percent-encodes all bytes of `path` that are not allowed in a URI path.
+fn encode_path(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    for b in path.bytes() {
+        if b.is_ascii_alphanumeric() || b"-._~/!$&'()*+,;=:@%".contains(&b) {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
@@ -0,0 +1,265 @@
+//! Built-in transformations of asset contents, applied after the modifiers
+//! (in both modes).
+
+use std::{borrow::Cow, sync::Arc};
+
+use bytes::Bytes;
+
+use crate::DataSource;
+
+
+/// Built-in transformations configured for an entry.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TransformSettings {
+    /// See `EntryBuilder::with_base_href`.
+    pub(crate) base_href: bool,
+
+    /// See `EntryBuilder::with_canonical_link`.
+    pub(crate) canonical_link: bool,
+
+    /// See `EntryBuilder::normalize_text`.
+    pub(crate) normalize_text: bool,
+
+    /// See `EntryBuilder::prepend` and `EntryBuilder::append`.
+    pub(crate) prepend: Vec<Snippet>,
+    pub(crate) append: Vec<Snippet>,
+
+    /// The banner passed to `Builder::set_banner`, copied into all entries
+    /// in `Builder::build`.
+    pub(crate) banner: Option<Arc<str>>,
+
+    /// The URL passed to `Builder::set_public_url`, always ending in `/`.
+    /// Copied into all entries needing it in `Builder::build`.
+    pub(crate) public_url: Option<Arc<str>>,
+
+    /// See `EntryBuilder::with_content_type`, copied in `Builder::build`.
+    /// Used to only apply HTML transformations to HTML assets.
+    pub(crate) content_type: Option<Cow<'static, str>>,
+}
+
+/// Content added by `EntryBuilder::prepend`, `EntryBuilder::append` and their
+/// variants.
+#[derive(Debug, Clone)]
+pub(crate) enum Snippet {
+    /// Inline content or a file, loaded like the content of assets.
+    Source(DataSource),
+
+    /// The final content of another asset, given by *unhashed HTTP path*.
+    Asset(Cow<'static, str>),
+}
+
+impl TransformSettings {
+    /// Returns the *unhashed HTTP paths* of all assets whose content is
+    /// prepended or appended.
+    pub(crate) fn dependencies(&self) -> impl Iterator<Item = &str> {
+        self.prepend.iter().chain(&self.append).filter_map(|snippet| match snippet {
+            Snippet::Asset(path) => Some(&**path),
+            Snippet::Source(_) => None,
+        })
+    }
+
+    /// Returns whether any transformation was configured for the entry
+    /// itself, i.e. ignoring global settings like the banner.
+    pub(crate) fn is_configured(&self) -> bool {
+        self.base_href
+            || self.canonical_link
+            || self.normalize_text
+            || !self.prepend.is_empty()
+            || !self.append.is_empty()
+    }
+
+    /// Returns whether any transformation needs the public URL.
+    pub(crate) fn needs_public_url(&self) -> bool {
+        self.base_href || self.canonical_link
+    }
+
+    /// Strips a UTF-8 BOM and converts all line endings to `\n`, if
+    /// configured and the content is not binary. Applied to the raw content,
+    /// before templates and modifiers.
+    pub(crate) fn normalize(&self, content: Bytes) -> Bytes {
+        const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+        if !self.normalize_text || crate::template::is_binary(&content) {
+            return content;
+        }
+
+        let content = if content.starts_with(BOM) { content.slice(BOM.len()..) } else { content };
+        if !content.contains(&b'\r') {
+            return content;
+        }
+
+        let mut out = Vec::with_capacity(content.len());
+        let mut bytes = content.iter().copied().peekable();
+        while let Some(b) = bytes.next() {
+            if b == b'\r' {
+                bytes.next_if_eq(&b'\n');
+                out.push(b'\n');
+            } else {
+                out.push(b);
+            }
+        }
+        out.into()
+    }
+
+    /// Applies all configured transformations to the content of the asset
+    /// with the given *unhashed HTTP path*. `prepend` and `append` are the
+    /// loaded contents of the corresponding snippets.
+    pub(crate) fn apply(&self, http_path: &str, content: Bytes, prepend: &[Bytes], append: &[Bytes]) -> Bytes {
+        let mut content = content;
+        if !prepend.is_empty() || !append.is_empty() {
+            content = prepend.iter()
+                .chain([&content])
+                .chain(append)
+                .flat_map(|part| part.iter().copied())
+                .collect::<Vec<_>>()
+                .into();
+        }
+
+        if let Some(banner) = self.banner.as_deref().filter(|_| cfg!(prod_mode)) {
+            let banner = format!("/*! {} */\n", banner.replace("*/", "* /"));
+            if gets_banner(http_path) {
+                let mut out = banner.into_bytes();
+                out.extend_from_slice(&content);
+                content = out.into();
+            } else if http_path.strip_suffix(".map").is_some_and(gets_banner) {
+                // The banner moves the code of the asset down, so its source
+                // map has to be shifted by the same number of lines.
+                if let Some(shifted) = shift_source_map(&content, banner.matches('\n').count()) {
+                    content = shifted.into();
+                }
+            }
+        }
+
+        let is_html = self.content_type.as_deref()
+            .or_else(|| crate::mime::guess(http_path))
+            .is_some_and(|ty| ty.starts_with("text/html"));
+        if let Some(url) = self.public_url.as_ref().filter(|_| is_html) {
+            // Both tags are inserted at the start of `<head>`, so the canonical
+            // link goes first to end up after `<base>`.
+            if self.canonical_link {
+                let url = format!("{url}{http_path}");
+                content = crate::util::set_canonical_link(&content, &url).into();
+            }
+            if self.base_href {
+                content = crate::util::set_base_href(&content, url_path(url)).into();
+            }
+        }
+
+        content
+    }
+}
+
+/// Returns whether the asset with the given path gets the banner of
+/// `Builder::set_banner`.
+fn gets_banner(http_path: &str) -> bool {
+    [".js", ".mjs", ".css"].iter().any(|ext| http_path.ends_with(ext))
+}
+
+/// Shifts all mappings of the given source map down by `lines` lines, by
+/// prepending that many `;` to its `mappings`. Returns `None` if it has no
+/// `mappings`, e.g. because it's not a source map.
+fn shift_source_map(map: &[u8], lines: usize) -> Option<Vec<u8>> {
+    let key = b"\"mappings\"";
+    let after_key = map.windows(key.len()).position(|w| w == key)? + key.len();
+    let is_space = |b: &u8| b.is_ascii_whitespace();
+    let colon = after_key + map[after_key..].iter().position(|b| !is_space(b))?;
+    if map[colon] != b':' {
+        return None;
+    }
+    let quote = colon + 1 + map[colon + 1..].iter().position(|b| !is_space(b))?;
+    if map[quote] != b'"' {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(map.len() + lines);
+    out.extend_from_slice(&map[..=quote]);
+    out.extend_from_slice(";".repeat(lines).as_bytes());
+    out.extend_from_slice(&map[quote + 1..]);
+    Some(out)
+}
+
+/// Returns the path of the given URL, e.g. `/docs/` for
+/// `https://example.com/docs/`. URLs without scheme are returned unchanged.
+fn url_path(url: &str) -> &str {
+    match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("/", |i| &rest[i..]),
+        None => url,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_text() {
+        let settings = TransformSettings { normalize_text: true, ..Default::default() };
+        let normalize = |s: &[u8]| settings.normalize(Bytes::copy_from_slice(s));
+        assert_eq!(normalize(b"\xEF\xBB\xBFa {}\r\nb {}\rc\n"), &b"a {}\nb {}\nc\n"[..]);
+        assert_eq!(normalize(b"a\r\n\r\nb"), &b"a\n\nb"[..]);
+        assert_eq!(normalize(b"unchanged\n"), &b"unchanged\n"[..]);
+        assert_eq!(normalize(b"\xEF\xBB\xBFbinary\0\r\n"), &b"\xEF\xBB\xBFbinary\0\r\n"[..]);
+
+        let disabled = TransformSettings::default();
+        assert_eq!(disabled.normalize(Bytes::from_static(b"a\r\n")), &b"a\r\n"[..]);
+    }
+
+    #[test]
+    fn prepend_append_banner() {
+        let settings = TransformSettings {
+            banner: Some("v1.0 */ (c) me".into()),
+            ..Default::default()
+        };
+        let content = || Bytes::from_static(b"<content>");
+        let apply = |path| settings.apply(path, content(), &["a".into(), "b".into()], &["z".into()]);
+
+        assert_eq!(apply("foo.txt"), "ab<content>z");
+        let expected = if cfg!(prod_mode) { "/*! v1.0 * / (c) me */\nab<content>z" } else { "ab<content>z" };
+        assert_eq!(apply("foo.js"), expected);
+        assert_eq!(apply("foo.css"), expected);
+        assert_eq!(TransformSettings::default().apply("foo.js", content(), &[], &[]), content());
+    }
+
+    #[test]
+    fn banner_shifts_source_maps() {
+        let settings = TransformSettings {
+            banner: Some("line 1\nline 2".into()),
+            ..Default::default()
+        };
+        let map = || Bytes::from_static(br#"{"version":3, "mappings" : "AAAA;AACA"}"#);
+        let expected = if cfg!(prod_mode) {
+            &br#"{"version":3, "mappings" : ";;AAAA;AACA"}"#[..]
+        } else {
+            &map()[..]
+        };
+        assert_eq!(settings.apply("foo.js.map", map(), &[], &[]), expected);
+        assert_eq!(settings.apply("foo.css.map", map(), &[], &[]), expected);
+        assert_eq!(settings.apply("foo.json.map", map(), &[], &[]), map());
+        assert_eq!(shift_source_map(b"{}", 1), None);
+    }
+
+    #[test]
+    fn html_only() {
+        let settings = TransformSettings {
+            base_href: true,
+            public_url: Some("https://example.com/docs/".into()),
+            ..Default::default()
+        };
+        let content = || Bytes::from_static(b"<head></head>");
+        let html = Bytes::from_static(br#"<head><base href="/docs/"></head>"#);
+        assert_eq!(settings.apply("index.html", content(), &[], &[]), html);
+        assert_eq!(settings.apply("page", content(), &[], &[]), content());
+        assert_eq!(settings.apply("data.xml", content(), &[], &[]), content());
+
+        let settings = TransformSettings { content_type: Some("text/html".into()), ..settings };
+        assert_eq!(settings.apply("page", content(), &[], &[]), html);
+    }
+
+    #[test]
+    fn public_url_path() {
+        assert_eq!(url_path("https://example.com/docs/"), "/docs/");
+        assert_eq!(url_path("https://example.com"), "/");
+        assert_eq!(url_path("/docs/"), "/docs/");
+    }
+}
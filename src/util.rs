@@ -1,5 +1,7 @@
 //! Utility functions.
 
+use std::ops::Range;
+
 use aho_corasick::AhoCorasick;
 
 
@@ -40,3 +42,243 @@ where
     });
     out.into()
 }
+
+/// Sets the `<base href>` of the given HTML document to `href`: an existing
+/// `<base>` tag is replaced, otherwise one is inserted at the start of
+/// `<head>` (or the start of the document, if there is no `<head>`). Useful
+/// for apps deployed under a sub-path, see also
+/// [`EntryBuilder::with_base_href`][crate::EntryBuilder::with_base_href].
+pub fn set_base_href(html: &[u8], href: &str) -> Vec<u8> {
+    let tag = format!(r#"<base href="{}">"#, escape_attr(href));
+    let existing = find_tags(html, "base").next();
+    replace_or_insert(html, existing, &tag)
+}
+
+/// Sets the canonical URL of the given HTML document to `url`: an existing
+/// `<link rel="canonical">` tag is replaced, otherwise one is inserted at
+/// the start of `<head>` (or the start of the document, if there is no
+/// `<head>`). See also
+/// [`EntryBuilder::with_canonical_link`][crate::EntryBuilder::with_canonical_link].
+pub fn set_canonical_link(html: &[u8], url: &str) -> Vec<u8> {
+    let tag = format!(r#"<link rel="canonical" href="{}">"#, escape_attr(url));
+    let existing = find_tags(html, "link").find(|range| {
+        let tag = html[range.clone()].to_ascii_lowercase();
+        [&b"rel=\"canonical\""[..], b"rel='canonical'", b"rel=canonical"]
+            .iter()
+            .any(|attr| tag.windows(attr.len()).any(|w| w == *attr))
+    });
+    replace_or_insert(html, existing, &tag)
+}
+
+/// Returns the byte ranges of all start tags with the given (lowercase) name,
+/// including `<` and `>`.
+pub(crate) fn find_tags<'a>(html: &'a [u8], name: &'a str) -> impl 'a + Iterator<Item = Range<usize>> {
+    let needle = format!("<{name}");
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        while let Some(pos) = html[offset..]
+            .windows(needle.len())
+            .position(|w| w.eq_ignore_ascii_case(needle.as_bytes()))
+        {
+            let start = offset + pos;
+            offset = start + needle.len();
+
+            // Make sure the tag name ends here, e.g. `<header>` is not `<head>`.
+            match html.get(offset) {
+                Some(b) if b.is_ascii_whitespace() || *b == b'>' || *b == b'/' => {}
+                _ => continue,
+            }
+            let end = html[offset..].iter().position(|b| *b == b'>')? + offset + 1;
+            offset = end;
+            return Some(start..end);
+        }
+        None
+    })
+}
+
+/// Returns the byte ranges and `href` values of all `<link rel="preload">`
+/// and `<link rel="prefetch">` tags in the given HTML document.
+pub(crate) fn find_preloads(html: &[u8]) -> Vec<(Range<usize>, &str)> {
+    find_tags(html, "link")
+        .filter_map(|range| {
+            let tag = std::str::from_utf8(&html[range.clone()]).ok()?;
+            let rel = attr_value(tag, "rel")?;
+            let is_preload = rel.split_ascii_whitespace()
+                .any(|r| r.eq_ignore_ascii_case("preload") || r.eq_ignore_ascii_case("prefetch"));
+            if !is_preload {
+                return None;
+            }
+            Some((range, attr_value(tag, "href")?))
+        })
+        .collect()
+}
+
+/// Returns the value of the attribute `name` (lowercase) in the given start
+/// tag, without quotes. Character references are not decoded.
+fn attr_value<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let lower = tag.to_ascii_lowercase();
+    let pos = lower.match_indices(name).find_map(|(i, _)| {
+        // Only match whole attribute names, not e.g. `data-href`.
+        let rest = lower[i + name.len()..].trim_start();
+        let whole = lower[..i].ends_with(|c: char| c.is_ascii_whitespace());
+        (whole && rest.starts_with('=')).then(|| tag.len() - rest.len() + 1)
+    })?;
+    let value = tag[pos..].trim_start();
+    match value.chars().next() {
+        Some(q @ ('"' | '\'')) => value[1..].split(q).next(),
+        _ => value.split(|c: char| c.is_ascii_whitespace() || c == '>').next(),
+    }
+}
+
+/// Replaces `existing` with `tag` or, if it's `None`, inserts `tag` at the
+/// start of `<head>`.
+fn replace_or_insert(html: &[u8], existing: Option<Range<usize>>, tag: &str) -> Vec<u8> {
+    let range = existing.unwrap_or_else(|| {
+        let pos = find_tags(html, "head").next().map_or(0, |head| head.end);
+        pos..pos
+    });
+
+    let mut out = Vec::with_capacity(html.len() + tag.len());
+    out.extend_from_slice(&html[..range.start]);
+    out.extend_from_slice(tag.as_bytes());
+    out.extend_from_slice(&html[range.end..]);
+    out
+}
+
+fn escape_attr(s: &str) -> String {
+    s.replace('&', "&amp;").replace('"', "&quot;")
+}
+
+/// Returns `s` as JSON string literal, including quotes.
+pub(crate) fn json_string(s: &str) -> String {
+    use std::fmt::Write;
+
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A key identifying the given content, the first 128 bits of its SHA-256.
+/// See `Asset::cache_key`.
+#[cfg(feature = "hash")]
+pub(crate) fn cache_key(content: &[u8]) -> [u8; 16] {
+    use sha2::{Digest, Sha256};
+
+    let hash = Sha256::digest(content);
+    let mut out = [0; 16];
+    out.copy_from_slice(&hash[..16]);
+    out
+}
+
+/// Formats the output of `cache_key` as lowercase hex string.
+#[cfg(feature = "hash")]
+pub(crate) fn format_cache_key(key: &[u8; 16]) -> String {
+    key.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Hash function for [Subresource Integrity][sri] hashes, see [`sri_hash`].
+///
+/// Type is only available if the crate feature `hash` is enabled.
+///
+/// [sri]: https://developer.mozilla.org/en-US/docs/Web/Security/Subresource_Integrity
+#[cfg(feature = "hash")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum SriAlgorithm {
+    Sha256,
+    /// The default, also used by the template fragment `sri`.
+    #[default]
+    Sha384,
+    Sha512,
+}
+
+/// Returns the [Subresource Integrity][sri] hash of `content`, e.g.
+/// `sha384-<base64>`, for the `integrity` attribute of `<script>` and
+/// `<link>` elements.
+///
+/// ```
+/// use reinda::util::{sri_hash, SriAlgorithm};
+///
+/// assert_eq!(
+///     sri_hash(b"alert('Hello, world.');", SriAlgorithm::Sha256),
+///     "sha256-qznLcsROx4GACP2dm0UCKCzCG+HiZ1guq6ZZDob/Tng=",
+/// );
+/// ```
+///
+/// Function is only available if the crate feature `hash` is enabled.
+///
+/// [sri]: https://developer.mozilla.org/en-US/docs/Web/Security/Subresource_Integrity
+#[cfg(feature = "hash")]
+pub fn sri_hash(content: &[u8], algorithm: SriAlgorithm) -> String {
+    use base64::Engine;
+    use sha2::{Digest, Sha256, Sha384, Sha512};
+
+    let (prefix, hash) = match algorithm {
+        SriAlgorithm::Sha256 => ("sha256", Sha256::digest(content).to_vec()),
+        SriAlgorithm::Sha384 => ("sha384", Sha384::digest(content).to_vec()),
+        SriAlgorithm::Sha512 => ("sha512", Sha512::digest(content).to_vec()),
+    };
+    format!("{prefix}-{}", base64::engine::general_purpose::STANDARD.encode(hash))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_strings() {
+        assert_eq!(json_string("a\"b\\c\n\u{1}ä"), r#""a\"b\\c\n\u0001ä""#);
+    }
+
+    #[test]
+    fn base_href() {
+        let set = |html: &str| String::from_utf8(set_base_href(html.as_bytes(), "/app/")).unwrap();
+        assert_eq!(
+            set("<html><head>\n<title>x</title></head></html>"),
+            "<html><head><base href=\"/app/\">\n<title>x</title></head></html>",
+        );
+        assert_eq!(
+            set("<HEAD lang=en><BASE HREF='/'><header></header>"),
+            "<HEAD lang=en><base href=\"/app/\"><header></header>",
+        );
+        assert_eq!(set("<p>hi</p>"), "<base href=\"/app/\"><p>hi</p>");
+    }
+
+    #[test]
+    fn canonical_link() {
+        let url = "https://example.com/app/?a=1&b=\"2\"";
+        let set = |html: &str| String::from_utf8(set_canonical_link(html.as_bytes(), url)).unwrap();
+        let tag = r#"<link rel="canonical" href="https://example.com/app/?a=1&amp;b=&quot;2&quot;">"#;
+        assert_eq!(
+            set(r#"<head><link rel="stylesheet" href="a.css"><link rel=canonical href="/x"></head>"#),
+            format!(r#"<head><link rel="stylesheet" href="a.css">{tag}</head>"#),
+        );
+        assert_eq!(set("<head></head>"), format!("<head>{tag}</head>"));
+    }
+
+    #[test]
+    fn preloads() {
+        let html = r#"<link rel="stylesheet" href="a.css"><LINK REL=preload HREF=big.png as=image>
+            <link data-href="x" rel='prefetch' href='next.html'><link rel="preload">"#;
+        let found = find_preloads(html.as_bytes()).into_iter()
+            .map(|(range, href)| (&html[range], href))
+            .collect::<Vec<_>>();
+        assert_eq!(found, [
+            ("<LINK REL=preload HREF=big.png as=image>", "big.png"),
+            ("<link data-href=\"x\" rel='prefetch' href='next.html'>", "next.html"),
+        ]);
+    }
+}
@@ -0,0 +1,294 @@
+//! Watching the files of assets for changes in dev mode, e.g. to reload the
+//! page in the browser. See [`Assets::subscribe_changes`].
+//!
+//! Module is only available if the crate feature `watch` is enabled.
+
+use std::sync::Mutex;
+
+use tokio::{sync::broadcast, task::JoinHandle};
+
+use crate::Assets;
+
+
+/// Number of changes buffered per receiver. Receivers falling further behind
+/// get [`RecvError::Lagged`][broadcast::error::RecvError::Lagged].
+const CHANNEL_CAPACITY: usize = 256;
+
+/// How often the files are checked for changes if the file system
+/// notifications of the OS are unavailable.
+#[cfg(dev_mode)]
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(300);
+
+/// How long to wait for more notifications before checking the files, as
+/// saving a file often causes several.
+#[cfg(dev_mode)]
+const DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// A change of the file an asset is loaded from, received via
+/// [`Assets::subscribe_changes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Change {
+    /// The *unhashed HTTP path* of the changed asset.
+    pub http_path: String,
+
+    /// What happened to the file.
+    pub kind: ChangeKind,
+}
+
+/// What happened to the file of an asset, see [`Change`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ChangeKind {
+    /// A file matching a glob entry was created, i.e. there is a new asset.
+    Added,
+
+    /// The file (or a file prepended or appended to it) was modified.
+    Modified,
+
+    /// The file was deleted or renamed.
+    Removed,
+}
+
+/// The channel shared by all clones of an `Assets`. The watcher task only
+/// holds a weak sender, so it stops once all clones are dropped. It is also
+/// aborted when this is dropped or `Assets::shutdown` is called.
+#[derive(Debug, Default)]
+pub(crate) struct Watcher(Mutex<WatcherState>);
+
+#[derive(Debug, Default)]
+struct WatcherState {
+    sender: Option<broadcast::Sender<Change>>,
+    task: Option<JoinHandle<()>>,
+    shut_down: bool,
+}
+
+impl Watcher {
+    /// Stops the watcher task (if running) and closes the channel, so that
+    /// all receivers get `RecvError::Closed`. Returns the task to await.
+    pub(crate) fn shutdown(&self) -> Option<JoinHandle<()>> {
+        let mut state = self.0.lock().unwrap();
+        state.shut_down = true;
+        state.sender = None;
+        let task = state.task.take()?;
+        task.abort();
+        Some(task)
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        // The aborted task is not awaited, it stops at its next `.await`.
+        let _ = self.shutdown();
+    }
+}
+
+impl Assets {
+    /// Returns a receiver for changes of the files assets are loaded from,
+    /// e.g. to push live-reload events to the browser via server-sent events
+    /// or a WebSocket.
+    ///
+    /// In dev mode, the first call starts a task that checks all files for
+    /// changes whenever the OS reports changes in their directories (via the
+    /// `notify` crate), or every 300ms if that's unavailable. This includes
+    /// files prepended or appended to assets,
+    /// [extra watched files][crate::EntryBuilder::with_extra_watch] and files
+    /// newly matching glob entries. How changes are detected is configured
+    /// with [`Builder::set_change_detection`][crate::Builder::set_change_detection].
+    /// A change is sent for the asset whose file changed; assets depending on
+    /// it (e.g. via templates) are not reported separately. The task stops
+    /// when all clones of this `Assets` are dropped or [`Self::shutdown`] is
+    /// called. Must be called from within a Tokio runtime.
+    ///
+    /// In prod mode, all files are loaded during the build, so nothing is
+    /// ever received. After [`Self::shutdown`], the returned receiver is
+    /// closed immediately.
+    ///
+    /// Method is only available if the crate feature `watch` is enabled.
+    pub fn subscribe_changes(&self) -> broadcast::Receiver<Change> {
+        let mut state = self.watcher.0.lock().unwrap();
+        if state.shut_down {
+            return broadcast::channel(1).1;
+        }
+        if let Some(sender) = &state.sender {
+            return sender.subscribe();
+        }
+
+        let (sender, receiver) = broadcast::channel(CHANNEL_CAPACITY);
+        #[cfg(dev_mode)]
+        {
+            state.task = Some(dev::spawn(self.inner.clone(), sender.downgrade()));
+        }
+        state.sender = Some(sender);
+        receiver
+    }
+}
+
+#[cfg(dev_mode)]
+mod dev {
+    use std::{
+        collections::{BTreeSet, HashMap, HashSet},
+        path::{Path, PathBuf},
+    };
+
+    use notify::{RecursiveMode, Watcher as _};
+    use tokio::sync::{broadcast, mpsc};
+
+    use crate::{change::FileVersion, imp::AssetsInner};
+    use super::{Change, ChangeKind, DEBOUNCE, POLL_INTERVAL};
+
+
+    /// The files of each asset (by HTTP path) with their version. Assets
+    /// without existing file are missing.
+    type Snapshot = HashMap<String, Vec<(PathBuf, FileVersion)>>;
+
+    pub(super) fn spawn(
+        assets: AssetsInner,
+        sender: broadcast::WeakSender<Change>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let (notify_tx, mut notify_rx) = mpsc::unbounded_channel();
+            let mut fs_watcher = FsWatcher::new(notify_tx);
+            update_watches(&mut fs_watcher, &assets).await;
+            let mut state = snapshot(assets.clone()).await;
+            loop {
+                match &fs_watcher {
+                    Some(_) => {
+                        if notify_rx.recv().await.is_none() {
+                            return;
+                        }
+                        tokio::time::sleep(DEBOUNCE).await;
+                        while notify_rx.try_recv().is_ok() {}
+                    }
+                    None => tokio::time::sleep(POLL_INTERVAL).await,
+                }
+                if sender.upgrade().is_none() {
+                    return;
+                }
+
+                // New directories might have to be watched, e.g. if a
+                // directory matching a glob was created.
+                update_watches(&mut fs_watcher, &assets).await;
+                let new_state = snapshot(assets.clone()).await;
+                let changes = diff(&state, &new_state);
+                state = new_state;
+                let Some(sender) = sender.upgrade() else { return };
+                for change in changes {
+                    log::debug!(target: "reinda", "{:?}: '{}'", change.kind, change.http_path);
+
+                    // Sending only fails if there are no receivers right now.
+                    let _ = sender.send(change);
+                }
+            }
+        })
+    }
+
+    /// Calls `FsWatcher::update`, falling back to polling if that fails.
+    async fn update_watches(fs_watcher: &mut Option<FsWatcher>, assets: &AssetsInner) {
+        if let Some(w) = fs_watcher {
+            if let Err(e) = w.update(assets).await {
+                log::warn!(target: "reinda", "cannot watch files, polling instead: {e}");
+                *fs_watcher = None;
+            }
+        }
+    }
+
+    /// Watches the directories of all watched files via the `notify` crate,
+    /// sending a message for each change in them.
+    struct FsWatcher {
+        watcher: notify::RecommendedWatcher,
+
+        /// Watched directories, with whether they are watched recursively.
+        watched: HashSet<(PathBuf, bool)>,
+    }
+
+    impl FsWatcher {
+        /// Returns `None` (after logging a warning) if file system
+        /// notifications are unavailable, in which case the files are polled.
+        fn new(tx: mpsc::UnboundedSender<()>) -> Option<Self> {
+            let handler = move |event: notify::Result<notify::Event>| {
+                // Reading files (e.g. to hash them) must not cause another
+                // check.
+                if event.is_ok_and(|e| e.kind.is_access()) {
+                    return;
+                }
+                let _ = tx.send(());
+            };
+            match notify::recommended_watcher(handler) {
+                Ok(watcher) => Some(Self { watcher, watched: HashSet::new() }),
+                Err(e) => {
+                    log::warn!(target: "reinda", "cannot watch files, polling instead: {e}");
+                    None
+                }
+            }
+        }
+
+        /// Starts watching all directories that are not watched yet. A
+        /// directory that doesn't exist is replaced by its closest existing
+        /// ancestor, watched non-recursively, to notice its creation.
+        async fn update(&mut self, assets: &AssetsInner) -> notify::Result<()> {
+            let assets = assets.clone();
+            let dirs = tokio::task::spawn_blocking(move || assets.watched_dirs())
+                .await
+                .unwrap_or_default();
+            for (dir, recursive) in dirs {
+                let Some(target) = existing_ancestor(&dir) else { continue };
+                let recursive = recursive && target == dir;
+                let key = (target, recursive);
+                if self.watched.contains(&key)
+                    || (!recursive && self.watched.contains(&(key.0.clone(), true)))
+                {
+                    continue;
+                }
+
+                let mode = match recursive {
+                    true => RecursiveMode::Recursive,
+                    false => RecursiveMode::NonRecursive,
+                };
+                self.watcher.watch(&key.0, mode)?;
+                self.watched.insert(key);
+            }
+            Ok(())
+        }
+    }
+
+    /// Returns `path` or its closest existing ancestor, treating the empty
+    /// path as the current directory.
+    fn existing_ancestor(path: &Path) -> Option<PathBuf> {
+        let path = if path.as_os_str().is_empty() { Path::new(".") } else { path };
+        path.ancestors()
+            .map(|p| if p.as_os_str().is_empty() { Path::new(".") } else { p })
+            .find(|p| p.is_dir())
+            .map(ToOwned::to_owned)
+    }
+
+    async fn snapshot(assets: AssetsInner) -> Snapshot {
+        let task = tokio::task::spawn_blocking(move || {
+            let mut out = Snapshot::new();
+            for (http_path, fs_path) in assets.watched_files() {
+                if let Ok(version) = assets.change_detector().version_blocking(&fs_path) {
+                    out.entry(http_path).or_default().push((fs_path, version));
+                }
+            }
+            out.values_mut().for_each(|files| files.sort());
+            out
+        });
+        task.await.unwrap_or_default()
+    }
+
+    /// Returns all changes between two snapshots, sorted by HTTP path.
+    fn diff(old: &Snapshot, new: &Snapshot) -> Vec<Change> {
+        let paths = old.keys().chain(new.keys()).collect::<BTreeSet<_>>();
+        paths.into_iter()
+            .filter_map(|http_path| {
+                let kind = match (old.get(http_path), new.get(http_path)) {
+                    (None, Some(_)) => ChangeKind::Added,
+                    (Some(_), None) => ChangeKind::Removed,
+                    (Some(a), Some(b)) if a != b => ChangeKind::Modified,
+                    _ => return None,
+                };
+                Some(Change { http_path: http_path.clone(), kind })
+            })
+            .collect()
+    }
+}
@@ -214,6 +214,3260 @@ async fn use_case_web() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[tokio::test]
+async fn panicking_modifier() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["peter.txt"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("peter.txt", &EMBEDS["peter.txt"])
+        .with_modifier([] as [&str; 0], |_, _| panic!("oh no"));
+    let result = builder.build().await;
+
+    #[cfg(prod_mode)]
+    {
+        let err = result.unwrap_err();
+        assert!(matches!(
+            &err,
+            reinda::BuildError::ModifierPanicked { http_path, error }
+                if http_path == "peter.txt" && error.message() == "oh no"
+        ));
+        assert_eq!(std::error::Error::source(&err).unwrap().to_string(), "oh no");
+    }
+
+    #[cfg(dev_mode)]
+    {
+        let assets = result?;
+        let err = assets.get("peter.txt").unwrap().content().await.unwrap_err();
+        assert!(err.to_string().contains("oh no"));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn build_deadline() -> Result<(), Box<dyn std::error::Error>> {
+    use std::time::Duration;
+
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["peter.txt"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("peter.txt", &EMBEDS["peter.txt"])
+        .with_modifier([] as [&str; 0], |content, _| {
+            std::thread::sleep(Duration::from_millis(500));
+            content
+        });
+    let result = builder.build_with_deadline(Duration::from_millis(50)).await;
+
+    // In dev mode, modifiers only run when loading the asset.
+    #[cfg(prod_mode)]
+    assert!(matches!(result, Err(reinda::BuildError::DeadlineExceeded(_))));
+    #[cfg(dev_mode)]
+    assert!(result.is_ok());
+
+    Ok(())
+}
+
+// A modifier still running after the deadline must not block the shutdown of
+// the runtime.
+#[cfg(prod_mode)]
+#[test]
+fn build_deadline_shutdown() {
+    use std::time::Duration;
+
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    let mut builder = Assets::builder();
+    builder.add_bytes("hang.txt", "hang")
+        .with_modifier([] as [&str; 0], |content, _| {
+            std::thread::sleep(Duration::from_secs(3600));
+            content
+        });
+    let result = runtime.block_on(builder.build_with_deadline(Duration::from_millis(50)));
+    assert!(matches!(result, Err(reinda::BuildError::DeadlineExceeded(_))));
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        drop(runtime);
+        tx.send(()).unwrap();
+    });
+    rx.recv_timeout(Duration::from_secs(5)).expect("runtime did not shut down");
+}
+
+#[tokio::test]
+#[cfg(feature = "hash")]
+async fn write_to_dir() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["peter.txt"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("peter.txt", &EMBEDS["peter.txt"]);
+    builder.add_embedded("nested/dir/wolf.txt", &EMBEDS["peter.txt"]);
+    let assets = builder.build().await?;
+
+    let dir = std::env::temp_dir().join(format!("reinda-test-export-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("old"))?;
+    std::fs::write(dir.join("old/stale.txt"), "stale")?;
+    let options = reinda::ExportOptions::new().with_checksums();
+    assets.write_to_dir_with(&dir, &options).await?;
+
+    let expected = "Peter und der Wolf.\n";
+    let hash = "c709a2edabf89089fb3781ec3496d7efa2bf48fb610687315cf8126a9fa5838a";
+    assert_eq!(std::fs::read_to_string(dir.join("peter.txt"))?, expected);
+    assert_eq!(std::fs::read_to_string(dir.join("nested/dir/wolf.txt"))?, expected);
+    assert_eq!(
+        std::fs::read_to_string(dir.join("nested/dir/wolf.txt.sha256"))?,
+        format!("{hash}  wolf.txt\n"),
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join("SHA256SUMS"))?,
+        format!("{hash}  nested/dir/wolf.txt\n{hash}  peter.txt\n"),
+    );
+
+    // Exporting again does not touch unchanged files.
+    let modified = std::fs::metadata(dir.join("peter.txt"))?.modified()?;
+    assets.write_to_dir_with(&dir, &options).await?;
+    assert_eq!(std::fs::metadata(dir.join("peter.txt"))?.modified()?, modified);
+
+    // Only files of previous exports are removed, and only if requested.
+    let mut builder = Assets::builder();
+    builder.add_embedded("peter.txt", &EMBEDS["peter.txt"]);
+    let assets = builder.build().await?;
+    assets.write_to_dir(&dir).await?;
+    assert!(dir.join("nested/dir/wolf.txt").exists());
+    std::fs::remove_file(dir.join("SHA256SUMS"))?;
+    assets.write_to_dir_with(&dir, &reinda::ExportOptions::new().remove_stale()).await?;
+    assert!(dir.join("peter.txt").exists());
+    assert!(!dir.join("nested").exists());
+    assert!(dir.join("old/stale.txt").exists());
+    assert_eq!(std::fs::read_to_string(dir.join(".reinda-export"))?, "peter.txt\n");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg(feature = "sidecar")]
+async fn sidecar() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "examples/assets",
+        files: ["robots.txt", "fonts/*.woff2"],
+        sidecar: "target/reinda-test-sidecar.pack",
+    };
+
+    std::env::set_var("REINDA_SIDECAR_DIR", env!("CARGO_MANIFEST_DIR"));
+    let mut builder = Assets::builder();
+    builder.add_embedded("robots.txt", &EMBEDS["robots.txt"]);
+    builder.add_embedded("fonts/", &EMBEDS["fonts/*.woff2"]);
+    let assets = builder.build().await?;
+
+    #[cfg(prod_mode)]
+    assert!(std::path::Path::new("target/reinda-test-sidecar.pack").exists());
+
+    assert_eq!(
+        assets.get("robots.txt").unwrap().content().await?,
+        include_bytes!("../examples/assets/robots.txt").as_slice(),
+    );
+    assert_eq!(
+        assets.get("fonts/latin-400.woff2").unwrap().content().await?,
+        include_bytes!("../examples/assets/fonts/latin-400.woff2").as_slice(),
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg(feature = "compress")]
+async fn precompression() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::{CompressionConfig, Encoding};
+
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "examples/assets",
+        files: ["style.css", "robots.txt", "fonts/latin-400.woff2"],
+    };
+
+    let mut config = CompressionConfig::default();
+    config.min_size = 100;
+    config.gzip_level = 1;
+
+    let mut builder = Assets::builder();
+    builder.set_compression(config);
+    builder.add_embedded("style.css", &EMBEDS["style.css"]);
+    builder.add_embedded("robots.txt", &EMBEDS["robots.txt"]);
+    builder.add_embedded("font.woff2", &EMBEDS["fonts/latin-400.woff2"]);
+    builder.add_embedded("font.css", &EMBEDS["fonts/latin-400.woff2"]);
+    builder.add_embedded("vendor.css", &EMBEDS["style.css"]);
+    builder.add_embedded("best.css", &EMBEDS["style.css"])
+        .with_compression_config({
+            let mut config = CompressionConfig::default();
+            config.min_size = 100;
+            config.only_best_variant = true;
+            config
+        });
+    builder.add_embedded("uncompressed.css", &EMBEDS["style.css"])
+        .with_compression_config({
+            let mut config = CompressionConfig::default();
+            config.content_type_allowlist = Some(vec!["text/html".into()]);
+            config
+        });
+    builder.add_embedded("opt-out.css", &EMBEDS["style.css"]).with_no_compression();
+    let assets = builder.build().await?;
+
+    // `vendor.css` has the same content and compression settings as
+    // `style.css`, so their variants are only stored once. Others might be
+    // shared as well (e.g. the Brotli variant of `best.css`).
+    if cfg!(prod_mode) {
+        let css = assets.get("style.css").unwrap();
+        let size = css.encoded_content(Encoding::Brotli).await?.unwrap().len()
+            + css.encoded_content(Encoding::Gzip).await?.unwrap().len();
+        assert!(assets.deduplicated_bytes() >= size);
+    } else {
+        assert_eq!(assets.deduplicated_bytes(), 0);
+    }
+
+    let css = assets.get("style.css").unwrap();
+    let original = css.content().await?;
+    let br = css.encoded_content(Encoding::Brotli).await?.unwrap();
+    let mut decompressed = Vec::new();
+    brotli::BrotliDecompress(&mut &*br, &mut decompressed)?;
+    assert_eq!(decompressed, original);
+
+    let gzip = css.encoded_content(Encoding::Gzip).await?.unwrap();
+    let mut decompressed = Vec::new();
+    std::io::Read::read_to_end(&mut flate2::read::GzDecoder::new(&*gzip), &mut decompressed)?;
+    assert_eq!(decompressed, original);
+
+    let best = assets.get("best.css").unwrap();
+    assert!(best.encoded_content(Encoding::Brotli).await?.is_some());
+    assert!(best.encoded_content(Encoding::Gzip).await?.is_none());
+
+    // Too small, not in allowlist, overridden, skipped extension, not
+    // compressible enough or opted out.
+    for path in ["robots.txt", "font.woff2", "uncompressed.css", "font.css", "opt-out.css"] {
+        let asset = assets.get(path).unwrap();
+        assert!(asset.encoded_content(Encoding::Brotli).await?.is_none());
+        assert!(asset.encoded_content(Encoding::Gzip).await?.is_none());
+    }
+
+    let (content, encoding) = css.negotiate_content("gzip;q=0.9, br;q=0.5").await?;
+    assert_eq!((content, encoding), (gzip.clone(), Some(Encoding::Gzip)));
+    let (content, encoding) = css.negotiate_content("deflate").await?;
+    assert_eq!((content, encoding), (original, None));
+
+    let req = reinda::http::Request::get("/style.css").with_header("accept-encoding", "gzip, br");
+    let res = assets.serve(&req).await;
+    assert_eq!(res.header("content-encoding"), Some("br"));
+    assert_eq!(res.header("vary"), Some("accept-encoding"));
+    assert_eq!(res.body(), &br);
+    let req = reinda::http::Request::get("/robots.txt").with_header("accept-encoding", "gzip, br");
+    let res = assets.serve(&req).await;
+    assert_eq!(res.header("content-encoding"), None);
+    assert_eq!(res.header("vary"), None);
+
+    let res = assets.serve(&reinda::http::Request::get("/opt-out.css")).await;
+    assert!(!res.is_compressible());
+    assert_eq!(res.header("cache-control"), Some("no-cache, no-transform"));
+    assert!(assets.serve(&reinda::http::Request::get("/style.css")).await.is_compressible());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg(feature = "hash")]
+async fn template() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["template.html", "peter.txt"],
+    };
+    const FONT: reinda::Embeds = reinda::embed! {
+        base_path: "examples/assets",
+        files: ["fonts/latin-400.woff2"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("index.html", &EMBEDS["template.html"]).with_template();
+    builder.add_embedded("style.css", &EMBEDS["peter.txt"]).with_hash();
+    builder.add_embedded("font.woff2", &FONT["fonts/latin-400.woff2"]).with_template();
+    let assets = builder.build().await?;
+
+    let index = assets.get("index.html").unwrap().content().await?;
+    let index = std::str::from_utf8(&index)?;
+    #[cfg(prod_mode)]
+    {
+        let css_path = assets.iter()
+            .map(|(path, _)| path)
+            .find(|path| path.starts_with("style.") && path.ends_with(".css"))
+            .unwrap();
+        assert_ne!(css_path, "style.css");
+        assert_eq!(index, format!("<link rel=\"stylesheet\" href=\"/{css_path}\">\n"));
+    }
+    #[cfg(dev_mode)]
+    assert_eq!(index, "<link rel=\"stylesheet\" href=\"/style.css\">\n");
+
+    // Binary files are passed through unchanged.
+    let font = assets.get("font.woff2").unwrap().content().await?;
+    assert_eq!(font, std::fs::read("examples/assets/fonts/latin-400.woff2")?);
+
+    // Referencing a non-existing asset is an error.
+    let mut builder = Assets::builder();
+    builder.add_embedded("index.html", &EMBEDS["template.html"]).with_template();
+    #[cfg(prod_mode)]
+    {
+        let err = builder.build().await.unwrap_err();
+        assert!(matches!(err, reinda::BuildError::Template { .. }));
+        assert!(std::error::Error::source(&err).is_some());
+    }
+    #[cfg(dev_mode)]
+    assert!(builder.build().await?.get("index.html").unwrap().content().await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg(feature = "hash")]
+async fn internal() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["template.html", "peter.txt"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("index.html", &EMBEDS["template.html"]).with_template();
+    builder.add_embedded("style.css", &EMBEDS["peter.txt"]).with_hash().internal();
+    let assets = builder.build().await?;
+
+    assert_eq!(assets.len(), 1);
+    assert_eq!(assets.iter().map(|(path, _)| path).collect::<Vec<_>>(), ["index.html"]);
+    assert!(assets.get("style.css").is_none());
+    assert_eq!(assets.paths_under("style").count(), 0);
+
+    // Still referencable from templates.
+    let index = assets.get("index.html").unwrap().content().await?;
+    assert!(std::str::from_utf8(&index)?.contains("href=\"/style."));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn serve() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::{Request, SourceMapPolicy};
+
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "examples/assets",
+        files: ["index.html", "bundle.8f29ad31.js.map"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("index.html", &EMBEDS["index.html"]);
+    builder.add_embedded("staging.html", &EMBEDS["index.html"]).with_noindex();
+    builder.add_embedded("export.html", &EMBEDS["index.html"]).with_download("Export.html");
+    builder.add_embedded("index.tpl", &EMBEDS["index.html"]).with_content_type("text/html");
+    builder.add_embedded("bundle.js.map", &EMBEDS["bundle.8f29ad31.js.map"]);
+    builder.set_source_map_policy(SourceMapPolicy::WithHeader {
+        name: "x-sourcemap-token".into(),
+        value: "secret".into(),
+    });
+    builder.set_request_rewriter(|path| match path.strip_prefix("de/") {
+        Some(rest) => rest.to_owned().into(),
+        None => path.into(),
+    });
+    let assets = builder.build().await?;
+
+    let res = assets.serve(&Request::get("/index.html")).await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.header("Content-Type"), Some("text/html; charset=utf-8"));
+    assert_eq!(res.header("cache-control"), Some("no-cache"));
+    assert_eq!(res.header("x-robots-tag"), None);
+    assert_eq!(res.header("content-disposition"), None);
+    assert_eq!(res.body(), &std::fs::read("examples/assets/index.html")?);
+
+    let res = assets.serve(&Request::get("/staging.html")).await;
+    assert_eq!(res.header("x-robots-tag"), Some("noindex"));
+
+    assert_eq!(assets.get("index.html").unwrap().content_type(), "text/html; charset=utf-8");
+    assert_eq!(assets.get("bundle.js.map").unwrap().content_type(), "application/json");
+    assert_eq!(assets.get("index.tpl").unwrap().content_type(), "text/html");
+    let res = assets.serve(&Request::get("/index.tpl")).await;
+    assert_eq!(res.header("content-type"), Some("text/html"));
+
+    let res = assets.serve(&Request::get("/export.html")).await;
+    assert_eq!(res.header("content-disposition"), Some(r#"attachment; filename="Export.html""#));
+
+    // Rewritten paths
+    let res = assets.serve(&Request::get("/de/index.html?foo=bar")).await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.body(), &std::fs::read("examples/assets/index.html")?);
+    assert!(assets.get("de/index.html").is_none());
+
+    let res = assets.serve(&Request::new("HEAD", "/index.html")).await;
+    assert_eq!(res.status(), 200);
+    assert!(res.body().is_empty());
+
+    let res = assets.serve(&Request::new("POST", "/index.html")).await;
+    assert_eq!(res.status(), 405);
+    assert_eq!(res.header("allow"), Some("GET, HEAD"));
+    let res = assets.serve(&Request::new("OPTIONS", "/index.html")).await;
+    assert_eq!(res.status(), 204);
+    assert_eq!(res.header("allow"), Some("GET, HEAD"));
+    assert!(res.body().is_empty());
+    assert_eq!(assets.serve(&Request::new("OPTIONS", "/index.htm")).await.status(), 404);
+    assert_eq!(assets.serve(&Request::new("DELETE", "/index.htm")).await.status(), 404);
+
+    let res = assets.serve(&Request::get("/index.htm")).await;
+    assert_eq!(res.status(), 404);
+    #[cfg(dev_mode)]
+    assert!(std::str::from_utf8(res.body())?.contains("/index.html"));
+
+    // Source maps
+    assert_eq!(assets.serve(&Request::get("/bundle.js.map")).await.status(), 404);
+    let req = Request::get("/bundle.js.map").with_header("X-Sourcemap-Token", "secret");
+    assert_eq!(assets.serve(&req).await.status(), 200);
+    assert!(assets.get("bundle.js.map").is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn profiles() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["peter.txt"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.set_profile(Some("staging"));
+    builder.add_embedded("peter.txt", &EMBEDS["peter.txt"]);
+    builder.profile("staging", |b| {
+        b.add_embedded("staging.txt", &EMBEDS["peter.txt"]);
+    });
+    builder.profile("prod", |b| {
+        b.add_embedded("prod.txt", &EMBEDS["peter.txt"]);
+    });
+    assert_eq!(builder.active_profile(), Some("staging"));
+    let assets = builder.build().await?;
+
+    assert!(assets.get("peter.txt").is_some());
+    assert!(assets.get("staging.txt").is_some());
+    assert!(assets.get("prod.txt").is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn inspect_builder() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "examples/assets",
+        files: ["index.html", "fonts/*.woff2"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("index.html", &EMBEDS["index.html"])
+        .with_path_fixup(["fonts/a.woff2"])
+        .with_template();
+    builder.add_embedded("fonts/", &EMBEDS["fonts/*.woff2"]).internal();
+    builder.add_file("peter.txt", "tests/files/peter.txt");
+
+    let entries = builder.entries().collect::<Vec<_>>();
+    assert_eq!(entries.len(), 3);
+    assert!(!entries[0].is_glob());
+    assert!(entries[0].has_modifier());
+    assert!(entries[0].is_template());
+    assert_eq!(entries[0].modifier_dependencies(), ["fonts/a.woff2"]);
+    assert!(entries[1].is_glob());
+    assert!(entries[1].is_internal());
+    assert!(!entries[1].has_modifier());
+    assert_eq!(entries[2].fs_paths(), [std::path::Path::new("tests/files/peter.txt")]);
+    #[cfg(prod_mode)]
+    assert!(entries[0].fs_paths().is_empty());
+
+    for entry in builder.entries_mut() {
+        entry.with_noindex();
+    }
+    assert!(builder.entries().all(|e| !e.is_hashed()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn source_maps_hidden_for_all_methods() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::{Request, SourceMapPolicy};
+
+    let mut builder = Assets::builder();
+    builder.add_bytes("app.js", "app");
+    builder.add_bytes("app.js.map", "{}");
+    builder.set_source_map_policy(SourceMapPolicy::Never);
+    let assets = builder.build().await?;
+
+    // Existing and missing source maps are indistinguishable.
+    for method in ["GET", "HEAD", "OPTIONS", "POST"] {
+        for path in ["/app.js.map", "/missing.js.map"] {
+            let res = assets.serve(&Request::new(method, path)).await;
+            assert_eq!(res.status(), 404, "{method} {path}");
+            assert_eq!(res.header("allow"), None, "{method} {path}");
+        }
+    }
+    assert_eq!(assets.serve(&Request::new("OPTIONS", "/app.js")).await.status(), 204);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn merge_builders() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::{Request, SourceMapPolicy};
+
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["peter.txt"],
+    };
+
+    let mut a = Assets::builder();
+    a.add_embedded("a.txt", &EMBEDS["peter.txt"]);
+
+    let mut b = Assets::builder();
+    b.add_embedded("b.txt", &EMBEDS["peter.txt"]);
+    b.add_embedded("b.txt.map", &EMBEDS["peter.txt"]);
+    b.set_source_map_policy(SourceMapPolicy::Never);
+    let b2 = b.clone();
+
+    a.merge(b);
+    a.merge(b2);
+    let assets = a.build().await?;
+    assert_eq!(assets.len(), 3);
+    assert!(assets.get("a.txt").is_some());
+    assert!(assets.get("b.txt").is_some());
+    assert_eq!(assets.serve(&Request::get("b.txt.map")).await.status(), 404);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn build_error_context() -> Result<(), Box<dyn std::error::Error>> {
+    let mut builder = Assets::builder();
+    builder.add_file("missing.txt", "tests/files/does-not-exist.txt");
+
+    #[cfg(prod_mode)]
+    {
+        let err = builder.build().await.unwrap_err();
+        assert!(err.is_not_found());
+        assert_eq!(err.http_path(), Some("missing.txt"));
+        assert_eq!(err.fs_path(), Some(std::path::Path::new("tests/files/does-not-exist.txt")));
+        assert!(std::error::Error::source(&err).is_some());
+        assert!(err.to_string().contains("missing.txt"));
+    }
+    #[cfg(dev_mode)]
+    {
+        let assets = builder.build().await?;
+        assert!(assets.get("missing.txt").unwrap().content().await.is_err());
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn optional_entries() -> Result<(), Box<dyn std::error::Error>> {
+    let mut builder = Assets::builder();
+    builder.add_file("peter.txt", "tests/files/peter.txt").optional();
+    builder.add_file("theme.css", "tests/files/does-not-exist.css").optional();
+    let assets = builder.build().await?;
+
+    assert_eq!(assets.len(), 1);
+    assert!(assets.get("peter.txt").is_some());
+    assert!(assets.get("theme.css").is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unreferenced() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["template.html", "peter.txt"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.warn_unreferenced();
+    builder.add_embedded("index.html", &EMBEDS["template.html"]).with_template();
+    builder.add_embedded("style.css", &EMBEDS["peter.txt"]);
+    builder.add_embedded("unused.txt", &EMBEDS["peter.txt"]);
+    builder.add_embedded("fixup.txt", &EMBEDS["peter.txt"]).with_path_fixup(["used.txt"]);
+    builder.add_embedded("used.txt", &EMBEDS["peter.txt"]);
+    let assets = builder.build().await?;
+
+    assert_eq!(assets.unreferenced().await, ["fixup.txt", "index.html", "unused.txt"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn embedded_size_by_entry() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "examples/assets",
+        files: ["robots.txt", "fonts/*.woff2"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("robots.txt", &EMBEDS["robots.txt"]);
+    builder.add_embedded("fonts/", &EMBEDS["fonts/*.woff2"]);
+    builder.add_file("style.css", "examples/assets/style.css");
+    let assets = builder.build().await?;
+
+    let sizes = assets.embedded_size_by_entry();
+    assert_eq!(sizes.len(), 2);
+    let fonts = sizes.iter().find(|s| s.http_path() == "fonts/").unwrap();
+    assert_eq!(fonts.embed_pattern(), "fonts/*.woff2");
+    assert_eq!(fonts.files(), EMBEDS["fonts/*.woff2"].files().count());
+    assert_eq!(fonts.bytes(), EMBEDS["fonts/*.woff2"].embedded_size());
+    assert!(sizes.windows(2).all(|w| w[0].bytes() >= w[1].bytes()));
+
+    if cfg!(prod_mode) {
+        assert!(fonts.bytes() > 0);
+    } else {
+        assert!(sizes.iter().all(|s| s.bytes() == 0));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg(all(feature = "testing", feature = "hash"))]
+async fn test_server() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::testing::TestServer;
+
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "examples/assets",
+        files: ["style.css", "fonts/*.woff2"],
+    };
+    const INDEX: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["template.html"],
+    };
+
+    let build = |fixup: bool| {
+        let mut builder = Assets::builder();
+        builder.add_embedded("index.html", &INDEX["template.html"]).with_template();
+        let font_paths = builder.add_embedded("static/font/open-sans/", &EMBEDS["fonts/*.woff2"])
+            .with_hash()
+            .http_paths();
+        let css = builder.add_embedded("style.css", &EMBEDS["style.css"]).with_hash();
+        if fixup {
+            css.with_path_fixup(font_paths);
+        }
+        builder.build()
+    };
+
+    let server = TestServer::start(build(true).await?).await?;
+    assert!(server.url("/index.html").ends_with("/index.html"));
+    let index = server.get("index.html").await?;
+    assert_eq!(index.status(), 200);
+    assert_eq!(index.header("content-type"), Some("text/html; charset=utf-8"));
+    assert_eq!(server.check_references(&["index.html"]).await?, []);
+
+    let broken = server.check_references(&["nope.html"]).await?;
+    assert_eq!(broken.len(), 1);
+    assert_eq!(broken[0].page, None);
+    assert_eq!(broken[0].status, 404);
+
+    // Without the fixup, the CSS file references the unhashed font paths.
+    let server = TestServer::start(build(false).await?).await?;
+    let broken = server.check_references(&["index.html"]).await?;
+    if cfg!(prod_mode) {
+        assert_eq!(broken.len(), 4);
+        assert!(broken.iter().all(|b| b.status == 404));
+        assert!(broken[0].page.as_deref().unwrap().starts_with("style."));
+        assert!(broken[0].reference.starts_with("static/font/open-sans/"));
+    } else {
+        assert_eq!(broken, []);
+    }
+
+    // Invalid responses are answered with an error instead of dropping the
+    // connection.
+    let mut builder = Assets::builder();
+    builder.add_bytes("bad.txt", "bad").with_content_type("text/plain\nx");
+    let server = TestServer::start(builder.build().await?).await?;
+    let res = server.get("bad.txt").await?;
+    assert_eq!(res.status(), 500);
+    assert!(std::str::from_utf8(res.body())?.contains("invalid response created by `Assets::serve`"));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg(feature = "hash")]
+async fn check_links() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "examples/assets",
+        files: ["style.css", "fonts/*.woff2"],
+    };
+    const PAGES: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["links.html"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("docs/links.html", &PAGES["links.html"]);
+    builder.add_embedded("static/font/open-sans/", &EMBEDS["fonts/*.woff2"]).with_hash();
+    builder.add_embedded("style.css", &EMBEDS["style.css"]);
+    let assets = builder.build().await?;
+
+    let broken = assets.check_links().await?;
+    let html = broken.iter().filter(|b| b.asset == "docs/links.html").collect::<Vec<_>>();
+    assert_eq!(html.len(), 1);
+    assert_eq!(html[0].reference, "img/missing.png");
+    assert_eq!(html[0].resolved, "docs/img/missing.png");
+
+    // The fonts are hashed, but `style.css` has no path fixup.
+    let css = broken.iter().filter(|b| b.asset == "style.css").count();
+    assert_eq!(css, if cfg!(prod_mode) { 4 } else { 0 });
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn base_href_and_canonical_link() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["links.html"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.set_public_url("https://example.com/docs");
+    builder.add_embedded("guide/index.html", &EMBEDS["links.html"])
+        .with_base_href()
+        .with_canonical_link();
+    builder.add_embedded("plain.html", &EMBEDS["links.html"]);
+    let assets = builder.build().await?;
+
+    let html = assets.get("guide/index.html").unwrap().content().await?;
+    let html = std::str::from_utf8(&html)?;
+    assert!(html.contains(concat!(
+        r#"<head><base href="/docs/">"#,
+        r#"<link rel="canonical" href="https://example.com/docs/guide/index.html">"#,
+    )));
+
+    let plain = assets.get("plain.html").unwrap().content().await?;
+    assert_eq!(plain, std::fs::read("tests/files/links.html")?);
+
+    // Missing public URL.
+    let mut builder = Assets::builder();
+    builder.add_embedded("guide/index.html", &EMBEDS["links.html"]).with_base_href();
+    let err = builder.build().await.unwrap_err();
+    assert!(matches!(err, reinda::BuildError::MissingPublicUrl { .. }));
+    assert_eq!(err.http_path(), Some("guide/index.html"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn normalize_text() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["bom-crlf.css", "peter.txt"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("normalized.css", &EMBEDS["bom-crlf.css"]).normalize_text();
+    builder.add_embedded("original.css", &EMBEDS["bom-crlf.css"]);
+    let assets = builder.build().await?;
+
+    let normalized = assets.get("normalized.css").unwrap().content().await?;
+    assert_eq!(normalized, "body {\n    margin: 0;\n}\n");
+    let original = assets.get("original.css").unwrap().content().await?;
+    assert_eq!(original, std::fs::read("tests/files/bom-crlf.css")?);
+
+    let mut builder = Assets::builder();
+    builder.normalize_text();
+    builder.add_embedded("style.css", &EMBEDS["bom-crlf.css"]);
+    builder.add_embedded("peter.txt", &EMBEDS["peter.txt"]);
+    let assets = builder.build().await?;
+    assert_eq!(assets.get("style.css").unwrap().content().await?, normalized);
+    assert_eq!(
+        assets.get("peter.txt").unwrap().content().await?,
+        std::fs::read("tests/files/peter.txt")?,
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn banner_prepend_append() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["peter.txt"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.set_banner("my-app v1.2.3");
+    builder.add_embedded("peter.js", &EMBEDS["peter.txt"])
+        .prepend("// start\n")
+        .append("// end\n");
+    builder.add_embedded("peter.txt", &EMBEDS["peter.txt"]);
+    let assets = builder.build().await?;
+
+    let peter = std::fs::read_to_string("tests/files/peter.txt")?;
+    let js = assets.get("peter.js").unwrap().content().await?;
+    let banner = if cfg!(prod_mode) { "/*! my-app v1.2.3 */\n" } else { "" };
+    assert_eq!(js, format!("{banner}// start\n{peter}// end\n"));
+    assert_eq!(assets.get("peter.txt").unwrap().content().await?, peter);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn prepend_append_sources() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["peter.txt", "bom-crlf.css"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("license.txt", &EMBEDS["peter.txt"])
+        .internal()
+        .prepend("/* ")
+        .append(" */\n");
+    builder.add_embedded("style.css", &EMBEDS["bom-crlf.css"])
+        .normalize_text()
+        .prepend_asset("license.txt")
+        .append_file("tests/files/peter.txt");
+    let assets = builder.build().await?;
+
+    let peter = std::fs::read_to_string("tests/files/peter.txt")?;
+    let css = assets.get("style.css").unwrap().content().await?;
+    assert_eq!(css, format!("/* {peter} */\nbody {{\n    margin: 0;\n}}\n{peter}"));
+    assert!(assets.get("license.txt").is_none());
+    assert_eq!(assets.unreferenced().await, ["style.css"]);
+
+    // Prepending and appending each other is a cycle.
+    let mut builder = Assets::builder();
+    builder.add_bytes("a.txt", "a").prepend_asset("b.txt");
+    builder.add_bytes("b.txt", "b").append_asset("a.txt");
+    #[cfg(prod_mode)]
+    assert!(matches!(builder.build().await, Err(reinda::BuildError::CyclicDependencies(_))));
+    #[cfg(dev_mode)]
+    {
+        let err = builder.build().await?.get("a.txt").unwrap().content().await.unwrap_err();
+        assert!(err.to_string().contains("cyclic"));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn prepend_missing_asset() {
+    let mut builder = Assets::builder();
+    builder.add_bytes("a.txt", "a").prepend_asset("license.txt");
+    assert!(matches!(
+        builder.build().await,
+        Err(reinda::BuildError::MissingDependency { asset, dependency })
+            if asset == "a.txt" && dependency == "license.txt"
+    ));
+
+    let mut builder = Assets::builder();
+    builder.add_bytes("b.txt", "b").append_asset("footer.txt");
+    let err = builder.build().await.unwrap_err();
+    assert_eq!(err.http_path(), Some("b.txt"));
+    assert_eq!(err.to_string(), "asset 'b.txt' specified dependency 'footer.txt', but that asset does not exist");
+}
+
+#[tokio::test]
+async fn verbatim() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::Request;
+
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "examples/assets",
+        files: ["fonts/*.woff2", "bundle.*.js"],
+    };
+    const CSS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["bom-crlf.css"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.set_banner("banner");
+    builder.normalize_text();
+    builder.add_embedded("fonts/", &EMBEDS["fonts/*.woff2"]).verbatim();
+    builder.add_embedded("", &EMBEDS["bundle.*.js"]).verbatim();
+    builder.add_embedded("style.css", &CSS["bom-crlf.css"]).verbatim();
+    builder.add_file("logo.svg", "examples/assets/logo.svg").verbatim();
+    builder.add_embedded("processed.css", &CSS["bom-crlf.css"]);
+    assert!(builder.entries().take(4).all(|e| e.is_verbatim()));
+    let assets = builder.build().await?;
+
+    let expected = [
+        ("fonts/latin-400.woff2", "examples/assets/fonts/latin-400.woff2"),
+        ("fonts/latin-i700.woff2", "examples/assets/fonts/latin-i700.woff2"),
+        ("bundle.8f29ad31.js", "examples/assets/bundle.8f29ad31.js"),
+        ("style.css", "tests/files/bom-crlf.css"),
+        ("logo.svg", "examples/assets/logo.svg"),
+    ];
+    for (http_path, fs_path) in expected {
+        let original = std::fs::read(fs_path)?;
+        assert_eq!(assets.get(http_path).unwrap().content().await?, original, "{http_path}");
+        let response = assets.serve(&Request::get(http_path)).await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.body(), &original, "{http_path}");
+    }
+
+    let processed = assets.get("processed.css").unwrap().content().await?;
+    assert_ne!(processed, std::fs::read("tests/files/bom-crlf.css")?);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[should_panic(expected = "is marked as verbatim")]
+async fn verbatim_with_processing() {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["peter.txt"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("peter.txt", &EMBEDS["peter.txt"]).verbatim().append("!");
+    let _ = builder.build().await;
+}
+
+#[tokio::test]
+async fn acme_challenges() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::Request;
+
+    let dir = std::env::temp_dir().join(format!("reinda-test-acme-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+
+    let mut builder = Assets::builder();
+    builder.add_acme_dir(&dir);
+    let assets = builder.build().await?;
+
+    // Files created after building are served as well.
+    std::fs::write(dir.join("tok3n_-x"), "tok3n_-x.thumbprint")?;
+    let res = assets.serve(&Request::get("/.well-known/acme-challenge/tok3n_-x")).await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.body(), "tok3n_-x.thumbprint");
+    assert_eq!(res.header("cache-control"), Some("no-store"));
+    assert!(assets.get(".well-known/acme-challenge/tok3n_-x").is_none());
+
+    let res = assets.serve(&Request::get("/.well-known/acme-challenge/missing")).await;
+    assert_eq!(res.status(), 404);
+    let res = assets.serve(&Request::get("/.well-known/acme-challenge/../secret")).await;
+    assert_eq!(res.status(), 404);
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn multiple_base_paths() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: ["tests/files", "examples/assets"],
+        files: ["robots.txt", "*.css"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("robots.txt", &EMBEDS["robots.txt"]);
+    builder.add_embedded("", &EMBEDS["*.css"]);
+    let assets = builder.build().await?;
+
+    // The first base path containing the file wins.
+    let robots = assets.get("robots.txt").unwrap().content().await?;
+    assert_eq!(robots, std::fs::read("tests/files/robots.txt")?);
+    assert_eq!(assets.iter().map(|(path, _)| path).collect::<Vec<_>>(), [
+        "bom-crlf.css",
+        "robots.txt",
+        "style.css",
+    ]);
+    let style = assets.get("style.css").unwrap().content().await?;
+    assert_eq!(style, std::fs::read("examples/assets/style.css")?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn base_path_env_var() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["peter.txt", "*.css"],
+    };
+
+    let dir = std::env::temp_dir().join(format!("reinda-test-base-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("peter.txt"), "overridden")?;
+
+    // A custom name is used so that other tests are not affected.
+    std::env::set_var("REINDA_TEST_BASE_PATH", std::env::join_paths([dir.clone(), "tests/files".into()])?);
+    let mut builder = Assets::builder();
+    builder.set_base_path_env_var("REINDA_TEST_BASE_PATH");
+    builder.add_embedded("peter.txt", &EMBEDS["peter.txt"]);
+    builder.add_embedded("", &EMBEDS["*.css"]);
+    let assets = builder.build().await?;
+
+    let peter = assets.get("peter.txt").unwrap().content().await?;
+    let css = assets.get("bom-crlf.css").unwrap().content().await?;
+    assert_eq!(css, std::fs::read("tests/files/bom-crlf.css")?);
+    if cfg!(dev_mode) {
+        assert_eq!(peter, "overridden");
+    } else {
+        assert_eq!(peter, std::fs::read("tests/files/peter.txt")?);
+    }
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[cfg(feature = "hash")]
+#[tokio::test]
+async fn renamed() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::Request;
+
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "examples/assets",
+        files: ["style.css", "robots.txt"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("static/style.css", &EMBEDS["style.css"]).with_hash();
+    builder.add_embedded("robots.txt", &EMBEDS["robots.txt"]);
+    builder.add_renamed("css/main.css", "static/style.css");
+    builder.add_renamed("robots-old.txt", "robots.txt");
+    let assets = builder.build().await?;
+
+    let hashed_style = assets.iter().map(|(path, _)| path).find(|p| p.ends_with(".css")).unwrap();
+    let res = assets.serve(&Request::get("/css/main.css")).await;
+    assert_eq!(res.status(), 308);
+    assert_eq!(res.header("location"), Some(&*format!("../{hashed_style}")));
+    let res = assets.serve(&Request::get("/robots-old.txt?x=1")).await;
+    assert_eq!(res.status(), 308);
+    assert_eq!(res.header("location"), Some("robots.txt"));
+    assert!(assets.get("css/main.css").is_none());
+
+    // All methods share the lookup, including redirects.
+    let res = assets.serve(&Request::new("OPTIONS", "/robots-old.txt")).await;
+    assert_eq!(res.status(), 308);
+    assert_eq!(res.header("location"), Some("robots.txt"));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[should_panic = "but that asset does not exist"]
+async fn renamed_to_missing() {
+    let mut builder = Assets::builder();
+    builder.add_renamed("old.txt", "new.txt");
+    let _ = builder.build().await;
+}
+
+#[tokio::test]
+async fn image_variants() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::Request;
+
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["img/pixel.png", "img/pixel.avif", "img/pixel.webp"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("img/pixel.png", &EMBEDS["img/pixel.png"])
+        .with_variant("img/pixel.avif")
+        .with_variant("img/pixel.webp");
+    builder.add_embedded("img/pixel.avif", &EMBEDS["img/pixel.avif"]);
+    builder.add_embedded("img/pixel.webp", &EMBEDS["img/pixel.webp"]);
+    let assets = builder.build().await?;
+
+    let serve = |accept: &'static str| {
+        let assets = assets.clone();
+        async move {
+            let req = Request::get("/img/pixel.png").with_header("accept", accept);
+            assets.serve(&req).await
+        }
+    };
+
+    let res = serve("image/avif,image/webp,*/*;q=0.8").await;
+    assert_eq!(res.body(), "avif-pixel");
+    assert_eq!(res.header("content-type"), Some("image/avif"));
+    assert_eq!(res.header("vary"), Some("accept"));
+    assert_eq!(serve("image/webp,*/*").await.body(), "webp-pixel");
+    let res = serve("image/*").await;
+    assert_eq!(res.body(), "png-pixel");
+    assert_eq!(res.header("content-type"), Some("image/png"));
+    assert_eq!(res.header("vary"), Some("accept"));
+
+    // Variants are still served under their own path.
+    let res = assets.serve(&Request::get("/img/pixel.webp")).await;
+    assert_eq!(res.body(), "webp-pixel");
+    assert_eq!(res.header("vary"), None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn missing_variant() {
+    let mut builder = Assets::builder();
+    builder.add_bytes("theme.css", "a").with_labeled_variant("dark", "theme-dark.css");
+    let err = builder.build().await.unwrap_err();
+    assert!(matches!(
+        &err,
+        reinda::BuildError::MissingDependency { asset, dependency }
+            if asset == "theme.css" && dependency == "theme-dark.css"
+    ));
+    assert_eq!(err.http_path(), Some("theme.css"));
+}
+
+#[tokio::test]
+async fn variant_selector() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::Request;
+
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["theme/theme.css", "theme/theme-dark.css"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("theme.css", &EMBEDS["theme/theme.css"])
+        .with_labeled_variant("dark", "theme-dark.css");
+    builder.add_embedded("theme-dark.css", &EMBEDS["theme/theme-dark.css"]);
+    builder.set_variant_selector("cookie", |req, variants| {
+        let dark = req.header("cookie").is_some_and(|c| c.contains("theme=dark"));
+        variants.iter().position(|v| dark && v.label() == Some("dark"))
+    });
+    let assets = builder.build().await?;
+
+    let req = Request::get("/theme.css").with_header("cookie", "theme=dark");
+    let res = assets.serve(&req).await;
+    assert_eq!(res.body(), "body { color: white; }\n");
+    assert_eq!(res.header("content-type"), Some("text/css; charset=utf-8"));
+    assert_eq!(res.header("vary"), Some("cookie"));
+
+    let res = assets.serve(&Request::get("/theme.css").with_header("accept", "text/css")).await;
+    assert_eq!(res.body(), "body { color: black; }\n");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn client_hints() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::Request;
+
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["img/pixel.png", "img/pixel.webp", "img/pixel@2x.png", "img/pixel@3x.png", "img/pixel-small.png"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.use_client_hints();
+    builder.add_embedded("pixel.png", &EMBEDS["img/pixel.png"])
+        .with_variant("pixel.webp")
+        .with_dpr_variant(2.0, "pixel@2x.png")
+        .with_dpr_variant(3.0, "pixel@3x.png")
+        .with_dpr_variant(0.5, "pixel-small.png");
+    for name in [".webp", "@2x.png", "@3x.png", "-small.png"] {
+        let http_path = format!("pixel{name}");
+        builder.add_embedded(http_path, &EMBEDS[&*format!("img/pixel{name}")]);
+    }
+    let assets = builder.build().await?;
+
+    let serve = |headers: &[(&'static str, &'static str)]| {
+        let (assets, headers) = (assets.clone(), headers.to_vec());
+        async move {
+            let mut req = Request::get("/pixel.png");
+            for (name, value) in headers {
+                req = req.with_header(name, value);
+            }
+            assets.serve(&req).await.body().clone()
+        }
+    };
+
+    assert_eq!(serve(&[]).await, "png-pixel");
+    assert_eq!(serve(&[("accept", "image/webp")]).await, "webp-pixel");
+    assert_eq!(serve(&[("accept", "image/webp"), ("sec-ch-dpr", "1")]).await, "webp-pixel");
+    assert_eq!(serve(&[("sec-ch-dpr", "2")]).await, "png-pixel-2x-bigger");
+    assert_eq!(serve(&[("sec-ch-dpr", "2.5")]).await, "png-pixel-3x-even-bigger");
+    assert_eq!(serve(&[("dpr", "4")]).await, "png-pixel-3x-even-bigger");
+    assert_eq!(serve(&[("sec-ch-dpr", "2"), ("save-data", "on")]).await, "small");
+
+    let res = assets.serve(&Request::get("/pixel.png")).await;
+    assert_eq!(res.header("vary"), Some("accept, save-data, sec-ch-dpr, dpr"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn save_data_preloads() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::Request;
+
+    let mut builder = Assets::builder();
+    builder.set_save_data_preload_limit(10);
+    builder.add_bytes("index.html", concat!(
+        r#"<link rel="preload" href="big.png" as="image">"#,
+        r#"<link rel="preload" href="/small.png" as="image">"#,
+        r#"<link rel="prefetch" href="https://example.com/big.png">"#,
+    ));
+    builder.add_bytes("big.png", "a-rather-big-image");
+    builder.add_bytes("small.png", "tiny");
+    let assets = builder.build().await?;
+
+    let full = assets.serve(&Request::get("/index.html")).await;
+    assert_eq!(*full.body(), assets.get("index.html").unwrap().content().await?);
+    assert_eq!(full.header("vary"), Some("save-data"));
+
+    let req = Request::get("/index.html").with_header("save-data", "on");
+    let saved = assets.serve(&req).await;
+    assert_eq!(saved.body(), concat!(
+        r#"<link rel="preload" href="/small.png" as="image">"#,
+        r#"<link rel="prefetch" href="https://example.com/big.png">"#,
+    ));
+    assert_eq!(saved.header("vary"), Some("save-data"));
+    #[cfg(feature = "hash")]
+    assert_ne!(saved.header("etag"), full.header("etag"));
+
+    let head = assets.serve(&Request::new("HEAD", "/index.html").with_header("save-data", "on")).await;
+    assert_eq!(head.header("content-length"), Some(&*saved.body().len().to_string()));
+
+    let res = assets.serve(&Request::get("/big.png").with_header("save-data", "on")).await;
+    assert_eq!(res.header("vary"), None);
+
+    Ok(())
+}
+
+#[cfg(feature = "hash")]
+#[tokio::test]
+async fn cache_key() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::Request;
+
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["img/pixel.png", "img/pixel.webp"],
+    };
+
+    let build = || async {
+        let mut builder = Assets::builder();
+        builder.set_cache_key_header("x-cache-key");
+        builder.add_embedded("pixel.png", &EMBEDS["img/pixel.png"]).with_variant("pixel.webp");
+        builder.add_embedded("pixel.webp", &EMBEDS["img/pixel.webp"]);
+        builder.build().await
+    };
+    let (a, b) = (build().await?, build().await?);
+
+    // Independent of the instance, but dependent on the content.
+    let key = a.get("pixel.png").unwrap().cache_key().await?;
+    assert_eq!(key.len(), 32);
+    assert_eq!(key, b.get("pixel.png").unwrap().cache_key().await?);
+    let webp_key = a.get("pixel.webp").unwrap().cache_key().await?;
+    assert_ne!(key, webp_key);
+
+    let res = a.serve(&Request::get("/pixel.png")).await;
+    assert_eq!(res.header("x-cache-key"), Some(&*key));
+    let res = a.serve(&Request::get("/pixel.png").with_header("accept", "image/webp")).await;
+    assert_eq!(res.header("x-cache-key"), Some(&*webp_key));
+
+    // The encoding is part of the key, like in the ETag.
+    #[cfg(feature = "compress")]
+    {
+        let mut config = reinda::CompressionConfig::default();
+        config.min_size = 100;
+        let mut builder = Assets::builder();
+        builder.set_compression(config);
+        builder.set_cache_key_header("x-cache-key");
+        builder.add_bytes("a.txt", "a".repeat(1000));
+        let assets = builder.build().await?;
+        let key = assets.get("a.txt").unwrap().cache_key().await?;
+        let res = assets.serve(&Request::get("/a.txt")).await;
+        assert_eq!(res.header("x-cache-key"), Some(&*key));
+        let res = assets.serve(&Request::get("/a.txt").with_header("accept-encoding", "gzip")).await;
+        assert_eq!(res.header("content-encoding"), Some("gzip"));
+        assert_eq!(res.header("x-cache-key"), Some(&*format!("{key}-gzip")));
+        assert_eq!(res.header("etag"), Some(&*format!("\"{key}-gzip\"")));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cache_policy() -> Result<(), reinda::BuildError> {
+    use std::time::Duration;
+    use reinda::http::{CachePolicy, Request};
+
+    let mut policy = CachePolicy::default();
+    policy.max_age = Some(Duration::from_secs(60));
+    policy.s_maxage = Some(Duration::from_secs(3600));
+    policy.surrogate_max_age = Some(Duration::from_secs(86400));
+
+    let mut builder = Assets::builder();
+    builder.add_file("peter.txt", "tests/files/peter.txt").with_cache_policy(policy);
+    builder.add_file("robots.txt", "tests/files/robots.txt");
+    let assets = builder.build().await?;
+
+    let res = assets.serve(&Request::get("/peter.txt")).await;
+    assert_eq!(res.header("cache-control"), Some("public, max-age=60, s-maxage=3600"));
+    assert_eq!(res.header("surrogate-control"), Some("max-age=86400"));
+
+    let res = assets.serve(&Request::get("/robots.txt")).await;
+    assert_eq!(res.header("cache-control"), Some("no-cache"));
+    assert_eq!(res.header("surrogate-control"), None);
+
+    Ok(())
+}
+
+#[cfg(feature = "axum")]
+#[tokio::test]
+async fn axum_router() -> Result<(), Box<dyn std::error::Error>> {
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+
+    let mut builder = Assets::builder();
+    builder.add_file("peter.txt", "tests/files/peter.txt");
+    let assets = builder.build().await?;
+    let app = Router::new()
+        .route("/api", get(|| async { "api" }))
+        .merge(assets.into_axum_router());
+
+    let get = |path: &'static str| app.clone().oneshot(Request::get(path).body(Body::empty()).unwrap());
+    let res = get("/peter.txt").await?;
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.headers()["content-type"], "text/plain; charset=utf-8");
+    assert_eq!(res.headers()["cache-control"], "no-cache");
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await?;
+    assert_eq!(body, std::fs::read("tests/files/peter.txt")?);
+
+    assert_eq!(get("/api").await?.status(), 200);
+    assert_eq!(get("/missing.txt").await?.status(), 404);
+
+    Ok(())
+}
+
+#[cfg(feature = "actix")]
+#[tokio::test]
+async fn actix_service() -> Result<(), Box<dyn std::error::Error>> {
+    use actix_web::{test, web, App};
+
+    let mut builder = Assets::builder();
+    builder.add_file("peter.txt", "tests/files/peter.txt");
+    let assets = builder.build().await?;
+    let app = test::init_service(
+        App::new()
+            .route("/api", web::get().to(|| async { "api" }))
+            .service(web::scope("/static").default_service(assets.clone().into_actix_service()))
+            .default_service(assets.into_actix_service())
+    ).await;
+
+    for path in ["/peter.txt", "/static/peter.txt?v=1"] {
+        let res = test::call_service(&app, test::TestRequest::get().uri(path).to_request()).await;
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.headers().get("content-type").unwrap(), "text/plain; charset=utf-8");
+        assert_eq!(res.headers().get("cache-control").unwrap(), "no-cache");
+        let body = test::read_body(res).await;
+        assert_eq!(body, std::fs::read("tests/files/peter.txt")?);
+    }
+
+    let status = |path| {
+        let req = test::TestRequest::get().uri(path).to_request();
+        let res = test::call_service(&app, req);
+        async { res.await.status() }
+    };
+    assert_eq!(status("/api").await, 200);
+    assert_eq!(status("/missing.txt").await, 404);
+
+    Ok(())
+}
+
+#[cfg(feature = "hash")]
+#[tokio::test]
+async fn etag() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::Request;
+
+    let dir = std::env::temp_dir().join(format!("reinda-test-etag-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("data.txt");
+    std::fs::write(&path, "first")?;
+
+    let mut builder = Assets::builder();
+    builder.add_file("data.txt", path.to_str().unwrap());
+    let assets = builder.build().await?;
+    let asset = assets.get("data.txt").unwrap();
+
+    let etag = asset.etag().await?;
+    assert!(etag.starts_with('"') && etag.ends_with('"'));
+    assert!(asset.matches_etag(&etag).await?);
+    assert!(asset.matches_etag(&format!("\"other\", W/{etag}")).await?);
+    assert!(!asset.matches_etag("\"other\"").await?);
+
+    let res = assets.serve(&Request::get("/data.txt")).await;
+    assert_eq!(res.header("etag"), Some(&*etag));
+    let res = assets.serve(&Request::get("/data.txt").with_header("if-none-match", &etag)).await;
+    assert_eq!(res.status(), 304);
+    assert!(res.body().is_empty());
+    assert_eq!(res.header("etag"), Some(&*etag));
+
+    // In dev mode, the ETag follows the file.
+    std::fs::write(&path, "second")?;
+    assert_eq!(asset.etag().await? != etag, cfg!(dev_mode));
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn range_requests_and_streaming() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::Request;
+
+    let dir = std::env::temp_dir().join(format!("reinda-test-range-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("video.bin");
+    std::fs::write(&path, "0123456789")?;
+
+    let mut builder = Assets::builder();
+    builder.add_file("video.bin", path.to_str().unwrap());
+    let upper_path = dir.join("upper.txt");
+    std::fs::write(&upper_path, "abcdef")?;
+    builder.add_file("upper.txt", upper_path.to_str().unwrap())
+        .with_modifier([] as [&str; 0], |content, _| content.to_ascii_uppercase().into());
+    let assets = builder.build().await?;
+
+    let asset = assets.get("video.bin").unwrap();
+    assert_eq!(asset.content_len().await?, 10);
+    assert_eq!(asset.content_range(2..5).await?, "234");
+    assert_eq!(asset.content_range(8..20).await?, "89");
+    assert_eq!(asset.content_range(20..30).await?, "");
+    let upper = assets.get("upper.txt").unwrap();
+    assert_eq!(upper.content_len().await?, 6);
+    assert_eq!(upper.content_range(1..3).await?, "BC");
+
+    use tokio::io::AsyncReadExt;
+    let mut streamed = String::new();
+    asset.stream().await?.read_to_string(&mut streamed).await?;
+    assert_eq!(streamed, "0123456789");
+    let mut streamed = String::new();
+    upper.stream().await?.read_to_string(&mut streamed).await?;
+    assert_eq!(streamed, "ABCDEF");
+
+    let range = |path, range| {
+        let req = Request::get(path).with_header("range", range);
+        let assets = &assets;
+        async move { assets.serve(&req).await }
+    };
+    let res = range("video.bin", "bytes=2-4").await;
+    assert_eq!(res.status(), 206);
+    assert_eq!(res.body(), "234");
+    assert_eq!(res.header("content-range"), Some("bytes 2-4/10"));
+    let res = range("video.bin", "bytes=-3").await;
+    assert_eq!((res.status(), &**res.body()), (206, &b"789"[..]));
+    let res = range("upper.txt", "bytes=4-").await;
+    assert_eq!((res.status(), &**res.body()), (206, &b"EF"[..]));
+    let res = range("video.bin", "bytes=10-").await;
+    assert_eq!(res.status(), 416);
+    assert_eq!(res.header("content-range"), Some("bytes */10"));
+
+    // Ignored headers: multiple ranges, `If-Range`, invalid values.
+    let res = range("video.bin", "bytes=0-1,4-5").await;
+    assert_eq!((res.status(), &**res.body()), (200, &b"0123456789"[..]));
+    let req = Request::get("video.bin").with_header("range", "bytes=0-1").with_header("if-range", "\"x\"");
+    assert_eq!(assets.serve(&req).await.status(), 200);
+    let res = assets.serve(&Request::get("video.bin")).await;
+    assert_eq!(res.header("accept-ranges"), Some("bytes"));
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+/// Compares the public API (with the features used below) to the snapshot
+/// in `tests/public-api.txt`, so that changes to it are always deliberate.
+/// Needs a nightly toolchain for the rustdoc JSON output, so it has to be
+/// run explicitly: `cargo test --test main -- --ignored public_api`. Set
+/// `UPDATE_PUBLIC_API=1` to update the snapshot instead.
+#[test]
+#[ignore]
+fn public_api() -> Result<(), Box<dyn std::error::Error>> {
+    use serde_json::Value;
+
+    // Collects `(path, line)`, with the declaration of each item first.
+    fn walk(index: &Value, id: &Value, path: &str, out: &mut Vec<(String, u8, String)>) {
+        let Some(item) = index.get(id.to_string()) else { return };
+        let non_exhaustive = item["attrs"].to_string().contains("non_exhaustive");
+        let attrs = if non_exhaustive { "#[non_exhaustive] " } else { "" };
+        let (kind, inner) = item["inner"].as_object().and_then(|o| o.iter().next()).unwrap();
+        let child = |name: &Value| format!("{path}::{}", name.as_str().unwrap_or("_"));
+        let mut push = |order, line: String| out.push((path.to_owned(), order, line));
+        match &**kind {
+            "module" => {
+                push(0, format!("pub mod {path}"));
+                for id in inner["items"].as_array().unwrap() {
+                    let name = &index[id.to_string()]["name"];
+                    walk(index, id, &child(name), out);
+                }
+            }
+            "use" => {
+                // Re-exports have no name themselves, so `path` ends in `_`.
+                let parent = path.rsplit_once("::").map_or(path, |(parent, _)| parent);
+                let path = format!("{parent}::{}", inner["name"].as_str().unwrap());
+                match index.get(inner["id"].to_string()) {
+                    Some(_) => walk(index, &inner["id"], &path, out),
+                    None => out.push((path.clone(), 0, format!("pub use {path} = {}", inner["source"].as_str().unwrap()))),
+                }
+            }
+            "struct" | "enum" => {
+                push(0, format!("{attrs}pub {kind} {path}"));
+                let members = inner["kind"]["plain"]["fields"].as_array()
+                    .or_else(|| inner["kind"]["tuple"].as_array())
+                    .or_else(|| inner["variants"].as_array());
+                for id in members.into_iter().flatten().filter(|id| !id.is_null()) {
+                    let name = child(&index[id.to_string()]["name"]);
+                    let member = if kind == "enum" { "variant" } else { "pub field" };
+                    out.push((name.clone(), 0, format!("{member} {name}")));
+                }
+                for id in inner["impls"].as_array().unwrap() {
+                    let imp = &index[id.to_string()]["inner"]["impl"];
+                    if imp["is_synthetic"] == true || !imp["blanket_impl"].is_null() {
+                        continue;
+                    }
+                    match imp["trait"]["path"].as_str() {
+                        Some("StructuralPartialEq") => {}
+                        Some(name) => out.push((path.to_owned(), 1, format!("impl {name} for {path}"))),
+                        None => for id in imp["items"].as_array().unwrap() {
+                            let member = &index[id.to_string()];
+                            if member["visibility"] == "public" {
+                                walk(index, id, &child(&member["name"]), out);
+                            }
+                        },
+                    }
+                }
+            }
+            "function" => {
+                let asyncness = if inner["header"]["is_async"] == true { "async " } else { "" };
+                push(0, format!("pub {asyncness}fn {path}"));
+            }
+            "constant" => push(0, format!("pub const {path}")),
+            "trait" | "macro" | "type_alias" => push(0, format!("pub {kind} {path}")),
+            _ => {}
+        }
+    }
+
+    let target_dir = concat!(env!("CARGO_MANIFEST_DIR"), "/target/public-api");
+    let status = std::process::Command::new("cargo")
+        .args(["+nightly", "rustdoc", "--lib", "--features", "axum,actix,zstd,testing,watch,reload,url,runtime-mutation,minify,serde"])
+        .args(["--target-dir", target_dir, "--", "-Z", "unstable-options", "--output-format", "json"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .status()?;
+    assert!(status.success(), "building the rustdoc JSON failed");
+
+    let json = std::fs::read(format!("{target_dir}/doc/reinda.json"))?;
+    let json = serde_json::from_slice::<Value>(&json)?;
+    let mut out = vec![];
+    walk(&json["index"], &json["root"], "reinda", &mut out);
+    out.sort();
+    out.dedup();
+    let actual = out.into_iter().map(|(_, _, line)| line + "\n").collect::<String>();
+
+    let snapshot = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/public-api.txt");
+    if std::env::var_os("UPDATE_PUBLIC_API").is_some() {
+        std::fs::write(snapshot, &actual)?;
+    } else {
+        let expected = std::fs::read_to_string(snapshot)?;
+        assert!(actual == expected, "public API changed, run with `UPDATE_PUBLIC_API=1` \
+            and check the diff of `tests/public-api.txt`");
+    }
+
+    Ok(())
+}
+
+/// Builds a realistic asset set for the end-to-end tests: a page referring to
+/// a hashed stylesheet (via path fixup) which in turn refers to hashed fonts,
+/// all with precompression.
+#[cfg(all(feature = "compress", feature = "hash", any(feature = "axum", feature = "actix")))]
+async fn e2e_assets() -> Result<Assets, reinda::BuildError> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "examples/assets",
+        files: ["index.html", "robots.txt", "style.css", "fonts/*.woff2"],
+    };
+
+    let mut config = reinda::CompressionConfig::default();
+    config.min_size = 100;
+    config.gzip_level = 1;
+
+    let mut builder = Assets::builder();
+    builder.set_compression(config);
+    builder.add_embedded("robots.txt", &EMBEDS["robots.txt"]);
+    let font_paths = builder.add_embedded("static/font/open-sans/", &EMBEDS["fonts/*.woff2"])
+        .with_hash()
+        .http_paths();
+    let css_path = builder.add_embedded("static/style.css", &EMBEDS["style.css"])
+        .with_path_fixup(font_paths)
+        .with_hash()
+        .single_http_path()
+        .unwrap();
+    builder.add_embedded("index.html", &EMBEDS["index.html"])
+        .with_path_fixup([css_path.into_owned()]);
+    builder.build().await
+}
+
+/// Sends a request over a new connection with a minimal HTTP/1.1 client and
+/// returns status, headers (with lowercase names) and body. This uses a raw
+/// TCP connection to test the integrations exactly as browsers see them.
+#[cfg(all(feature = "compress", feature = "hash", any(feature = "axum", feature = "actix")))]
+async fn e2e_request(
+    addr: std::net::SocketAddr,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+) -> std::io::Result<(u16, Vec<(String, String)>, Vec<u8>)> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut req = format!("{method} {path} HTTP/1.1\r\nhost: {addr}\r\nconnection: close\r\n");
+    for (name, value) in headers {
+        req.push_str(&format!("{name}: {value}\r\n"));
+    }
+    req.push_str("\r\n");
+
+    let mut stream = tokio::net::TcpStream::connect(addr).await?;
+    stream.write_all(req.as_bytes()).await?;
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw).await?;
+
+    let split = raw.windows(4).position(|w| w == b"\r\n\r\n").expect("incomplete response");
+    let head = std::str::from_utf8(&raw[..split]).unwrap();
+    let mut lines = head.split("\r\n");
+    let status = lines.next().unwrap().split(' ').nth(1).unwrap().parse().unwrap();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.to_ascii_lowercase(), value.trim().to_owned()))
+        .collect::<Vec<_>>();
+
+    let mut body = raw[split + 4..].to_vec();
+    if headers.iter().any(|(n, v)| n == "transfer-encoding" && v == "chunked") {
+        let (mut rest, mut decoded) = (&body[..], Vec::new());
+        loop {
+            let line_end = rest.windows(2).position(|w| w == b"\r\n").unwrap();
+            let size = std::str::from_utf8(&rest[..line_end]).unwrap();
+            let size = usize::from_str_radix(size.split(';').next().unwrap().trim(), 16).unwrap();
+            if size == 0 {
+                break;
+            }
+            decoded.extend_from_slice(&rest[line_end + 2..][..size]);
+            rest = &rest[line_end + 2 + size + 2..];
+        }
+        body = decoded;
+    }
+
+    Ok((status, headers, body))
+}
+
+/// Checks the full request/response behavior of a server serving
+/// `e2e_assets` at `addr`.
+#[cfg(all(feature = "compress", feature = "hash", any(feature = "axum", feature = "actix")))]
+async fn e2e_check(addr: std::net::SocketAddr, assets: &Assets) -> Result<(), Box<dyn std::error::Error>> {
+    let header = |headers: &[(String, String)], name: &str| headers.iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.clone());
+
+    // The page refers to the stylesheet with its hashed path in prod mode.
+    let (status, headers, body) = e2e_request(addr, "GET", "/index.html", &[]).await?;
+    assert_eq!(status, 200);
+    assert_eq!(header(&headers, "content-type").as_deref(), Some("text/html; charset=utf-8"));
+    assert_eq!(header(&headers, "cache-control").as_deref(), Some("no-cache"));
+    let body = String::from_utf8(body)?;
+    let css_path = body.split("href=\"").nth(1).unwrap().split('"').next().unwrap().to_owned();
+    assert_eq!(css_path != "/static/style.css", cfg!(prod_mode));
+    assert_eq!(body.as_bytes(), &*assets.get("index.html").unwrap().content().await?);
+
+    // Precompressed variant with ETag, which allows conditional requests.
+    let accept = [("accept-encoding", "gzip")];
+    let (status, headers, body) = e2e_request(addr, "GET", &css_path, &accept).await?;
+    assert_eq!(status, 200);
+    assert_eq!(header(&headers, "content-encoding").as_deref(), Some("gzip"));
+    assert_eq!(header(&headers, "vary").as_deref(), Some("accept-encoding"));
+    let expected_cache_control = if cfg!(prod_mode) { "public, max-age=31536000, immutable" } else { "no-cache" };
+    assert_eq!(header(&headers, "cache-control").as_deref(), Some(expected_cache_control));
+    let css = assets.get(css_path.trim_start_matches('/')).unwrap();
+    assert_eq!(Some(body), css.encoded_content(reinda::Encoding::Gzip).await?.map(|b| b.to_vec()));
+
+    let etag = header(&headers, "etag").unwrap();
+    let conditional = [("accept-encoding", "gzip"), ("if-none-match", &*etag)];
+    let (status, headers, body) = e2e_request(addr, "GET", &css_path, &conditional).await?;
+    assert_eq!(status, 304);
+    assert!(body.is_empty());
+    assert_eq!(header(&headers, "etag"), Some(etag.clone()));
+    let other = [("if-none-match", &*etag)];
+    assert_eq!(e2e_request(addr, "GET", &css_path, &other).await?.0, 200);
+
+    // The fonts referenced by the stylesheet.
+    let css = String::from_utf8(css.content().await?.to_vec())?;
+    let font_path = css.split("url(").nth(1).unwrap().split(')').next().unwrap().trim_matches(['"', '\'']);
+    let font_path = format!("/{font_path}");
+    let (status, headers, _) = e2e_request(addr, "GET", &font_path, &accept).await?;
+    assert_eq!(status, 200);
+    assert_eq!(header(&headers, "content-type").as_deref(), Some("font/woff2"));
+
+    // Range, HEAD, missing assets and other methods.
+    let (status, headers, body) = e2e_request(addr, "GET", "/robots.txt", &[("range", "bytes=0-3")]).await?;
+    assert_eq!((status, &*body), (206, &assets.get("robots.txt").unwrap().content().await?[..4]));
+    assert_eq!(header(&headers, "content-range").map(|v| v.starts_with("bytes 0-3/")), Some(true));
+    let (status, headers, body) = e2e_request(addr, "HEAD", "/robots.txt", &[]).await?;
+    assert_eq!((status, body.len()), (200, 0));
+    let len = assets.get("robots.txt").unwrap().content_len().await?.to_string();
+    assert_eq!(header(&headers, "content-length"), Some(len));
+    assert_eq!(e2e_request(addr, "GET", "/missing.txt", &[]).await?.0, 404);
+    assert_eq!(e2e_request(addr, "POST", "/robots.txt", &[]).await?.0, 405);
+    assert_eq!(e2e_request(addr, "POST", "/missing.txt", &[]).await?.0, 404);
+    let (status, headers, _) = e2e_request(addr, "OPTIONS", "/robots.txt", &[]).await?;
+    assert_eq!((status, header(&headers, "allow")), (204, Some("GET, HEAD".into())));
+
+    Ok(())
+}
+
+#[cfg(all(feature = "compress", feature = "hash", feature = "axum"))]
+#[tokio::test]
+async fn e2e_axum() -> Result<(), Box<dyn std::error::Error>> {
+    let assets = e2e_assets().await?;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let app = assets.clone().into_axum_router();
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    e2e_check(addr, &assets).await?;
+    server.abort();
+    Ok(())
+}
+
+#[cfg(all(feature = "compress", feature = "hash", feature = "actix"))]
+#[tokio::test]
+async fn e2e_actix() -> Result<(), Box<dyn std::error::Error>> {
+    use actix_web::{App, HttpServer};
+
+    let assets = e2e_assets().await?;
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+
+    // actix-web needs its own runtime, so the server runs in its own thread.
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let server_assets = assets.clone();
+    let thread = std::thread::spawn(move || actix_web::rt::System::new().block_on(async move {
+        let server = HttpServer::new(move || App::new().default_service(server_assets.clone().into_actix_service()))
+            .workers(1)
+            .disable_signals()
+            .listen(listener)?
+            .run();
+        tx.send(server.handle()).unwrap();
+        server.await
+    }));
+    let handle = rx.await?;
+
+    e2e_check(addr, &assets).await?;
+    handle.stop(true).await;
+    thread.join().unwrap()?;
+    Ok(())
+}
+
+#[cfg(all(feature = "watch", dev_mode))]
+#[tokio::test]
+async fn watch_changes() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::watch::{Change, ChangeKind};
+    use std::time::Duration;
+    use tokio::sync::broadcast::error::RecvError;
+
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["theme/*.css"],
+    };
+
+    let dir = std::env::temp_dir().join(format!("reinda-test-watch-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("theme"))?;
+    std::fs::write(dir.join("theme/theme.css"), "body {}")?;
+    std::fs::write(dir.join("data.txt"), "first")?;
+    std::fs::create_dir_all(dir.join("static"))?;
+    std::fs::write(dir.join("static/a.txt"), "a")?;
+
+    // A custom name is used so that other tests are not affected.
+    std::env::set_var("REINDA_TEST_WATCH_BASE_PATH", &dir);
+    let mut builder = Assets::builder();
+    builder.set_base_path_env_var("REINDA_TEST_WATCH_BASE_PATH");
+    builder.add_embedded("css/", &EMBEDS["theme/*.css"]);
+    builder.add_file("data.txt", dir.join("data.txt"));
+    builder.add_dir("static/", dir.join("static"));
+    let assets = builder.build().await?;
+    let mut changes = assets.subscribe_changes();
+
+    // Give the watcher time to take its initial snapshot.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    async fn next(changes: &mut tokio::sync::broadcast::Receiver<Change>) -> (String, ChangeKind) {
+        let change = tokio::time::timeout(Duration::from_secs(5), changes.recv()).await.unwrap().unwrap();
+        (change.http_path, change.kind)
+    }
+
+    std::fs::write(dir.join("data.txt"), "second")?;
+    assert_eq!(next(&mut changes).await, ("data.txt".into(), ChangeKind::Modified));
+    std::fs::write(dir.join("theme/new.css"), "a {}")?;
+    assert_eq!(next(&mut changes).await, ("css/new.css".into(), ChangeKind::Added));
+    std::fs::remove_file(dir.join("theme/theme.css"))?;
+    assert_eq!(next(&mut changes).await, ("css/theme.css".into(), ChangeKind::Removed));
+    std::fs::create_dir_all(dir.join("static/sub"))?;
+    std::fs::write(dir.join("static/sub/b.txt"), "b")?;
+    assert_eq!(next(&mut changes).await, ("static/sub/b.txt".into(), ChangeKind::Added));
+    std::fs::write(dir.join("static/a.txt"), "aa")?;
+    assert_eq!(next(&mut changes).await, ("static/a.txt".into(), ChangeKind::Modified));
+
+    // Shutting down closes all receivers, also ones subscribing later.
+    assets.shutdown().await;
+    assert_eq!(changes.recv().await, Err(RecvError::Closed));
+    assert_eq!(assets.subscribe_changes().recv().await, Err(RecvError::Closed));
+    assert!(assets.get("data.txt").is_some());
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn defaults_for() {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["img/*.png", "theme/*.css", "peter.txt"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_file("before.css", "tests/files/theme/theme.css");
+    builder.defaults_for("text/css", |entry| {
+        entry.with_template();
+    });
+    builder.defaults_for("image/*", |entry| {
+        entry.internal();
+    });
+    builder.add_embedded("theme/", &EMBEDS["theme/*.css"]);
+    builder.add_embedded("img/", &EMBEDS["img/*.png"]);
+    builder.add_embedded("peter.txt", &EMBEDS["peter.txt"]);
+    builder.add_file("explicit.css", "tests/files/theme/theme.css").force_template();
+
+    let configured = builder.entries()
+        .map(|e| (e.http_paths()[0].to_string(), e.is_template(), e.is_internal()))
+        .collect::<Vec<_>>();
+    assert_eq!(configured, [
+        ("before.css".to_owned(), false, false),
+        ("theme/theme-dark.css".to_owned(), true, false),
+        ("img/pixel-small.png".to_owned(), false, true),
+        ("peter.txt".to_owned(), false, false),
+        ("explicit.css".to_owned(), true, false),
+    ]);
+}
+
+#[tokio::test]
+async fn template_conditionals() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["conditional.html", "peter.txt"],
+    };
+
+    // The debug script does not exist, but is never referenced.
+    let mut builder = Assets::builder();
+    builder.add_var("debug", "false");
+    builder.add_embedded("index.html", &EMBEDS["conditional.html"]).with_template();
+    let assets = builder.build().await?;
+    let index = assets.get("index.html").unwrap().content().await?;
+    assert_eq!(std::str::from_utf8(&index)?, "<h1>Hello</h1>\n<p>prod</p>\n");
+
+    let mut builder = Assets::builder();
+    builder.add_var("debug", "true");
+    builder.add_embedded("index.html", &EMBEDS["conditional.html"]).with_template();
+    builder.add_embedded("debug.js", &EMBEDS["peter.txt"]);
+    let assets = builder.build().await?;
+    let index = assets.get("index.html").unwrap().content().await?;
+    assert_eq!(
+        std::str::from_utf8(&index)?,
+        "<h1>Hello</h1>\n<script src=\"/debug.js\"></script>\n<p>dev</p>\n",
+    );
+
+    // Undefined variables are an error.
+    let mut builder = Assets::builder();
+    builder.add_embedded("index.html", &EMBEDS["conditional.html"]).with_template();
+    #[cfg(prod_mode)]
+    assert!(matches!(builder.build().await, Err(reinda::BuildError::Template { .. })));
+    #[cfg(dev_mode)]
+    assert!(builder.build().await?.get("index.html").unwrap().content().await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pipeline_plugins() -> Result<(), Box<dyn std::error::Error>> {
+    use std::borrow::Cow;
+    use bytes::Bytes;
+    use reinda::{ModifierContext, Pipeline};
+
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["peter.txt", "robots.txt"],
+    };
+
+    struct Uppercase;
+    impl Pipeline for Uppercase {
+        fn name(&self) -> &str {
+            "uppercase"
+        }
+        fn applies_to(&self, http_path: &str) -> bool {
+            http_path.ends_with(".txt")
+        }
+        fn transform(&self, content: Bytes, _: ModifierContext) -> Result<Bytes, String> {
+            Ok(content.to_ascii_uppercase().into())
+        }
+    }
+
+    /// Appends the (hashed) path of `robots.txt` to `peter.txt`.
+    struct Link;
+    impl Pipeline for Link {
+        fn name(&self) -> &str {
+            "link"
+        }
+        fn applies_to(&self, http_path: &str) -> bool {
+            http_path == "peter.txt"
+        }
+        fn dependencies(&self) -> Vec<Cow<'static, str>> {
+            vec!["robots.txt".into()]
+        }
+        fn transform(&self, content: Bytes, ctx: ModifierContext) -> Result<Bytes, String> {
+            Ok(format!("{}{}", String::from_utf8_lossy(&content), ctx.resolve_path("robots.txt")).into())
+        }
+    }
+
+    struct Failing;
+    impl Pipeline for Failing {
+        fn name(&self) -> &str {
+            "failing"
+        }
+        fn applies_to(&self, _: &str) -> bool {
+            true
+        }
+        fn transform(&self, _: Bytes, _: ModifierContext) -> Result<Bytes, String> {
+            Err("oh no".into())
+        }
+    }
+
+    let mut builder = Assets::builder();
+    builder.register_plugin(Uppercase).register_plugin(Link);
+    builder.add_embedded("peter.txt", &EMBEDS["peter.txt"]);
+    builder.add_embedded("robots.txt", &EMBEDS["robots.txt"]);
+    builder.add_embedded("verbatim.txt", &EMBEDS["peter.txt"]).verbatim();
+    let assets = builder.build().await?;
+
+    let peter = assets.get("peter.txt").unwrap().content().await?;
+    let expected = format!("{}robots.txt", include_str!("files/peter.txt").to_uppercase());
+    assert_eq!(std::str::from_utf8(&peter)?, expected);
+    let verbatim = assets.get("verbatim.txt").unwrap().content().await?;
+    assert_eq!(verbatim, include_bytes!("files/peter.txt")[..]);
+    assert_eq!(assets.unreferenced().await, ["peter.txt", "verbatim.txt"]);
+
+    let mut builder = Assets::builder();
+    builder.register_plugin(Failing);
+    builder.add_embedded("peter.txt", &EMBEDS["peter.txt"]);
+    let result = builder.build().await;
+
+    #[cfg(prod_mode)]
+    assert!(matches!(
+        result.unwrap_err(),
+        reinda::BuildError::Pipeline { http_path, step, message }
+            if http_path == "peter.txt" && step == "failing" && message == "oh no"
+    ));
+
+    #[cfg(dev_mode)]
+    {
+        let err = result?.get("peter.txt").unwrap().content().await.unwrap_err();
+        assert!(err.to_string().contains("oh no"));
+    }
+
+    // Dependencies of steps have to exist.
+    let mut builder = Assets::builder();
+    builder.register_plugin(Link);
+    builder.add_embedded("peter.txt", &EMBEDS["peter.txt"]);
+    assert!(matches!(
+        builder.build().await,
+        Err(reinda::BuildError::MissingDependency { asset, dependency })
+            if asset == "peter.txt" && dependency == "robots.txt"
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn template_includes() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["page.html", "header.html", "peter.txt"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("index.html", &EMBEDS["page.html"]).with_template();
+    builder.add_embedded("header.html", &EMBEDS["header.html"]).with_template().internal();
+    builder.add_embedded("peter.txt", &EMBEDS["peter.txt"]);
+    let assets = builder.build().await?;
+    let index = assets.get("index.html").unwrap().content().await?;
+    assert_eq!(
+        std::str::from_utf8(&index)?,
+        "<header><a href=\"/peter.txt\">Peter</a></header>\n<main></main>\n",
+    );
+
+    // Including itself is a cycle.
+    let mut builder = Assets::builder();
+    builder.add_embedded("header.html", &EMBEDS["page.html"]).with_template();
+    #[cfg(prod_mode)]
+    assert!(matches!(builder.build().await, Err(reinda::BuildError::CyclicDependencies(_))));
+    #[cfg(dev_mode)]
+    {
+        let err = builder.build().await?.get("header.html").unwrap().content().await.unwrap_err();
+        assert!(err.to_string().contains("cyclic"));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn glob_order() -> Result<(), Box<dyn std::error::Error>> {
+    // Without sorting, the files of the first base path would come first.
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: ["examples/assets", "tests/files"],
+        files: ["*.css", "**/*.css"],
+    };
+
+    let paths = |pattern| EMBEDS[pattern].files().map(|f| f.path()).collect::<Vec<_>>();
+    assert_eq!(paths("*.css"), ["bom-crlf.css", "style.css"]);
+    assert_eq!(paths("**/*.css"), [
+        "bom-crlf.css",
+        "style.css",
+        "theme/theme-dark.css",
+        "theme/theme.css",
+    ]);
+
+    let mut builder = Assets::builder();
+    let entry = builder.add_embedded("static/", &EMBEDS["**/*.css"]);
+    assert_eq!(entry.http_paths(), [
+        "static/bom-crlf.css",
+        "static/style.css",
+        "static/theme/theme-dark.css",
+        "static/theme/theme.css",
+    ]);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg(feature = "hash")]
+async fn template_loops() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: ["examples/assets", "tests/files"],
+        files: ["fonts/*.woff2", "fonts.html"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("fonts/", &EMBEDS["fonts/*.woff2"]).with_hash();
+    builder.add_embedded("index.html", &EMBEDS["fonts.html"]).with_template();
+    let assets = builder.build().await?;
+
+    let index = assets.get("index.html").unwrap().content().await?;
+    let fonts = assets.iter()
+        .map(|(path, _)| path)
+        .filter(|path| path.ends_with(".woff2") && path.contains("400"))
+        .collect::<Vec<_>>();
+    assert_eq!(fonts.len(), 2);
+    #[cfg(prod_mode)]
+    assert!(fonts.iter().all(|path| path.matches('.').count() == 2));
+    let expected = fonts.iter().map(|path| format!("<link href=\"/{path}\">")).collect::<String>();
+    assert_eq!(std::str::from_utf8(&index)?, expected);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn inspector() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::Request;
+
+    let mut builder = Assets::builder();
+    builder.add_file("robots.txt", "tests/files/robots.txt");
+    builder.add_file("header.html", "tests/files/header.html").internal();
+    builder.add_file("page.html", "tests/files/page.html").with_template();
+    builder.enable_inspector("/__reinda");
+    let assets = builder.build().await?;
+
+    let res = assets.serve(&Request::get("/__reinda")).await;
+    #[cfg(dev_mode)]
+    {
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.header("content-type"), Some("text/html; charset=utf-8"));
+        assert_eq!(res.header("cache-control"), Some("no-store"));
+        let body = std::str::from_utf8(res.body())?;
+        assert!(body.contains("<h1>3 assets</h1>"));
+        assert!(body.contains("<a href=\"/robots.txt\">/robots.txt</a>"));
+        assert!(body.contains("/header.html (internal)"));
+        assert!(body.contains("<td>template</td><td>header.html</td>"));
+    }
+    #[cfg(prod_mode)]
+    assert_eq!(res.status(), 404);
+
+    let res = assets.serve(&Request::get("/__reinda.json")).await;
+    #[cfg(dev_mode)]
+    {
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.header("content-type"), Some("application/json"));
+        let json: serde_json::Value = serde_json::from_slice(res.body())?;
+        let assets = json["assets"].as_array().unwrap();
+        let paths = assets.iter().map(|a| a["path"].as_str().unwrap()).collect::<Vec<_>>();
+        assert_eq!(paths, ["header.html", "page.html", "robots.txt"]);
+        assert_eq!(assets[1]["processing"], serde_json::json!(["template"]));
+        assert_eq!(assets[1]["dependencies"], serde_json::json!(["header.html"]));
+        assert_eq!(assets[0]["internal"], true);
+        assert!(assets[2]["size"].is_u64());
+    }
+    #[cfg(prod_mode)]
+    assert_eq!(res.status(), 404);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn template_vars() -> Result<(), Box<dyn std::error::Error>> {
+    let mut builder = Assets::builder();
+    builder.add_var("name", "reinda");
+    builder.add_var("version", "1.2.3");
+    builder.add_file("a.html", "tests/files/version.html").with_template();
+    builder.add_file("b.html", "tests/files/version.html").with_template().add_var("version", "2.0");
+    let assets = builder.build().await?;
+
+    let a = assets.get("a.html").unwrap().content().await?;
+    assert_eq!(std::str::from_utf8(&a)?, "<footer>reinda v1.2.3</footer>\n");
+    let b = assets.get("b.html").unwrap().content().await?;
+    assert_eq!(std::str::from_utf8(&b)?, "<footer>reinda v2.0</footer>\n");
+
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg(feature = "hash")]
+async fn sri_hashes() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::{util::sri_hash, SriAlgorithm};
+
+    let mut builder = Assets::builder();
+    builder.add_file("index.html", "tests/files/sri.html").with_template();
+    builder.add_file("peter.js", "tests/files/peter.txt")
+        .with_modifier([] as [&str; 0], |content, _| [&content[..], b"// modified"].concat().into());
+    let assets = builder.build().await?;
+
+    // The hash is of the final content, after the modifier ran.
+    let peter = assets.get("peter.js").unwrap();
+    let mut expected = std::fs::read("tests/files/peter.txt")?;
+    expected.extend_from_slice(b"// modified");
+    let sri = peter.sri_hash(SriAlgorithm::Sha384).await?;
+    assert_eq!(sri, sri_hash(&expected, SriAlgorithm::Sha384));
+    assert!(sri.starts_with("sha384-"));
+    assert!(peter.sri_hash(SriAlgorithm::Sha256).await?.starts_with("sha256-"));
+
+    let index = assets.get("index.html").unwrap().content().await?;
+    assert!(std::str::from_utf8(&index)?.contains(&format!("integrity=\"{sri}\"")));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg(feature = "hash")]
+async fn csp_hashes() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::util::{sri_hash, SriAlgorithm};
+
+    let mut builder = Assets::builder();
+    builder.add_var("version", "1.0");
+    builder.add_file("index.html", "tests/files/inline.html").with_template();
+    builder.add_file("robots.txt", "tests/files/robots.txt");
+    let assets = builder.build().await?;
+
+    // Hashes are of the rendered template.
+    let hash = |s: &str| format!("'{}'", sri_hash(s.as_bytes(), SriAlgorithm::Sha256));
+    let hashes = assets.csp_hashes("index.html").await?;
+    assert_eq!(hashes.scripts, [hash("console.log(\"1.0\");")]);
+    assert_eq!(hashes.styles, [hash("p { color: red; }")]);
+
+    assert!(assets.csp_hashes("robots.txt").await?.is_empty());
+    let err = assets.csp_hashes("missing.html").await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn manifest() -> Result<(), Box<dyn std::error::Error>> {
+    let mut builder = Assets::builder();
+    builder.add_file("robots.txt", "tests/files/robots.txt");
+    let _style = builder.add_file("style.css", "examples/assets/style.css");
+    #[cfg(feature = "hash")]
+    _style.with_hash();
+    builder.add_file("header.html", "tests/files/header.html").internal();
+    let assets = builder.build().await?;
+
+    let manifest = assets.manifest().await?;
+    assert_eq!(manifest.entries.keys().collect::<Vec<_>>(), ["robots.txt", "style.css"]);
+    let style = manifest.get("style.css").unwrap();
+    assert_eq!(style.size, std::fs::metadata("examples/assets/style.css")?.len());
+    assert_eq!(style.content_type, "text/css; charset=utf-8");
+    assert!(assets.get(&style.path).is_some());
+    #[cfg(all(prod_mode, feature = "hash"))]
+    assert_ne!(style.path, "style.css");
+    #[cfg(feature = "hash")]
+    assert_eq!(style.sha256.as_ref().unwrap().len(), 64);
+
+    #[cfg(feature = "serde")]
+    {
+        let path = std::env::temp_dir().join(format!("reinda-test-manifest-{}.json", std::process::id()));
+        assets.write_manifest_json(&path).await?;
+        let json: serde_json::Value = serde_json::from_slice(&std::fs::read(&path)?)?;
+        assert_eq!(json["style.css"]["path"], style.path.as_str());
+        assert_eq!(json["robots.txt"]["content_type"], "text/plain; charset=utf-8");
+        std::fs::remove_file(&path)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "reload")]
+#[tokio::test]
+async fn reload() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::{http::Request, reload::ReloadableAssets};
+
+    let builder = |path: &'static str| move || {
+        let mut builder = Assets::builder();
+        builder.add_file("data.txt", path);
+        builder
+    };
+    let assets = ReloadableAssets::new(builder("tests/files/robots.txt")().build().await?);
+    let old = assets.current();
+
+    assets.reload(builder("tests/files/peter.txt")).await?;
+    let res = assets.serve(&Request::get("/data.txt")).await;
+    assert_eq!(*res.body(), std::fs::read("tests/files/peter.txt")?);
+
+    // Previously retrieved assets are unchanged.
+    let content = old.get("data.txt").unwrap().content().await?;
+    assert_eq!(content, std::fs::read("tests/files/robots.txt")?);
+
+    // Failed builds keep the current assets.
+    #[cfg(prod_mode)]
+    {
+        assert!(assets.reload(builder("tests/files/missing.txt")).await.is_err());
+        let res = assets.serve(&Request::get("/data.txt")).await;
+        assert_eq!(*res.body(), std::fs::read("tests/files/peter.txt")?);
+    }
+
+    // Reloads once per trigger, until it returns `false`.
+    let mut triggers = 0;
+    let mut builds = 0;
+    let build = || {
+        builds += 1;
+        builder("tests/files/robots.txt")()
+    };
+    assets.reload_on(build, || {
+        triggers += 1;
+        std::future::ready(triggers <= 2)
+    }).await;
+    assert_eq!((triggers, builds), (3, 2));
+    let res = assets.serve(&Request::get("/data.txt")).await;
+    assert_eq!(*res.body(), std::fs::read("tests/files/robots.txt")?);
+
+    // A config that makes building panic neither ends the loop nor replaces
+    // the current assets.
+    let mut builds = 0;
+    let build = || {
+        builds += 1;
+        let mut builder = Assets::builder();
+        builder.add_bytes("data.txt", "lazy").lazy();
+        builder
+    };
+    let mut triggers = 0;
+    assets.reload_on(build, || {
+        triggers += 1;
+        std::future::ready(triggers <= 2)
+    }).await;
+    assert_eq!(builds, 2);
+    let res = assets.serve(&Request::get("/data.txt")).await;
+    assert_eq!(*res.body(), std::fs::read("tests/files/robots.txt")?);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg(feature = "compress")]
+async fn write_to_dir_precompressed() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::{CompressionConfig, Encoding};
+
+    let mut config = CompressionConfig::default();
+    config.min_size = 100;
+    let mut builder = Assets::builder();
+    builder.set_compression(config);
+    builder.add_file("style.css", "examples/assets/style.css");
+    builder.add_file("robots.txt", "tests/files/robots.txt");
+    let assets = builder.build().await?;
+
+    let dir = std::env::temp_dir().join(format!("reinda-test-export-br-{}", std::process::id()));
+    assets.write_to_dir_with(&dir, &reinda::ExportOptions::new().with_precompressed()).await?;
+
+    let style = assets.get("style.css").unwrap();
+    for &encoding in Encoding::ALL {
+        let written = std::fs::read(dir.join(format!("style.css.{}", encoding.file_extension())))?;
+        assert_eq!(written, style.encoded_content(encoding).await?.unwrap());
+    }
+
+    // Too small to be compressed.
+    assert!(dir.join("robots.txt").exists());
+    assert!(!dir.join("robots.txt.gz").exists());
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn custom_status() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::Request;
+
+    let mut builder = Assets::builder();
+    builder.add_file("gone.html", "tests/files/peter.txt").with_status(410);
+    builder.add_file("peter.txt", "tests/files/peter.txt");
+    let assets = builder.build().await?;
+    let content = std::fs::read("tests/files/peter.txt")?;
+
+    assert_eq!(assets.get("gone.html").unwrap().status(), 410);
+    assert_eq!(assets.get("peter.txt").unwrap().status(), 200);
+
+    let res = assets.serve(&Request::get("/gone.html")).await;
+    assert_eq!(res.status(), 410);
+    assert_eq!(*res.body(), content);
+    assert_eq!(res.header("content-type"), Some("text/html; charset=utf-8"));
+
+    // Neither ranges nor conditional requests apply.
+    let res = assets.serve(&Request::get("/gone.html").with_header("range", "bytes=0-1")).await;
+    assert_eq!(res.status(), 410);
+    assert_eq!(*res.body(), content);
+    if let Some(etag) = res.header("etag") {
+        let req = Request::get("/gone.html").with_header("if-none-match", etag);
+        assert_eq!(assets.serve(&req).await.status(), 410);
+    }
+
+    let res = assets.serve(&Request::get("/peter.txt").with_header("range", "bytes=0-1")).await;
+    assert_eq!(res.status(), 206);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn json_errors() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::Request;
+
+    let mut builder = Assets::builder();
+    builder.add_file("api/users.json", "tests/files/peter.txt");
+    builder.enable_json_errors("/api/");
+    let assets = builder.build().await?;
+
+    let req = Request::get("/api/user.json?id=3").with_header("accept", "application/json");
+    let res = assets.serve(&req).await;
+    assert_eq!(res.status(), 404);
+    assert_eq!(res.header("content-type"), Some("application/json"));
+    let body: serde_json::Value = serde_json::from_slice(res.body())?;
+    assert_eq!(body["error"], "Not Found");
+    assert_eq!(body["status"], 404);
+    assert_eq!(body["path"], "/api/user.json");
+    #[cfg(dev_mode)]
+    assert_eq!(body["suggestions"], serde_json::json!(["/api/users.json"]));
+
+    let req = Request::get("/api/nope").with_header("accept", "application/problem+json, */*;q=0.5");
+    assert_eq!(assets.serve(&req).await.header("content-type"), Some("application/json"));
+
+    // Browsers and other prefixes get plain text.
+    for req in [
+        Request::get("/api/nope").with_header("accept", "text/html,*/*;q=0.8"),
+        Request::get("/api/nope").with_header("accept", "application/json;q=0"),
+        Request::get("/api/nope"),
+        Request::get("/nope").with_header("accept", "application/json"),
+    ] {
+        let res = assets.serve(&req).await;
+        assert_eq!(res.status(), 404);
+        assert_eq!(res.header("content-type"), Some("text/plain; charset=utf-8"));
+    }
+
+    // Prefixes only match whole segments.
+    let mut builder = Assets::builder();
+    builder.enable_json_errors("/api");
+    let assets = builder.build().await?;
+    let req = |path| Request::get(path).with_header("accept", "application/json");
+    assert_eq!(assets.serve(&req("/api/nope")).await.header("content-type"), Some("application/json"));
+    assert_eq!(assets.serve(&req("/api")).await.header("content-type"), Some("application/json"));
+    let res = assets.serve(&req("/apiv2/nope")).await;
+    assert_eq!(res.header("content-type"), Some("text/plain; charset=utf-8"));
+
+    Ok(())
+}
+
+#[cfg(feature = "url")]
+#[tokio::test]
+async fn remote_assets() -> Result<(), Box<dyn std::error::Error>> {
+    use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+
+    let requests = Arc::new(AtomicUsize::new(0));
+    let app = axum::Router::new().route("/vendor.js", axum::routing::get({
+        let requests = requests.clone();
+        move || async move {
+            requests.fetch_add(1, Ordering::SeqCst);
+            "console.log('vendor');"
+        }
+    }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let mut builder = Assets::builder();
+    builder.add_url("vendor.js", format!("http://{addr}/vendor.js"));
+    let assets = builder.build().await?;
+
+    let (_, vendor) = assets.iter().find(|(_, a)| a.content_type().contains("javascript")).unwrap();
+    assert_eq!(vendor.content().await?, "console.log('vendor');");
+    assert_eq!(vendor.content().await?, "console.log('vendor');");
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+    let mut builder = Assets::builder();
+    builder.add_url("missing.js", format!("http://{addr}/missing.js"));
+    let result = builder.build().await;
+    if cfg!(prod_mode) {
+        let err = result.unwrap_err();
+        assert!(err.is_not_found());
+        assert_eq!(err.http_path(), Some("missing.js"));
+    } else {
+        let err = result?.get("missing.js").unwrap().content().await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    server.abort();
+    Ok(())
+}
+
+#[tokio::test]
+async fn generated_assets() -> Result<(), Box<dyn std::error::Error>> {
+    use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut builder = Assets::builder();
+    let _style = builder.add_file("style.css", "examples/assets/style.css");
+    #[cfg(feature = "hash")]
+    _style.with_hash();
+    builder.add_bytes("robots.txt", "User-agent: *\n");
+    builder.add_with("config.js", {
+        let calls = calls.clone();
+        move || {
+            let n = calls.fetch_add(1, Ordering::SeqCst);
+            async move { Ok(format!("const n = {n}; const css = '/{{{{: path:style.css :}}}}';")) }
+        }
+    }).with_template();
+    let assets = builder.build().await?;
+
+    assert_eq!(assets.get("robots.txt").unwrap().content().await?, "User-agent: *\n");
+
+    let (style_path, _) = assets.iter().find(|(p, _)| p.ends_with(".css")).unwrap();
+    let config = assets.get("config.js").unwrap();
+    assert_eq!(config.content().await?, format!("const n = 0; const css = '/{style_path}';"));
+
+    // Only dev mode calls the generator again.
+    let expected_n = if cfg!(prod_mode) { 0 } else { 1 };
+    assert_eq!(config.content().await?, format!("const n = {expected_n}; const css = '/{style_path}';"));
+    assert_eq!(calls.load(Ordering::SeqCst), expected_n + 1);
+
+    let mut builder = Assets::builder();
+    builder.add_with("broken.txt", || async { Err::<String, _>(std::io::Error::other("nope")) });
+    let result = builder.build().await;
+    if cfg!(prod_mode) {
+        assert_eq!(result.unwrap_err().http_path(), Some("broken.txt"));
+    } else {
+        assert!(result?.get("broken.txt").unwrap().content().await.is_err());
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn router() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::{http::Request, AssetsRouter};
+
+    let mut builder = Assets::builder();
+    builder.add_file("robots.txt", "tests/files/robots.txt");
+    builder.add_file("uploads/peter.txt", "tests/files/robots.txt");
+    let app = builder.build().await?;
+
+    let mut builder = Assets::builder();
+    builder.add_file("peter.txt", "tests/files/peter.txt");
+    let uploads = builder.build().await?;
+
+    let mut router = AssetsRouter::new();
+    router.mount("/uploads/", uploads).mount("/", app);
+    assert_eq!(router.mounts().map(|(prefix, _)| prefix).collect::<Vec<_>>(), ["uploads/", ""]);
+
+    let peter = std::fs::read("tests/files/peter.txt")?;
+    let robots = std::fs::read("tests/files/robots.txt")?;
+    assert_eq!(router.get("uploads/peter.txt").unwrap().content().await?, peter);
+    assert_eq!(router.get("/robots.txt").unwrap().content().await?, robots);
+    assert!(router.get("peter.txt").is_none());
+
+    let mut paths = router.iter().map(|(path, _)| path).collect::<Vec<_>>();
+    paths.sort();
+    assert_eq!(paths, ["robots.txt", "uploads/peter.txt", "uploads/peter.txt"]);
+
+    let res = router.serve(&Request::get("/uploads/peter.txt?v=1")).await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(*res.body(), peter);
+    let res = router.serve(&Request::get("/robots.txt")).await;
+    assert_eq!(*res.body(), robots);
+    assert_eq!(router.serve(&Request::get("/uploads/robots.txt")).await.status(), 404);
+
+    let mut router = AssetsRouter::new();
+    router.mount("static/", Assets::builder().build().await?);
+    assert_eq!(router.serve(&Request::get("/robots.txt")).await.status(), 404);
+
+    // The query string is forwarded to the set.
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["theme/theme.css", "theme/theme-dark.css"],
+    };
+    let mut builder = Assets::builder();
+    builder.add_embedded("theme.css", &EMBEDS["theme/theme.css"])
+        .with_labeled_variant("dark", "theme-dark.css");
+    builder.add_embedded("theme-dark.css", &EMBEDS["theme/theme-dark.css"]);
+    builder.set_variant_selector("cookie", |req, variants| {
+        let dark = req.path().ends_with("?theme=dark");
+        variants.iter().position(|v| dark && v.label() == Some("dark"))
+    });
+    router.mount("themes/", builder.build().await?);
+    let res = router.serve(&Request::get("/themes/theme.css?theme=dark")).await;
+    assert_eq!(res.body(), "body { color: white; }\n");
+    let res = router.serve(&Request::get("/themes/theme.css")).await;
+    assert_eq!(res.body(), "body { color: black; }\n");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn head_requests() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::Request;
+
+    let mut builder = Assets::builder();
+    builder.add_file("style.css", "examples/assets/style.css");
+    builder.add_file("gone.html", "tests/files/peter.txt").with_status(410);
+    let assets = builder.build().await?;
+
+    let get = assets.serve(&Request::get("/style.css")).await;
+    let head = assets.serve(&Request::new("HEAD", "/style.css")).await;
+    assert_eq!(head.status(), 200);
+    assert!(head.body().is_empty());
+    assert_eq!(head.header("content-length"), Some(&*get.body().len().to_string()));
+    for name in ["content-type", "cache-control", "etag"] {
+        assert_eq!(head.header(name), get.header(name));
+    }
+    assert_eq!(get.header("content-length"), None);
+
+    let meta = assets.head("style.css").await?;
+    assert_eq!(meta.status, 200);
+    assert_eq!(meta.content_length, get.body().len() as u64);
+    assert_eq!(Some(&*meta.content_type), get.header("content-type"));
+    assert_eq!(Some(&*meta.cache_control), get.header("cache-control"));
+    assert_eq!(meta.etag.as_deref(), get.header("etag"));
+    assert_eq!(assets.head("gone.html").await?.status, 410);
+    assert_eq!(assets.head("nope.css").await.unwrap_err().kind(), std::io::ErrorKind::NotFound);
+
+    #[cfg(feature = "compress")]
+    {
+        let req = |method| Request::new(method, "/style.css").with_header("accept-encoding", "br");
+        let get = assets.serve(&req("GET")).await;
+        let head = assets.serve(&req("HEAD")).await;
+        assert_eq!(head.header("content-encoding"), get.header("content-encoding"));
+        assert_eq!(head.header("content-length"), Some(&*get.body().len().to_string()));
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "runtime-mutation")]
+#[tokio::test]
+async fn runtime_mutation() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::http::Request;
+
+    let mut builder = Assets::builder();
+    builder.add_file("robots.txt", "tests/files/robots.txt");
+    let assets = builder.build().await?;
+    let clone = assets.clone();
+
+    let path = assets.insert("/uploads/avatar.txt", "first").await?;
+    assert_eq!(path, "uploads/avatar.txt");
+    assert_eq!(clone.get("uploads/avatar.txt").unwrap().content().await?, "first");
+    let res = clone.serve(&Request::get("/uploads/avatar.txt")).await;
+    assert_eq!((res.status(), &**res.body()), (200, &b"first"[..]));
+    assert!(!assets.iter().any(|(path, _)| path.starts_with("uploads/")));
+
+    // Replacing, also the built assets.
+    assets.insert("uploads/avatar.txt", "second").await?;
+    assert_eq!(assets.get("uploads/avatar.txt").unwrap().content().await?, "second");
+    assets.insert("robots.txt", "User-agent: *").await?;
+    assert_eq!(assets.get("robots.txt").unwrap().content().await?, "User-agent: *");
+    assert!(assets.remove("robots.txt").is_some());
+    assert_eq!(
+        assets.get("robots.txt").unwrap().content().await?,
+        std::fs::read("tests/files/robots.txt")?,
+    );
+
+    assert!(assets.remove("/uploads/avatar.txt").is_some());
+    assert!(assets.remove("uploads/avatar.txt").is_none());
+    assert_eq!(clone.serve(&Request::get("/uploads/avatar.txt")).await.status(), 404);
+
+    #[cfg(feature = "hash")]
+    {
+        let path = assets.insert_with("gen/config.js", "let a;", |entry| { entry.with_hash(); }).await?;
+        if cfg!(prod_mode) {
+            assert_ne!(path, "gen/config.js");
+            let new_path = assets.insert_with("gen/config.js", "let b;", |e| { e.with_hash(); }).await?;
+            assert!(assets.get(&path).is_none());
+            assert_eq!(assets.get(&new_path).unwrap().content().await?, "let b;");
+        } else {
+            assert_eq!(assets.get(&path).unwrap().content().await?, "let a;");
+        }
+    }
+
+    let err = assets.insert_with("secret.txt", "x", |e| { e.internal(); }).await.unwrap_err();
+    assert!(matches!(err, reinda::BuildError::InternalInsert { .. }));
+    assert_eq!(err.http_path(), Some("secret.txt"));
+    assert!(assets.get("secret.txt").is_none());
+
+    // Inserted assets cannot depend on other assets.
+    let err = assets.insert_with("a.txt", "a", |e| { e.prepend_asset("peter.txt"); }).await.unwrap_err();
+    assert!(matches!(err, reinda::BuildError::MissingDependency { .. }));
+    let err = assets.insert_with("b.png", "b", |e| { e.with_variant("b.avif"); }).await.unwrap_err();
+    assert!(matches!(err, reinda::BuildError::MissingDependency { .. }));
+
+    // Inserted assets see the variables and public URL of the builder.
+    let mut builder = Assets::builder();
+    builder.add_var("version", "1.0");
+    builder.set_public_url("https://example.com/docs");
+    let assets = builder.build().await?;
+    assets.insert_with("about.txt", "v{{: var:version :}}", |e| {
+        e.with_template().with_modifier([] as [&str; 0], |content, ctx| {
+            let url = ctx.public_url().unwrap_or("-");
+            format!("{} {url}", String::from_utf8_lossy(&content)).into()
+        });
+    }).await?;
+    assert_eq!(assets.get("about.txt").unwrap().content().await?, "v1.0 https://example.com/docs/");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn directories() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::temp_dir().join(format!("reinda-test-dir-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("js/vendor"))?;
+    std::fs::write(dir.join("style.css"), "body {}")?;
+    std::fs::write(dir.join("js/app.js"), "app();")?;
+    std::fs::write(dir.join("js/app.js.map"), "{}")?;
+    std::fs::write(dir.join("js/vendor/lib.js"), "lib();")?;
+
+    let mut builder = Assets::builder();
+    builder.add_dir("static/", &dir).with_exclude("*.map");
+    builder.add_dir("scripts/", &dir).with_include("js/*.js").with_exclude("js/vendor/*");
+    builder.add_dir("missing/", dir.join("missing")).optional();
+    let assets = builder.build().await?;
+
+    let mut paths = assets.iter().map(|(path, _)| path.to_owned()).collect::<Vec<_>>();
+    paths.sort();
+    assert_eq!(paths, [
+        "scripts/js/app.js",
+        "static/js/app.js",
+        "static/js/vendor/lib.js",
+        "static/style.css",
+    ]);
+    assert_eq!(assets.get("static/js/vendor/lib.js").unwrap().content().await?, "lib();");
+    assert!(assets.get("static/js/app.js.map").is_none());
+    assert!(assets.get("static/../style.css").is_none());
+
+    // Files added later are only picked up in dev mode.
+    std::fs::write(dir.join("late.txt"), "late")?;
+    assert_eq!(assets.get("static/late.txt").is_some(), cfg!(dev_mode));
+
+    let mut builder = Assets::builder();
+    builder.add_dir("missing/", dir.join("missing"));
+    let result = builder.build().await;
+    assert_eq!(result.is_err(), cfg!(prod_mode));
+    if let Err(e) = result {
+        assert!(e.is_not_found());
+        assert_eq!(e.http_path(), Some("missing/"));
+    }
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn glob_filters() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["img/*" except ["img/*.webp", "img/pixel@3x.png"]],
+    };
+
+    let glob = EMBEDS["img/*"].as_glob().unwrap();
+    let embedded = glob.files().map(|f| f.path()).collect::<Vec<_>>();
+    assert_eq!(embedded, [
+        "img/pixel-small.png",
+        "img/pixel.avif",
+        "img/pixel.png",
+        "img/pixel@2x.png",
+    ]);
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("img/", &EMBEDS["img/*"])
+        .with_exclude("*.avif")
+        .with_filter(|path| !path.contains("small"));
+    let assets = builder.build().await?;
+
+    let mut paths = assets.iter().map(|(path, _)| path.to_owned()).collect::<Vec<_>>();
+    paths.sort();
+    assert_eq!(paths, ["img/pixel.png", "img/pixel@2x.png"]);
+
+    // Also in dev mode, where files are looked up at runtime.
+    for path in ["img/pixel.webp", "img/pixel@3x.png", "img/pixel.avif", "img/pixel-small.png"] {
+        assert!(assets.get(path).is_none(), "{}", path);
+    }
+    assert!(assets.get("img/pixel.png").is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn hot_cache() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::temp_dir().join(format!("reinda-test-hot-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("a.txt"), "a")?;
+    std::fs::write(dir.join("b.txt"), "b")?;
+
+    let mut builder = Assets::builder();
+    builder.add_dir("", &dir);
+    builder.enable_hot_cache(1);
+    let assets = builder.build().await?;
+
+    for _ in 0..3 {
+        assert_eq!(assets.get("a.txt").unwrap().content().await?, "a");
+    }
+    assert_eq!(assets.get("b.txt").unwrap().content().await?, "b");
+
+    // Changed files are loaded again in dev mode.
+    std::fs::write(dir.join("a.txt"), "changed")?;
+    let expected = if cfg!(dev_mode) { "changed" } else { "a" };
+    assert_eq!(assets.get("a.txt").unwrap().content().await?, expected);
+
+    let stats = assets.hot_cache_stats();
+    if cfg!(dev_mode) {
+        let stats = stats.unwrap();
+        assert_eq!((stats.capacity, stats.entries, stats.bytes), (1, 1, 7));
+        assert_eq!((stats.hits, stats.misses), (2, 3));
+    } else {
+        assert!(stats.is_none());
+    }
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn extended_glob_syntax() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["img/pixel.{avif,webp}", "**.css" except "theme/*-dark.{css,scss}"],
+    };
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("img/", &EMBEDS["img/pixel.{avif,webp}"]);
+    builder.add_embedded("css/", &EMBEDS["**.css"]);
+    let assets = builder.build().await?;
+
+    let mut paths = assets.iter().map(|(path, _)| path.to_owned()).collect::<Vec<_>>();
+    paths.sort();
+    assert_eq!(paths, ["css/bom-crlf.css", "css/theme/theme.css", "img/pixel.avif", "img/pixel.webp"]);
+
+    // Lookups in dev mode use the same syntax.
+    assert!(assets.get("img/pixel.webp").is_some());
+    assert!(assets.get("img/pixel.png").is_none());
+    assert!(assets.get("css/theme/theme-dark.css").is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn mixed_mode_entries() -> Result<(), Box<dyn std::error::Error>> {
+    const EMBEDS: reinda::Embeds = reinda::embed! {
+        base_path: "tests/files",
+        files: ["peter.txt" always_embed, "robots.txt", "theme/*.css" always_embed except "*-dark.*"],
+    };
+
+    let dir = std::env::temp_dir().join(format!("reinda-test-lazy-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("video.bin"), "0123456789")?;
+
+    let mut builder = Assets::builder();
+    builder.add_embedded("peter.txt", &EMBEDS["peter.txt"]);
+    builder.add_embedded("robots.txt", &EMBEDS["robots.txt"]);
+    builder.add_embedded("theme/", &EMBEDS["theme/*.css"]);
+    builder.add_bytes("bytes.txt", "bytes");
+    builder.add_file("video.bin", dir.join("video.bin")).lazy();
+    let assets = builder.build().await?;
+
+    let expected_mode = if cfg!(prod_mode) { reinda::Mode::Prod } else { reinda::Mode::Dev };
+    assert_eq!(assets.mode(), expected_mode);
+
+    assert!(assets.get("peter.txt").unwrap().is_embedded());
+    assert_eq!(assets.get("robots.txt").unwrap().is_embedded(), cfg!(prod_mode));
+    assert!(!assets.get("bytes.txt").unwrap().is_embedded());
+    assert!(!assets.get("video.bin").unwrap().is_embedded());
+    assert!(EMBEDS["peter.txt"].embedded_size() > 0);
+    assert_eq!(EMBEDS["robots.txt"].embedded_size() > 0, cfg!(prod_mode));
+    let theme = EMBEDS["theme/*.css"].files().map(|f| f.path()).collect::<Vec<_>>();
+    assert!(theme.iter().all(|path| !path.ends_with("-dark.css")));
+    for (path, asset) in assets.iter().filter(|(path, _)| path.starts_with("theme/")) {
+        assert!(asset.is_embedded(), "{} is not embedded", path);
+    }
+
+    let video = assets.get("video.bin").unwrap();
+    assert_eq!(video.content().await?, "0123456789");
+    assert_eq!(video.content_len().await?, 10);
+    assert_eq!(video.content_range(2..5).await?, "234");
+    let res = assets.serve(&reinda::http::Request::get("video.bin").with_header("range", "bytes=-3")).await;
+    assert_eq!((res.status(), &**res.body()), (206, &b"789"[..]));
+
+    // Lazy assets are read on every request, in all modes.
+    std::fs::write(dir.join("video.bin"), "9876543210")?;
+    assert_eq!(video.content().await?, "9876543210");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+#[should_panic(expected = "is marked as lazy")]
+async fn lazy_requires_file() {
+    let mut builder = Assets::builder();
+    builder.add_bytes("a.txt", "a").lazy();
+    let _ = builder.build().await;
+}
+
+#[cfg(feature = "hash")]
+#[tokio::test]
+#[should_panic(expected = "is marked as lazy, but also hashed")]
+async fn lazy_and_hashed() {
+    let mut builder = Assets::builder();
+    builder.add_file("peter.txt", "tests/files/peter.txt").lazy().with_hash();
+    let _ = builder.build().await;
+}
+
+#[cfg(feature = "minify")]
+#[tokio::test]
+async fn minify_modifiers() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::modifiers::{minify_css, minify_html, minify_js};
+
+    let js = "function add(first, second) {\n    return first + second;\n}\n";
+    let css = "body {\n    color: #ff0000;\n}\n";
+    let html = "<!DOCTYPE html>\n<html>\n  <body>\n    <p>  Hello  </p>\n  </body>\n</html>\n";
+
+    let mut builder = Assets::builder();
+    builder.add_bytes("app.js", js).with_modifier([] as [&str; 0], minify_js());
+    builder.add_bytes("style.css", css).with_modifier([] as [&str; 0], minify_css());
+    builder.add_bytes("index.html", html).with_modifier([] as [&str; 0], minify_html());
+    builder.add_bytes("broken.js", "function (").with_modifier([] as [&str; 0], minify_js());
+    let assets = builder.build().await?;
+
+    let content = |path| {
+        let asset = assets.get(path).unwrap();
+        async move { String::from_utf8(asset.content().await.unwrap().to_vec()).unwrap() }
+    };
+    if cfg!(prod_mode) {
+        let js = content("app.js").await;
+        assert!(js.starts_with("var add=") && !js.contains("second"), "{}", js);
+        assert_eq!(content("style.css").await, "body{color:red}");
+        let html = content("index.html").await;
+        assert!(html.len() < 60 && html.contains("<p>Hello</p>"), "{}", html);
+    } else {
+        assert_eq!(content("app.js").await, js);
+        assert_eq!(content("style.css").await, css);
+        assert_eq!(content("index.html").await, html);
+    }
+    assert_eq!(content("broken.js").await, "function (");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn modifier_context_env() -> Result<(), Box<dyn std::error::Error>> {
+    use bytes::Bytes;
+
+    fn describe(content: Bytes, ctx: reinda::ModifierContext) -> Bytes {
+        let var = |name| ctx.var(name).unwrap_or("-");
+        format!(
+            "{} {} {} {}",
+            String::from_utf8_lossy(&content),
+            var("version"),
+            var("missing"),
+            ctx.public_url().unwrap_or("-"),
+        ).into()
+    }
+
+    let mut builder = Assets::builder();
+    builder.add_var("version", "1.0");
+    builder.set_public_url("https://example.com/docs");
+    builder.add_bytes("a.txt", "a").with_modifier([] as [&str; 0], describe);
+    builder.add_bytes("b.txt", "b")
+        .add_var("version", "2.0")
+        .with_modifier([] as [&str; 0], describe);
+    let assets = builder.build().await?;
+    assert_eq!(assets.get("a.txt").unwrap().content().await?, "a 1.0 - https://example.com/docs/");
+    assert_eq!(assets.get("b.txt").unwrap().content().await?, "b 2.0 - https://example.com/docs/");
+
+    let mut builder = Assets::builder();
+    builder.add_bytes("a.txt", "a").with_modifier([] as [&str; 0], describe);
+    let assets = builder.build().await?;
+    assert_eq!(assets.get("a.txt").unwrap().content().await?, "a - - -");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fallible_modifier() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::ModifierError;
+
+    let mut builder = Assets::builder();
+    builder.add_bytes("data.json", "{").with_fallible_modifier([] as [&str; 0], |content, _| {
+        let text = std::str::from_utf8(&content)
+            .map_err(|e| ModifierError::with_source("not UTF-8", e))?;
+        if !text.ends_with('}') {
+            return Err("unbalanced braces".into());
+        }
+        Ok(content)
+    });
+    builder.add_bytes("index.html", "{{: path:data.json :}}").with_template();
+    builder.add_bytes("app.js", "x").with_modifier(["index.html"], |content, _| content);
+    builder.add_bytes("ok.txt", "ok").with_fallible_modifier([] as [&str; 0], |c, _| Ok(c));
+    let result = builder.build().await;
+
+    #[cfg(prod_mode)]
+    {
+        let err = result.unwrap_err();
+        assert_eq!(err.http_path(), Some("data.json"));
+        match &err {
+            reinda::BuildError::Modifier { dependents, error, .. } => {
+                assert_eq!(dependents, &["index.html", "app.js"]);
+                assert_eq!(error.message(), "unbalanced braces");
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+        assert_eq!(
+            err.to_string(),
+            "modifier of asset 'data.json' failed: unbalanced braces \
+                (required by 'index.html' <- 'app.js')",
+        );
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[cfg(dev_mode)]
+    {
+        let assets = result?;
+        assert_eq!(assets.get("ok.txt").unwrap().content().await?, "ok");
+        let err = assets.get("data.json").unwrap().content().await.unwrap_err();
+        assert!(err.to_string().contains("unbalanced braces"), "{}", err);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn transform_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    use std::time::Duration;
+
+    let mut builder = Assets::builder();
+    builder.set_transform_timeout(Duration::from_millis(50));
+    builder.add_bytes("slow.txt", "slow").with_modifier([] as [&str; 0], |content, _| {
+        std::thread::sleep(Duration::from_millis(500));
+        content
+    });
+    builder.add_bytes("patient.txt", "patient")
+        .with_transform_timeout(Duration::from_secs(5))
+        .with_modifier([] as [&str; 0], |content, _| {
+            std::thread::sleep(Duration::from_millis(100));
+            content
+        });
+    let result = builder.build().await;
+
+    #[cfg(prod_mode)]
+    {
+        let err = result.unwrap_err();
+        assert!(matches!(
+            &err,
+            reinda::BuildError::TransformTimeout { http_path, timeout }
+                if http_path == "slow.txt" && *timeout == Duration::from_millis(50)
+        ));
+    }
+
+    #[cfg(dev_mode)]
+    {
+        let assets = result?;
+        assert_eq!(assets.get("patient.txt").unwrap().content().await?, "patient");
+        let err = assets.get("slow.txt").unwrap().content().await.unwrap_err();
+        assert!(err.to_string().contains("took longer than 50ms"), "{}", err);
+    }
+
+    Ok(())
+}
+
+// A transform still running after its timeout must not block the shutdown of
+// the runtime.
+#[test]
+fn transform_timeout_shutdown() {
+    use std::time::Duration;
+
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    runtime.block_on(async {
+        let mut builder = Assets::builder();
+        builder.set_transform_timeout(Duration::from_millis(50));
+        builder.add_bytes("hang.txt", "hang").with_modifier([] as [&str; 0], |content, _| {
+            std::thread::sleep(Duration::from_secs(3600));
+            content
+        });
+        let result = builder.build().await;
+
+        #[cfg(prod_mode)]
+        assert!(matches!(result, Err(reinda::BuildError::TransformTimeout { .. })));
+        #[cfg(dev_mode)]
+        assert!(result.unwrap().get("hang.txt").unwrap().content().await.is_err());
+    });
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        drop(runtime);
+        tx.send(()).unwrap();
+    });
+    rx.recv_timeout(Duration::from_secs(5)).expect("runtime did not shut down");
+}
+
+#[cfg(dev_mode)]
+#[tokio::test]
+async fn transform_timeout_fails_fast() -> Result<(), Box<dyn std::error::Error>> {
+    use std::{sync::{atomic::{AtomicUsize, Ordering}, Arc}, time::Duration};
+
+    let dir = std::env::temp_dir().join(format!("reinda-test-timeout-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("slow.txt");
+    std::fs::write(&path, "slow")?;
+
+    let runs = Arc::new(AtomicUsize::new(0));
+    let mut builder = Assets::builder();
+    builder.set_transform_timeout(Duration::from_millis(50));
+    let counter = runs.clone();
+    builder.add_file("slow.txt", &path).with_modifier([] as [&str; 0], move |content, _| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(200));
+        content
+    });
+    let assets = builder.build().await?;
+
+    // The second load fails without running the modifier again.
+    let asset = assets.get("slow.txt").unwrap();
+    assert!(asset.content().await.is_err());
+    assert!(asset.content().await.is_err());
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    // Once the file changes, it is tried again.
+    std::fs::write(&path, "slower")?;
+    assert!(asset.content().await.is_err());
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn change_detection_by_content() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::ChangeDetection;
+    use std::time::{Duration, SystemTime};
+
+    let dir = std::env::temp_dir().join(format!("reinda-test-change-detection-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("a.txt");
+    std::fs::write(&path, "a")?;
+
+    let mut builder = Assets::builder();
+    builder.add_dir("", &dir);
+    builder.enable_hot_cache(1);
+    builder.set_change_detection(ChangeDetection::MetadataThenContent);
+    let assets = builder.build().await?;
+    assert_eq!(assets.get("a.txt").unwrap().content().await?, "a");
+
+    // Touching the file does not invalidate the cache, changing it does.
+    std::fs::File::options().write(true).open(&path)?
+        .set_modified(SystemTime::now() + Duration::from_secs(10))?;
+    assert_eq!(assets.get("a.txt").unwrap().content().await?, "a");
+    std::fs::write(&path, "b")?;
+    let expected = if cfg!(dev_mode) { "b" } else { "a" };
+    assert_eq!(assets.get("a.txt").unwrap().content().await?, expected);
+
+    if let Some(stats) = assets.hot_cache_stats() {
+        assert_eq!((stats.hits, stats.misses), (1, 2));
+    }
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg(feature = "hash")]
+async fn modifier_chain() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::ModifierKind;
+
+    let mut builder = Assets::builder();
+    builder.add_bytes("style.css", "body {}").with_hash();
+    let entry = builder.add_bytes("index.html", "<link href=\"style.css\">")
+        .with_path_fixup(["style.css"])
+        .with_modifier([] as [&str; 0], |content, _| {
+            format!("{}!", std::str::from_utf8(&content).unwrap()).into()
+        })
+        .with_modifier(["style.css"], |content, _| content.to_ascii_uppercase().into());
+    let kinds = entry.modifiers().iter().map(|m| m.kind).collect::<Vec<_>>();
+    assert_eq!(kinds, [ModifierKind::PathFixup, ModifierKind::Custom, ModifierKind::Custom]);
+    assert_eq!(entry.modifier_dependencies(), ["style.css", "style.css"]);
+
+    // Clearing removes all modifiers, later ones are kept.
+    let cleared = builder.add_bytes("cleared.txt", "a")
+        .with_modifier([] as [&str; 0], |_, _| "never".into())
+        .clear_modifiers()
+        .with_modifier([] as [&str; 0], |content, _| [&content[..], b"b"].concat().into());
+    assert_eq!(cleared.modifiers().len(), 1);
+
+    let assets = builder.build().await?;
+    let css_path = assets.iter().map(|(path, _)| path).find(|path| path.ends_with(".css")).unwrap();
+    let expected = format!("<LINK HREF=\"{}\">!", css_path.to_ascii_uppercase());
+    assert_eq!(assets.get("index.html").unwrap().content().await?, expected);
+    assert_eq!(assets.get("cleared.txt").unwrap().content().await?, "ab");
+
+    Ok(())
+}
+
+#[cfg(all(feature = "watch", dev_mode))]
+#[tokio::test]
+async fn watch_extra_files() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::watch::ChangeKind;
+    use std::time::Duration;
+
+    let dir = std::env::temp_dir().join(format!("reinda-test-extra-watch-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("config.txt"), "red")?;
+    std::fs::write(dir.join("style.css"), "body {}")?;
+
+    let config = dir.join("config.txt");
+    let mut builder = Assets::builder();
+    builder.enable_hot_cache(1);
+    builder.add_file("style.css", dir.join("style.css"))
+        .with_extra_watch([&config])
+        .with_modifier([] as [&str; 0], move |content, _| {
+            let color = std::fs::read_to_string(&config).unwrap();
+            [&content[..], color.as_bytes()].concat().into()
+        });
+    let assets = builder.build().await?;
+    assert_eq!(assets.get("style.css").unwrap().content().await?, "body {}red");
+
+    let mut changes = assets.subscribe_changes();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    std::fs::write(dir.join("config.txt"), "blue")?;
+    let change = tokio::time::timeout(Duration::from_secs(5), changes.recv()).await??;
+    assert_eq!((&*change.http_path, change.kind), ("style.css", ChangeKind::Modified));
+
+    // Assets with extra watched files are not cached.
+    assert_eq!(assets.get("style.css").unwrap().content().await?, "body {}blue");
+    assert_eq!(assets.hot_cache_stats().unwrap().entries, 0);
+
+    assets.shutdown().await;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn post_build() -> Result<(), Box<dyn std::error::Error>> {
+    let mut builder = Assets::builder();
+    builder.set_public_url("https://example.com/docs");
+    builder.add_bytes("index.html", "<h1>Home</h1>");
+    builder.add_file("about/peter.txt", "tests/files/peter.txt");
+    builder.add_bytes("secret.txt", "internal").internal();
+    builder.with_post_build(|view| {
+        let url = view.public_url().unwrap().to_owned();
+        let pages = view.iter()
+            .map(|asset| format!("{url}{} {}", asset.http_path, asset.content.len()))
+            .collect::<Vec<_>>();
+        view.add("/sitemap.txt", pages.join("\n"));
+    });
+    builder.with_post_build(|view| {
+        // Assets added by earlier hooks are visible.
+        let sitemap = view.get("sitemap.txt").unwrap().content.clone();
+        view.add("sitemap-copy.txt", sitemap);
+    });
+    let assets = builder.build().await?;
+
+    let peter_len = std::fs::metadata("tests/files/peter.txt")?.len();
+    let expected = format!(
+        "https://example.com/docs/about/peter.txt {peter_len}\nhttps://example.com/docs/index.html 13",
+    );
+    assert_eq!(assets.get("sitemap.txt").unwrap().content().await?, expected);
+    assert_eq!(assets.get("sitemap-copy.txt").unwrap().content().await?, expected);
+    assert_eq!(assets.len(), 4);
+
+    // Paths of built assets, including internal and hashed ones, are taken.
+    async fn build_adding(path: &str) -> Result<Assets, reinda::BuildError> {
+        let mut builder = Assets::builder();
+        builder.add_bytes("index.html", "<h1>Home</h1>");
+        builder.add_bytes("secret.txt", "internal").internal();
+        #[cfg(feature = "hash")]
+        builder.add_bytes("app.js", "let a;").with_hash();
+        let path = path.to_owned();
+        builder.with_post_build(move |view| {
+            view.add(path.clone(), "x");
+        });
+        builder.build().await
+    }
+    let is_duplicate = |r| matches!(r, Err(reinda::BuildError::DuplicatePath { .. }));
+    assert!(is_duplicate(build_adding("index.html").await));
+    assert!(is_duplicate(build_adding("/secret.txt").await));
+    assert!(build_adding("new.txt").await?.get("new.txt").is_some());
+    #[cfg(feature = "hash")]
+    {
+        let assets = build_adding("new.txt").await?;
+        let hashed = assets
+            .iter()
+            .map(|(path, _)| path)
+            .find(|p| p.ends_with(".js"))
+            .unwrap();
+        assert!(is_duplicate(build_adding(hashed).await));
+        assert!(is_duplicate(build_adding("app.js").await));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn percent_encoded_paths() -> Result<(), Box<dyn std::error::Error>> {
+    use reinda::{http::Request, AssetsRouter};
+
+    let mut builder = Assets::builder();
+    builder.add_bytes("café.txt", "coffee");
+    builder.add_bytes("100%.txt", "full");
+    let assets = builder.build().await?;
+
+    let response = assets.serve(&Request::get("/caf%C3%A9.txt?v=1")).await;
+    assert_eq!((response.status(), &**response.body()), (200, &b"coffee"[..]));
+    assert_eq!(assets.serve(&Request::get("/100%25.txt")).await.status(), 200);
+    assert_eq!(assets.serve(&Request::get("/caf%2F.txt")).await.status(), 404);
+    assert_eq!(assets.serve(&Request::get("/x/%2E%2E/caf%C3%A9.txt")).await.status(), 404);
+
+    // The router forwards the path without decoding it twice.
+    let mut router = AssetsRouter::new();
+    router.mount("/drinks/", assets);
+    assert_eq!(router.serve(&Request::get("/drinks/caf%C3%A9.txt")).await.status(), 200);
+    assert_eq!(router.serve(&Request::get("/drinks/100%25.txt")).await.status(), 200);
+
+    Ok(())
+}
+
 // TODO:
 // - cyclic dependencies
 // - missing dependencies (modifier asks for other path)